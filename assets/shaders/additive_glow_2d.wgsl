@@ -0,0 +1,11 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material_color: vec4<f32>;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // Fade out from the center of the quad to its edges
+    let distance = length(mesh.uv - vec2(0.5)) * 2.0;
+    let falloff = pow(saturate(1.0 - distance), 2.0);
+    return vec4(material_color.rgb, material_color.a * falloff);
+}
@@ -0,0 +1,17 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material_color: vec4<f32>;
+
+// One field for each color target of `Material2d::color_target_states`
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec4<f32>,
+}
+
+@fragment
+fn fragment(mesh: VertexOutput) -> FragmentOutput {
+    var out: FragmentOutput;
+    out.color = material_color;
+    out.uv = vec4<f32>(mesh.uv, 0.0, 1.0);
+    return out;
+}
@@ -0,0 +1,15 @@
+#import bevy_sprite::{
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::globals,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> velocity: vec2<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var scrolling_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var scrolling_sampler: sampler;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // The elapsed time comes from the globals uniform of the view bind group
+    let uv = mesh.uv + velocity * globals.time;
+    return textureSample(scrolling_texture, scrolling_sampler, uv);
+}
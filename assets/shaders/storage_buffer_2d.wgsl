@@ -0,0 +1,15 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct StorageBufferMaterial {
+    brightness: f32,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: StorageBufferMaterial;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var<storage, read> colors: array<vec4<f32>>;
+
+@fragment
+fn fragment(mesh: VertexOutput) -> @location(0) vec4<f32> {
+    // Each mesh picks its color from the storage buffer with its `MeshTag`.
+    let color = colors[mesh.tag % arrayLength(&colors)];
+    return vec4(color.rgb * material.brightness, color.a);
+}
@@ -1,5 +1,6 @@
 use criterion::criterion_main;
 
+mod pointer_events;
 mod ray_mesh_intersection;
 
-criterion_main!(ray_mesh_intersection::benches);
+criterion_main!(pointer_events::benches, ray_mesh_intersection::benches);
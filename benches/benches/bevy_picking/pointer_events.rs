@@ -0,0 +1,119 @@
+use core::hint::black_box;
+
+use benches::bench;
+use bevy_app::App;
+use bevy_camera::{Camera, NormalizedRenderTarget};
+use bevy_ecs::{observer::On, system::RunSystemOnce};
+use bevy_math::Vec2;
+use bevy_picking::{
+    backend::HitData,
+    events::{pointer_events, Move, Over, Pointer, PointerEventKind, PointerEventMask, Press},
+    hover::{HoverMap, PreviousHoverMap},
+    pointer::{
+        update_pointer_map, Location, PointerAction, PointerButton, PointerId, PointerInput,
+        PointerLocation, PointerMap,
+    },
+    InteractionPlugin,
+};
+use bevy_platform::collections::HashMap;
+use criterion::{criterion_group, BenchmarkId, Criterion};
+
+criterion_group!(benches, multi_event_frame, masked_event_frame);
+
+fn location(position: Vec2) -> Location {
+    Location {
+        target: NormalizedRenderTarget::None {
+            width: 800,
+            height: 600,
+        },
+        position,
+    }
+}
+
+/// Creates an app where the mouse starts hovering `entities` entities, each observing the events
+/// of a typical frame, and holding `mask` if any.
+fn setup(entities: usize, mask: Option<PointerEventMask>) -> App {
+    let mut app = App::new();
+    app.add_plugins(InteractionPlugin)
+        .add_message::<PointerInput>()
+        .init_resource::<PointerMap>();
+    let world = app.world_mut();
+    world.spawn((PointerId::Mouse, PointerLocation::new(location(Vec2::ZERO))));
+    world.run_system_once(update_pointer_map).unwrap();
+
+    let camera = world.spawn(Camera::default()).id();
+    let mut hits = HashMap::default();
+    for _ in 0..entities {
+        let mut entity = world.spawn_empty();
+        entity
+            .observe(|event: On<Pointer<Over>>| {
+                black_box(event.entity);
+            })
+            .observe(|event: On<Pointer<Press>>| {
+                black_box(event.entity);
+            })
+            .observe(|event: On<Pointer<Move>>| {
+                black_box(event.entity);
+            });
+        if let Some(mask) = mask {
+            entity.insert(mask);
+        }
+        hits.insert(entity.id(), HitData::new(camera, 0.0, None, None));
+    }
+    world
+        .resource_mut::<HoverMap>()
+        .insert(PointerId::Mouse, hits);
+    app
+}
+
+/// Emits `Over`, `Press`, and several `Move` events on every hovered entity in a single frame.
+fn multi_event_frame(c: &mut Criterion) {
+    event_frame(c, bench!("multi_event_frame"), None);
+}
+
+/// Emits the same events as [`multi_event_frame`] on entities whose [`PointerEventMask`] only
+/// wants `Click` events, so that none of them are triggered.
+fn masked_event_frame(c: &mut Criterion) {
+    let mask = PointerEventMask::new([PointerEventKind::Click]);
+    event_frame(c, bench!("masked_event_frame"), Some(mask));
+}
+
+fn event_frame(c: &mut Criterion, name: &str, mask: Option<PointerEventMask>) {
+    let mut group = c.benchmark_group(name);
+
+    for entities in [1, 10, 100] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(entities),
+            &entities,
+            |b, &entities| {
+                b.iter_batched_ref(
+                    || {
+                        let mut app = setup(entities, mask);
+                        let world = app.world_mut();
+                        world.resource_mut::<PreviousHoverMap>().clear();
+                        world.write_message(PointerInput::new(
+                            PointerId::Mouse,
+                            location(Vec2::ZERO),
+                            PointerAction::Press(PointerButton::Primary),
+                        ));
+                        for i in 1..=4 {
+                            let delta = Vec2::splat(i as f32);
+                            world.write_message(PointerInput::new(
+                                PointerId::Mouse,
+                                location(delta),
+                                PointerAction::Move { delta },
+                            ));
+                        }
+                        app
+                    },
+                    |app| {
+                        app.world_mut().run_system_once(pointer_events).unwrap();
+                    },
+                    criterion::BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+
+    group.finish();
+}
@@ -1,4 +1,6 @@
-use crate::core_2d::Opaque2d;
+use crate::core_2d::{
+    main_pass_2d_color_attachments, ExtraColorTargets2d, Opaque2d, ViewExtraColorAttachments2d,
+};
 use bevy_ecs::prelude::*;
 use bevy_render::{
     camera::ExtractedCamera,
@@ -21,13 +23,22 @@ pub fn main_opaque_pass_2d(
         &ExtractedView,
         &ViewTarget,
         &ViewDepthTexture,
+        Option<&ViewExtraColorAttachments2d>,
+        Has<ExtraColorTargets2d>,
     )>,
     opaque_phases: Res<ViewBinnedRenderPhases<Opaque2d>>,
     alpha_mask_phases: Res<ViewBinnedRenderPhases<AlphaMask2d>>,
     mut ctx: RenderContext,
 ) {
     let view_entity = view.entity();
-    let (camera, extracted_view, target, depth) = view.into_inner();
+    let (camera, extracted_view, target, depth, extra_color_attachments, has_extra_color_targets) =
+        view.into_inner();
+
+    // The pipelines of the view write to all of its extra color targets, so it isn't rendered
+    // until every one of them is loaded.
+    if has_extra_color_targets && extra_color_attachments.is_none() {
+        return;
+    }
 
     let (Some(opaque_phase), Some(alpha_mask_phase)) = (
         opaque_phases.get(&extracted_view.retained_view_entity),
@@ -46,7 +57,7 @@ pub fn main_opaque_pass_2d(
     let diagnostics = ctx.diagnostic_recorder();
     let diagnostics = diagnostics.as_deref();
 
-    let color_attachments = [Some(target.get_color_attachment())];
+    let color_attachments = main_pass_2d_color_attachments(target, extra_color_attachments);
     let depth_stencil_attachment = Some(depth.get_attachment(StoreOp::Store));
 
     let mut render_pass = ctx.begin_tracked_render_pass(RenderPassDescriptor {
@@ -1,4 +1,6 @@
-use crate::core_2d::Transparent2d;
+use crate::core_2d::{
+    main_pass_2d_color_attachments, ExtraColorTargets2d, Transparent2d, ViewExtraColorAttachments2d,
+};
 use bevy_ecs::prelude::*;
 use bevy_render::{
     camera::ExtractedCamera,
@@ -19,12 +21,21 @@ pub fn main_transparent_pass_2d(
         &ExtractedView,
         &ViewTarget,
         &ViewDepthTexture,
+        Option<&ViewExtraColorAttachments2d>,
+        Has<ExtraColorTargets2d>,
     )>,
     transparent_phases: Res<ViewSortedRenderPhases<Transparent2d>>,
     mut ctx: RenderContext,
 ) {
     let view_entity = view.entity();
-    let (camera, extracted_view, target, depth) = view.into_inner();
+    let (camera, extracted_view, target, depth, extra_color_attachments, has_extra_color_targets) =
+        view.into_inner();
+
+    // The pipelines of the view write to all of its extra color targets, so it isn't rendered
+    // until every one of them is loaded.
+    if has_extra_color_targets && extra_color_attachments.is_none() {
+        return;
+    }
 
     let Some(transparent_phase) = transparent_phases.get(&extracted_view.retained_view_entity)
     else {
@@ -37,7 +48,7 @@ pub fn main_transparent_pass_2d(
     let diagnostics = ctx.diagnostic_recorder();
     let diagnostics = diagnostics.as_deref();
 
-    let color_attachments = [Some(target.get_color_attachment())];
+    let color_attachments = main_pass_2d_color_attachments(target, extra_color_attachments);
     // NOTE: For the transparent pass we load the depth buffer. There should be no
     // need to write to it, but store is set to `true` as a workaround for issue #3776,
     // https://github.com/bevyengine/bevy/issues/3776
@@ -1,11 +1,13 @@
 mod main_opaque_pass_2d_node;
 mod main_transparent_pass_2d_node;
+mod prepass_2d;
 
 use core::ops::Range;
 
-use bevy_asset::UntypedAssetId;
+use bevy_asset::{Handle, UntypedAssetId};
 use bevy_camera::{Camera, Camera2d};
-use bevy_image::ToExtents;
+use bevy_color::LinearRgba;
+use bevy_image::{Image, ToExtents};
 use bevy_platform::collections::{HashMap, HashSet};
 use bevy_render::{
     batching::gpu_preprocessing::GpuPreprocessingMode,
@@ -15,6 +17,7 @@ use bevy_render::{
 };
 pub use main_opaque_pass_2d_node::*;
 pub use main_transparent_pass_2d_node::*;
+pub use prepass_2d::*;
 
 use crate::schedule::Core2d;
 use crate::tonemapping::{tonemapping, DebandDither, Tonemapping};
@@ -25,20 +28,21 @@ use bevy_ecs::prelude::*;
 use bevy_math::FloatOrd;
 use bevy_render::{
     camera::ExtractedCamera,
-    extract_component::ExtractComponentPlugin,
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_asset::RenderAssets,
     render_phase::{
         sort_phase_system, BinnedPhaseItem, CachedRenderPipelinePhaseItem, DrawFunctionId,
         DrawFunctions, PhaseItem, PhaseItemExtraIndex, SortedPhaseItem, ViewBinnedRenderPhases,
         ViewSortedRenderPhases,
     },
     render_resource::{
-        BindGroupId, CachedRenderPipelineId, TextureDescriptor, TextureDimension, TextureFormat,
-        TextureUsages,
+        BindGroupId, CachedRenderPipelineId, RenderPassColorAttachment, TextureDescriptor,
+        TextureDimension, TextureFormat, TextureUsages,
     },
     renderer::RenderDevice,
     sync_world::MainEntity,
-    texture::TextureCache,
-    view::{Msaa, ViewDepthTexture},
+    texture::{CachedTexture, ColorAttachment, GpuImage, TextureCache},
+    view::{Msaa, ViewDepthTexture, ViewTarget},
     Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
 };
 
@@ -53,7 +57,10 @@ impl Plugin for Core2dPlugin {
                 CameraRenderGraph::new(Core2d)
             })
             .register_required_components_with::<Camera2d, Tonemapping>(|| Tonemapping::None)
-            .add_plugins(ExtractComponentPlugin::<Camera2d>::default());
+            .add_plugins((
+                ExtractComponentPlugin::<Camera2d>::default(),
+                ExtractComponentPlugin::<ExtraColorTargets2d>::default(),
+            ));
 
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
@@ -71,6 +78,7 @@ impl Plugin for Core2dPlugin {
                 (
                     sort_phase_system::<Transparent2d>.in_set(RenderSystems::PhaseSort),
                     prepare_core_2d_depth_textures.in_set(RenderSystems::PrepareResources),
+                    prepare_core_2d_extra_color_attachments.in_set(RenderSystems::PrepareResources),
                 ),
             )
             .add_schedule(Core2d::base_schedule())
@@ -454,3 +462,76 @@ pub fn prepare_core_2d_depth_textures(
             .insert(ViewDepthTexture::new(cached_texture, Some(0.0)));
     }
 }
+
+/// Images bound as additional color attachments, after the view target, by the main 2D passes of
+/// a [`Camera2d`].
+///
+/// This lets materials write to multiple render targets. Every pipeline drawn by the camera must
+/// write to all of its color attachments, so this is meant for cameras dedicated to such
+/// materials, usually restricted with `RenderLayers`. Up to three images are supported.
+///
+/// The main 2D passes skip the camera until all of the images are loaded. The images are cleared
+/// to transparent black each frame. They need the [`TextureUsages::RENDER_ATTACHMENT`] usage and
+/// the sample count of the camera, so the camera should use [`Msaa::Off`].
+#[derive(Component, ExtractComponent, Clone, Default, Debug)]
+pub struct ExtraColorTargets2d(pub Vec<Handle<Image>>);
+
+/// The color attachments of the [`ExtraColorTargets2d`] of a view, bound by the main 2D passes
+/// after the view target.
+#[derive(Component)]
+pub struct ViewExtraColorAttachments2d(pub Vec<ColorAttachment>);
+
+/// The color attachments of the main 2D passes: the view target, followed by the
+/// [`ViewExtraColorAttachments2d`] of the view.
+pub(crate) fn main_pass_2d_color_attachments<'a>(
+    target: &'a ViewTarget,
+    extra_color_attachments: Option<&'a ViewExtraColorAttachments2d>,
+) -> Vec<Option<RenderPassColorAttachment<'a>>> {
+    core::iter::once(target.get_color_attachment())
+        .chain(
+            extra_color_attachments
+                .into_iter()
+                .flat_map(|attachments| attachments.0.iter().map(ColorAttachment::get_attachment)),
+        )
+        .map(Some)
+        .collect()
+}
+
+pub fn prepare_core_2d_extra_color_attachments(
+    mut commands: Commands,
+    images: Res<RenderAssets<GpuImage>>,
+    views_2d: Query<(Entity, Option<&ExtraColorTargets2d>), With<Camera2d>>,
+) {
+    for (view, extra_color_targets) in &views_2d {
+        let Some(extra_color_targets) = extra_color_targets else {
+            commands
+                .entity(view)
+                .remove::<ViewExtraColorAttachments2d>();
+            continue;
+        };
+        // The main passes skip the view until all of its targets are loaded, rather than binding
+        // fewer attachments than its pipelines write.
+        let Some(attachments) = extra_color_targets
+            .0
+            .iter()
+            .map(|image| {
+                images.get(image).map(|image| {
+                    let texture = CachedTexture {
+                        texture: image.texture.clone(),
+                        default_view: image.texture_view.clone(),
+                    };
+                    ColorAttachment::new(texture, None, None, Some(LinearRgba::NONE))
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            commands
+                .entity(view)
+                .remove::<ViewExtraColorAttachments2d>();
+            continue;
+        };
+        commands
+            .entity(view)
+            .insert(ViewExtraColorAttachments2d(attachments));
+    }
+}
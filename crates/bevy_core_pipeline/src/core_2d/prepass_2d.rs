@@ -0,0 +1,315 @@
+use core::ops::Range;
+
+use bevy_app::{App, Plugin};
+use bevy_camera::{Camera, Camera2d};
+use bevy_color::LinearRgba;
+use bevy_ecs::prelude::*;
+use bevy_image::ToExtents;
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_render::{
+    batching::gpu_preprocessing::GpuPreprocessingMode,
+    camera::ExtractedCamera,
+    diagnostic::RecordDiagnostics,
+    render_phase::{
+        BinnedPhaseItem, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions, PhaseItem,
+        PhaseItemExtraIndex, ViewBinnedRenderPhases,
+    },
+    render_resource::{
+        CachedRenderPipelineId, RenderPassDescriptor, StoreOp, TextureDescriptor, TextureDimension,
+        TextureUsages,
+    },
+    renderer::{RenderContext, RenderDevice, ViewQuery},
+    sync_world::{MainEntity, RenderEntity},
+    texture::{ColorAttachment, TextureCache},
+    view::{ExtractedView, Msaa, RetainedViewEntity, ViewDepthTexture},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
+};
+use tracing::error;
+#[cfg(feature = "trace")]
+use tracing::info_span;
+
+use super::{main_opaque_pass_2d, BatchSetKey2d, Opaque2dBinKey};
+use crate::prepass::{MotionVectorPrepass, ViewPrepassTextures, MOTION_VECTOR_PREPASS_FORMAT};
+use crate::schedule::Core2d;
+use crate::Core2dSystems;
+
+/// Adds a depth-only prepass to every active 2D camera.
+///
+/// The prepass draws the [`Prepass2d`] phase into the camera's [`ViewDepthTexture`] before the
+/// main opaque pass runs, so the main pass can reject occluded fragments before shading them.
+/// Phase items are queued by the material that renders them, the same way [`Opaque2d`] items
+/// are.
+///
+/// Cameras with a [`MotionVectorPrepass`] also get a motion vector texture, bound as the only
+/// color attachment of the prepass and exposed to later passes through [`ViewPrepassTextures`].
+///
+/// [`Opaque2d`]: super::Opaque2d
+pub struct Prepass2dPlugin;
+
+impl Plugin for Prepass2dPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<DrawFunctions<Prepass2d>>()
+            .init_resource::<ViewBinnedRenderPhases<Prepass2d>>()
+            .add_systems(ExtractSchedule, extract_core_2d_prepass_phases)
+            .add_systems(
+                Render,
+                prepare_core_2d_prepass_textures.in_set(RenderSystems::PrepareResources),
+            )
+            .add_systems(
+                Core2d,
+                prepass_2d
+                    .before(main_opaque_pass_2d)
+                    .in_set(Core2dSystems::MainPass),
+            );
+    }
+}
+
+/// Opaque 2D [`BinnedPhaseItem`]s drawn by the depth prepass.
+pub struct Prepass2d {
+    /// Determines which objects can be placed into a *batch set*.
+    ///
+    /// Objects in a single batch set can potentially be multi-drawn together,
+    /// if it's enabled and the current platform supports it.
+    pub batch_set_key: BatchSetKey2d,
+    /// The key, which determines which can be batched.
+    pub bin_key: Opaque2dBinKey,
+    /// An entity from which data will be fetched, including the mesh if
+    /// applicable.
+    pub representative_entity: (Entity, MainEntity),
+    /// The ranges of instances.
+    pub batch_range: Range<u32>,
+    /// An extra index, which is either a dynamic offset or an index in the
+    /// indirect parameters list.
+    pub extra_index: PhaseItemExtraIndex,
+}
+
+impl PhaseItem for Prepass2d {
+    #[inline]
+    fn entity(&self) -> Entity {
+        self.representative_entity.0
+    }
+
+    #[inline]
+    fn main_entity(&self) -> MainEntity {
+        self.representative_entity.1
+    }
+
+    #[inline]
+    fn draw_function(&self) -> DrawFunctionId {
+        self.bin_key.draw_function
+    }
+
+    #[inline]
+    fn batch_range(&self) -> &Range<u32> {
+        &self.batch_range
+    }
+
+    #[inline]
+    fn batch_range_mut(&mut self) -> &mut Range<u32> {
+        &mut self.batch_range
+    }
+
+    fn extra_index(&self) -> PhaseItemExtraIndex {
+        self.extra_index.clone()
+    }
+
+    fn batch_range_and_extra_index_mut(&mut self) -> (&mut Range<u32>, &mut PhaseItemExtraIndex) {
+        (&mut self.batch_range, &mut self.extra_index)
+    }
+}
+
+impl BinnedPhaseItem for Prepass2d {
+    // Since 2D meshes presently can't be multidrawn, the batch set key is
+    // irrelevant.
+    type BatchSetKey = BatchSetKey2d;
+
+    type BinKey = Opaque2dBinKey;
+
+    fn new(
+        batch_set_key: Self::BatchSetKey,
+        bin_key: Self::BinKey,
+        representative_entity: (Entity, MainEntity),
+        batch_range: Range<u32>,
+        extra_index: PhaseItemExtraIndex,
+    ) -> Self {
+        Prepass2d {
+            batch_set_key,
+            bin_key,
+            representative_entity,
+            batch_range,
+            extra_index,
+        }
+    }
+}
+
+impl CachedRenderPipelinePhaseItem for Prepass2d {
+    #[inline]
+    fn cached_pipeline(&self) -> CachedRenderPipelineId {
+        self.bin_key.pipeline
+    }
+}
+
+pub fn extract_core_2d_prepass_phases(
+    mut commands: Commands,
+    mut prepass_2d_phases: ResMut<ViewBinnedRenderPhases<Prepass2d>>,
+    cameras_2d: Extract<
+        Query<(Entity, RenderEntity, &Camera, Has<MotionVectorPrepass>), With<Camera2d>>,
+    >,
+    mut live_entities: Local<HashSet<RetainedViewEntity>>,
+) {
+    live_entities.clear();
+
+    for (main_entity, entity, camera, motion_vector_prepass) in &cameras_2d {
+        if !camera.is_active {
+            continue;
+        }
+
+        // This is the main 2D camera, so we use the first subview index (0).
+        let retained_view_entity = RetainedViewEntity::new(main_entity.into(), None, 0);
+
+        prepass_2d_phases.prepare_for_new_frame(retained_view_entity, GpuPreprocessingMode::None);
+
+        live_entities.insert(retained_view_entity);
+
+        let mut camera_commands = commands
+            .get_entity(entity)
+            .expect("Camera entity wasn't synced.");
+
+        if motion_vector_prepass {
+            camera_commands.insert(MotionVectorPrepass);
+        } else {
+            camera_commands.remove::<MotionVectorPrepass>();
+        }
+    }
+
+    // Clear out all dead views.
+    prepass_2d_phases.retain(|camera_entity, _| live_entities.contains(camera_entity));
+}
+
+/// Creates the motion vector texture of the 2D cameras with a [`MotionVectorPrepass`].
+pub fn prepare_core_2d_prepass_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    prepass_2d_phases: Res<ViewBinnedRenderPhases<Prepass2d>>,
+    views_2d: Query<
+        (
+            Entity,
+            &ExtractedCamera,
+            &ExtractedView,
+            &Msaa,
+            Has<MotionVectorPrepass>,
+        ),
+        With<Camera2d>,
+    >,
+) {
+    let mut motion_vectors_textures = <HashMap<_, _>>::default();
+    for (entity, camera, view, msaa, motion_vector_prepass) in &views_2d {
+        if !motion_vector_prepass || !prepass_2d_phases.contains_key(&view.retained_view_entity) {
+            commands.entity(entity).remove::<ViewPrepassTextures>();
+            continue;
+        }
+
+        let Some(physical_target_size) = camera.physical_target_size else {
+            continue;
+        };
+
+        let size = physical_target_size.to_extents();
+
+        let cached_motion_vectors_texture = motion_vectors_textures
+            .entry(camera.target.clone())
+            .or_insert_with(|| {
+                texture_cache.get(
+                    &render_device,
+                    TextureDescriptor {
+                        label: Some("prepass_2d_motion_vectors_texture"),
+                        size,
+                        mip_level_count: 1,
+                        sample_count: msaa.samples(),
+                        dimension: TextureDimension::D2,
+                        format: MOTION_VECTOR_PREPASS_FORMAT,
+                        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    },
+                )
+            })
+            .clone();
+
+        commands.entity(entity).insert(ViewPrepassTextures {
+            depth: None,
+            normal: None,
+            // Cleared to zero, so that pixels without an opaque mesh have no motion.
+            motion_vectors: Some(ColorAttachment::new(
+                cached_motion_vectors_texture,
+                None,
+                None,
+                Some(LinearRgba::BLACK),
+            )),
+            deferred: None,
+            deferred_lighting_pass_id: None,
+            size,
+        });
+    }
+}
+
+pub fn prepass_2d(
+    world: &World,
+    view: ViewQuery<(
+        &ExtractedCamera,
+        &ExtractedView,
+        &ViewDepthTexture,
+        Option<&ViewPrepassTextures>,
+    )>,
+    prepass_phases: Res<ViewBinnedRenderPhases<Prepass2d>>,
+    mut ctx: RenderContext,
+) {
+    let view_entity = view.entity();
+    let (camera, extracted_view, depth, prepass_textures) = view.into_inner();
+
+    let Some(prepass_phase) = prepass_phases.get(&extracted_view.retained_view_entity) else {
+        return;
+    };
+
+    let motion_vectors = prepass_textures.and_then(|textures| textures.motion_vectors.as_ref());
+
+    // The motion vector texture is still cleared when nothing is drawn.
+    if prepass_phase.is_empty() && motion_vectors.is_none() {
+        return;
+    }
+
+    #[cfg(feature = "trace")]
+    let _span = info_span!("prepass_2d").entered();
+
+    let diagnostics = ctx.diagnostic_recorder();
+    let diagnostics = diagnostics.as_deref();
+
+    let depth_stencil_attachment = Some(depth.get_attachment(StoreOp::Store));
+    let color_attachments: Vec<_> = motion_vectors
+        .map(|motion_vectors| Some(motion_vectors.get_attachment()))
+        .into_iter()
+        .collect();
+
+    let mut render_pass = ctx.begin_tracked_render_pass(RenderPassDescriptor {
+        label: Some("prepass_2d"),
+        color_attachments: &color_attachments,
+        depth_stencil_attachment,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+        multiview_mask: None,
+    });
+    let pass_span = diagnostics.pass_span(&mut render_pass, "prepass_2d");
+
+    if let Some(viewport) = camera.viewport.as_ref() {
+        render_pass.set_camera_viewport(viewport);
+    }
+
+    if let Err(err) = prepass_phase.render(&mut render_pass, world, view_entity) {
+        error!("Error encountered while rendering the 2d prepass phase {err:?}");
+    }
+
+    pass_span.end(&mut render_pass);
+}
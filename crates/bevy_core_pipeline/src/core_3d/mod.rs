@@ -719,18 +719,22 @@ pub fn prepare_prepass_textures(
     alpha_mask_3d_prepass_phases: Res<ViewBinnedRenderPhases<AlphaMask3dPrepass>>,
     opaque_3d_deferred_phases: Res<ViewBinnedRenderPhases<Opaque3dDeferred>>,
     alpha_mask_3d_deferred_phases: Res<ViewBinnedRenderPhases<AlphaMask3dDeferred>>,
-    views_3d: Query<(
-        Entity,
-        &ExtractedCamera,
-        &ExtractedView,
-        &Msaa,
-        Has<DepthPrepass>,
-        Has<NormalPrepass>,
-        Has<MotionVectorPrepass>,
-        Has<DeferredPrepass>,
-        Has<DepthPrepassDoubleBuffer>,
-        Has<DeferredPrepassDoubleBuffer>,
-    )>,
+    views_3d: Query<
+        (
+            Entity,
+            &ExtractedCamera,
+            &ExtractedView,
+            &Msaa,
+            Has<DepthPrepass>,
+            Has<NormalPrepass>,
+            Has<MotionVectorPrepass>,
+            Has<DeferredPrepass>,
+            Has<DepthPrepassDoubleBuffer>,
+            Has<DeferredPrepassDoubleBuffer>,
+        ),
+        // 2D cameras prepare their own prepass textures.
+        With<Camera3d>,
+    >,
 ) {
     let mut depth_textures1 = <HashMap<_, _>>::default();
     let mut depth_textures2 = <HashMap<_, _>>::default();
@@ -67,6 +67,10 @@ pub struct NormalPrepass;
 ///
 /// Motion vectors are stored in the range -1,1, with +x right and +y down.
 /// A value of (1.0,1.0) indicates a pixel moved from the top left corner to the bottom right corner of the screen.
+///
+/// It can also be added to a [`bevy_camera::Camera2d`] when the
+/// [`Prepass2dPlugin`](crate::core_2d::Prepass2dPlugin) is added, in which case the 2D prepass
+/// writes the motion vectors of the opaque materials that opt in.
 #[derive(Component, Default, Reflect, Clone)]
 #[reflect(Component, Default, Clone)]
 pub struct MotionVectorPrepass;
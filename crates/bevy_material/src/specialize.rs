@@ -1,8 +1,10 @@
+use alloc::vec::Vec;
 use bevy_ecs::world::World;
 use bevy_mesh::{MeshVertexBufferLayoutRef, MissingVertexAttributeError};
 use bevy_platform::sync::Arc;
 use core::any::Any;
 use thiserror::Error;
+use wgpu_types::TextureFormat;
 
 use crate::{
     descriptor::{CachedRenderPipelineId, RenderPipelineDescriptor},
@@ -50,4 +52,48 @@ pub type UserSpecializeFn = fn(
 pub enum SpecializedMeshPipelineError {
     #[error(transparent)]
     MissingVertexAttribute(#[from] MissingVertexAttributeError),
+    #[error(transparent)]
+    ColorTargetFormatMismatch(#[from] ColorTargetFormatMismatchError),
+    #[error(transparent)]
+    ColorTargetCountMismatch(#[from] ColorTargetCountMismatchError),
+    #[error(transparent)]
+    StorageBuffersUnsupported(#[from] StorageBuffersUnsupportedError),
+}
+
+#[derive(Error, Debug)]
+#[error("Material expects color target formats {expected:?}, but the view provides {actual:?} (pipeline type: {pipeline_type:?})")]
+pub struct ColorTargetFormatMismatchError {
+    pub pipeline_type: Option<&'static str>,
+    /// The color target formats declared by the material.
+    pub expected: Vec<TextureFormat>,
+    /// The color target formats of the pipeline being specialized.
+    pub actual: Vec<Option<TextureFormat>>,
+}
+
+#[derive(Error, Debug)]
+#[error("Material writes {expected} color targets, but the view binds {actual} color attachments (pipeline type: {pipeline_type:?})")]
+pub struct ColorTargetCountMismatchError {
+    pub pipeline_type: Option<&'static str>,
+    /// The number of color targets declared by the material.
+    pub expected: usize,
+    /// The number of color attachments bound by the view's render passes.
+    pub actual: usize,
+}
+
+#[derive(Error, Debug)]
+#[error("Material binds a storage buffer at binding {binding}, but the render device does not support storage buffers (pipeline type: {pipeline_type:?})")]
+pub struct StorageBuffersUnsupportedError {
+    pub pipeline_type: Option<&'static str>,
+    /// The binding index of the first storage buffer in the material's bind group layout.
+    pub binding: u32,
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[error("Material pipeline was specialized for depth format {expected:?}, but the view's depth attachment is {actual:?} (pipeline type: {pipeline_type:?})")]
+pub struct DepthFormatMismatchError {
+    pub pipeline_type: Option<&'static str>,
+    /// The depth format of the specialized pipeline, or `None` if it has no depth-stencil state.
+    pub expected: Option<TextureFormat>,
+    /// The format of the view's depth attachment.
+    pub actual: TextureFormat,
 }
@@ -0,0 +1,220 @@
+//! Forwards [`Pointer`] events to async code, so that tasks can `await` interactions instead of
+//! observing them.
+//!
+//! Add a [`PointerEventStreamPlugin`] for each event type to forward, and subscribe to the events
+//! with the [`PointerEventStreams`] resource. Each frame, after [`PickingSystems::Hover`], the events
+//! written by [`pointer_events`](crate::events::pointer_events) are sent to every subscribed
+//! [`PointerEventStream`].
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::{async_events::PointerEventStreams, events::Click};
+//! fn wait_for_click(mut streams: ResMut<PointerEventStreams<Click>>, mut commands: Commands) {
+//!     let button = commands.spawn_empty().id();
+//!     let clicks = streams.subscribe_entity(button, 4);
+//!     let task = async move {
+//!         while let Some(click) = clicks.next().await {
+//!             println!("{button} was clicked with {:?}", click.button);
+//!         }
+//!     };
+//!     // Spawn `task` on a task pool.
+//! #   drop(task);
+//! }
+//! ```
+//!
+//! This module is only available with the `async_events` feature.
+
+use alloc::vec::Vec;
+use core::{fmt::Debug, marker::PhantomData};
+
+use async_channel::{Receiver, Sender};
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+
+use crate::{events::Pointer, PickingSystems};
+
+/// Forwards the [`Pointer<E>`] events to the streams subscribed through [`PointerEventStreams<E>`].
+///
+/// Requires the [`InteractionPlugin`](crate::InteractionPlugin), which writes the events.
+pub struct PointerEventStreamPlugin<E>(PhantomData<fn() -> E>);
+
+impl<E> Default for PointerEventStreamPlugin<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E: Debug + Clone + Reflect> Plugin for PointerEventStreamPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerEventStreams<E>>().add_systems(
+            PreUpdate,
+            forward_pointer_events::<E>.after(PickingSystems::Hover),
+        );
+    }
+}
+
+/// The subscriptions to the [`Pointer<E>`] events, see [`PointerEventStreamPlugin`].
+#[derive(Resource)]
+pub struct PointerEventStreams<E: Debug + Clone + Reflect> {
+    subscribers: Vec<(Option<Entity>, Sender<Pointer<E>>)>,
+}
+
+impl<E: Debug + Clone + Reflect> Default for PointerEventStreams<E> {
+    fn default() -> Self {
+        Self {
+            subscribers: Vec::new(),
+        }
+    }
+}
+
+impl<E: Debug + Clone + Reflect> PointerEventStreams<E> {
+    /// Subscribes to all the events, on any entity.
+    ///
+    /// The stream buffers up to `capacity` events, and drops the newer ones while it is full.
+    pub fn subscribe(&mut self, capacity: usize) -> PointerEventStream<E> {
+        self.add_subscriber(None, capacity)
+    }
+
+    /// Subscribes to the events triggered on `entity`.
+    ///
+    /// Only events that target `entity` are sent, not the ones that bubble up to it from its
+    /// descendants. The stream buffers up to `capacity` events, and drops the newer ones while it
+    /// is full.
+    pub fn subscribe_entity(&mut self, entity: Entity, capacity: usize) -> PointerEventStream<E> {
+        self.add_subscriber(Some(entity), capacity)
+    }
+
+    /// The number of streams still subscribed.
+    pub fn len(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Returns `true` if no stream is subscribed.
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    fn add_subscriber(&mut self, entity: Option<Entity>, capacity: usize) -> PointerEventStream<E> {
+        let (sender, receiver) = async_channel::bounded(capacity.max(1));
+        self.subscribers.push((entity, sender));
+        PointerEventStream(receiver)
+    }
+}
+
+/// Receives the [`Pointer<E>`] events subscribed to with [`PointerEventStreams<E>`].
+///
+/// Dropping the stream unsubscribes it.
+#[derive(Debug, Clone)]
+pub struct PointerEventStream<E: Debug + Clone + Reflect>(Receiver<Pointer<E>>);
+
+impl<E: Debug + Clone + Reflect> PointerEventStream<E> {
+    /// Waits for the next event.
+    ///
+    /// Returns `None` once the [`PointerEventStreams<E>`] resource is removed, and no events are
+    /// left.
+    pub async fn next(&self) -> Option<Pointer<E>> {
+        self.0.recv().await.ok()
+    }
+
+    /// Returns the next event if one was already received, without waiting.
+    pub fn try_next(&self) -> Option<Pointer<E>> {
+        self.0.try_recv().ok()
+    }
+
+    /// Returns the underlying channel, which also implements `Stream`.
+    pub fn into_receiver(self) -> Receiver<Pointer<E>> {
+        self.0
+    }
+}
+
+/// Sends the [`Pointer<E>`] events written this frame to the subscribed streams, and forgets the
+/// streams that were dropped.
+pub fn forward_pointer_events<E: Debug + Clone + Reflect>(
+    mut events: MessageReader<Pointer<E>>,
+    mut streams: ResMut<PointerEventStreams<E>>,
+) {
+    streams
+        .subscribers
+        .retain(|(_, sender)| !sender.is_closed());
+    if streams.subscribers.is_empty() {
+        events.clear();
+        return;
+    }
+    for event in events.read() {
+        for (entity, sender) in &streams.subscribers {
+            if entity.is_some_and(|entity| entity != event.entity) {
+                continue;
+            }
+            // A full stream drops the event, and a closed one is forgotten next frame.
+            let _ = sender.try_send(event.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use bevy_camera::NormalizedRenderTarget;
+    use bevy_math::Vec2;
+    use bevy_tasks::{block_on, TaskPool};
+
+    use super::*;
+    use crate::{
+        backend::HitData,
+        events::Click,
+        pointer::{Location, PointerButton, PointerId},
+    };
+
+    #[test]
+    fn awaited_click_resolves_once_dispatched() {
+        let mut app = App::new();
+        app.add_message::<Pointer<Click>>()
+            .add_plugins(PointerEventStreamPlugin::<Click>::default());
+        let button = app.world_mut().spawn_empty().id();
+        let other = app.world_mut().spawn_empty().id();
+        let clicks = app
+            .world_mut()
+            .resource_mut::<PointerEventStreams<Click>>()
+            .subscribe_entity(button, 4);
+
+        let pool = TaskPool::new();
+        let task = pool.spawn(async move { clicks.next().await.map(|click| click.entity) });
+        let click = |entity| {
+            Pointer::new(
+                PointerId::Mouse,
+                Location {
+                    target: NormalizedRenderTarget::None {
+                        width: 800,
+                        height: 600,
+                    },
+                    position: Vec2::ZERO,
+                },
+                Click {
+                    button: PointerButton::Primary,
+                    hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+                    duration: Duration::ZERO,
+                    count: 1,
+                },
+                entity,
+            )
+        };
+
+        // Clicks on other entities don't wake the task.
+        app.world_mut().write_message(click(other));
+        app.update();
+        assert!(!task.is_finished());
+
+        app.world_mut().write_message(click(button));
+        app.update();
+        assert_eq!(block_on(task), Some(button));
+
+        // The finished task dropped its stream, which is forgotten.
+        app.update();
+        assert!(app
+            .world()
+            .resource::<PointerEventStreams<Click>>()
+            .is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+//! Accumulates drag events into a polyline, for drawing and gesture applications.
+//!
+//! Add a [`DragPath`] component to an entity and, whenever that entity is dragged, the pointer
+//! positions of its [`DragStart`] and [`Drag`] events are collected. The raw points are available
+//! through [`DragPath::points`], and a smoothed copy through [`DragPath::smoothed`]. The observers
+//! recording the paths are only added once the first [`DragPath`] is.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::{drag_path::*, prelude::*};
+//! # let mut world = World::default();
+//! world
+//!     .spawn(DragPath::new(PathSmoothing::Chaikin { iterations: 2 }))
+//!     .observe(|drag_end: On<Pointer<DragEnd>>, paths: Query<&DragPath>| {
+//!         let path = paths.get(drag_end.entity).unwrap().smoothed();
+//!         println!("Drew a path with {} points", path.len());
+//!     });
+//! ```
+
+use alloc::vec::Vec;
+
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
+use bevy_math::Vec2;
+use bevy_reflect::prelude::*;
+
+use crate::events::{Drag, DragCancel, DragEnd, DragStart, Pointer};
+
+/// How a [`DragPath`] is smoothed by [`DragPath::smoothed`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum PathSmoothing {
+    /// The raw points are returned unchanged.
+    #[default]
+    None,
+    /// Chaikin's corner cutting. Each iteration replaces every segment with two points at a
+    /// quarter and three quarters of its length, while keeping the endpoints fixed.
+    Chaikin {
+        /// The number of times corners are cut.
+        iterations: u32,
+    },
+    /// A Catmull-Rom spline passing through every raw point.
+    CatmullRom {
+        /// The number of points generated for each segment between two raw points.
+        samples_per_segment: u32,
+    },
+}
+
+/// Collects the positions of the drags on this entity into a polyline.
+///
+/// The path is cleared on [`DragStart`], extended on every [`Drag`], and marked finished on
+/// [`DragEnd`]. A [`DragCancel`] clears it again, since the drag it recorded was aborted. The
+/// points are in the pointer's render target space, like
+/// [`Location::position`](crate::pointer::Location::position).
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component, Debug, Default, Clone)]
+#[component(on_add = add_drag_path_observers)]
+pub struct DragPath {
+    /// How the path returned by [`DragPath::smoothed`] is smoothed.
+    pub smoothing: PathSmoothing,
+    points: Vec<Vec2>,
+    is_dragging: bool,
+}
+
+impl DragPath {
+    /// Creates an empty path with the given smoothing.
+    pub fn new(smoothing: PathSmoothing) -> Self {
+        Self {
+            smoothing,
+            ..Default::default()
+        }
+    }
+
+    /// The positions recorded during the current or most recent drag.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Returns `true` while a drag is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.is_dragging
+    }
+
+    /// Appends a point to the path.
+    pub fn push(&mut self, point: Vec2) {
+        if self.points.last() != Some(&point) {
+            self.points.push(point);
+        }
+    }
+
+    /// Removes all recorded points.
+    pub fn reset(&mut self) {
+        self.points.clear();
+    }
+
+    /// Returns the recorded points, smoothed according to [`DragPath::smoothing`].
+    pub fn smoothed(&self) -> Vec<Vec2> {
+        match self.smoothing {
+            PathSmoothing::None => self.points.clone(),
+            PathSmoothing::Chaikin { iterations } => chaikin(&self.points, iterations),
+            PathSmoothing::CatmullRom {
+                samples_per_segment,
+            } => catmull_rom(&self.points, samples_per_segment),
+        }
+    }
+
+    fn on_drag_start(drag_start: On<Pointer<DragStart>>, mut paths: Query<&mut DragPath>) {
+        if drag_start.entity != drag_start.original_event_target() {
+            return;
+        }
+        if let Ok(mut path) = paths.get_mut(drag_start.entity) {
+            path.reset();
+            path.is_dragging = true;
+            path.push(drag_start.pointer_location.position);
+        }
+    }
+
+    fn on_drag(drag: On<Pointer<Drag>>, mut paths: Query<&mut DragPath>) {
+        if drag.entity != drag.original_event_target() {
+            return;
+        }
+        if let Ok(mut path) = paths.get_mut(drag.entity) {
+            path.push(drag.pointer_location.position);
+        }
+    }
+
+    fn on_drag_end(drag_end: On<Pointer<DragEnd>>, mut paths: Query<&mut DragPath>) {
+        if drag_end.entity != drag_end.original_event_target() {
+            return;
+        }
+        if let Ok(mut path) = paths.get_mut(drag_end.entity) {
+            path.is_dragging = false;
+        }
+    }
+
+    fn on_drag_cancel(drag_cancel: On<Pointer<DragCancel>>, mut paths: Query<&mut DragPath>) {
+        if drag_cancel.entity != drag_cancel.original_event_target() {
+            return;
+        }
+        if let Ok(mut path) = paths.get_mut(drag_cancel.entity) {
+            path.reset();
+            path.is_dragging = false;
+        }
+    }
+}
+
+/// Marks the worlds the [`DragPath`] observers were added to.
+#[derive(Resource)]
+struct DragPathObservers;
+
+/// Adds the observers recording the [`DragPath`]s when the first one is added to the world.
+fn add_drag_path_observers(mut world: DeferredWorld, _: HookContext) {
+    if world.contains_resource::<DragPathObservers>() {
+        return;
+    }
+    world.commands().queue(|world: &mut World| {
+        // Several paths may have been added before this command is applied.
+        if world.contains_resource::<DragPathObservers>() {
+            return;
+        }
+        world.insert_resource(DragPathObservers);
+        world.add_observer(DragPath::on_drag_start);
+        world.add_observer(DragPath::on_drag);
+        world.add_observer(DragPath::on_drag_end);
+        world.add_observer(DragPath::on_drag_cancel);
+    });
+}
+
+fn chaikin(points: &[Vec2], iterations: u32) -> Vec<Vec2> {
+    let mut path = points.to_vec();
+    for _ in 0..iterations {
+        if path.len() < 3 {
+            break;
+        }
+        let mut next = Vec::with_capacity(path.len() * 2);
+        next.push(path[0]);
+        for segment in path.windows(2) {
+            next.push(segment[0].lerp(segment[1], 0.25));
+            next.push(segment[0].lerp(segment[1], 0.75));
+        }
+        next.push(path[path.len() - 1]);
+        path = next;
+    }
+    path
+}
+
+fn catmull_rom(points: &[Vec2], samples_per_segment: u32) -> Vec<Vec2> {
+    if points.len() < 3 || samples_per_segment == 0 {
+        return points.to_vec();
+    }
+    let last = points.len() - 1;
+    let mut path = Vec::with_capacity(last * samples_per_segment as usize + 1);
+    for i in 0..last {
+        let p0 = points[i.saturating_sub(1)];
+        let p1 = points[i];
+        let p2 = points[i + 1];
+        let p3 = points[(i + 2).min(last)];
+        for sample in 0..samples_per_segment {
+            let t = sample as f32 / samples_per_segment as f32;
+            let t2 = t * t;
+            let t3 = t2 * t;
+            path.push(
+                0.5 * ((2.0 * p1)
+                    + (p2 - p0) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+            );
+        }
+    }
+    path.push(points[last]);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::NormalizedRenderTarget;
+
+    use super::*;
+    use crate::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    };
+
+    fn zigzag() -> Vec<Vec2> {
+        (0..9)
+            .map(|i| Vec2::new(i as f32 * 10.0, if i % 2 == 0 { 0.0 } else { 10.0 }))
+            .collect()
+    }
+
+    /// The largest change in direction between two consecutive segments, in radians.
+    fn max_turn(path: &[Vec2]) -> f32 {
+        path.windows(3)
+            .map(|p| (p[1] - p[0]).angle_to(p[2] - p[1]).abs())
+            .fold(0.0, f32::max)
+    }
+
+    fn path(smoothing: PathSmoothing) -> DragPath {
+        let mut path = DragPath::new(smoothing);
+        for point in zigzag() {
+            path.push(point);
+        }
+        path
+    }
+
+    #[test]
+    fn smoothing_reduces_curvature_and_keeps_endpoints() {
+        let raw = zigzag();
+        for smoothing in [
+            PathSmoothing::Chaikin { iterations: 3 },
+            PathSmoothing::CatmullRom {
+                samples_per_segment: 8,
+            },
+        ] {
+            let smoothed = path(smoothing).smoothed();
+            assert!(smoothed.len() > raw.len(), "{smoothing:?}");
+            assert_eq!(smoothed.first(), raw.first(), "{smoothing:?}");
+            assert_eq!(smoothed.last(), raw.last(), "{smoothing:?}");
+            assert!(
+                max_turn(&smoothed) < max_turn(&raw) * 0.5,
+                "{smoothing:?} turned by {} (raw {})",
+                max_turn(&smoothed),
+                max_turn(&raw),
+            );
+        }
+    }
+
+    #[test]
+    fn drag_events_are_accumulated() {
+        let mut world = World::default();
+        let camera = world.spawn_empty().id();
+        // The observers are only added along with the first path.
+        assert!(!world.contains_resource::<DragPathObservers>());
+        let entity = world.spawn(DragPath::default()).id();
+        world.spawn(DragPath::default());
+        world.flush();
+        assert!(world.contains_resource::<DragPathObservers>());
+
+        let location = |position: Vec2| Location {
+            target: NormalizedRenderTarget::None {
+                width: 100,
+                height: 100,
+            },
+            position,
+        };
+        let button = PointerButton::Primary;
+        let raw = zigzag();
+        world.trigger(Pointer::new(
+            PointerId::Mouse,
+            location(raw[0]),
+            DragStart {
+                button,
+                hit: HitData::new(camera, 0.0, None, None),
+            },
+            entity,
+        ));
+        for pair in raw.windows(2) {
+            world.trigger(Pointer::new(
+                PointerId::Mouse,
+                location(pair[1]),
+                Drag {
+                    button,
+                    distance: pair[1] - raw[0],
+                    delta: pair[1] - pair[0],
+                    velocity: Vec2::ZERO,
+                    world_distance: None,
+                    world_delta: None,
+                },
+                entity,
+            ));
+        }
+        assert!(world.get::<DragPath>(entity).unwrap().is_dragging());
+
+        world.trigger(Pointer::new(
+            PointerId::Mouse,
+            location(raw[raw.len() - 1]),
+            DragEnd {
+                button,
+                distance: raw[raw.len() - 1] - raw[0],
+            },
+            entity,
+        ));
+        let path = world.get::<DragPath>(entity).unwrap();
+        assert!(!path.is_dragging());
+        assert_eq!(path.points(), raw.as_slice());
+    }
+
+    #[test]
+    fn cancelled_drags_clear_the_path() {
+        let mut world = World::default();
+        let camera = world.spawn_empty().id();
+        let entity = world.spawn(DragPath::default()).id();
+        world.flush();
+
+        let location = |position: Vec2| Location {
+            target: NormalizedRenderTarget::None {
+                width: 100,
+                height: 100,
+            },
+            position,
+        };
+        let button = PointerButton::Primary;
+        world.trigger(Pointer::new(
+            PointerId::Mouse,
+            location(Vec2::ZERO),
+            DragStart {
+                button,
+                hit: HitData::new(camera, 0.0, None, None),
+            },
+            entity,
+        ));
+        world.trigger(Pointer::new(
+            PointerId::Mouse,
+            location(Vec2::ONE),
+            Drag {
+                button,
+                distance: Vec2::ONE,
+                delta: Vec2::ONE,
+                velocity: Vec2::ZERO,
+                world_distance: None,
+                world_delta: None,
+            },
+            entity,
+        ));
+        assert!(world.get::<DragPath>(entity).unwrap().is_dragging());
+
+        world.trigger(Pointer::new(
+            PointerId::Mouse,
+            location(Vec2::ONE),
+            DragCancel {
+                button,
+                distance: Vec2::ONE,
+            },
+            entity,
+        ));
+        let path = world.get::<DragPath>(entity).unwrap();
+        assert!(!path.is_dragging());
+        assert!(path.points().is_empty());
+    }
+
+    #[test]
+    fn no_smoothing_returns_raw_points() {
+        assert_eq!(path(PathSmoothing::None).smoothed(), zigzag());
+    }
+}
@@ -30,29 +30,49 @@
 //! # Events Types
 //!
 //! The events this module defines fall into a few broad categories:
-//! + Hovering and movement: [`Over`], [`Move`], and [`Out`].
-//! + Clicking and pressing: [`Press`], [`Release`], and [`Click`].
-//! + Dragging and dropping: [`DragStart`], [`Drag`], [`DragEnd`], [`DragEnter`], [`DragOver`], [`DragDrop`], [`DragLeave`].
+//! + Hovering and movement: [`Over`], [`Move`], and [`Out`], and [`HoverEnter`] and [`HoverExit`]
+//!   for whole subtrees. [`FallbackMove`] reports movement over nothing.
+//! + Clicking and pressing: [`Press`], [`PressRepeat`], [`LongPress`], [`Release`], and [`Click`].
+//! + Dragging and dropping: [`DragStart`], [`Drag`], [`DragEnd`], [`DragCancel`], [`DragEnter`], [`DragOver`], [`DragDrop`], [`DragLeave`].
 //!
 //! When received by an observer, these events will always be wrapped by the [`Pointer`] type, which contains
 //! general metadata about the pointer event.
+//!
+//! # External UIs
+//!
+//! A retained-mode UI that manages its own widgets can use this crate as its input source by
+//! inserting a [`PointerEventAdapter`]. It receives every event before the world does, and can
+//! consume the ones the UI handled so that entities behind the UI don't react to them.
 
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
 use core::{fmt::Debug, time::Duration};
 
-use bevy_camera::NormalizedRenderTarget;
-use bevy_ecs::{prelude::*, query::QueryData, system::SystemParam, traversal::Traversal};
-use bevy_input::mouse::MouseScrollUnit;
-use bevy_math::Vec2;
+use bevy_camera::{Camera, NormalizedRenderTarget};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    entity::{Entities, EntityHashMap, EntityHashSet},
+    event::PropagateEntityTrigger,
+    prelude::*,
+    query::QueryData,
+    system::{Adapt, AdapterSystem, IntoObserverSystem, RunSystemError, SystemIn, SystemParam},
+    traversal::Traversal,
+};
+use bevy_math::{FloatOrd, Vec2};
 use bevy_platform::collections::HashMap;
 use bevy_platform::time::Instant;
 use bevy_reflect::prelude::*;
+use bevy_transform::components::GlobalTransform;
 use bevy_window::Window;
 use tracing::debug;
 
 use crate::{
     backend::{prelude::PointerLocation, HitData},
+    gesture::{Pinch, Rotate},
     hover::{HoverMap, PreviousHoverMap},
-    pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput, PointerMap},
+    pointer::{
+        Location, PointerAction, PointerButton, PointerId, PointerInput, PointerKind, PointerMap,
+        PointerModifiers, ScrollUnit,
+    },
 };
 
 /// Stores the common data needed for all pointer events.
@@ -69,6 +89,16 @@ pub struct Pointer<E: Debug + Clone + Reflect> {
     pub pointer_id: PointerId,
     /// The location of the pointer during this event
     pub pointer_location: Location,
+    /// The modifier keys held when the [`PointerInput`] causing this event was produced.
+    ///
+    /// Events that aren't caused by an input, such as [`Over`] or [`LongPress`], report the
+    /// modifiers of the latest input of the pointer.
+    pub modifiers: PointerModifiers,
+    /// The time of the [`PointerInput`] causing this event, see [`PointerInput::time`].
+    ///
+    /// Events that aren't caused by an input, such as [`Over`] or [`LongPress`], report the time
+    /// [`pointer_events`] ran at, as read from the [`PickingClock`].
+    pub pointer_time: Instant,
     /// Additional event-specific data. [`DragDrop`] for example, has an additional field to describe
     /// the `Entity` that is being dropped on the target.
     pub event: E,
@@ -130,10 +160,17 @@ impl<E: Debug + Clone + Reflect> Pointer<E> {
         Self {
             pointer_id: id,
             pointer_location: location,
+            modifiers: PointerModifiers::default(),
+            pointer_time: Instant::now(),
             event,
             entity,
         }
     }
+
+    /// The kind of the pointer that triggered this event.
+    pub fn pointer_kind(&self) -> PointerKind {
+        self.pointer_id.kind()
+    }
 }
 
 /// Fires when a pointer is canceled, and its current interaction state is dropped.
@@ -150,6 +187,12 @@ pub struct Cancel {
 pub struct Over {
     /// Information about the picking intersection.
     pub hit: HitData,
+    /// The [velocity](PointerState::velocity) of the pointer when it crossed into the target, in
+    /// screen pixels per second.
+    ///
+    /// This is only tracked while a [`VelocitySettings`] resource is present, and is `None`
+    /// otherwise.
+    pub velocity: Option<Vec2>,
 }
 
 /// Fires when a pointer crosses out of the bounds of the [target entity](EntityEvent::event_target).
@@ -160,6 +203,86 @@ pub struct Out {
     pub hit: HitData,
 }
 
+/// Fires when a pointer crosses into the subtree of the [target entity](EntityEvent::event_target),
+/// that is when it starts hovering the entity or one of its descendants while it hovered none of
+/// them before.
+///
+/// Unlike [`Over`], moving the pointer between two descendants of an entity doesn't make the entity
+/// receive this event again, which makes it suited to highlighting nested UI. The event is
+/// triggered on the deepest entity entered and bubbles up to [`outermost`](Self::outermost), so
+/// that every entity whose subtree was entered receives it exactly once.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct HoverEnter {
+    /// Information about the picking intersection with the entity the event was triggered on.
+    pub hit: HitData,
+    /// The outermost entity whose subtree was entered, where the event stops bubbling.
+    pub outermost: Entity,
+}
+
+/// Fires when a pointer crosses out of the subtree of the [target entity](EntityEvent::event_target),
+/// that is when it no longer hovers the entity or any of its descendants. See [`HoverEnter`].
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct HoverExit {
+    /// Information about the latest prior picking intersection with the entity the event was
+    /// triggered on.
+    ///
+    /// If that entity wasn't hovered itself, for example because its hovered descendant was
+    /// despawned, this is the pointer's nearest prior intersection instead.
+    pub hit: HitData,
+    /// The outermost entity whose subtree was exited, where the event stops bubbling.
+    pub outermost: Entity,
+}
+
+/// An event that only bubbles up to the outermost entity of the subtree the pointer crossed.
+pub(crate) trait SubtreeEvent: Debug + Clone + Reflect {
+    fn outermost(&self) -> Entity;
+}
+
+impl SubtreeEvent for HoverEnter {
+    fn outermost(&self) -> Entity {
+        self.outermost
+    }
+}
+
+impl SubtreeEvent for HoverExit {
+    fn outermost(&self) -> Entity {
+        self.outermost
+    }
+}
+
+/// Stops a [`SubtreeEvent`] from bubbling past its outermost entity.
+pub(crate) fn stop_at_outermost<E: SubtreeEvent>(mut event: On<Pointer<E>>) {
+    if event.entity == event.outermost() {
+        event.propagate(false);
+    }
+}
+
+/// Fires when the [target entity](EntityEvent::event_target) becomes the topmost entity hovered by
+/// a pointer.
+///
+/// Unlike [`Over`], which fires for every entity the pointer starts hovering, only one entity per
+/// pointer is topmost at a time: the hovered entity with the smallest [`HitData::depth`], with ties
+/// broken by [`Entity`] order. This is useful for focus rings and similar indicators that should
+/// only be shown on the frontmost of several overlapping entities.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct BecameTopmost {
+    /// Information about the picking intersection.
+    pub hit: HitData,
+}
+
+/// Fires when the [target entity](EntityEvent::event_target) stops being the topmost entity
+/// hovered by a pointer, either because another entity is now in front of it or because it is no
+/// longer hovered. See [`BecameTopmost`].
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct LostTopmost {
+    /// Information about the latest prior picking intersection.
+    pub hit: HitData,
+}
+
 /// Fires when a pointer button is pressed over the [target entity](EntityEvent::event_target).
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
@@ -170,7 +293,62 @@ pub struct Press {
     pub hit: HitData,
 }
 
+/// Fires repeatedly while a pointer button is held down over the
+/// [target entity](EntityEvent::event_target) it was pressed on, such as to keep stepping a
+/// scrollbar while one of its arrows is held.
+///
+/// Only emitted while a [`RepeatSettings`] resource is present. The first repeat fires
+/// [`RepeatSettings::delay`] after the [`Press`], and the following ones every
+/// [`RepeatSettings::interval`], at most once per frame. Releasing the button or moving the pointer
+/// off the entity stops the repeats until the next press.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct PressRepeat {
+    /// Pointer button held to trigger this event.
+    pub button: PointerButton,
+    /// Information about the picking intersection.
+    pub hit: HitData,
+    /// The number of repeats fired for this press so far, starting at 1.
+    pub count: u32,
+}
+
+/// Fires once when a pointer button has been held down over the
+/// [target entity](EntityEvent::event_target) it was pressed on for
+/// [`PressSettings::long_press_duration`], such as to open a context menu on touch screens.
+///
+/// Releasing the button, canceling the pointer, moving the pointer off the entity, or starting to
+/// drag the entity before then prevents the long press. It is not followed by a [`DragStart`]
+/// either, so it only fires for presses that stay in place.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct LongPress {
+    /// Pointer button held to trigger this event.
+    pub button: PointerButton,
+    /// Information about the picking intersection.
+    pub hit: HitData,
+    /// How long the button has been held.
+    pub duration: Duration,
+}
+
+/// Fires the first time a pointer presses or clicks the [target entity](EntityEvent::event_target),
+/// and never again for that entity.
+///
+/// This is useful for analytics and tutorials that react to the first time the user interacts
+/// with something. The entities that already fired this event are kept in
+/// [`FirstInteractions`], until they are despawned.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct FirstInteraction {
+    /// Pointer button used in the interaction.
+    pub button: PointerButton,
+    /// Information about the picking intersection.
+    pub hit: HitData,
+}
+
 /// Fires when a pointer button is released over the [target entity](EntityEvent::event_target).
+///
+/// See [`PressSettings::release_pressed_entities`] to also release pressed entities that are no
+/// longer hovered.
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub struct Release {
@@ -178,6 +356,9 @@ pub struct Release {
     pub button: PointerButton,
     /// Information about the picking intersection.
     pub hit: HitData,
+    /// How long the button was held since it pressed the target entity, or `None` if it didn't
+    /// press it, such as when the pointer was pressed elsewhere and moved onto it.
+    pub duration: Option<Duration>,
 }
 
 /// Fires when a pointer sends a pointer pressed event followed by a pointer released event, with the same
@@ -191,9 +372,20 @@ pub struct Click {
     pub hit: HitData,
     /// Duration between the pointer pressed and lifted for this click
     pub duration: Duration,
+    /// The number of consecutive clicks on this entity, starting at `1`, such as `2` for a
+    /// double-click.
+    ///
+    /// A click follows the previous one if it is on the same entity with the same button, within
+    /// the [`ClickSettings::multi_click_window`]. Releasing the button over other entities, or
+    /// canceling the pointer, starts the count again.
+    pub count: u32,
 }
 
 /// Fires while a pointer is moving over the [target entity](EntityEvent::event_target).
+///
+/// Unlike most pointer events, it doesn't bubble up to the ancestors of the target, since it fires
+/// on every movement. An observer on the target can call `propagate(true)` to bubble it anyway, and
+/// a [`PointerEventPropagation`] can change this default.
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub struct Move {
@@ -206,6 +398,26 @@ pub struct Move {
     /// using methods on [`Camera`](bevy_camera::Camera) to convert from screen-space to
     /// world-space.
     pub delta: Vec2,
+    /// The total change in position of this pointer across every move processed so far this frame,
+    /// including this one.
+    ///
+    /// When several moves are coalesced into a single frame, the last [`Move`] for a pointer
+    /// carries the sum of all of their deltas. Like [`Move::delta`], this is in screen pixels.
+    pub frame_delta: Vec2,
+}
+
+/// Fires on the [`FallbackMoveTarget`] while a pointer is moving without hovering any entity.
+///
+/// Like other pointer events, it bubbles up from the fallback target to its ancestors. It is never
+/// fired for a pointer that hovers an entity, which receives a [`Move`] instead.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct FallbackMove {
+    /// The change in position since the last move event, in screen pixels, like [`Move::delta`].
+    pub delta: Vec2,
+    /// The total change in position of this pointer across every move processed so far this frame,
+    /// including this one, like [`Move::frame_delta`].
+    pub frame_delta: Vec2,
 }
 
 /// Fires when the [target entity](EntityEvent::event_target) receives a pointer pressed event followed by a pointer move event.
@@ -238,6 +450,23 @@ pub struct Drag {
     /// using methods on [`Camera`](bevy_camera::Camera) to convert from screen-space to
     /// world-space.
     pub delta: Vec2,
+    /// The velocity of the pointer, in screen pixels per second, computed as [`delta`](Self::delta)
+    /// divided by the time since the last drag event.
+    ///
+    /// When several moves arrive at the same instant, such as within one frame, the velocity of
+    /// the last drag event is reported again, or zero for the first one.
+    pub velocity: Vec2,
+    /// The [`distance`](Self::distance) of the drag in world coordinates, on the plane of the
+    /// camera that the drag started in.
+    ///
+    /// The screen positions are projected with
+    /// [`Camera::viewport_to_world_2d`](bevy_camera::Camera::viewport_to_world_2d), which suits
+    /// 2D cameras and other orthographic cameras pointing along the Z axis. This is `None` if the
+    /// camera no longer exists or its viewport is unknown.
+    pub world_distance: Option<Vec2>,
+    /// The [`delta`](Self::delta) of the drag in world coordinates, projected like
+    /// [`world_distance`](Self::world_distance).
+    pub world_delta: Option<Vec2>,
 }
 
 /// Fires when a pointer is dragging the [target entity](EntityEvent::event_target) and a pointer released event is received.
@@ -255,7 +484,31 @@ pub struct DragEnd {
     pub distance: Vec2,
 }
 
+/// Fires when the drag of the [target entity](EntityEvent::event_target) is aborted, instead of
+/// [`DragEnd`].
+///
+/// A drag is aborted when its pointer is [canceled](PointerAction::Cancel), when it resumes after
+/// a gap with [`DragResumePolicy::Cancel`], or with [`PointerState::cancel_drag`], such as when
+/// Escape is pressed mid-drag. The entities it was
+/// dragged over receive a [`DragLeave`], but no [`DragDrop`].
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct DragCancel {
+    /// Pointer button of the aborted drag.
+    pub button: PointerButton,
+    /// The vector of drag movement measured from start to the last pointer position.
+    ///
+    /// This is stored in screen pixels, not world coordinates. Screen pixels go from top-left to
+    /// bottom-right, whereas (in 2D) world coordinates go from bottom-left to top-right. Consider
+    /// using methods on [`Camera`](bevy_camera::Camera) to convert from screen-space to
+    /// world-space.
+    pub distance: Vec2,
+}
+
 /// Fires when a pointer dragging the `dragged` entity enters the [target entity](EntityEvent::event_target)
+///
+/// When a drag enters several entities in the same frame, this is triggered on them in the order
+/// given by [`Entity`]'s [`Ord`] implementation.
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub struct DragEnter {
@@ -268,6 +521,9 @@ pub struct DragEnter {
 }
 
 /// Fires while the `dragged` entity is being dragged over the [target entity](EntityEvent::event_target).
+///
+/// Like [`Move`], it doesn't bubble up to the ancestors of the target unless an observer on the
+/// target calls `propagate(true)`, or a [`PointerEventPropagation`] says otherwise.
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub struct DragOver {
@@ -303,6 +559,180 @@ pub struct DragDrop {
     pub hit: HitData,
 }
 
+/// A pointer event caused by a specific [`PointerButton`].
+pub trait ButtonEvent: Debug + Clone + Reflect {
+    /// The button that caused this event.
+    fn button(&self) -> PointerButton;
+}
+
+macro_rules! impl_button_event {
+    ($($event:ident),* $(,)?) => {
+        $(
+            impl ButtonEvent for $event {
+                fn button(&self) -> PointerButton {
+                    self.button
+                }
+            }
+        )*
+    };
+}
+
+impl_button_event!(
+    Press,
+    PressRepeat,
+    LongPress,
+    FirstInteraction,
+    Release,
+    Click,
+    DragStart,
+    Drag,
+    DragEnd,
+    DragCancel,
+    DragEnter,
+    DragOver,
+    DragLeave,
+    DragDrop,
+);
+
+/// Typed data carried by a dragged entity, such as the item a dragged inventory slot holds.
+///
+/// Observers of the events triggered on the entities it is dragged over, such as [`DragDrop`],
+/// can read it with [`DragTargetEvent::payload`], instead of inferring what the drag means from the
+/// components of the dragged entity.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::events::{DragDrop, DragPayload, DragTargetEvent, Pointer};
+/// #[derive(Clone)]
+/// struct Item(u32);
+///
+/// fn on_drop(drop: On<Pointer<DragDrop>>, items: Query<&DragPayload<Item>>) {
+///     if let Some(Item(id)) = drop.payload(&items) {
+///         println!("Dropped item {id} on {}", drop.entity);
+///     }
+/// }
+/// ```
+///
+/// The events are triggered while [`pointer_events`] runs, so the dragged entity and its payload
+/// are still there when the observers run, even if one of them despawns it with [`Commands`].
+#[derive(Component, Debug, Clone, Default, PartialEq, Deref, DerefMut)]
+pub struct DragPayload<T: Send + Sync + 'static>(pub T);
+
+/// A pointer event triggered on an entity that another one is dragged over or dropped onto.
+pub trait DragTargetEvent: Debug + Clone + Reflect {
+    /// The entity being dragged, or dropped.
+    fn dragged(&self) -> Entity;
+
+    /// The [`DragPayload`] of the [`dragged`](Self::dragged) entity, if it has one.
+    fn payload<'a, T: Send + Sync + 'static>(
+        &self,
+        payloads: &'a Query<&DragPayload<T>>,
+    ) -> Option<&'a T> {
+        payloads.get(self.dragged()).ok().map(|payload| &payload.0)
+    }
+}
+
+impl DragTargetEvent for DragEnter {
+    fn dragged(&self) -> Entity {
+        self.dragged
+    }
+}
+
+impl DragTargetEvent for DragOver {
+    fn dragged(&self) -> Entity {
+        self.dragged
+    }
+}
+
+impl DragTargetEvent for DragLeave {
+    fn dragged(&self) -> Entity {
+        self.dragged
+    }
+}
+
+impl DragTargetEvent for DragDrop {
+    fn dragged(&self) -> Entity {
+        self.dropped
+    }
+}
+
+/// Adapts an observer of a [`ButtonEvent`] to only run for one [`PointerButton`], see
+/// [`ObserveButtonExt`].
+#[derive(Clone, Copy)]
+pub struct ButtonFilter(pub PointerButton);
+
+impl<E: ButtonEvent, S> Adapt<S> for ButtonFilter
+where
+    S: System<In = On<'static, 'static, Pointer<E>>, Out = ()>,
+{
+    type In = On<'static, 'static, Pointer<E>>;
+    type Out = ();
+
+    fn adapt(
+        &mut self,
+        input: <Self::In as SystemInput>::Inner<'_>,
+        run_system: impl FnOnce(SystemIn<'_, S>) -> Result<(), RunSystemError>,
+    ) -> Result<(), RunSystemError> {
+        if input.button() == self.0 {
+            run_system(input)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Extension trait for observing the [`ButtonEvent`]s of a single [`PointerButton`] on an entity.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::{events::{Click, ObserveButtonExt, Pointer}, pointer::PointerButton};
+/// fn spawn_button(mut commands: Commands) {
+///     commands
+///         .spawn_empty()
+///         .observe_button(PointerButton::Secondary, |click: On<Pointer<Click>>| {
+///             // Only right clicks get here.
+///         });
+/// }
+/// ```
+pub trait ObserveButtonExt {
+    /// Observes `E` on this entity like [`EntityCommands::observe`], but only runs `observer`
+    /// for events caused by `button`.
+    fn observe_button<E: ButtonEvent, M>(
+        &mut self,
+        button: PointerButton,
+        observer: impl IntoObserverSystem<Pointer<E>, (), M>,
+    ) -> &mut Self;
+}
+
+impl ObserveButtonExt for EntityCommands<'_> {
+    fn observe_button<E: ButtonEvent, M>(
+        &mut self,
+        button: PointerButton,
+        observer: impl IntoObserverSystem<Pointer<E>, (), M>,
+    ) -> &mut Self {
+        self.observe(button_observer(button, observer))
+    }
+}
+
+impl ObserveButtonExt for EntityWorldMut<'_> {
+    fn observe_button<E: ButtonEvent, M>(
+        &mut self,
+        button: PointerButton,
+        observer: impl IntoObserverSystem<Pointer<E>, (), M>,
+    ) -> &mut Self {
+        self.observe(button_observer(button, observer))
+    }
+}
+
+fn button_observer<E: ButtonEvent, M, O: IntoObserverSystem<Pointer<E>, (), M>>(
+    button: PointerButton,
+    observer: O,
+) -> AdapterSystem<ButtonFilter, O::System> {
+    let system = IntoObserverSystem::into_system(observer);
+    let name = system.name();
+    AdapterSystem::new(ButtonFilter(button), system, name)
+}
+
 /// Dragging state.
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
@@ -323,18 +753,33 @@ pub struct DragEntry {
     /// [`Camera::viewport_to_world_2d`](bevy_camera::Camera::viewport_to_world_2d) to
     /// convert from screen-space to world-space.
     pub latest_pos: Vec2,
+    /// The time at which the pointer reached [`latest_pos`](Self::latest_pos), used to compute
+    /// [`Drag::velocity`].
+    pub latest_time: Instant,
+    /// The [`Drag::velocity`] of the latest drag event, in screen pixels per second.
+    pub velocity: Vec2,
+    /// The [`DragLock`] of the dragged entity when the drag started.
+    pub lock: Option<DragLock>,
+    /// The camera of the press that started the drag, used to compute the world-space vectors
+    /// of [`Drag`].
+    pub camera: Entity,
+}
+
+impl DragEntry {
+    /// Applies the [`lock`](Self::lock) of the drag to `vector`, if it has one.
+    fn locked(&self, vector: Vec2) -> Vec2 {
+        self.lock.map_or(vector, |lock| lock.apply(vector))
+    }
 }
 
 /// Fires while a pointer is scrolling over the [target entity](EntityEvent::event_target).
 #[derive(Clone, PartialEq, Debug, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub struct Scroll {
-    /// The mouse scroll unit.
-    pub unit: MouseScrollUnit,
-    /// The horizontal scroll value.
-    pub x: f32,
-    /// The vertical scroll value.
-    pub y: f32,
+    /// How far the pointer scrolled, in `unit`s.
+    pub delta: Vec2,
+    /// Whether `delta` is measured in lines or pixels.
+    pub unit: ScrollUnit,
     /// Information about the picking intersection.
     pub hit: HitData,
 }
@@ -349,14 +794,72 @@ pub struct PointerButtonState {
     pub dragging: HashMap<Entity, DragEntry>,
     /// Stores the hit data for each entity currently being dragged over by the pointer.
     pub dragging_over: HashMap<Entity, HitData>,
+    /// Stores the time of the next [`PressRepeat`] and the number already fired, for each pressed
+    /// entity that is still repeating.
+    pub repeating: HashMap<Entity, (Instant, u32)>,
+    /// The pressed entities that can no longer receive a [`LongPress`] until the next press,
+    /// because they already did or because they were dragged.
+    pub long_pressed: EntityHashSet,
+    /// The entity that has captured this button on the pointer, if any.
+    ///
+    /// See [`PointerState::capture`].
+    pub captured: Option<Entity>,
+    /// Whether the [`captured`](Self::captured) entity keeps its capture when the button is
+    /// released.
+    ///
+    /// See [`PointerState::capture_retained`].
+    pub capture_retained: bool,
+    /// Stores the time and [`Click::count`] of the last click on each entity clicked by the last
+    /// release of the button.
+    pub clicks: HashMap<Entity, (Instant, u32)>,
+    /// The farthest the pointer has moved from where the button was pressed, in screen pixels, for
+    /// the [`ClickSettings::click_tolerance`].
+    pub press_drift: f32,
 }
 
 impl PointerButtonState {
-    /// Clears all press and drag data tracked for this button on its pointer.
+    /// Clears all press, drag, and capture data tracked for this button on its pointer.
+    ///
+    /// The [`clicks`](Self::clicks) are kept, so that they can be followed by the next click.
     pub fn clear(&mut self) {
         self.pressing.clear();
         self.dragging.clear();
         self.dragging_over.clear();
+        self.repeating.clear();
+        self.long_pressed.clear();
+        self.captured = None;
+        self.capture_retained = false;
+        self.press_drift = 0.0;
+    }
+
+    /// Updates the [`press_drift`](Self::press_drift) with the pointer's current position.
+    fn record_press_drift(&mut self, position: Vec2) {
+        for (press_location, ..) in self.pressing.values() {
+            let drift = position.distance(press_location.position);
+            self.press_drift = self.press_drift.max(drift);
+        }
+    }
+
+    /// Forgets the entities for which `keep` returns `false`, returning `true` if any were
+    /// forgotten.
+    fn retain_entities(&mut self, keep: impl Fn(Entity) -> bool) -> bool {
+        let len = |state: &Self| {
+            state.pressing.len()
+                + state.dragging.len()
+                + state.dragging_over.len()
+                + state.repeating.len()
+                + state.long_pressed.len()
+                + state.clicks.len()
+        };
+        let before = len(self);
+        self.pressing.retain(|entity, _| keep(*entity));
+        self.dragging.retain(|entity, _| keep(*entity));
+        self.dragging_over.retain(|entity, _| keep(*entity));
+        self.repeating.retain(|entity, _| keep(*entity));
+        self.long_pressed.retain(|entity| keep(*entity));
+        self.clicks.retain(|entity, _| keep(*entity));
+        let uncaptured = self.captured.take_if(|entity| !keep(*entity)).is_some();
+        uncaptured || len(self) != before
     }
 }
 
@@ -365,6 +868,19 @@ impl PointerButtonState {
 pub struct PointerState {
     /// Pressing and dragging state, organized by pointer and button.
     pub pointer_buttons: HashMap<(PointerId, PointerButton), PointerButtonState>,
+    /// The captures last reported with [`PointerCaptureChanged`].
+    reported_captures: HashMap<(PointerId, PointerButton), Entity>,
+    /// The hovered entities of each pointer and all their ancestors, for [`HoverEnter`] and
+    /// [`HoverExit`].
+    hovered_subtrees: HashMap<PointerId, EntityHashSet>,
+    /// The recent positions of each pointer, for [`PointerState::velocity`].
+    motion: HashMap<PointerId, Vec<(Instant, Vec2)>>,
+    /// The buttons whose drags are aborted on the next run of [`pointer_events`].
+    canceled_drags: Vec<(PointerId, PointerButton)>,
+    /// The modifier keys of the latest input of each pointer, for [`Pointer::modifiers`].
+    modifiers: HashMap<PointerId, PointerModifiers>,
+    /// The last known location of each pointer, to report the events flushed when it is removed.
+    last_locations: HashMap<PointerId, Location>,
 }
 
 impl PointerState {
@@ -384,498 +900,5852 @@ impl PointerState {
             .or_default()
     }
 
-    /// Clears all the data associated with all of the buttons on a pointer. Does not free the underlying memory.
+    /// Clears all the data associated with all of the buttons on a pointer, including their
+    /// [`clicks`](PointerButtonState::clicks). Does not free the underlying memory.
     pub fn clear(&mut self, pointer_id: PointerId) {
         for button in PointerButton::iter() {
             if let Some(state) = self.pointer_buttons.get_mut(&(pointer_id, button)) {
                 state.clear();
+                state.clicks.clear();
             }
         }
     }
-}
 
-/// A helper system param for accessing the picking event writers.
-#[derive(SystemParam)]
-pub struct PickingMessageWriters<'w> {
-    cancel_events: MessageWriter<'w, Pointer<Cancel>>,
-    click_events: MessageWriter<'w, Pointer<Click>>,
-    pressed_events: MessageWriter<'w, Pointer<Press>>,
-    drag_drop_events: MessageWriter<'w, Pointer<DragDrop>>,
-    drag_end_events: MessageWriter<'w, Pointer<DragEnd>>,
-    drag_enter_events: MessageWriter<'w, Pointer<DragEnter>>,
-    drag_events: MessageWriter<'w, Pointer<Drag>>,
-    drag_leave_events: MessageWriter<'w, Pointer<DragLeave>>,
-    drag_over_events: MessageWriter<'w, Pointer<DragOver>>,
-    drag_start_events: MessageWriter<'w, Pointer<DragStart>>,
-    scroll_events: MessageWriter<'w, Pointer<Scroll>>,
-    move_events: MessageWriter<'w, Pointer<Move>>,
-    out_events: MessageWriter<'w, Pointer<Out>>,
-    over_events: MessageWriter<'w, Pointer<Over>>,
-    released_events: MessageWriter<'w, Pointer<Release>>,
+    /// Aborts the drags of a button of the pointer on the next run of [`pointer_events`].
+    ///
+    /// The dragged entities receive a [`DragCancel`] instead of a [`DragEnd`], and the entities
+    /// they were dragged over a [`DragLeave`] without a [`DragDrop`]. The presses that started the
+    /// drags are forgotten, so releasing the button afterwards doesn't click them.
+    pub fn cancel_drag(&mut self, pointer_id: PointerId, button: PointerButton) {
+        self.canceled_drags.push((pointer_id, button));
+    }
+
+    /// Captures a button of the pointer for `entity`, replacing any previous capture.
+    ///
+    /// While the capture is held, [`pointer_events`] routes the [`Move`] events of the pointer,
+    /// and the [`Release`] and [`Click`] events of the button, to `entity` instead of the hovered
+    /// entities, so that it keeps receiving them after the pointer leaves it. Drags of the button
+    /// that start during the capture drag `entity` rather than the pressed entities, so capturing
+    /// from a [`Press`] observer makes `entity` the target of the [`DragStart`], [`Drag`], and
+    /// [`DragEnd`] events. [`DragEnter`], [`DragOver`], [`DragLeave`], and [`DragDrop`] still
+    /// follow the entities actually hovered, as do [`Over`] and [`Out`].
+    ///
+    /// The capture is released when the button is released or the pointer is canceled, or
+    /// explicitly with [`PointerState::release_capture`]. Each change is reported with a
+    /// [`PointerCaptureChanged`] event the next time [`pointer_events`] runs.
+    pub fn capture(&mut self, pointer_id: PointerId, button: PointerButton, entity: Entity) {
+        let state = self.get_mut(pointer_id, button);
+        state.captured = Some(entity);
+        state.capture_retained = false;
+    }
+
+    /// Captures a button of the pointer for `entity` like [`PointerState::capture`], but keeps the
+    /// capture when the button is released, until it is released explicitly with
+    /// [`PointerState::release_capture`] or the pointer is canceled.
+    pub fn capture_retained(
+        &mut self,
+        pointer_id: PointerId,
+        button: PointerButton,
+        entity: Entity,
+    ) {
+        let state = self.get_mut(pointer_id, button);
+        state.captured = Some(entity);
+        state.capture_retained = true;
+    }
+
+    /// Releases the capture of a button of the pointer, returning the entity that held it.
+    pub fn release_capture(
+        &mut self,
+        pointer_id: PointerId,
+        button: PointerButton,
+    ) -> Option<Entity> {
+        let state = self.pointer_buttons.get_mut(&(pointer_id, button))?;
+        state.capture_retained = false;
+        state.captured.take()
+    }
+
+    /// The entity that has captured a button of the pointer, if any.
+    pub fn captured(&self, pointer_id: PointerId, button: PointerButton) -> Option<Entity> {
+        self.get(pointer_id, button)
+            .and_then(|state| state.captured)
+    }
+
+    /// The [`HitData`] recorded when the button of the pointer pressed `entity`, if it is still
+    /// pressing it.
+    ///
+    /// This is the hit of the [`Press`] event, which observers of later events such as [`Move`] or
+    /// [`Drag`] can use to anchor effects to the point where the entity was pressed.
+    pub fn press_hit(
+        &self,
+        pointer_id: PointerId,
+        button: PointerButton,
+        entity: Entity,
+    ) -> Option<&HitData> {
+        self.get(pointer_id, button)
+            .and_then(|state| state.pressing.get(&entity))
+            .map(|(_, _, hit)| hit)
+    }
+
+    /// Returns `true` if any button of any pointer is pressing `entity`.
+    pub fn is_pressed(&self, entity: Entity) -> bool {
+        self.pointer_buttons
+            .values()
+            .any(|state| state.pressing.contains_key(&entity))
+    }
+
+    /// Returns `true` if any button of any pointer is dragging `entity`.
+    pub fn is_dragged(&self, entity: Entity) -> bool {
+        self.pointer_buttons
+            .values()
+            .any(|state| state.dragging.contains_key(&entity))
+    }
+
+    /// Returns the pointers and buttons that are pressing `entity`, in no particular order.
+    pub fn pressing_pointers(
+        &self,
+        entity: Entity,
+    ) -> impl Iterator<Item = (PointerId, PointerButton)> + '_ {
+        self.pointer_buttons
+            .iter()
+            .filter(move |(_, state)| state.pressing.contains_key(&entity))
+            .map(|(key, _)| *key)
+    }
+
+    /// The velocity of the pointer, in screen pixels per second, averaged over the
+    /// [`VelocitySettings::window`].
+    ///
+    /// The motion of the pointers is only tracked while a [`VelocitySettings`] resource is present.
+    /// Returns `None` otherwise, or if the pointer wasn't seen twice within the window yet.
+    pub fn velocity(&self, pointer_id: PointerId) -> Option<Vec2> {
+        let samples = self.motion.get(&pointer_id)?;
+        let ((first_time, first_position), (last_time, last_position)) =
+            (samples.first()?, samples.last()?);
+        let elapsed = last_time.saturating_duration_since(*first_time);
+        (!elapsed.is_zero()).then(|| (last_position - first_position) / elapsed.as_secs_f32())
+    }
+
+    /// Records the `position` of the pointer at `now`, and forgets the positions older than
+    /// `window`.
+    fn record_motion(
+        &mut self,
+        pointer_id: PointerId,
+        position: Vec2,
+        now: Instant,
+        window: Duration,
+    ) {
+        let samples = self.motion.entry(pointer_id).or_default();
+        samples.retain(|(time, _)| now.saturating_duration_since(*time) <= window);
+        samples.push((now, position));
+    }
+
+    /// Forgets everything about the pointers for which `keep` returns `false`.
+    fn retain_pointers(&mut self, keep: impl Fn(PointerId) -> bool) {
+        self.pointer_buttons
+            .retain(|(pointer_id, _), _| keep(*pointer_id));
+        self.reported_captures
+            .retain(|(pointer_id, _), _| keep(*pointer_id));
+        self.hovered_subtrees
+            .retain(|pointer_id, _| keep(*pointer_id));
+        self.motion.retain(|pointer_id, _| keep(*pointer_id));
+        self.modifiers.retain(|pointer_id, _| keep(*pointer_id));
+        self.last_locations
+            .retain(|pointer_id, _| keep(*pointer_id));
+    }
+
+    /// Returns the captures that changed since the last call.
+    fn take_capture_changes(&mut self) -> Vec<PointerCaptureChanged> {
+        let mut changes = Vec::new();
+        for ((pointer, button), state) in &self.pointer_buttons {
+            let key = (*pointer, *button);
+            if self.reported_captures.get(&key).copied() == state.captured {
+                continue;
+            }
+            match state.captured {
+                Some(entity) => self.reported_captures.insert(key, entity),
+                None => self.reported_captures.remove(&key),
+            };
+            changes.push(PointerCaptureChanged {
+                pointer: *pointer,
+                button: *button,
+                captured: state.captured,
+            });
+        }
+        changes.sort_unstable_by_key(|change| (change.pointer, change.button));
+        changes
+    }
 }
 
-/// Dispatches interaction events to the target entities.
+/// Fires when a button of a pointer is captured by an entity, or when that capture is released.
 ///
-/// Within a single frame, events are dispatched in the following order:
-/// + [`Out`] → [`DragLeave`].
-/// + [`DragEnter`] → [`Over`].
-/// + Any number of any of the following:
-///   + For each movement: [`DragStart`] → [`Drag`] → [`DragOver`] → [`Move`].
-///   + For each button press: [`Press`] or [`Click`] → [`Release`] → [`DragDrop`] → [`DragEnd`] → [`DragLeave`].
-///   + For each pointer cancellation: [`Cancel`].
+/// Captures are managed with [`PointerState::capture`] and [`PointerState::release_capture`],
+/// and released automatically when the button is released, unless made with
+/// [`PointerState::capture_retained`]. Changes are reported by
+/// [`pointer_events`], both as a message and as a global observer event. Replacing a capture
+/// with another reports only the new entity.
+#[derive(Message, Event, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq, Debug)]
+pub struct PointerCaptureChanged {
+    /// The pointer whose capture changed.
+    pub pointer: PointerId,
+    /// The button whose capture changed.
+    pub button: PointerButton,
+    /// The entity now capturing the button, or `None` if the capture was released.
+    pub captured: Option<Entity>,
+}
+
+/// Fires when a pointer stops hovering any entity, after having hovered at least one.
 ///
-/// Additionally, across multiple frames, the following are also strictly
-/// ordered by the interaction state machine:
-/// + When a pointer moves over the target:
-///   [`Over`], [`Move`], [`Out`].
-/// + When a pointer presses buttons on the target:
-///   [`Press`], [`Click`], [`Release`].
-/// + When a pointer drags the target:
-///   [`DragStart`], [`Drag`], [`DragEnd`].
-/// + When a pointer drags something over the target:
-///   [`DragEnter`], [`DragOver`], [`DragDrop`], [`DragLeave`].
-/// + When a pointer is canceled:
-///   No other events will follow the [`Cancel`] event for that pointer.
+/// Unlike [`Out`], this isn't targeted at an entity: it reports that the pointer is now over empty
+/// space, such as to clear a selection or highlight. It is reported by
+/// [`pointer_hover_transitions`], both as a message and as a global observer event, after the
+/// [`Out`] events of the same frame.
+#[derive(Message, Event, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[reflect(Clone, PartialEq, Debug)]
+pub struct PointerHoverNothing {
+    /// The pointer that hovers nothing.
+    pub pointer: PointerId,
+}
+
+/// Fires when a pointer starts hovering an entity, after having hovered nothing.
 ///
-/// Two events -- [`Over`] and [`Out`] -- are driven only by the [`HoverMap`].
-/// The rest rely on additional data from the [`PointerInput`] event stream. To
-/// receive these events for a custom pointer, you must add [`PointerInput`]
-/// events.
+/// This is the counterpart of [`PointerHoverNothing`], and is reported in the same way.
+#[derive(Message, Event, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[reflect(Clone, PartialEq, Debug)]
+pub struct PointerHoverStarted {
+    /// The pointer that started hovering.
+    pub pointer: PointerId,
+}
+
+/// The time of the most recent input from each pointer, for idle detection.
 ///
-/// When the pointer goes from hovering entity A to entity B, entity A will
-/// receive [`Out`] and then entity B will receive [`Over`]. No entity will ever
-/// receive both an [`Over`] and an [`Out`] event during the same frame.
+/// Updated by [`pointer_events`] whenever a pointer moves, presses, releases, or scrolls.
+/// Cancellations don't count as activity.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PointerActivity {
+    last_input: HashMap<PointerId, Instant>,
+}
+
+impl PointerActivity {
+    /// The time of the most recent input from the pointer, if it has sent any.
+    pub fn last_input(&self, pointer_id: PointerId) -> Option<Instant> {
+        self.last_input.get(&pointer_id).copied()
+    }
+
+    /// How long the pointer has been idle at `now`.
+    ///
+    /// Returns [`Duration::MAX`] for pointers that haven't sent any input yet.
+    pub fn idle_duration(&self, pointer_id: PointerId, now: Instant) -> Duration {
+        self.last_input(pointer_id)
+            .map_or(Duration::MAX, |last_input| {
+                now.saturating_duration_since(last_input)
+            })
+    }
+
+    /// Records input from the pointer at `now`.
+    pub fn record(&mut self, pointer_id: PointerId, now: Instant) {
+        self.last_input.insert(pointer_id, now);
+    }
+}
+
+/// The timing of [`PressRepeat`] events.
+///
+/// [`PressRepeat`] events are only emitted while this resource is present.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct RepeatSettings {
+    /// The time between a [`Press`] and its first repeat.
+    pub delay: Duration,
+    /// The time between two consecutive repeats.
+    pub interval: Duration,
+}
+
+impl Default for RepeatSettings {
+    fn default() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            interval: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Options for how [`pointer_events`] resolves presses.
+///
+/// This resource is not inserted by default; when it is missing, the default options are used.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct PressSettings {
+    /// Whether a pressed entity always receives [`Release`] when its button is released.
+    ///
+    /// [`Release`] is normally only sent to the entities the pointer hovered in the previous frame.
+    /// A pressed entity that stopped being hovered, for example because another entity moved in
+    /// front of it and now blocks it, then never sees its button released. When this is `true`,
+    /// such entities receive [`Release`] too, with the [`HitData`] of their [`Press`], so that
+    /// button-like state always resolves. They don't receive [`Click`], which still requires the
+    /// release to happen over the entity.
+    pub release_pressed_entities: bool,
+    /// How long a button must be held over an entity before it receives a [`LongPress`], as
+    /// measured by the [`PickingClock`].
+    pub long_press_duration: Duration,
+}
+
+impl Default for PressSettings {
+    fn default() -> Self {
+        Self {
+            release_pressed_entities: false,
+            long_press_duration: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Options for how [`pointer_events`] emits [`Click`] events and counts consecutive clicks in
+/// [`Click::count`].
+///
+/// This resource is not inserted by default; when it is missing, the default options are used.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct ClickSettings {
+    /// The longest time between two clicks for the second to follow the first, as measured by the
+    /// [`PickingClock`] when the button is released.
+    pub multi_click_window: Duration,
+    /// How far a pointer may move from where it pressed an entity and still click it when
+    /// released, in screen pixels.
+    ///
+    /// The farthest the pointer got from the press counts, even if it came back before the release.
+    /// This is independent of the [`DragSettings::drag_threshold`]: with a tolerance above the
+    /// threshold, a press that drifts a few pixels, as fingers do on touchscreens, both drags the
+    /// entity and clicks it. The release then emits [`Click`] → [`Release`] → [`DragDrop`] →
+    /// [`DragEnd`], after the [`DragStart`] and [`Drag`] events of the drift. Defaults to
+    /// [`f32::INFINITY`], which clicks however far the pointer moved.
+    pub click_tolerance: f32,
+}
+
+impl Default for ClickSettings {
+    fn default() -> Self {
+        Self {
+            multi_click_window: Duration::from_millis(300),
+            click_tolerance: f32::INFINITY,
+        }
+    }
+}
+
+/// Options for how [`pointer_events`] starts drags.
+///
+/// This resource is not inserted by default; when it is missing, the default options are used.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct DragSettings {
+    /// How far a pointer must move from where it pressed an entity before the press becomes a
+    /// drag, in screen pixels.
+    ///
+    /// Until then, the entity receives no [`DragStart`] or [`Drag`], so that a little jitter while
+    /// clicking doesn't start a drag. Once the pointer moves further, the first [`Drag`] covers
+    /// all the movement since the press. Defaults to `0.0`, which starts dragging on the first
+    /// movement.
+    pub drag_threshold: f32,
+    /// Whether the entities dragged over keep receiving a [`DragOver`] every frame while the
+    /// pointer holds still over them.
+    ///
+    /// Each such [`DragOver`] reports the latest hit of the entity. Defaults to `false`, which
+    /// only emits [`DragOver`] when the pointer moves.
+    pub continuous_drag_over: bool,
+}
+
+/// Options for how [`pointer_events`] dispatches the moves of the pointers.
+///
+/// This resource is not inserted by default; when it is missing, the default options are used.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct MoveSettings {
+    /// Whether the moves of a pointer read in the same frame are merged into one, with their summed
+    /// delta and the location of the last one.
+    ///
+    /// This emits a single [`Move`], [`Drag`], and [`DragOver`] per frame for each pointer and
+    /// entity, rather than one for each input. Moves separated by another input of the same
+    /// pointer, such as a press, aren't merged, and the merged move is dispatched in place of the
+    /// first one. [`Drag::distance`] is still measured from the start of the drag. Defaults to
+    /// `false`, which keeps every intermediate move, as gesture recognizers may rely on them.
+    pub coalesce_moves: bool,
+}
+
+/// The entity that receives a [`FallbackMove`] when a pointer moves without hovering any entity,
+/// such as the root of a tool that draws a custom cursor or a selection marquee.
+///
+/// This resource is not inserted by default; when it is missing, such moves aren't reported.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Resource, Debug, Clone, PartialEq)]
+pub struct FallbackMoveTarget(pub Entity);
+
+/// Enables tracking the [velocity](PointerState::velocity) of the pointers, which is reported in
+/// [`Over::velocity`].
+///
+/// This resource is not inserted by default; when it is missing, the motion of the pointers isn't
+/// tracked.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct VelocitySettings {
+    /// How far back the positions of a pointer are averaged over. Longer windows smooth out
+    /// jittery input, but react more slowly to changes of speed. Defaults to 100 milliseconds.
+    pub window: Duration,
+}
+
+impl Default for VelocitySettings {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(100),
+        }
+    }
+}
+
+/// What [`pointer_events`] does with a drag whose pointer resumes after a gap in its input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum DragResumePolicy {
+    /// Limits the [`Drag::delta`] to [`DragResumeSettings::max_jump`].
+    #[default]
+    Clamp,
+    /// Skips the movement across the gap, and continues dragging from the new position.
+    Reset,
+    /// Aborts the drag with [`DragCancel`] and [`DragLeave`], without dropping it. The drag
+    /// doesn't start again until the button is pressed again.
+    Cancel,
+}
+
+/// Detects gaps in the input of dragging pointers, such as while the window was minimized or the
+/// app hitched, and resumes their drags according to a [`DragResumePolicy`].
+///
+/// Without it, the first [`Drag`] after a gap has a [`Drag::delta`] spanning all the movement that
+/// was missed. A drag resumes after a gap if its pointer sent no input for longer than
+/// [`max_gap`](Self::max_gap), as measured by the [`PickingClock`], or if it moved further than
+/// [`max_jump`](Self::max_jump) in a single input.
+///
+/// This resource is not inserted by default; when it is missing, drags always follow the pointer.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct DragResumeSettings {
+    /// How drags resume after a gap.
+    pub policy: DragResumePolicy,
+    /// The longest time without input from the pointer that isn't a gap.
+    pub max_gap: Duration,
+    /// The largest movement in a single input that isn't a gap, in screen pixels.
+    pub max_jump: f32,
+}
+
+impl Default for DragResumeSettings {
+    fn default() -> Self {
+        Self {
+            policy: DragResumePolicy::default(),
+            max_gap: Duration::from_millis(250),
+            max_jump: 200.0,
+        }
+    }
+}
+
+impl DragResumeSettings {
+    /// Returns `true` if a movement of `delta` after `idle` time without input resumes a drag
+    /// after a gap.
+    pub fn is_gap(&self, idle: Duration, delta: Vec2) -> bool {
+        idle > self.max_gap || delta.length() > self.max_jump
+    }
+}
+
+/// The clock [`pointer_events`] reads the time of presses and repeats from.
+///
+/// Uses the system clock by default. Switching to a [`PickingClock::Manual`] clock makes
+/// time-dependent events such as [`Click::duration`] and [`PressRepeat`] deterministic, which is
+/// useful for tests and input replays.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub enum PickingClock {
+    /// Reads the time from [`Instant::now`].
+    #[default]
+    Real,
+    /// Stays at the given time until it is [advanced](PickingClock::advance).
+    Manual(Instant),
+}
+
+impl PickingClock {
+    /// Creates a manual clock, starting at the current time.
+    pub fn manual() -> Self {
+        Self::Manual(Instant::now())
+    }
+
+    /// Returns the current time.
+    pub fn now(&self) -> Instant {
+        match self {
+            Self::Real => Instant::now(),
+            Self::Manual(now) => *now,
+        }
+    }
+
+    /// Moves a manual clock forward by `duration`. Has no effect on the real clock.
+    pub fn advance(&mut self, duration: Duration) {
+        if let Self::Manual(now) = self {
+            *now += duration;
+        }
+    }
+}
+
+/// The drag components read by [`pointer_events`].
+#[derive(SystemParam)]
+pub struct DragComponents<'w, 's> {
+    handles: Query<'w, 's, (Entity, &'static DragHandle, Option<&'static ChildOf>)>,
+    locks: Query<'w, 's, &'static DragLock>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+}
+
+impl DragComponents<'_, '_> {
+    /// Projects the screen-space `offset` from `origin` into world space through `camera`.
+    fn world_offset(&self, camera: Entity, origin: Vec2, offset: Vec2) -> Option<Vec2> {
+        let (camera, transform) = self.cameras.get(camera).ok()?;
+        let start = camera.viewport_to_world_2d(transform, origin).ok()?;
+        let end = camera
+            .viewport_to_world_2d(transform, origin + offset)
+            .ok()?;
+        Some(end - start)
+    }
+}
+
+/// The clock and options read by [`pointer_events`].
+#[derive(SystemParam)]
+pub struct PointerEventSettings<'w> {
+    clock: Res<'w, PickingClock>,
+    repeat: Option<Res<'w, RepeatSettings>>,
+    press: Option<Res<'w, PressSettings>>,
+    click: Option<Res<'w, ClickSettings>>,
+    drag: Option<Res<'w, DragSettings>>,
+    drag_resume: Option<Res<'w, DragResumeSettings>>,
+    velocity: Option<Res<'w, VelocitySettings>>,
+    moves: Option<Res<'w, MoveSettings>>,
+    fallback_move_target: Option<Res<'w, FallbackMoveTarget>>,
+}
+
+/// The entities that have already received a [`FirstInteraction`] event.
+///
+/// Entities are removed once they are despawned. Removing an entity by hand makes its next press
+/// or click fire [`FirstInteraction`] again.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct FirstInteractions(pub EntityHashSet);
+
+/// Tracks [`FirstInteractions`] for [`pointer_events`].
+#[derive(SystemParam)]
+pub struct FirstInteractionTracker<'w> {
+    interacted: ResMut<'w, FirstInteractions>,
+    entities: &'w Entities,
+}
+
+impl FirstInteractionTracker<'_> {
+    /// Forgets the entities that have been despawned.
+    fn prune(&mut self) {
+        let entities = self.entities;
+        self.interacted.retain(|entity| entities.contains(*entity));
+    }
+
+    /// Records an interaction with `entity`, returning `true` if it is the first one.
+    fn record(&mut self, entity: Entity) -> bool {
+        self.interacted.insert(entity)
+    }
+}
+
+/// A helper system param for accessing the picking event writers.
+#[derive(SystemParam)]
+pub struct PickingMessageWriters<'w> {
+    cancel_events: MessageWriter<'w, Pointer<Cancel>>,
+    click_events: MessageWriter<'w, Pointer<Click>>,
+    pressed_events: MessageWriter<'w, Pointer<Press>>,
+    press_repeat_events: MessageWriter<'w, Pointer<PressRepeat>>,
+    long_press_events: MessageWriter<'w, Pointer<LongPress>>,
+    first_interaction_events: MessageWriter<'w, Pointer<FirstInteraction>>,
+    drag_drop_events: MessageWriter<'w, Pointer<DragDrop>>,
+    drag_end_events: MessageWriter<'w, Pointer<DragEnd>>,
+    drag_cancel_events: MessageWriter<'w, Pointer<DragCancel>>,
+    drag_enter_events: MessageWriter<'w, Pointer<DragEnter>>,
+    drag_events: MessageWriter<'w, Pointer<Drag>>,
+    drag_leave_events: MessageWriter<'w, Pointer<DragLeave>>,
+    drag_over_events: MessageWriter<'w, Pointer<DragOver>>,
+    drag_start_events: MessageWriter<'w, Pointer<DragStart>>,
+    scroll_events: MessageWriter<'w, Pointer<Scroll>>,
+    move_events: MessageWriter<'w, Pointer<Move>>,
+    fallback_move_events: MessageWriter<'w, Pointer<FallbackMove>>,
+    out_events: MessageWriter<'w, Pointer<Out>>,
+    over_events: MessageWriter<'w, Pointer<Over>>,
+    released_events: MessageWriter<'w, Pointer<Release>>,
+    became_topmost_events: MessageWriter<'w, Pointer<BecameTopmost>>,
+    lost_topmost_events: MessageWriter<'w, Pointer<LostTopmost>>,
+    hover_enter_events: MessageWriter<'w, Pointer<HoverEnter>>,
+    hover_exit_events: MessageWriter<'w, Pointer<HoverExit>>,
+    pinch_events: MessageWriter<'w, Pointer<Pinch>>,
+    rotate_events: MessageWriter<'w, Pointer<Rotate>>,
+    processed_inputs: Option<ResMut<'w, Messages<ProcessedPointerInput>>>,
+    any_events: Option<ResMut<'w, Messages<AnyPointerEvent>>>,
+    trigger_buffers: Option<ResMut<'w, TriggerBuffers>>,
+}
+
+/// Identifies a type of [`Pointer`] event emitted by [`pointer_events`] or by the built-in
+/// [gesture recognizers](crate::gesture).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub enum PointerEventKind {
+    /// [`Cancel`] events.
+    Cancel,
+    /// [`Over`] events.
+    Over,
+    /// [`Out`] events.
+    Out,
+    /// [`Press`] events.
+    Press,
+    /// [`PressRepeat`] events.
+    PressRepeat,
+    /// [`LongPress`] events.
+    LongPress,
+    /// [`Release`] events.
+    Release,
+    /// [`FirstInteraction`] events.
+    FirstInteraction,
+    /// [`Click`] events.
+    Click,
+    /// [`Move`] events.
+    Move,
+    /// [`FallbackMove`] events.
+    FallbackMove,
+    /// [`DragStart`] events.
+    DragStart,
+    /// [`Drag`] events.
+    Drag,
+    /// [`DragEnd`] events.
+    DragEnd,
+    /// [`DragCancel`] events.
+    DragCancel,
+    /// [`DragEnter`] events.
+    DragEnter,
+    /// [`DragOver`] events.
+    DragOver,
+    /// [`DragLeave`] events.
+    DragLeave,
+    /// [`DragDrop`] events.
+    DragDrop,
+    /// [`Scroll`] events.
+    Scroll,
+    /// [`BecameTopmost`] events.
+    BecameTopmost,
+    /// [`LostTopmost`] events.
+    LostTopmost,
+    /// [`HoverEnter`] events.
+    HoverEnter,
+    /// [`HoverExit`] events.
+    HoverExit,
+    /// [`Pinch`] events, emitted by the
+    /// [`PinchRotateRecognizer`](crate::gesture::PinchRotateRecognizer).
+    Pinch,
+    /// [`Rotate`] events, emitted by the
+    /// [`PinchRotateRecognizer`](crate::gesture::PinchRotateRecognizer).
+    Rotate,
+}
+
+impl PointerEventKind {
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+/// Prevents this entity from being the target of the listed kinds of [`Pointer`] events.
+///
+/// When [`pointer_events`] would trigger a masked event on this entity, the event is instead
+/// triggered on the entity it would have bubbled to next, so ancestors still receive it. This
+/// allows, for example, a list item to react to hovering while leaving drags to the scroll
+/// container it is placed in. The event is still written as a [`Message`] unchanged.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::events::{PickEventMask, PointerEventKind};
+/// # let mut world = World::default();
+/// world.spawn(PickEventMask::new([
+///     PointerEventKind::DragStart,
+///     PointerEventKind::Drag,
+///     PointerEventKind::DragEnd,
+/// ]));
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, Default, Clone, PartialEq)]
+pub struct PickEventMask {
+    masked: u32,
+}
+
+impl PickEventMask {
+    /// Creates a mask for the given event kinds.
+    pub fn new(kinds: impl IntoIterator<Item = PointerEventKind>) -> Self {
+        let mut mask = Self::default();
+        for kind in kinds {
+            mask.insert(kind);
+        }
+        mask
+    }
+
+    /// Adds `kind` to the mask.
+    pub fn insert(&mut self, kind: PointerEventKind) {
+        self.masked |= kind.bit();
+    }
+
+    /// Removes `kind` from the mask.
+    pub fn remove(&mut self, kind: PointerEventKind) {
+        self.masked &= !kind.bit();
+    }
+
+    /// Returns `true` if events of this `kind` are masked.
+    pub fn masks(&self, kind: PointerEventKind) -> bool {
+        self.masked & kind.bit() != 0
+    }
+}
+
+/// Lists the only kinds of [`Pointer`] events that are triggered on this entity.
+///
+/// Entities without this component receive every event. When [`pointer_events`] would trigger
+/// an unlisted event on this entity, the event isn't triggered at all, which saves the cost of
+/// dispatching events that nothing observes, such as the [`Move`] and [`DragOver`] events of
+/// the many entities in a dense UI that only react to [`Click`]. Unlike with a
+/// [`PickEventMask`], the event doesn't reach the ancestors of the entity either. The event is
+/// still written as a [`Message`].
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::events::{PointerEventKind, PointerEventMask};
+/// # let mut world = World::default();
+/// world.spawn(PointerEventMask::new([PointerEventKind::Click]));
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, Clone, PartialEq)]
+pub struct PointerEventMask {
+    wanted: u32,
+}
+
+impl PointerEventMask {
+    /// Creates a mask that only lets the given event kinds through.
+    pub fn new(kinds: impl IntoIterator<Item = PointerEventKind>) -> Self {
+        let mut mask = Self { wanted: 0 };
+        for kind in kinds {
+            mask.insert(kind);
+        }
+        mask
+    }
+
+    /// Lets events of this `kind` through.
+    pub fn insert(&mut self, kind: PointerEventKind) {
+        self.wanted |= kind.bit();
+    }
+
+    /// Stops events of this `kind` from being triggered on the entity.
+    pub fn remove(&mut self, kind: PointerEventKind) {
+        self.wanted &= !kind.bit();
+    }
+
+    /// Returns `true` if events of this `kind` are triggered on the entity.
+    pub fn wants(&self, kind: PointerEventKind) -> bool {
+        self.wanted & kind.bit() != 0
+    }
+}
+
+/// Reports which [`Pointer`] events [`pointer_events`] generated from a [`PointerInput`].
+///
+/// Moves are coalesced through the [`HoverMap`], so not every input results in events. This
+/// message is written once for every input read by [`pointer_events`], in the order they were
+/// read, listing the kind and original target of each event the input generated. This is
+/// intended for debugging tools and input accounting.
+///
+/// These messages are only written if they have been registered, with
+/// `app.add_message::<ProcessedPointerInput>()`. The listed events are those generated before
+/// any [`PickingLimits`], [`PointerEventAdapter`], [`PickEventMask`], or [`PointerEventMask`] was
+/// applied.
+#[derive(Message, Debug, Clone, Reflect)]
+#[reflect(Clone)]
+pub struct ProcessedPointerInput {
+    /// The input that was processed.
+    pub input: PointerInput,
+    /// The kind and target entity of each event generated by the input, in dispatch order.
+    pub events: Vec<(PointerEventKind, Entity)>,
+}
+
+impl ProcessedPointerInput {
+    /// Returns `true` if the input generated any events.
+    pub fn contributed(&self) -> bool {
+        !self.events.is_empty()
+    }
+}
+
+macro_rules! any_pointer_event {
+    ($($event:ident),* $(,)?) => {
+        /// A [`Pointer`] event of any type emitted by [`pointer_events`] or by the built-in
+        /// [gesture recognizers](crate::gesture), so that debugging tools, replay recorders, or
+        /// network forwarders can read all of them from a single stream.
+        ///
+        /// These messages are only written if they have been registered, such as by adding the
+        /// [`PointerEventMirrorPlugin`](crate::PointerEventMirrorPlugin). Each [`Pointer`] message
+        /// written by [`pointer_events`] is mirrored as it is written, so the stream keeps the
+        /// order in which the events of different types were dispatched. Like those messages,
+        /// they keep their original target even if a [`PickEventMask`] redirected the trigger.
+        #[derive(Message, Clone, Debug, Reflect)]
+        #[reflect(Clone, Debug)]
+        pub enum AnyPointerEvent {
+            $(
+                #[doc = concat!("A [`", stringify!($event), "`] event.")]
+                $event(Pointer<$event>),
+            )*
+        }
+
+        impl AnyPointerEvent {
+            /// The kind of the wrapped event.
+            pub fn kind(&self) -> PointerEventKind {
+                match self {
+                    $(Self::$event(_) => PointerEventKind::$event,)*
+                }
+            }
+
+            /// The entity the wrapped event was emitted for, see [`Pointer::entity`].
+            pub fn entity(&self) -> Entity {
+                match self {
+                    $(Self::$event(event) => event.entity,)*
+                }
+            }
+
+            /// The pointer of the wrapped event, see [`Pointer::pointer_id`].
+            pub fn pointer_id(&self) -> PointerId {
+                match self {
+                    $(Self::$event(event) => event.pointer_id,)*
+                }
+            }
+
+            /// The location of the pointer during the wrapped event, see
+            /// [`Pointer::pointer_location`].
+            pub fn pointer_location(&self) -> &Location {
+                match self {
+                    $(Self::$event(event) => &event.pointer_location,)*
+                }
+            }
+        }
+
+        $(
+            impl From<Pointer<$event>> for AnyPointerEvent {
+                fn from(event: Pointer<$event>) -> Self {
+                    Self::$event(event)
+                }
+            }
+        )*
+
+        impl PointerEventDispatcher<'_, '_, '_> {
+            /// Sends the wrapped event, see [`PointerEventDispatcher::send`].
+            fn send_any(&mut self, event: AnyPointerEvent) {
+                match event {
+                    $(AnyPointerEvent::$event(event) => self.send(event),)*
+                }
+            }
+        }
+    };
+}
+
+any_pointer_event!(
+    Cancel,
+    Over,
+    Out,
+    Press,
+    PressRepeat,
+    LongPress,
+    FirstInteraction,
+    Release,
+    Click,
+    Move,
+    FallbackMove,
+    DragStart,
+    Drag,
+    DragEnd,
+    DragCancel,
+    DragEnter,
+    DragOver,
+    DragLeave,
+    DragDrop,
+    Scroll,
+    BecameTopmost,
+    LostTopmost,
+    HoverEnter,
+    HoverExit,
+    Pinch,
+    Rotate,
+);
+
+/// Makes drags that start on this entity move another entity, such as the window it is the title
+/// bar of.
+///
+/// When a pointer presses a handle and starts moving, [`pointer_events`] starts the drag on the
+/// handle's target instead: [`DragStart`], [`Drag`], and [`DragEnd`] are triggered on the target,
+/// and the target is the [`DragEnter::dragged`] entity reported to anything it is dragged over.
+/// Pressing a target directly no longer starts a drag, so only its handles can move it.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::events::DragHandle;
+/// # let mut world = World::default();
+/// let window = world.spawn_empty().id();
+/// world.spawn((DragHandle::Parent, ChildOf(window)));
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, Default, Clone, PartialEq)]
+pub enum DragHandle {
+    /// Drag this entity's [`ChildOf`] parent.
+    #[default]
+    Parent,
+    /// Drag the given entity.
+    Target(Entity),
+}
+
+impl DragHandle {
+    /// Returns the entity dragged through this handle, if it has one.
+    pub fn target(&self, child_of: Option<&ChildOf>) -> Option<Entity> {
+        match self {
+            Self::Parent => child_of.map(ChildOf::parent),
+            Self::Target(target) => Some(*target),
+        }
+    }
+}
+
+/// Constrains the drags of this entity to one axis, such as for the thumb of a slider.
+///
+/// [`pointer_events`] zeroes the locked component of [`Drag::delta`], [`Drag::distance`], and
+/// [`Drag::velocity`], and of [`DragEnd::distance`]. The lock is read when the drag starts, so
+/// changing or removing this component only affects the next drag.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, Clone, PartialEq)]
+pub enum DragLock {
+    /// Only move along the x axis.
+    X,
+    /// Only move along the y axis.
+    Y,
+}
+
+impl DragLock {
+    /// Returns `vector` with the locked component set to zero.
+    pub fn apply(self, vector: Vec2) -> Vec2 {
+        match self {
+            Self::X => Vec2::new(vector.x, 0.0),
+            Self::Y => Vec2::new(0.0, vector.y),
+        }
+    }
+}
+
+/// Decides which events are discarded once a [`PickingLimits`] cap is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum PickingOverflowPolicy {
+    /// Keep the first events emitted during the frame, and drop any that follow.
+    #[default]
+    DropNewest,
+    /// Keep the last events emitted during the frame, and drop earlier ones.
+    ///
+    /// With this policy, [`pointer_events`] holds back all of its events until it has processed
+    /// every input for the frame, so that earlier events can still be discarded. The relative
+    /// order of the events that are kept is preserved.
+    DropOldest,
+}
+
+/// Overrides whether the kinds of [`Pointer`] events emitted by [`pointer_events`] bubble up the
+/// hierarchy.
+///
+/// Each event type chooses whether it bubbles by default, see
+/// [`PointerEventKind::auto_propagates`]: every event does, except for [`Move`] and [`DragOver`],
+/// which fire on every movement. An observer on the target can still call `propagate` to change
+/// this for a single event.
+///
+/// This resource is not inserted by default; when it is missing, or doesn't override a kind, the
+/// default of the event type is used.
+///
+/// ```
+/// # use bevy_picking::events::{PointerEventKind, PointerEventPropagation};
+/// let propagation = PointerEventPropagation::default()
+///     .with_propagation(PointerEventKind::Move, true)
+///     .with_propagation(PointerEventKind::Scroll, false);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct PointerEventPropagation {
+    overridden: u32,
+    propagated: u32,
+}
+
+impl PointerEventPropagation {
+    /// Makes events of the given `kind` bubble if `propagate` is `true`, or stop at their target
+    /// otherwise.
+    pub fn with_propagation(mut self, kind: PointerEventKind, propagate: bool) -> Self {
+        self.set(kind, propagate);
+        self
+    }
+
+    /// Makes events of the given `kind` bubble if `propagate` is `true`, or stop at their target
+    /// otherwise.
+    pub fn set(&mut self, kind: PointerEventKind, propagate: bool) {
+        self.overridden |= kind.bit();
+        if propagate {
+            self.propagated |= kind.bit();
+        } else {
+            self.propagated &= !kind.bit();
+        }
+    }
+
+    /// Restores the default propagation of events of the given `kind`.
+    pub fn reset(&mut self, kind: PointerEventKind) {
+        self.overridden &= !kind.bit();
+        self.propagated &= !kind.bit();
+    }
+
+    /// Returns whether events of the given `kind` are overridden to bubble, or `None` if they use
+    /// the default of their type.
+    pub fn get(&self, kind: PointerEventKind) -> Option<bool> {
+        (self.overridden & kind.bit() != 0).then_some(self.propagated & kind.bit() != 0)
+    }
+
+    /// Returns `true` if events of the given `kind` bubble, taking overrides into account.
+    pub fn propagates(&self, kind: PointerEventKind) -> bool {
+        self.get(kind).unwrap_or(kind.auto_propagates())
+    }
+}
+
+/// Caps the number of [`Pointer`] events of each kind that [`pointer_events`] emits for a
+/// single pointer in a single frame.
+///
+/// This is a safety valve for pathological cases, such as dense input over very large scenes,
+/// where the event volume for one frame would otherwise grow without bound. This resource is not
+/// inserted by default; when it is missing, no limits are applied.
+///
+/// ```
+/// # use bevy_picking::events::{PickingLimits, PickingOverflowPolicy, PointerEventKind};
+/// let limits = PickingLimits::default()
+///     .with_limit(PointerEventKind::Move, 16)
+///     .with_policy(PickingOverflowPolicy::DropOldest);
+/// ```
+#[derive(Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Resource, Debug, Default, Clone)]
+pub struct PickingLimits {
+    /// The maximum number of events of each kind emitted per pointer per frame. Event kinds
+    /// without an entry are not limited.
+    pub max_events_per_frame: HashMap<PointerEventKind, usize>,
+    /// Which events to drop when a limit is exceeded.
+    pub policy: PickingOverflowPolicy,
+    dropped: HashMap<PointerEventKind, u64>,
+}
+
+impl PickingLimits {
+    /// Limits events of the given `kind` to `max` per pointer per frame.
+    pub fn with_limit(mut self, kind: PointerEventKind, max: usize) -> Self {
+        self.max_events_per_frame.insert(kind, max);
+        self
+    }
+
+    /// Sets the [`PickingOverflowPolicy`].
+    pub fn with_policy(mut self, policy: PickingOverflowPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The number of events of the given `kind` dropped since the counters were last reset.
+    pub fn dropped(&self, kind: PointerEventKind) -> u64 {
+        self.dropped.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// The number of events of any kind dropped since the counters were last reset.
+    pub fn total_dropped(&self) -> u64 {
+        self.dropped.values().sum()
+    }
+
+    /// Resets all drop counters to zero.
+    pub fn reset_dropped(&mut self) {
+        self.dropped.clear();
+    }
+
+    fn record_drop(&mut self, kind: PointerEventKind) {
+        *self.dropped.entry(kind).or_default() += 1;
+    }
+}
+
+/// A [`Pointer`] event offered to a [`PointerEventAdapter`] before it reaches the world.
+#[derive(Debug, Clone, Copy)]
+pub struct ForwardedPointerEvent<'a> {
+    /// The kind of the event.
+    pub kind: PointerEventKind,
+    /// The pointer that triggered the event.
+    pub pointer_id: PointerId,
+    /// The location of the pointer during the event.
+    pub pointer_location: &'a Location,
+    /// The entity the event targets.
+    pub entity: Entity,
+    /// The event payload, such as a [`Click`]. Use
+    /// [`try_downcast_ref`](PartialReflect::try_downcast_ref) to read it.
+    pub event: &'a dyn PartialReflect,
+}
+
+/// What a [`PointerEventAdapter`] did with a [`ForwardedPointerEvent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PointerEventHandling {
+    /// The event is delivered to the world as usual.
+    #[default]
+    Ignored,
+    /// The external UI handled the event; it is neither triggered nor written as a [`Message`].
+    Consumed,
+}
+
+/// Forwards every [`Pointer`] event emitted by [`pointer_events`] to an external event model,
+/// such as a retained-mode UI drawn over the world.
+///
+/// The callback sees each event before it is triggered or written, and can return
+/// [`PointerEventHandling::Consumed`] to keep world picking from receiving it. Pointer state is
+/// still tracked for consumed events, so a drag that starts in the UI continues to be reported
+/// to the callback. This resource is not inserted by default.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::events::{Click, PointerEventAdapter, PointerEventHandling};
+/// # let mut world = World::default();
+/// world.insert_resource(PointerEventAdapter::new(|event| {
+///     // Hand clicks to the UI when they land inside its panel.
+///     match event.event.try_downcast_ref::<Click>() {
+///         Some(_) if event.pointer_location.position.x < 200.0 => PointerEventHandling::Consumed,
+///         _ => PointerEventHandling::Ignored,
+///     }
+/// }));
+/// ```
+#[derive(Resource)]
+pub struct PointerEventAdapter {
+    callback: Box<dyn FnMut(ForwardedPointerEvent) -> PointerEventHandling + Send + Sync>,
+    consumed: HashMap<PointerEventKind, u64>,
+}
+
+impl PointerEventAdapter {
+    /// Creates an adapter that forwards events to `callback`.
+    pub fn new(
+        callback: impl FnMut(ForwardedPointerEvent) -> PointerEventHandling + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            callback: Box::new(callback),
+            consumed: HashMap::default(),
+        }
+    }
+
+    /// The number of events of the given `kind` consumed since the counters were last reset.
+    pub fn consumed(&self, kind: PointerEventKind) -> u64 {
+        self.consumed.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// Resets all consumed counters to zero.
+    pub fn reset_consumed(&mut self) {
+        self.consumed.clear();
+    }
+
+    /// Offers `event` to the callback, returning `true` if it was consumed.
+    fn forward<E: PickingEvent>(&mut self, event: &Pointer<E>) -> bool {
+        let handling = (self.callback)(ForwardedPointerEvent {
+            kind: E::KIND,
+            pointer_id: event.pointer_id,
+            pointer_location: &event.pointer_location,
+            entity: event.entity,
+            event: &event.event,
+        });
+        if handling == PointerEventHandling::Consumed {
+            *self.consumed.entry(E::KIND).or_default() += 1;
+            return true;
+        }
+        false
+    }
+}
+
+impl Debug for PointerEventAdapter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PointerEventAdapter")
+            .field("consumed", &self.consumed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A pointer event type emitted by [`pointer_events`].
+trait PickingEvent: Debug + Clone + Reflect {
+    const KIND: PointerEventKind;
+
+    /// Whether the [`Pointer`] events of this type bubble up the hierarchy, unless a
+    /// [`PointerEventPropagation`] overrides it.
+    const AUTO_PROPAGATE: bool = true;
+
+    fn writer<'a, 'w>(
+        writers: &'a mut PickingMessageWriters<'w>,
+    ) -> &'a mut MessageWriter<'w, Pointer<Self>>;
+
+    fn mirror(event: Pointer<Self>) -> AnyPointerEvent;
+
+    fn batched(batch: &mut TriggerBatch) -> &mut VecDeque<Pointer<Self>>;
+}
+
+macro_rules! impl_picking_event {
+    ($($event:ident => $writer:ident $((AUTO_PROPAGATE = $auto_propagate:literal))?),* $(,)?) => {
+        $(
+            impl PickingEvent for $event {
+                const KIND: PointerEventKind = PointerEventKind::$event;
+                $(const AUTO_PROPAGATE: bool = $auto_propagate;)?
+
+                fn writer<'a, 'w>(
+                    writers: &'a mut PickingMessageWriters<'w>,
+                ) -> &'a mut MessageWriter<'w, Pointer<Self>> {
+                    &mut writers.$writer
+                }
+
+                fn mirror(event: Pointer<Self>) -> AnyPointerEvent {
+                    AnyPointerEvent::$event(event)
+                }
+
+                fn batched(batch: &mut TriggerBatch) -> &mut VecDeque<Pointer<Self>> {
+                    &mut batch.$writer
+                }
+            }
+        )*
+
+        /// The observer triggers of a frame's [`Pointer`] events, applied in order by a single
+        /// command.
+        ///
+        /// The events are stored by type, so that queuing them doesn't box each one, and the
+        /// buffers are reused from frame to frame through the [`TriggerBuffers`].
+        #[derive(Default)]
+        struct TriggerBatch {
+            /// The kind of each event, in the order they are triggered.
+            order: Vec<PointerEventKind>,
+            $($writer: VecDeque<Pointer<$event>>,)*
+        }
+
+        impl TriggerBatch {
+            /// Triggers the next event of the given `kind`.
+            fn trigger_next(&mut self, kind: PointerEventKind, world: &mut World) {
+                match kind {
+                    $(PointerEventKind::$event => {
+                        if let Some(event) = self.$writer.pop_front() {
+                            trigger(world, event);
+                        }
+                    })*
+                }
+            }
+        }
+
+        impl PointerEventKind {
+            /// Returns `true` if events of this kind bubble up the hierarchy by default.
+            ///
+            /// A [`PointerEventPropagation`] can override this.
+            pub const fn auto_propagates(self) -> bool {
+                match self {
+                    $(Self::$event => <$event as PickingEvent>::AUTO_PROPAGATE,)*
+                }
+            }
+        }
+    };
+}
+
+impl_picking_event!(
+    Cancel => cancel_events,
+    Click => click_events,
+    Press => pressed_events,
+    PressRepeat => press_repeat_events,
+    LongPress => long_press_events,
+    FirstInteraction => first_interaction_events,
+    DragDrop => drag_drop_events,
+    DragEnd => drag_end_events,
+    DragCancel => drag_cancel_events,
+    DragEnter => drag_enter_events,
+    Drag => drag_events,
+    DragLeave => drag_leave_events,
+    DragOver => drag_over_events (AUTO_PROPAGATE = false),
+    DragStart => drag_start_events,
+    Scroll => scroll_events,
+    Move => move_events (AUTO_PROPAGATE = false),
+    FallbackMove => fallback_move_events,
+    Out => out_events,
+    Over => over_events,
+    Release => released_events,
+    BecameTopmost => became_topmost_events,
+    LostTopmost => lost_topmost_events,
+    HoverEnter => hover_enter_events,
+    HoverExit => hover_exit_events,
+    Pinch => pinch_events,
+    Rotate => rotate_events,
+);
+
+type PendingEvent<'w> = Box<dyn FnOnce(&mut TriggerBatch, &mut PickingMessageWriters<'w>)>;
+
+/// Triggers `event`, reading whether it bubbles from the [`PointerEventPropagation`], and falling
+/// back to the default of `E`.
+fn trigger<E: PickingEvent>(world: &mut World, event: Pointer<E>) {
+    let mut trigger = PropagateEntityTrigger::default();
+    trigger.propagate = world
+        .get_resource::<PointerEventPropagation>()
+        .and_then(|propagation| propagation.get(E::KIND))
+        .unwrap_or(E::AUTO_PROPAGATE);
+    world.trigger_with(event, trigger);
+}
+
+/// Holds the [`TriggerBatch`] buffers between frames, so that their allocations are reused.
+#[derive(Resource, Default)]
+pub(crate) struct TriggerBuffers(TriggerBatch);
+
+impl TriggerBatch {
+    fn push<E: PickingEvent>(&mut self, event: Pointer<E>) {
+        self.order.push(E::KIND);
+        E::batched(self).push_back(event);
+    }
+
+    /// Triggers the events in the order they were pushed. The commands queued by the observers of
+    /// each event are applied before the next one is triggered, exactly as if every event had been
+    /// triggered by its own command.
+    fn apply(&mut self, world: &mut World) {
+        let mut order = core::mem::take(&mut self.order);
+        for kind in order.drain(..) {
+            self.trigger_next(kind, world);
+            world.flush();
+        }
+        self.order = order;
+    }
+}
+
+/// The data needed to redirect events away from entities with a [`PickEventMask`], and to skip
+/// the events unwanted by a [`PointerEventMask`].
+#[derive(QueryData)]
+pub struct PickEventMaskTraversal {
+    mask: Option<&'static PickEventMask>,
+    wanted: Option<&'static PointerEventMask>,
+    child_of: Option<&'static ChildOf>,
+    is_window: Has<Window>,
+}
+
+/// Triggers and writes the events produced by [`pointer_events`], applying [`PickingLimits`],
+/// [`PickEventMask`]s and [`PointerEventMask`]s.
+struct PointerEventDispatcher<'l, 'w, 's> {
+    commands: Commands<'w, 's>,
+    writers: PickingMessageWriters<'w>,
+    limits: Option<&'l mut PickingLimits>,
+    adapter: Option<&'l mut PointerEventAdapter>,
+    masks: &'l Query<'w, 's, PickEventMaskTraversal>,
+    counts: HashMap<(PointerId, PointerEventKind), usize>,
+    queued: HashMap<(PointerId, PointerEventKind), VecDeque<usize>>,
+    pending: Vec<Option<PendingEvent<'w>>>,
+    triggers: TriggerBatch,
+    processed: Option<ProcessedPointerInput>,
+    modifiers: HashMap<PointerId, PointerModifiers>,
+    /// The time of the frame, reported by the events that aren't caused by an input.
+    now: Instant,
+    /// The time of the current input.
+    time: Instant,
+}
+
+impl<'l, 'w, 's> PointerEventDispatcher<'l, 'w, 's> {
+    fn new(
+        commands: Commands<'w, 's>,
+        writers: PickingMessageWriters<'w>,
+        limits: Option<&'l mut PickingLimits>,
+        adapter: Option<&'l mut PointerEventAdapter>,
+        masks: &'l Query<'w, 's, PickEventMaskTraversal>,
+        modifiers: HashMap<PointerId, PointerModifiers>,
+        now: Instant,
+    ) -> Self {
+        let mut writers = writers;
+        let triggers = writers
+            .trigger_buffers
+            .as_deref_mut()
+            .map(|buffers| core::mem::take(&mut buffers.0))
+            .unwrap_or_default();
+        Self {
+            commands,
+            writers,
+            limits,
+            adapter,
+            masks,
+            counts: HashMap::default(),
+            queued: HashMap::default(),
+            pending: Vec::new(),
+            triggers,
+            processed: None,
+            modifiers,
+            now,
+            time: now,
+        }
+    }
+
+    /// Attributes the events sent from now on to `input`, reporting the previous input.
+    fn begin_input(&mut self, input: &PointerInput) {
+        self.end_input();
+        self.modifiers.insert(input.pointer_id, input.modifiers);
+        self.time = input.time.unwrap_or(self.now);
+        if self.writers.processed_inputs.is_some() {
+            self.processed = Some(ProcessedPointerInput {
+                input: input.clone(),
+                events: Vec::new(),
+            });
+        }
+    }
+
+    /// Writes the [`ProcessedPointerInput`] of the current input, if any.
+    fn end_input(&mut self) {
+        self.time = self.now;
+        if let Some(processed) = self.processed.take()
+            && let Some(messages) = self.writers.processed_inputs.as_deref_mut()
+        {
+            messages.write(processed);
+        }
+    }
+
+    fn send<E: PickingEvent>(&mut self, mut event: Pointer<E>) {
+        event.modifiers = self
+            .modifiers
+            .get(&event.pointer_id)
+            .copied()
+            .unwrap_or_default();
+        event.pointer_time = self.time;
+        if let Some(processed) = self.processed.as_mut() {
+            processed.events.push((E::KIND, event.entity));
+        }
+        if let Some(adapter) = self.adapter.as_deref_mut()
+            && adapter.forward(&event)
+        {
+            return;
+        }
+        let target = self.trigger_target(&event);
+        let Some(limits) = self.limits.as_deref_mut() else {
+            Self::emit(&mut self.triggers, &mut self.writers, event, target);
+            return;
+        };
+        let key = (event.pointer_id, E::KIND);
+        let max = limits.max_events_per_frame.get(&E::KIND).copied();
+
+        match limits.policy {
+            PickingOverflowPolicy::DropNewest => {
+                let count = self.counts.entry(key).or_default();
+                if max.is_some_and(|max| *count >= max) {
+                    limits.record_drop(E::KIND);
+                    return;
+                }
+                *count += 1;
+                Self::emit(&mut self.triggers, &mut self.writers, event, target);
+            }
+            PickingOverflowPolicy::DropOldest => {
+                let index = self.pending.len();
+                self.pending.push(Some(Box::new(move |triggers, writers| {
+                    Self::emit(triggers, writers, event, target);
+                })));
+                let Some(max) = max else {
+                    return;
+                };
+                let queued = self.queued.entry(key).or_default();
+                queued.push_back(index);
+                if queued.len() > max
+                    && let Some(oldest) = queued.pop_front()
+                {
+                    self.pending[oldest] = None;
+                    limits.record_drop(E::KIND);
+                }
+            }
+        }
+    }
+
+    /// Finds the entity an event should be triggered on, skipping past any entity that masks
+    /// it in the same way the event would bubble, or `None` if the entity doesn't want it.
+    fn trigger_target<E: PickingEvent>(&self, event: &Pointer<E>) -> Option<Entity> {
+        let mut entity = event.entity;
+        loop {
+            let Ok(item) = self.masks.get(entity) else {
+                return Some(entity);
+            };
+            if !item.mask.is_some_and(|mask| mask.masks(E::KIND)) {
+                return item
+                    .wanted
+                    .is_none_or(|wanted| wanted.wants(E::KIND))
+                    .then_some(entity);
+            }
+            entity = match (item.child_of, &event.pointer_location.target) {
+                (Some(child_of), _) => child_of.parent(),
+                (None, NormalizedRenderTarget::Window(window)) if !item.is_window => {
+                    window.entity()
+                }
+                _ => return None,
+            };
+        }
+    }
+
+    fn emit<E: PickingEvent>(
+        triggers: &mut TriggerBatch,
+        writers: &mut PickingMessageWriters<'w>,
+        event: Pointer<E>,
+        target: Option<Entity>,
+    ) {
+        if let Some(target) = target {
+            let mut triggered = event.clone();
+            triggered.entity = target;
+            triggers.push(triggered);
+        }
+        if let Some(any_events) = writers.any_events.as_deref_mut() {
+            any_events.write(E::mirror(event.clone()));
+        }
+        E::writer(writers).write(event);
+    }
+
+    /// Emits any events held back by [`PickingOverflowPolicy::DropOldest`], and queues the
+    /// frame's [`TriggerBatch`].
+    fn flush(&mut self) {
+        for pending in self.pending.drain(..).flatten() {
+            pending(&mut self.triggers, &mut self.writers);
+        }
+        let mut triggers = core::mem::take(&mut self.triggers);
+        if triggers.order.is_empty() {
+            if let Some(buffers) = self.writers.trigger_buffers.as_deref_mut() {
+                buffers.0 = triggers;
+            }
+            return;
+        }
+        self.commands.queue(move |world: &mut World| {
+            triggers.apply(world);
+            world.insert_resource(TriggerBuffers(triggers));
+        });
+    }
+}
+
+/// Dispatches the [`Pointer`] events sent by [`GestureRecognizer`]s after [`pointer_events`], with
+/// the same [`PickingLimits`], [`PickEventMask`]s, [`PointerEventMask`]s, [`PointerEventAdapter`],
+/// [`PointerEventPropagation`] and [`AnyPointerEvent`] mirror as the events it emits.
+///
+/// [`GestureRecognizer`]: crate::gesture::GestureRecognizer
+#[derive(SystemParam)]
+pub struct GestureEventDispatcher<'w, 's> {
+    commands: Commands<'w, 's>,
+    writers: PickingMessageWriters<'w>,
+    limits: Option<ResMut<'w, PickingLimits>>,
+    adapter: Option<ResMut<'w, PointerEventAdapter>>,
+    masks: Query<'w, 's, PickEventMaskTraversal>,
+}
+
+impl GestureEventDispatcher<'_, '_> {
+    /// Dispatches `events`, in order, each at the time of the input it was recognized from.
+    pub(crate) fn dispatch(
+        mut self,
+        pointer_state: &PointerState,
+        now: Instant,
+        events: Vec<(Instant, AnyPointerEvent)>,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        let mut dispatcher = PointerEventDispatcher::new(
+            self.commands,
+            self.writers,
+            self.limits.as_deref_mut(),
+            self.adapter.as_deref_mut(),
+            &self.masks,
+            pointer_state.modifiers.clone(),
+            now,
+        );
+        for (time, event) in events {
+            dispatcher.time = time;
+            dispatcher.send_any(event);
+        }
+        dispatcher.flush();
+    }
+}
+
+/// Aborts every drag of a pointer button with [`DragCancel`], followed by [`DragLeave`] for the
+/// entities it was dragged over, and forgets the presses that started the drags.
+fn cancel_drags(
+    state: &mut PointerButtonState,
+    pointer_id: PointerId,
+    button: PointerButton,
+    location: &Location,
+    drag_handles: &EntityHashMap<Entity>,
+    dispatcher: &mut PointerEventDispatcher,
+) {
+    let mut dragging: Vec<_> = state.dragging.drain().collect();
+    dragging.sort_unstable_by_key(|(drag_target, _)| *drag_target);
+    let dragging_over = sorted_hits(Some(&state.dragging_over));
+    for (drag_target, drag) in dragging {
+        let drag_cancel_event = Pointer::new(
+            pointer_id,
+            location.clone(),
+            DragCancel {
+                button,
+                distance: drag.locked(drag.latest_pos - drag.start_pos),
+            },
+            drag_target,
+        );
+        dispatcher.send(drag_cancel_event);
+        for (dragged_over, hit) in &dragging_over {
+            let drag_leave_event = Pointer::new(
+                pointer_id,
+                location.clone(),
+                DragLeave {
+                    button,
+                    dragged: drag_target,
+                    hit: hit.clone(),
+                },
+                *dragged_over,
+            );
+            dispatcher.send(drag_leave_event);
+        }
+        state.pressing.retain(|press_target, _| {
+            drag_handles.get(press_target).unwrap_or(press_target) != &drag_target
+        });
+    }
+    state.dragging_over.clear();
+}
+
+/// Returns the hovered entity nearest to the camera, as described by [`BecameTopmost`].
+pub(crate) fn topmost_hit(hits: Option<&HashMap<Entity, HitData>>) -> Option<(Entity, &HitData)> {
+    hits?
+        .iter()
+        .min_by(|(a, a_hit), (b, b_hit)| {
+            FloatOrd(a_hit.depth)
+                .cmp(&FloatOrd(b_hit.depth))
+                .then_with(|| a.cmp(b))
+        })
+        .map(|(entity, hit)| (*entity, hit))
+}
+
+/// Splits the entities whose subtree a pointer crossed into chains, each running from a deepest
+/// entity up to its outermost crossed ancestor, so that a single event bubbling along each chain
+/// reaches every entity once. The chains are sorted by their deepest entity.
+fn subtree_chains(
+    crossed: &EntityHashSet,
+    parent: impl Fn(Entity) -> Option<Entity>,
+) -> Vec<(Entity, Entity)> {
+    let parents: EntityHashSet = crossed
+        .iter()
+        .filter_map(|entity| parent(*entity))
+        .collect();
+    let mut deepest: Vec<_> = crossed
+        .iter()
+        .filter(|entity| !parents.contains(*entity))
+        .copied()
+        .collect();
+    deepest.sort_unstable();
+    let mut reached = EntityHashSet::default();
+    deepest
+        .into_iter()
+        .map(|deepest| {
+            reached.insert(deepest);
+            let mut outermost = deepest;
+            while let Some(parent) = parent(outermost)
+                && crossed.contains(&parent)
+                && reached.insert(parent)
+            {
+                outermost = parent;
+            }
+            (deepest, outermost)
+        })
+        .collect()
+}
+
+/// Returns the hits sorted by [`Entity`], so that events emitted for several entities at
+/// once don't depend on [`HashMap`] iteration order.
+fn sorted_hits(hits: Option<&HashMap<Entity, HitData>>) -> Vec<(Entity, HitData)> {
+    let mut hits: Vec<_> = hits
+        .into_iter()
+        .flat_map(|hits| hits.iter().map(|(entity, hit)| (*entity, hit.clone())))
+        .collect();
+    hits.sort_unstable_by_key(|(entity, _)| *entity);
+    hits
+}
+
+/// Returns the hits of every pointer, sorted by [`PointerId`] and then by [`Entity`].
+fn sorted_hover_map(
+    hover_map: &HashMap<PointerId, HashMap<Entity, HitData>>,
+) -> Vec<(PointerId, Entity, HitData)> {
+    let mut pointer_ids: Vec<_> = hover_map.keys().copied().collect();
+    pointer_ids.sort_unstable();
+    pointer_ids
+        .into_iter()
+        .flat_map(|pointer_id| {
+            sorted_hits(hover_map.get(&pointer_id))
+                .into_iter()
+                .map(move |(entity, hit)| (pointer_id, entity, hit))
+        })
+        .collect()
+}
+
+/// Returns the keys of a map of per-entity state, sorted.
+fn sorted_entities<V>(map: &HashMap<Entity, V>) -> Vec<Entity> {
+    let mut entities: Vec<_> = map.keys().copied().collect();
+    entities.sort_unstable();
+    entities
+}
+
+/// The hit of an event routed to a capturing `entity`: where the pointer hovers it, or else where
+/// one of the `buttons` pressed it, or else a hit without a camera or position.
+fn capture_hit<'a>(
+    entity: Entity,
+    hovered: Option<&HashMap<Entity, HitData>>,
+    buttons: impl IntoIterator<Item = &'a PointerButtonState>,
+) -> HitData {
+    hovered
+        .and_then(|hovered| hovered.get(&entity))
+        .or_else(|| {
+            buttons
+                .into_iter()
+                .find_map(|state| state.pressing.get(&entity).map(|(_, _, hit)| hit))
+        })
+        .cloned()
+        .unwrap_or_else(|| HitData::new(Entity::PLACEHOLDER, 0.0, None, None))
+}
+
+/// Dispatches interaction events to the target entities.
+///
+/// Within a single frame, events are dispatched in the following order:
+/// + For each drag aborted with [`PointerState::cancel_drag`]: [`DragCancel`] → [`DragLeave`].
+/// + For each drag of a pointer removed since the last run: [`DragEnd`] → [`DragLeave`].
+/// + [`Out`] → [`DragLeave`] → [`HoverExit`].
+/// + [`DragEnter`] → [`Over`] → [`HoverEnter`].
+/// + Any number of any of the following:
+///   + For each movement: [`DragStart`] → [`Drag`] → [`DragOver`] → [`Move`], or
+///     [`FallbackMove`] if the pointer hovers nothing.
+///   + For each button press: [`Press`] or [`Click`], each followed by [`FirstInteraction`] if it is
+///     the entity's first → [`Release`] → [`DragDrop`] → [`DragEnd`] → [`DragLeave`].
+///   + For each pointer cancellation: [`Cancel`] → [`DragCancel`] → [`DragLeave`].
+/// + For each drag of a pointer that didn't move, with [`DragSettings::continuous_drag_over`]:
+///   [`DragOver`].
+/// + For each held button, once its [`RepeatSettings`] allow: [`PressRepeat`].
+/// + For each button held long enough, once per press: [`LongPress`].
+///
+/// Events of the same kind emitted for several pointers or entities at once are ordered by
+/// [`PointerId`], and then by target [`Entity`], rather than by the iteration order of the
+/// [`HoverMap`] and the [`PointerState`]. The order of events is therefore the same every time the
+/// same input is replayed over the same entities.
+///
+/// Additionally, across multiple frames, the following are also strictly
+/// ordered by the interaction state machine:
+/// + When a pointer moves over the target:
+///   [`Over`], [`Move`], [`Out`].
+/// + When a pointer moves over the target or its descendants:
+///   [`HoverEnter`], [`HoverExit`].
+/// + When a pointer presses buttons on the target:
+///   [`Press`], [`PressRepeat`] and [`LongPress`], [`Click`], [`Release`].
+/// + When a pointer drags the target:
+///   [`DragStart`], [`Drag`], and then either [`DragEnd`] or [`DragCancel`].
+/// + When a pointer drags something over the target:
+///   [`DragEnter`], [`DragOver`], [`DragDrop`], [`DragLeave`].
+/// + When a pointer is canceled:
+///   No other events will follow the [`Cancel`] event for that pointer, except the
+///   [`DragCancel`] and [`DragLeave`] events aborting its drags.
+///
+/// The hover events -- [`Over`], [`Out`], [`HoverEnter`], and [`HoverExit`] -- are driven only by
+/// the [`HoverMap`].
+/// When a pointer is removed from the [`PointerMap`], the entities it hovered receive a final
+/// [`Out`] and [`HoverExit`] at its last known location, and its presses and captures are
+/// forgotten, so that nothing stays hovered or pressed by it.
+/// The rest rely on additional data from the [`PointerInput`] event stream. To
+/// receive these events for a custom pointer, you must add [`PointerInput`]
+/// events.
+///
+/// When the pointer goes from hovering entity A to entity B, entity A will
+/// receive [`Out`] and then entity B will receive [`Over`]. No entity will ever
+/// receive both an [`Over`] and an [`Out`] event during the same frame.
+///
+/// When we account for event bubbling, this is no longer true. When the hovering focus shifts
+/// between children, parent entities may receive redundant [`Out`] → [`Over`] pairs.
+/// In the context of UI, this is especially problematic. Observe [`HoverEnter`] and [`HoverExit`]
+/// instead, which only fire when the pointer crosses into or out of the subtree of an entity.
+///
+/// Both [`Click`] and [`Release`] target the entity hovered in the *previous frame*,
+/// rather than the current frame. This is because touch pointers hover nothing
+/// on the frame they are released. The end effect is that these two events can
+/// be received sequentially after an [`Out`] event (but always on the same frame
+/// as the [`Out`] event).
+///
+/// Note: Though it is common for the [`PointerInput`] stream may contain
+/// multiple pointer movements and presses each frame, the hover state is
+/// determined only by the pointer's *final position*. Since the hover state
+/// ultimately determines which entities receive events, this may mean that an
+/// entity can receive events from before or after it was actually hovered.
+///
+/// If a [`PickingLimits`] resource is present, the number of events of each kind emitted per
+/// pointer in a frame is capped according to its settings.
+///
+/// Events are never triggered on an entity whose [`PickEventMask`] masks them; they are
+/// triggered on the next entity up the hierarchy instead. Events not listed by the
+/// [`PointerEventMask`] of the entity they would be triggered on aren't triggered at all.
+///
+/// If a [`PointerEventAdapter`] resource is present, every event is first offered to it, and
+/// events it consumes are dropped.
+///
+/// If a [`DragResumeSettings`] resource is present, drags resuming after a gap in the input of
+/// their pointer are clamped, reset, or canceled according to its policy.
+///
+/// If a [`FallbackMoveTarget`] resource is present, moves of pointers that hover nothing are
+/// reported to its entity with [`FallbackMove`] events.
+///
+/// If a [`MoveSettings`] resource enables it, the moves of each pointer in a frame are merged into
+/// one.
+///
+/// If a [`VelocitySettings`] resource is present, the motion of the pointers is tracked, and their
+/// velocity reported in [`Over`] events.
+///
+/// If [`ProcessedPointerInput`] messages are registered, one is written for each input read,
+/// listing the events it generated.
+pub fn pointer_events(
+    // Input
+    mut input_events: MessageReader<PointerInput>,
+    // ECS State
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    pointer_map: Res<PointerMap>,
+    hover_map: Res<HoverMap>,
+    previous_hover_map: Res<PreviousHoverMap>,
+    mut pointer_state: ResMut<PointerState>,
+    mut pointer_activity: ResMut<PointerActivity>,
+    mut limits: Option<ResMut<PickingLimits>>,
+    mut adapter: Option<ResMut<PointerEventAdapter>>,
+    masks: Query<PickEventMaskTraversal>,
+    drag_components: DragComponents,
+    mut first_interactions: FirstInteractionTracker,
+    settings: PointerEventSettings,
+    // Output
+    mut commands: Commands,
+    message_writers: PickingMessageWriters,
+    mut capture_changed_events: MessageWriter<PointerCaptureChanged>,
+) {
+    let mut report_capture_changes = |pointer_state: &mut PointerState, commands: &mut Commands| {
+        for change in pointer_state.take_capture_changes() {
+            capture_changed_events.write(change);
+            commands.trigger(change);
+        }
+    };
+    // Report captures changed since the last run, e.g. by observers of last frame's events.
+    report_capture_changes(&mut pointer_state, &mut commands);
+
+    first_interactions.prune();
+
+    // Setup utilities
+    let now = settings.clock.now();
+    let mut dispatcher = PointerEventDispatcher::new(
+        commands,
+        message_writers,
+        limits.as_deref_mut(),
+        adapter.as_deref_mut(),
+        &masks,
+        core::mem::take(&mut pointer_state.modifiers),
+        now,
+    );
+    let pointer_location = |pointer_id: PointerId| {
+        pointer_map
+            .get_entity(pointer_id)
+            .and_then(|entity| pointers.get(entity).ok())
+            .and_then(|(_, pointer)| pointer.location.clone())
+    };
+
+    // Find the pointers removed since the last run, along with their last known location.
+    let mut removed_pointers = HashMap::<PointerId, Location>::default();
+    pointer_state.last_locations.retain(|pointer_id, location| {
+        let removed = pointer_map.get_entity(*pointer_id).is_none();
+        if removed {
+            removed_pointers.insert(*pointer_id, location.clone());
+        }
+        !removed
+    });
+    for (pointer_id, pointer) in &pointers {
+        if let Some(location) = &pointer.location {
+            pointer_state
+                .last_locations
+                .insert(*pointer_id, location.clone());
+        }
+    }
+    let is_removed = |pointer_id: &PointerId| removed_pointers.contains_key(pointer_id);
+    // The events leaving the entities of removed pointers are reported at their last location.
+    let exit_location = |pointer_id: PointerId| {
+        pointer_location(pointer_id).or_else(|| removed_pointers.get(&pointer_id).cloned())
+    };
+
+    // Track the motion of the pointers, for their velocity.
+    if let Some(velocity) = settings.velocity.as_deref() {
+        for (pointer_id, pointer) in &pointers {
+            if let Some(location) = &pointer.location {
+                pointer_state.record_motion(*pointer_id, location.position, now, velocity.window);
+            }
+        }
+    }
+
+    let drag_handles: EntityHashMap<Entity> = drag_components
+        .handles
+        .iter()
+        .filter_map(|(handle, drag_handle, child_of)| {
+            drag_handle.target(child_of).map(|target| (handle, target))
+        })
+        .collect();
+    let handle_targets: EntityHashSet = drag_handles.values().copied().collect();
+
+    // Abort the drags canceled since the last run.
+    for (pointer_id, button) in core::mem::take(&mut pointer_state.canceled_drags) {
+        let location = pointer_location(pointer_id);
+        let state = pointer_state.get_mut(pointer_id, button);
+        match location {
+            Some(location) => cancel_drags(
+                state,
+                pointer_id,
+                button,
+                &location,
+                &drag_handles,
+                &mut dispatcher,
+            ),
+            // Without a location, there is nothing to report the events at.
+            None => state.clear(),
+        }
+    }
+
+    // End the drags of the removed pointers, without dropping them.
+    let mut removed: Vec<_> = removed_pointers.iter().collect();
+    removed.sort_unstable_by_key(|(pointer_id, _)| **pointer_id);
+    for (pointer_id, location) in removed {
+        for button in PointerButton::iter() {
+            let Some(state) = pointer_state
+                .pointer_buttons
+                .get_mut(&(*pointer_id, button))
+            else {
+                continue;
+            };
+            let mut dragging: Vec<_> = state.dragging.drain().collect();
+            dragging.sort_unstable_by_key(|(drag_target, _)| *drag_target);
+            let dragging_over = sorted_hits(Some(&state.dragging_over));
+            for (drag_target, drag) in dragging {
+                dispatcher.send(Pointer::new(
+                    *pointer_id,
+                    location.clone(),
+                    DragEnd {
+                        button,
+                        distance: drag.locked(drag.latest_pos - drag.start_pos),
+                    },
+                    drag_target,
+                ));
+                for (dragged_over, hit) in &dragging_over {
+                    dispatcher.send(Pointer::new(
+                        *pointer_id,
+                        location.clone(),
+                        DragLeave {
+                            button,
+                            dragged: drag_target,
+                            hit: hit.clone(),
+                        },
+                        *dragged_over,
+                    ));
+                }
+            }
+            state.dragging_over.clear();
+        }
+    }
+
+    // Diff the subtrees hovered by each pointer against the last frame.
+    let parent = |entity| {
+        masks
+            .get(entity)
+            .ok()
+            .and_then(|item| item.child_of)
+            .map(ChildOf::parent)
+    };
+    let mut subtree_pointers: Vec<_> = hover_map
+        .keys()
+        .chain(pointer_state.hovered_subtrees.keys())
+        .copied()
+        .collect();
+    subtree_pointers.sort_unstable();
+    subtree_pointers.dedup();
+    let mut exited_subtrees = Vec::new();
+    let mut entered_subtrees = Vec::new();
+    for pointer_id in subtree_pointers {
+        let mut subtree = EntityHashSet::default();
+        for hovered_entity in hover_map
+            .get(&pointer_id)
+            .filter(|_| !is_removed(&pointer_id))
+            .into_iter()
+            .flat_map(HashMap::keys)
+        {
+            let mut entity = Some(*hovered_entity);
+            while let Some(current) = entity
+                && subtree.insert(current)
+            {
+                entity = parent(current);
+            }
+        }
+        let previous = pointer_state
+            .hovered_subtrees
+            .remove(&pointer_id)
+            .unwrap_or_default();
+        let exited = previous.difference(&subtree).copied().collect();
+        let entered = subtree.difference(&previous).copied().collect();
+        exited_subtrees.extend(
+            subtree_chains(&exited, parent)
+                .into_iter()
+                .map(|chain| (pointer_id, chain)),
+        );
+        entered_subtrees.extend(
+            subtree_chains(&entered, parent)
+                .into_iter()
+                .map(|chain| (pointer_id, chain)),
+        );
+        if !subtree.is_empty() {
+            pointer_state.hovered_subtrees.insert(pointer_id, subtree);
+        }
+    }
+
+    // If the entity was hovered by a specific pointer last frame...
+    for (pointer_id, hovered_entity, hit) in sorted_hover_map(&previous_hover_map) {
+        // ...but is now not being hovered by that same pointer, or the pointer was removed...
+        if is_removed(&pointer_id)
+            || !hover_map
+                .get(&pointer_id)
+                .iter()
+                .any(|e| e.contains_key(&hovered_entity))
+        {
+            let Some(location) = exit_location(pointer_id) else {
+                debug!(
+                    "Unable to get location for pointer {:?} during pointer out",
+                    pointer_id
+                );
+                continue;
+            };
+
+            // Always send Out events
+            let out_event = Pointer::new(
+                pointer_id,
+                location.clone(),
+                Out { hit: hit.clone() },
+                hovered_entity,
+            );
+            dispatcher.send(out_event);
+
+            // Possibly send DragLeave events
+            for button in PointerButton::iter() {
+                let state = pointer_state.get_mut(pointer_id, button);
+                state.dragging_over.remove(&hovered_entity);
+                for drag_target in sorted_entities(&state.dragging) {
+                    let drag_leave_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        DragLeave {
+                            button,
+                            dragged: drag_target,
+                            hit: hit.clone(),
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(drag_leave_event);
+                }
+            }
+        }
+    }
+
+    // Emit HoverExit once for each subtree the pointers left.
+    for (pointer_id, (deepest, outermost)) in exited_subtrees {
+        let Some(location) = exit_location(pointer_id) else {
+            continue;
+        };
+        // The deepest entity may not have been hovered itself, for example when its hovered
+        // descendant was despawned, so fall back to the pointer's nearest previous hit.
+        let previous_hits = previous_hover_map.get(&pointer_id);
+        let hit = previous_hits
+            .and_then(|hovered| hovered.get(&deepest))
+            .or_else(|| {
+                previous_hits?
+                    .values()
+                    .min_by(|a, b| a.depth.total_cmp(&b.depth))
+            })
+            .cloned()
+            .unwrap_or_else(|| HitData::new(Entity::PLACEHOLDER, f32::INFINITY, None, None));
+        dispatcher.send(Pointer::new(
+            pointer_id,
+            location,
+            HoverExit { hit, outermost },
+            deepest,
+        ));
+    }
+
+    // Iterate all currently hovered entities for each pointer
+    for (pointer_id, hovered_entity, hit) in sorted_hover_map(&hover_map) {
+        // Continue if the pointer does not have a valid location.
+        let Some(location) = pointer_location(pointer_id) else {
+            debug!(
+                "Unable to get location for pointer {:?} during pointer over",
+                pointer_id
+            );
+            continue;
+        };
+
+        // For each button update its `dragging_over` state and possibly emit DragEnter events.
+        for button in PointerButton::iter() {
+            let state = pointer_state.get_mut(pointer_id, button);
+
+            // Only update the `dragging_over` state if there is at least one entity being dragged.
+            // Only emit DragEnter events for this `hovered_entity`, if it had no previous `dragging_over` state.
+            if !state.dragging.is_empty()
+                && state
+                    .dragging_over
+                    .insert(hovered_entity, hit.clone())
+                    .is_none()
+            {
+                for drag_target in sorted_entities(&state.dragging) {
+                    let drag_enter_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        DragEnter {
+                            button,
+                            dragged: drag_target,
+                            hit: hit.clone(),
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(drag_enter_event);
+                }
+            }
+        }
+
+        // Emit an Over event if the `hovered_entity` was not hovered by the same pointer the previous frame.
+        if !previous_hover_map
+            .get(&pointer_id)
+            .iter()
+            .any(|e| e.contains_key(&hovered_entity))
+        {
+            let over_event = Pointer::new(
+                pointer_id,
+                location.clone(),
+                Over {
+                    hit: hit.clone(),
+                    velocity: pointer_state.velocity(pointer_id),
+                },
+                hovered_entity,
+            );
+            dispatcher.send(over_event);
+        }
+    }
+
+    // Emit HoverEnter once for each subtree the pointers entered.
+    for (pointer_id, (deepest, outermost)) in entered_subtrees {
+        let Some(location) = pointer_location(pointer_id) else {
+            continue;
+        };
+        let Some(hit) = hover_map
+            .get(&pointer_id)
+            .and_then(|hovered| hovered.get(&deepest))
+        else {
+            continue;
+        };
+        dispatcher.send(Pointer::new(
+            pointer_id,
+            location,
+            HoverEnter {
+                hit: hit.clone(),
+                outermost,
+            },
+            deepest,
+        ));
+    }
+
+    // Emit LostTopmost and BecameTopmost where the frontmost hovered entity of a pointer changed.
+    let mut pointer_ids: Vec<_> = previous_hover_map
+        .keys()
+        .chain(hover_map.keys())
+        .copied()
+        .collect();
+    pointer_ids.sort_unstable();
+    pointer_ids.dedup();
+    for pointer_id in pointer_ids {
+        let previous = topmost_hit(previous_hover_map.get(&pointer_id));
+        let current = topmost_hit(hover_map.get(&pointer_id));
+        if previous.map(|(entity, _)| entity) == current.map(|(entity, _)| entity) {
+            continue;
+        }
+        let Some(location) = pointer_location(pointer_id) else {
+            continue;
+        };
+        if let Some((entity, hit)) = previous {
+            let lost_topmost_event = Pointer::new(
+                pointer_id,
+                location.clone(),
+                LostTopmost { hit: hit.clone() },
+                entity,
+            );
+            dispatcher.send(lost_topmost_event);
+        }
+        if let Some((entity, hit)) = current {
+            let became_topmost_event = Pointer::new(
+                pointer_id,
+                location,
+                BecameTopmost { hit: hit.clone() },
+                entity,
+            );
+            dispatcher.send(became_topmost_event);
+        }
+    }
+
+    // Dispatch input events...
+    let mut frame_deltas = HashMap::<PointerId, Vec2>::default();
+    let (coalesced, inputs) = if settings.moves.is_some_and(|moves| moves.coalesce_moves) {
+        (coalesce_moves(input_events.read()), None)
+    } else {
+        (Vec::new(), Some(input_events.read()))
+    };
+    for input in coalesced.iter().chain(inputs.into_iter().flatten()) {
+        dispatcher.begin_input(input);
+        let PointerInput {
+            pointer_id,
+            location,
+            action,
+            ..
+        } = input.clone();
+        // Inputs that aren't timestamped happened when they are read.
+        let time = input.time.unwrap_or(now);
+        let idle = pointer_activity.idle_duration(pointer_id, time);
+        if !matches!(action, PointerAction::Cancel) {
+            pointer_activity.record(pointer_id, time);
+        }
+        match action {
+            PointerAction::Press(button) => {
+                let state = pointer_state.get_mut(pointer_id, button);
+                state.press_drift = 0.0;
+
+                // If it's a press, emit a Pressed event and mark the hovered entities as pressed
+                for (hovered_entity, hit) in sorted_hits(hover_map.get(&pointer_id)) {
+                    let pressed_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        Press {
+                            button,
+                            hit: hit.clone(),
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(pressed_event);
+                    if first_interactions.record(hovered_entity) {
+                        dispatcher.send(Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            FirstInteraction {
+                                button,
+                                hit: hit.clone(),
+                            },
+                            hovered_entity,
+                        ));
+                    }
+                    // Also insert the press into the state
+                    state
+                        .pressing
+                        .insert(hovered_entity, (location.clone(), time, hit));
+                    if let Some(repeat) = settings.repeat.as_deref() {
+                        state
+                            .repeating
+                            .insert(hovered_entity, (time + repeat.delay, 0));
+                    }
+                }
+            }
+            PointerAction::Release(button) => {
+                let state = pointer_state.get_mut(pointer_id, button);
+                let click_settings = settings.click.as_deref().copied().unwrap_or_default();
+                let multi_click_window = click_settings.multi_click_window;
+                state.record_press_drift(location.position);
+                let within_tolerance = state.press_drift <= click_settings.click_tolerance;
+                // Only the entities clicked by this release can be clicked again in a row.
+                let previous_clicks = core::mem::take(&mut state.clicks);
+
+                // Emit Click and Release events on all the previously hovered entities, or only on
+                // the entity capturing the button.
+                let released = match state.captured {
+                    Some(captured) => vec![(
+                        captured,
+                        capture_hit(captured, previous_hover_map.get(&pointer_id), [&*state]),
+                    )],
+                    None => sorted_hits(previous_hover_map.get(&pointer_id)),
+                };
+                for (hovered_entity, hit) in released.iter().cloned() {
+                    // If this pointer previously pressed the hovered entity without moving too far,
+                    // emit a Click event
+                    if within_tolerance
+                        && let Some((_, press_instant, _)) = state.pressing.get(&hovered_entity)
+                    {
+                        let count = match previous_clicks.get(&hovered_entity) {
+                            Some((last_click, count))
+                                if time.saturating_duration_since(*last_click)
+                                    <= multi_click_window =>
+                            {
+                                count + 1
+                            }
+                            _ => 1,
+                        };
+                        state.clicks.insert(hovered_entity, (time, count));
+                        let click_event = Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            Click {
+                                button,
+                                hit: hit.clone(),
+                                duration: time.saturating_duration_since(*press_instant),
+                                count,
+                            },
+                            hovered_entity,
+                        );
+                        dispatcher.send(click_event);
+                        if first_interactions.record(hovered_entity) {
+                            dispatcher.send(Pointer::new(
+                                pointer_id,
+                                location.clone(),
+                                FirstInteraction {
+                                    button,
+                                    hit: hit.clone(),
+                                },
+                                hovered_entity,
+                            ));
+                        }
+                    }
+                    // Always send the Release event
+                    let released_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        Release {
+                            button,
+                            hit: hit.clone(),
+                            duration: state.pressing.get(&hovered_entity).map(
+                                |(_, press_instant, _)| {
+                                    time.saturating_duration_since(*press_instant)
+                                },
+                            ),
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(released_event);
+                }
+
+                // Pressed entities that are no longer hovered are released with their press hit.
+                if settings
+                    .press
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default()
+                    .release_pressed_entities
+                {
+                    let mut unhovered: Vec<_> = state
+                        .pressing
+                        .iter()
+                        .filter(|(entity, _)| {
+                            !released.iter().any(|(released, _)| released == *entity)
+                        })
+                        .map(|(entity, (_, press_instant, hit))| {
+                            (
+                                *entity,
+                                hit.clone(),
+                                time.saturating_duration_since(*press_instant),
+                            )
+                        })
+                        .collect();
+                    unhovered.sort_by_key(|(entity, ..)| *entity);
+                    for (pressed_entity, hit, duration) in unhovered {
+                        dispatcher.send(Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            Release {
+                                button,
+                                hit,
+                                duration: Some(duration),
+                            },
+                            pressed_entity,
+                        ));
+                    }
+                }
+
+                // Then emit the drop events.
+                let mut dragging: Vec<_> = state.dragging.drain().collect();
+                dragging.sort_unstable_by_key(|(drag_target, _)| *drag_target);
+                let dragging_over = sorted_hits(Some(&state.dragging_over));
+                for (drag_target, drag) in dragging {
+                    // Emit DragDrop
+                    for (dragged_over, hit) in &dragging_over {
+                        let drag_drop_event = Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            DragDrop {
+                                button,
+                                dropped: drag_target,
+                                hit: hit.clone(),
+                            },
+                            *dragged_over,
+                        );
+                        dispatcher.send(drag_drop_event);
+                    }
+                    // Emit DragEnd
+                    let drag_end_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        DragEnd {
+                            button,
+                            distance: drag.locked(drag.latest_pos - drag.start_pos),
+                        },
+                        drag_target,
+                    );
+                    dispatcher.send(drag_end_event);
+                    // Emit DragLeave
+                    for (dragged_over, hit) in &dragging_over {
+                        let drag_leave_event = Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            DragLeave {
+                                button,
+                                dragged: drag_target,
+                                hit: hit.clone(),
+                            },
+                            *dragged_over,
+                        );
+                        dispatcher.send(drag_leave_event);
+                    }
+                }
+
+                // Finally, we can clear the state of everything relating to presses or drags.
+                let retained = state.captured.filter(|_| state.capture_retained);
+                state.clear();
+                if retained.is_some() {
+                    state.captured = retained;
+                    state.capture_retained = true;
+                }
+            }
+            // Moved
+            PointerAction::Move { delta } => {
+                if delta == Vec2::ZERO {
+                    continue; // If delta is zero, the following events will not be triggered.
+                }
+                let frame_delta = *frame_deltas
+                    .entry(pointer_id)
+                    .and_modify(|frame_delta| *frame_delta += delta)
+                    .or_insert(delta);
+                let drag_threshold = settings
+                    .drag
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default()
+                    .drag_threshold;
+                // Triggers during movement even if not over an entity
+                for button in PointerButton::iter() {
+                    let state = pointer_state.get_mut(pointer_id, button);
+                    state.record_press_drift(location.position);
+
+                    // Emit DragEntry and DragStart the first time we move far enough while
+                    // pressing an entity
+                    for press_target in sorted_entities(&state.pressing) {
+                        let (press_location, press_time, hit) = &state.pressing[&press_target];
+                        if drag_threshold > 0.0
+                            && location.position.distance(press_location.position) <= drag_threshold
+                        {
+                            continue;
+                        }
+                        let drag_target = match (state.captured, drag_handles.get(&press_target)) {
+                            // The capturing entity is dragged instead of the pressed entities
+                            (Some(captured), _) => captured,
+                            (None, Some(target)) => *target,
+                            // Entities with handles can only be dragged through them
+                            (None, None) if handle_targets.contains(&press_target) => continue,
+                            (None, None) => press_target,
+                        };
+                        if state.dragging.contains_key(&drag_target) {
+                            continue; // This entity is already logged as being dragged
+                        }
+                        // The drag starts from the press, so that the first Drag covers the
+                        // movement up to the threshold.
+                        state.dragging.insert(
+                            drag_target,
+                            DragEntry {
+                                start_pos: press_location.position,
+                                latest_pos: press_location.position,
+                                latest_time: *press_time,
+                                velocity: Vec2::ZERO,
+                                lock: drag_components.locks.get(drag_target).ok().copied(),
+                                camera: hit.camera,
+                            },
+                        );
+                        let drag_start_event = Pointer::new(
+                            pointer_id,
+                            press_location.clone(),
+                            DragStart {
+                                button,
+                                hit: hit.clone(),
+                            },
+                            drag_target,
+                        );
+
+                        dispatcher.send(drag_start_event);
+
+                        // Insert dragging over state and emit DragEnter for hovered entities.
+                        for (hovered_entity, hit) in sorted_hits(hover_map.get(&pointer_id))
+                            .into_iter()
+                            .filter(|(hovered_entity, _)| {
+                                *hovered_entity != drag_target
+                                    && drag_handles.get(hovered_entity) != Some(&drag_target)
+                            })
+                        {
+                            // Inserting the `dragging_over` state here ensures the `DragEnter` event won't be dispatched twice.
+                            state.dragging_over.insert(hovered_entity, hit.clone());
+                            let drag_enter_event = Pointer::new(
+                                pointer_id,
+                                press_location.clone(),
+                                DragEnter {
+                                    button,
+                                    dragged: drag_target,
+                                    hit: hit.clone(),
+                                },
+                                hovered_entity,
+                            );
+                            dispatcher.send(drag_enter_event);
+                        }
+                    }
+
+                    // Emit Drag events to the entities we are dragging
+                    for drag_target in sorted_entities(&state.dragging) {
+                        let drag = state.dragging.get_mut(&drag_target).unwrap();
+                        let mut delta = location.position - drag.latest_pos;
+                        if delta == Vec2::ZERO {
+                            continue; // No need to emit a Drag event if there is no movement
+                        }
+                        if let Some(resume) = settings.drag_resume.as_deref()
+                            && resume.is_gap(idle, delta)
+                        {
+                            match resume.policy {
+                                DragResumePolicy::Clamp => {
+                                    let clamped = delta.clamp_length_max(resume.max_jump);
+                                    // Keeps the distance equal to the sum of the deltas.
+                                    drag.start_pos += delta - clamped;
+                                    delta = clamped;
+                                }
+                                DragResumePolicy::Reset => {
+                                    drag.start_pos += delta;
+                                    drag.latest_pos = location.position;
+                                    drag.latest_time = time;
+                                    continue;
+                                }
+                                DragResumePolicy::Cancel => {
+                                    let distance = drag.locked(drag.latest_pos - drag.start_pos);
+                                    state.dragging.remove(&drag_target);
+                                    // Forget the press, so that the drag doesn't start again.
+                                    state.pressing.retain(|press_target, _| {
+                                        drag_handles.get(press_target).unwrap_or(press_target)
+                                            != &drag_target
+                                    });
+                                    let drag_cancel_event = Pointer::new(
+                                        pointer_id,
+                                        location.clone(),
+                                        DragCancel { button, distance },
+                                        drag_target,
+                                    );
+                                    dispatcher.send(drag_cancel_event);
+                                    for (dragged_over, hit) in
+                                        sorted_hits(Some(&state.dragging_over))
+                                    {
+                                        let drag_leave_event = Pointer::new(
+                                            pointer_id,
+                                            location.clone(),
+                                            DragLeave {
+                                                button,
+                                                dragged: drag_target,
+                                                hit,
+                                            },
+                                            dragged_over,
+                                        );
+                                        dispatcher.send(drag_leave_event);
+                                    }
+                                    if state.dragging.is_empty() {
+                                        state.dragging_over.clear();
+                                    }
+                                    continue;
+                                }
+                            }
+                        }
+                        let delta = drag.locked(delta);
+                        // Moves at the same instant keep the last known velocity.
+                        let dt = time
+                            .saturating_duration_since(drag.latest_time)
+                            .as_secs_f32();
+                        if dt > 0.0 {
+                            drag.velocity = delta / dt;
+                        }
+                        let distance = drag.locked(location.position - drag.start_pos);
+                        let world_distance =
+                            drag_components.world_offset(drag.camera, drag.start_pos, distance);
+                        let world_delta =
+                            drag_components.world_offset(drag.camera, drag.latest_pos, delta);
+                        // Update drag position
+                        drag.latest_pos = location.position;
+                        drag.latest_time = time;
+
+                        let drag_event = Pointer::new(
+                            pointer_id,
+                            location.clone(),
+                            Drag {
+                                button,
+                                distance,
+                                delta,
+                                velocity: drag.velocity,
+                                world_distance,
+                                world_delta,
+                            },
+                            drag_target,
+                        );
+                        dispatcher.send(drag_event);
+
+                        // Emit corresponding DragOver to the hovered entities
+                        for (hovered_entity, hit) in sorted_hits(hover_map.get(&pointer_id))
+                            .into_iter()
+                            .filter(|(hovered_entity, _)| {
+                                *hovered_entity != drag_target
+                                    && drag_handles.get(hovered_entity) != Some(&drag_target)
+                            })
+                        {
+                            let drag_over_event = Pointer::new(
+                                pointer_id,
+                                location.clone(),
+                                DragOver {
+                                    button,
+                                    dragged: drag_target,
+                                    hit: hit.clone(),
+                                },
+                                hovered_entity,
+                            );
+                            dispatcher.send(drag_over_event);
+                        }
+                    }
+                }
+
+                // Moves are routed to the entities capturing a button of the pointer, if any.
+                let mut captured: Vec<_> = PointerButton::iter()
+                    .filter_map(|button| pointer_state.captured(pointer_id, button))
+                    .collect();
+                captured.sort_unstable();
+                captured.dedup();
+                let moved = if captured.is_empty() {
+                    sorted_hits(hover_map.get(&pointer_id))
+                } else {
+                    let buttons = || {
+                        PointerButton::iter()
+                            .filter_map(|button| pointer_state.get(pointer_id, button))
+                    };
+                    captured
+                        .into_iter()
+                        .map(|entity| {
+                            (
+                                entity,
+                                capture_hit(entity, hover_map.get(&pointer_id), buttons()),
+                            )
+                        })
+                        .collect()
+                };
+                for (hovered_entity, hit) in moved {
+                    // Emit Move events to the entities we are hovering or that captured the pointer
+                    let move_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        Move {
+                            hit: hit.clone(),
+                            delta,
+                            frame_delta,
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(move_event);
+                }
+
+                // Report moves over nothing to the fallback target
+                if let Some(target) = settings.fallback_move_target.as_deref()
+                    && hover_map.get(&pointer_id).is_none_or(HashMap::is_empty)
+                {
+                    dispatcher.send(Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        FallbackMove { delta, frame_delta },
+                        target.0,
+                    ));
+                }
+            }
+            PointerAction::Scroll { delta, unit } => {
+                for (hovered_entity, hit) in sorted_hits(hover_map.get(&pointer_id)) {
+                    // Emit Scroll events to the entities we are hovering
+                    let scroll_event = Pointer::new(
+                        pointer_id,
+                        location.clone(),
+                        Scroll {
+                            delta,
+                            unit,
+                            hit: hit.clone(),
+                        },
+                        hovered_entity,
+                    );
+                    dispatcher.send(scroll_event);
+                }
+            }
+            // Canceled
+            PointerAction::Cancel => {
+                // Emit a Cancel to the hovered entity.
+                for (hovered_entity, hit) in sorted_hits(hover_map.get(&pointer_id)) {
+                    let cancel_event =
+                        Pointer::new(pointer_id, location.clone(), Cancel { hit }, hovered_entity);
+                    dispatcher.send(cancel_event);
+                }
+                // Abort the drags of the canceled pointer
+                for button in PointerButton::iter() {
+                    cancel_drags(
+                        pointer_state.get_mut(pointer_id, button),
+                        pointer_id,
+                        button,
+                        &location,
+                        &drag_handles,
+                        &mut dispatcher,
+                    );
+                }
+                // Clear the state for the canceled pointer
+                pointer_state.clear(pointer_id);
+            }
+        }
+    }
+    dispatcher.end_input();
+
+    // Emit DragOver events for the drags of the pointers that held still this frame.
+    if settings
+        .drag
+        .as_deref()
+        .is_some_and(|drag| drag.continuous_drag_over)
+    {
+        let mut pointer_buttons: Vec<_> = pointer_state
+            .pointer_buttons
+            .iter()
+            .filter(|((pointer_id, _), state)| {
+                !state.dragging.is_empty() && !frame_deltas.contains_key(pointer_id)
+            })
+            .collect();
+        pointer_buttons.sort_unstable_by_key(|(key, _)| **key);
+        for ((pointer_id, button), state) in pointer_buttons {
+            let Some(location) = pointer_location(*pointer_id) else {
+                continue;
+            };
+            let dragging_over = sorted_hits(Some(&state.dragging_over));
+            for drag_target in sorted_entities(&state.dragging) {
+                for (dragged_over, hit) in dragging_over.iter().filter(|(dragged_over, _)| {
+                    *dragged_over != drag_target
+                        && drag_handles.get(dragged_over) != Some(&drag_target)
+                }) {
+                    let drag_over_event = Pointer::new(
+                        *pointer_id,
+                        location.clone(),
+                        DragOver {
+                            button: *button,
+                            dragged: drag_target,
+                            hit: hit.clone(),
+                        },
+                        *dragged_over,
+                    );
+                    dispatcher.send(drag_over_event);
+                }
+            }
+        }
+    }
+
+    // Emit PressRepeat events on the held entities that are still hovered.
+    if let Some(repeat) = settings.repeat.as_deref() {
+        let mut pointer_buttons: Vec<_> = pointer_state.pointer_buttons.iter_mut().collect();
+        pointer_buttons.sort_unstable_by_key(|(key, _)| **key);
+        for ((pointer_id, button), state) in pointer_buttons {
+            let hovered = hover_map.get(pointer_id);
+            // Moving off a pressed entity stops its repeats.
+            state
+                .repeating
+                .retain(|entity, _| hovered.is_some_and(|hovered| hovered.contains_key(entity)));
+            let Some(location) = pointer_location(*pointer_id) else {
+                continue;
+            };
+            let mut due: Vec<_> = state
+                .repeating
+                .iter_mut()
+                .filter(|(_, (next, _))| *next <= now)
+                .collect();
+            due.sort_unstable_by_key(|(entity, _)| **entity);
+            for (entity, (next, count)) in due {
+                *next += repeat.interval;
+                *count += 1;
+                let Some(hit) = hovered.and_then(|hovered| hovered.get(entity)) else {
+                    continue;
+                };
+                let press_repeat_event = Pointer::new(
+                    *pointer_id,
+                    location.clone(),
+                    PressRepeat {
+                        button: *button,
+                        hit: hit.clone(),
+                        count: *count,
+                    },
+                    *entity,
+                );
+                dispatcher.send(press_repeat_event);
+            }
+        }
+    }
+
+    // Emit LongPress events on the entities held long enough without being dragged.
+    let long_press_duration = settings
+        .press
+        .as_deref()
+        .copied()
+        .unwrap_or_default()
+        .long_press_duration;
+    let mut pointer_buttons: Vec<_> = pointer_state.pointer_buttons.iter_mut().collect();
+    pointer_buttons.sort_unstable_by_key(|(key, _)| **key);
+    for ((pointer_id, button), state) in pointer_buttons {
+        let PointerButtonState {
+            pressing,
+            dragging,
+            long_pressed,
+            ..
+        } = state;
+        long_pressed.extend(dragging.keys().copied());
+        let mut due: Vec<_> = pressing
+            .iter()
+            .filter(|(entity, (_, press_instant, _))| {
+                !long_pressed.contains(*entity)
+                    && now.saturating_duration_since(*press_instant) >= long_press_duration
+            })
+            .map(|(entity, (_, press_instant, _))| {
+                (*entity, now.saturating_duration_since(*press_instant))
+            })
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+        due.sort_unstable_by_key(|(entity, _)| *entity);
+        let Some(location) = pointer_location(*pointer_id) else {
+            continue;
+        };
+        let hovered = hover_map.get(pointer_id);
+        for (entity, duration) in due {
+            long_pressed.insert(entity);
+            // Moving off a pressed entity prevents its long press.
+            let Some(hit) = hovered.and_then(|hovered| hovered.get(&entity)) else {
+                continue;
+            };
+            dispatcher.send(Pointer::new(
+                *pointer_id,
+                location.clone(),
+                LongPress {
+                    button: *button,
+                    hit: hit.clone(),
+                    duration,
+                },
+                entity,
+            ));
+        }
+    }
+
+    // Forget the presses and captures of the removed pointers.
+    for pointer_id in removed_pointers.keys() {
+        pointer_state.clear(*pointer_id);
+    }
+
+    dispatcher.flush();
+    report_capture_changes(&mut pointer_state, &mut dispatcher.commands);
+    pointer_state.modifiers = dispatcher.modifiers;
+    // Evict the removed pointers once their release has been reported, so that pointers that come
+    // and go, like touches, don't accumulate.
+    pointer_state.retain_pointers(|pointer_id| pointer_map.get_entity(pointer_id).is_some());
+}
+
+/// Summarizes the interactions of every pointer at the end of [`pointer_events`], written by
+/// [`summarize_interactions`].
 ///
-/// When we account for event bubbling, this is no longer true. When the hovering focus shifts
-/// between children, parent entities may receive redundant [`Out`] → [`Over`] pairs.
-/// In the context of UI, this is especially problematic. Additional hierarchy-aware
-/// events will be added in a future release.
+/// This collects the hovered, pressed, and dragged entities from the [`HoverMap`] and the
+/// [`PointerState`] into a single message, for systems running after picking that need the final
+/// state of the frame, and for debugging tools.
+#[derive(Message, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct PickingFrameSummary {
+    /// The interactions of each pointer that interacts with at least one entity, sorted by
+    /// [`PointerId`].
+    pub pointers: Vec<PointerInteractionSummary>,
+}
+
+impl PickingFrameSummary {
+    /// Returns the interactions of `pointer_id`, if it interacts with any entity.
+    pub fn get(&self, pointer_id: PointerId) -> Option<&PointerInteractionSummary> {
+        self.pointers
+            .iter()
+            .find(|summary| summary.pointer == pointer_id)
+    }
+}
+
+/// The interactions of a single pointer in a [`PickingFrameSummary`].
 ///
-/// Both [`Click`] and [`Release`] target the entity hovered in the *previous frame*,
-/// rather than the current frame. This is because touch pointers hover nothing
-/// on the frame they are released. The end effect is that these two events can
-/// be received sequentially after an [`Out`] event (but always on the same frame
-/// as the [`Out`] event).
+/// All the entities are sorted, by button first where there is one.
+#[derive(Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub struct PointerInteractionSummary {
+    /// The pointer these interactions belong to.
+    pub pointer: PointerId,
+    /// The entities hovered by the pointer.
+    pub hovered: Vec<Entity>,
+    /// The entities pressed by each button of the pointer.
+    pub pressed: Vec<(PointerButton, Entity)>,
+    /// The entities dragged by each button of the pointer.
+    pub dragged: Vec<(PointerButton, Entity)>,
+}
+
+impl PointerInteractionSummary {
+    fn clear(&mut self) {
+        self.hovered.clear();
+        self.pressed.clear();
+        self.dragged.clear();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.hovered.is_empty() && self.pressed.is_empty() && self.dragged.is_empty()
+    }
+}
+
+/// Merges the moves of each pointer that aren't separated by another input of that pointer into the
+/// first of them, with their summed delta and the location of the last one.
+fn coalesce_moves<'a>(inputs: impl Iterator<Item = &'a PointerInput>) -> Vec<PointerInput> {
+    let mut coalesced: Vec<PointerInput> = Vec::new();
+    // The index of the move each pointer is merging its next moves into.
+    let mut merging = HashMap::<PointerId, usize>::default();
+    for input in inputs {
+        if let PointerAction::Move { delta } = input.action {
+            if let Some(&index) = merging.get(&input.pointer_id) {
+                let merged = &mut coalesced[index];
+                merged.location = input.location.clone();
+                merged.modifiers = input.modifiers;
+                merged.time = input.time;
+                if let PointerAction::Move {
+                    delta: merged_delta,
+                } = &mut merged.action
+                {
+                    *merged_delta += delta;
+                }
+                continue;
+            }
+            merging.insert(input.pointer_id, coalesced.len());
+        } else {
+            merging.remove(&input.pointer_id);
+        }
+        coalesced.push(input.clone());
+    }
+    coalesced
+}
+
+/// Removes the despawned entities from the [`PointerState`], before [`pointer_events`] runs.
 ///
-/// Note: Though it is common for the [`PointerInput`] stream may contain
-/// multiple pointer movements and presses each frame, the hover state is
-/// determined only by the pointer's *final position*. Since the hover state
-/// ultimately determines which entities receive events, this may mean that an
-/// entity can receive events from before or after it was actually hovered.
-pub fn pointer_events(
-    // Input
-    mut input_events: MessageReader<PointerInput>,
-    // ECS State
-    pointers: Query<&PointerLocation>,
-    pointer_map: Res<PointerMap>,
+/// A pressed or dragged entity that is despawned is forgotten without any further events, such as
+/// [`DragEnd`] or [`Click`], since they could no longer be triggered on it. Entities it was dragged
+/// over don't receive a [`DragDrop`] or [`DragLeave`] for it either. A capture held by a despawned
+/// entity is released, and reported with [`PointerCaptureChanged`].
+pub fn clear_despawned_pointer_state(mut pointer_state: ResMut<PointerState>, entities: &Entities) {
+    let spawned = |entity: Entity| entities.contains_spawned(entity);
+    let state = pointer_state.bypass_change_detection();
+    let mut changed = false;
+    for button_state in state.pointer_buttons.values_mut() {
+        changed |= button_state.retain_entities(spawned);
+    }
+    for subtree in state.hovered_subtrees.values_mut() {
+        subtree.retain(|entity| spawned(*entity));
+    }
+    if changed {
+        pointer_state.set_changed();
+    }
+}
+
+/// Writes a [`PickingFrameSummary`] of the interactions left by [`pointer_events`].
+///
+/// The summaries are assembled in a scratch space and moved into the message, rather than copied,
+/// so each of their buffers is only allocated once.
+pub fn summarize_interactions(
+    hover_map: Res<HoverMap>,
+    pointer_state: Res<PointerState>,
+    mut scratch: Local<Vec<PointerInteractionSummary>>,
+    mut summaries: MessageWriter<PickingFrameSummary>,
+) {
+    let mut pointer_ids: Vec<_> = hover_map
+        .keys()
+        .chain(
+            pointer_state
+                .pointer_buttons
+                .keys()
+                .map(|(pointer, _)| pointer),
+        )
+        .copied()
+        .collect();
+    pointer_ids.sort_unstable();
+    pointer_ids.dedup();
+
+    let mut count = 0;
+    for pointer in pointer_ids {
+        if scratch.len() == count {
+            scratch.push(PointerInteractionSummary::default());
+        }
+        let summary = &mut scratch[count];
+        summary.clear();
+        summary.pointer = pointer;
+        if let Some(hits) = hover_map.get(&pointer) {
+            summary.hovered.extend(hits.keys().copied());
+        }
+        for button in PointerButton::iter() {
+            let Some(state) = pointer_state.get(pointer, button) else {
+                continue;
+            };
+            summary
+                .pressed
+                .extend(state.pressing.keys().map(|entity| (button, *entity)));
+            summary
+                .dragged
+                .extend(state.dragging.keys().map(|entity| (button, *entity)));
+        }
+        if summary.is_empty() {
+            continue;
+        }
+        summary.hovered.sort_unstable();
+        summary.pressed.sort_unstable();
+        summary.dragged.sort_unstable();
+        count += 1;
+    }
+
+    // Pointers skipped as empty leave their summaries behind, to be reused next frame.
+    summaries.write(PickingFrameSummary {
+        pointers: scratch.drain(..count).collect(),
+    });
+}
+
+/// Reports [`PointerHoverStarted`] and [`PointerHoverNothing`] for each pointer whose hover set
+/// went from empty to non-empty, or the reverse, between the [`PreviousHoverMap`] and the
+/// [`HoverMap`].
+pub fn pointer_hover_transitions(
     hover_map: Res<HoverMap>,
     previous_hover_map: Res<PreviousHoverMap>,
-    mut pointer_state: ResMut<PointerState>,
-    // Output
     mut commands: Commands,
-    mut message_writers: PickingMessageWriters,
+    mut hover_started_events: MessageWriter<PointerHoverStarted>,
+    mut hover_nothing_events: MessageWriter<PointerHoverNothing>,
 ) {
-    // Setup utilities
-    let now = Instant::now();
-    let pointer_location = |pointer_id: PointerId| {
-        pointer_map
-            .get_entity(pointer_id)
-            .and_then(|entity| pointers.get(entity).ok())
-            .and_then(|pointer| pointer.location.clone())
+    let is_hovering = |map: &HashMap<PointerId, HashMap<Entity, HitData>>, pointer| {
+        map.get(&pointer).is_some_and(|hits| !hits.is_empty())
     };
+    let mut pointer_ids: Vec<_> = previous_hover_map
+        .keys()
+        .chain(hover_map.keys())
+        .copied()
+        .collect();
+    pointer_ids.sort_unstable();
+    pointer_ids.dedup();
+    for pointer in pointer_ids {
+        match (
+            is_hovering(&previous_hover_map, pointer),
+            is_hovering(&hover_map, pointer),
+        ) {
+            (true, false) => {
+                let event = PointerHoverNothing { pointer };
+                hover_nothing_events.write(event);
+                commands.trigger(event);
+            }
+            (false, true) => {
+                let event = PointerHoverStarted { pointer };
+                hover_started_events.write(event);
+                commands.trigger(event);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use std::sync::Mutex;
+
+    use bevy_app::App;
+    use bevy_camera::{Camera, NormalizedRenderTarget, RenderTargetInfo};
+    use bevy_math::{Mat4, UVec2, Vec3};
+
+    use super::*;
+    use crate::{handlers::PickHandlers, pointer::update_pointer_map, InteractionPlugin};
+
+    fn location(position: Vec2) -> Location {
+        Location {
+            target: NormalizedRenderTarget::None {
+                width: 800,
+                height: 600,
+            },
+            position,
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Moves(Vec<(Entity, Vec2)>);
+
+    /// Sets up an app where the mouse pointer hovers `count` entities, in this frame and the last.
+    fn hovering_app(count: usize) -> (App, Vec<Entity>) {
+        let mut app = App::new();
+        app.add_plugins(InteractionPlugin)
+            .add_message::<PointerInput>()
+            .init_resource::<PointerMap>()
+            .init_resource::<Moves>();
+        let world = app.world_mut();
+        world.spawn((PointerId::Mouse, PointerLocation::new(location(Vec2::ZERO))));
+        world.run_system_cached(update_pointer_map).unwrap();
+        world.add_observer(|event: On<Pointer<Move>>, mut moves: ResMut<Moves>| {
+            moves.0.push((event.entity, event.delta));
+        });
+
+        let camera = world.spawn(Camera::default()).id();
+        let entities: Vec<Entity> = (0..count).map(|_| world.spawn_empty().id()).collect();
+        let hits: HashMap<Entity, HitData> = entities
+            .iter()
+            .map(|entity| (*entity, HitData::new(camera, 0.0, None, None)))
+            .collect();
+        world
+            .resource_mut::<HoverMap>()
+            .insert(PointerId::Mouse, hits.clone());
+        world
+            .resource_mut::<PreviousHoverMap>()
+            .insert(PointerId::Mouse, hits);
+        (app, entities)
+    }
+
+    fn send_moves(app: &mut App, count: usize) {
+        for i in 1..=count {
+            let delta = Vec2::new(i as f32, 0.0);
+            app.world_mut().write_message(PointerInput::new(
+                PointerId::Mouse,
+                location(delta),
+                PointerAction::Move { delta },
+            ));
+        }
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+    }
+
+    #[test]
+    fn move_frame_delta_sums_coalesced_moves() {
+        let (mut app, _) = hovering_app(1);
+
+        send_moves(&mut app, 3);
+
+        let frame_deltas: Vec<_> = app
+            .world()
+            .resource::<Messages<Pointer<Move>>>()
+            .iter_current_update_messages()
+            .map(|event| event.frame_delta.x)
+            .collect();
+        assert_eq!(frame_deltas, vec![1.0, 3.0, 6.0]);
+    }
+
+    #[test]
+    fn fallback_move_fires_only_while_nothing_is_hovered() {
+        let (mut app, _) = hovering_app(0);
+        let fallback_moves = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<FallbackMove>>>()
+                .iter_current_update_messages()
+                .map(|event| (event.entity, event.delta.x))
+                .collect::<Vec<_>>()
+        };
+
+        // Without the resource, moves over nothing aren't reported.
+        send_moves(&mut app, 1);
+        assert!(fallback_moves(&app).is_empty());
+
+        let fallback = app.world_mut().spawn_empty().id();
+        app.insert_resource(FallbackMoveTarget(fallback));
+        send_moves(&mut app, 1);
+        assert_eq!(fallback_moves(&app), vec![(fallback, 1.0)]);
+        assert!(app.world().resource::<Moves>().0.is_empty());
+
+        // A hovered entity receives the move instead.
+        app.world_mut()
+            .resource_mut::<Messages<Pointer<FallbackMove>>>()
+            .clear();
+        let hovered = app.world_mut().spawn_empty().id();
+        app.world_mut().resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            HashMap::from([(hovered, HitData::new(Entity::PLACEHOLDER, 0.0, None, None))]),
+        );
+        send_moves(&mut app, 1);
+        assert!(fallback_moves(&app).is_empty());
+        assert_eq!(app.world().resource::<Moves>().0, vec![(hovered, Vec2::X)]);
+    }
+
+    #[test]
+    fn coalesced_moves_sum_their_deltas_once_per_frame() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(MoveSettings {
+            coalesce_moves: true,
+        });
+        let move_to = |app: &mut App, x: f32, previous: f32| {
+            send_input(
+                app,
+                Vec2::new(x, 0.0),
+                PointerAction::Move {
+                    delta: Vec2::new(x - previous, 0.0),
+                },
+            );
+        };
+        let moves = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<Move>>>()
+                .iter_current_update_messages()
+                .map(|event| (event.pointer_location.position.x, event.delta.x))
+                .collect::<Vec<_>>()
+        };
+        let drags = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<Drag>>>()
+                .iter_current_update_messages()
+                .map(|event| (event.distance.x, event.delta.x))
+                .collect::<Vec<_>>()
+        };
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        move_to(&mut app, 4.0, 0.0);
+        move_to(&mut app, 6.0, 4.0);
+        move_to(&mut app, 10.0, 6.0);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(moves(&app), vec![(10.0, 10.0)]);
+        assert_eq!(drags(&app), vec![(10.0, 10.0)]);
+        assert_eq!(
+            app.world().resource::<Moves>().0,
+            vec![(entities[0], Vec2::X * 10.0)]
+        );
+
+        // The distance of the drag is still measured from its start, across frames.
+        app.world_mut()
+            .resource_mut::<Messages<Pointer<Move>>>()
+            .clear();
+        app.world_mut()
+            .resource_mut::<Messages<Pointer<Drag>>>()
+            .clear();
+        move_to(&mut app, 11.0, 10.0);
+        move_to(&mut app, 13.0, 11.0);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(moves(&app), vec![(13.0, 3.0)]);
+        assert_eq!(drags(&app), vec![(13.0, 3.0)]);
+
+        // Moves separated by another input aren't merged.
+        app.world_mut()
+            .resource_mut::<Messages<Pointer<Move>>>()
+            .clear();
+        move_to(&mut app, 14.0, 13.0);
+        send_input(
+            &mut app,
+            Vec2::new(14.0, 0.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+        move_to(&mut app, 15.0, 14.0);
+        move_to(&mut app, 17.0, 15.0);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(moves(&app), vec![(14.0, 1.0), (17.0, 3.0)]);
+    }
+
+    #[test]
+    fn limits_drop_newest_moves() {
+        let (mut app, _) = hovering_app(3);
+        app.insert_resource(PickingLimits::default().with_limit(PointerEventKind::Move, 2));
+
+        send_moves(&mut app, 4);
+
+        let moves = &app.world().resource::<Moves>().0;
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|(_, delta)| delta.x == 1.0));
+        let limits = app.world().resource::<PickingLimits>();
+        assert_eq!(limits.dropped(PointerEventKind::Move), 10);
+        assert_eq!(limits.total_dropped(), 10);
+        assert_eq!(
+            app.world().resource::<Messages<Pointer<Move>>>().len(),
+            2,
+            "dropped events should not be written as messages either"
+        );
+    }
+
+    #[test]
+    fn limits_drop_oldest_moves() {
+        let (mut app, _) = hovering_app(3);
+        app.insert_resource(
+            PickingLimits::default()
+                .with_limit(PointerEventKind::Move, 2)
+                .with_policy(PickingOverflowPolicy::DropOldest),
+        );
+
+        send_moves(&mut app, 4);
+
+        let moves = &app.world().resource::<Moves>().0;
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|(_, delta)| delta.x == 4.0));
+        let limits = app.world().resource::<PickingLimits>();
+        assert_eq!(limits.dropped(PointerEventKind::Move), 10);
+    }
+
+    #[test]
+    fn limits_reset_each_frame() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingLimits::default().with_limit(PointerEventKind::Move, 1));
+
+        send_moves(&mut app, 2);
+        send_moves(&mut app, 2);
+
+        let moves = &app.world().resource::<Moves>().0;
+        assert_eq!(moves.len(), 2);
+        assert!(moves.iter().all(|(entity, _)| *entity == entities[0]));
+        assert_eq!(app.world().resource::<PickingLimits>().total_dropped(), 2);
+    }
+
+    #[derive(Resource, Default)]
+    struct Received(Vec<(PointerEventKind, Entity)>);
+
+    /// Records every entity that an event of type `E` is triggered on, including while bubbling.
+    fn record<E: PickingEvent>(app: &mut App) {
+        app.init_resource::<Received>();
+        app.world_mut()
+            .add_observer(|event: On<Pointer<E>>, mut received: ResMut<Received>| {
+                received.0.push((E::KIND, event.entity));
+            });
+    }
+
+    fn received(app: &App, kind: PointerEventKind) -> Vec<Entity> {
+        app.world()
+            .resource::<Received>()
+            .0
+            .iter()
+            .filter(|(received, _)| *received == kind)
+            .map(|(_, entity)| *entity)
+            .collect()
+    }
+
+    fn send_input(app: &mut App, position: Vec2, action: PointerAction) {
+        app.world_mut().write_message(PointerInput::new(
+            PointerId::Mouse,
+            location(position),
+            action,
+        ));
+    }
+
+    #[test]
+    fn masked_events_bubble_past_entity() {
+        let (mut app, _) = hovering_app(0);
+        record::<Over>(&mut app);
+        record::<Drag>(&mut app);
+        let world = app.world_mut();
+        let parent = world.spawn_empty().id();
+        let child = world
+            .spawn((
+                ChildOf(parent),
+                PickEventMask::new([PointerEventKind::Drag]),
+            ))
+            .id();
+        let camera = world.spawn(Camera::default()).id();
+        world.resource_mut::<PreviousHoverMap>().clear();
+        world.resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            HashMap::from_iter([(child, HitData::new(camera, 0.0, None, None))]),
+        );
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        assert_eq!(received(&app, PointerEventKind::Over), vec![child, parent]);
+        assert_eq!(received(&app, PointerEventKind::Drag), vec![parent]);
+        let drags = app.world().resource::<Messages<Pointer<Drag>>>();
+        assert_eq!(
+            drags.iter_current_update_messages().next().unwrap().entity,
+            child,
+            "the message should still name the dragged entity"
+        );
+    }
+
+    #[test]
+    fn pick_handlers_follow_masks() {
+        let (mut app, entities) = hovering_app(2);
+        let (masked, unwanted) = (entities[0], entities[1]);
+        app.init_resource::<Received>();
+        let handler = |click: &Pointer<Click>, commands: &mut Commands| {
+            let entity = click.entity;
+            commands.queue(move |world: &mut World| {
+                world
+                    .resource_mut::<Received>()
+                    .0
+                    .push((PointerEventKind::Click, entity));
+            });
+        };
+        let world = app.world_mut();
+        let parent = world.spawn(PickHandlers::default().with(handler)).id();
+        world.entity_mut(masked).insert((
+            ChildOf(parent),
+            PickEventMask::new([PointerEventKind::Click]),
+            PickHandlers::default().with(handler),
+        ));
+        world.entity_mut(unwanted).insert((
+            PointerEventMask::new([PointerEventKind::Press]),
+            PickHandlers::default().with(handler),
+        ));
+
+        for action in [
+            PointerAction::Press(PointerButton::Primary),
+            PointerAction::Release(PointerButton::Primary),
+        ] {
+            send_input(&mut app, Vec2::ONE, action);
+        }
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        // The click on the masked entity is handled by its parent, the one on the entity that
+        // doesn't want clicks isn't handled at all.
+        assert_eq!(received(&app, PointerEventKind::Click), vec![parent]);
+    }
+
+    #[test]
+    fn unwanted_events_are_not_triggered() {
+        let (mut app, entities) = hovering_app(2);
+        let (masked, sibling) = (entities[0], entities[1]);
+        record::<Press>(&mut app);
+        record::<Move>(&mut app);
+        record::<Click>(&mut app);
+        let world = app.world_mut();
+        let parent = world.spawn_empty().id();
+        world.entity_mut(masked).insert((
+            ChildOf(parent),
+            PointerEventMask::new([PointerEventKind::Click]),
+        ));
+        world.entity_mut(sibling).insert(ChildOf(parent));
+
+        for action in [
+            PointerAction::Move { delta: Vec2::ONE },
+            PointerAction::Press(PointerButton::Primary),
+            PointerAction::Release(PointerButton::Primary),
+        ] {
+            send_input(&mut app, Vec2::ONE, action);
+        }
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        assert_eq!(
+            received(&app, PointerEventKind::Press),
+            vec![sibling, parent]
+        );
+        // Moves don't bubble by default.
+        assert_eq!(received(&app, PointerEventKind::Move), vec![sibling]);
+        let mut clicked = received(&app, PointerEventKind::Click);
+        clicked.sort();
+        let mut expected = vec![masked, parent, sibling, parent];
+        expected.sort();
+        assert_eq!(clicked, expected);
+        let moves = app.world().resource::<Messages<Pointer<Move>>>();
+        assert!(
+            moves
+                .iter_current_update_messages()
+                .any(|event| event.entity == masked),
+            "the message should still be written"
+        );
+    }
+
+    #[test]
+    fn moves_stop_at_their_target_while_others_bubble() {
+        assert!(!PointerEventKind::Move.auto_propagates());
+        assert!(!PointerEventKind::DragOver.auto_propagates());
+        assert!(PointerEventKind::Over.auto_propagates());
+
+        let (mut app, _) = hovering_app(0);
+        record::<Over>(&mut app);
+        record::<Move>(&mut app);
+        let world = app.world_mut();
+        let root = world.spawn_empty().id();
+        let parent = world.spawn(ChildOf(root)).id();
+        let child = world.spawn(ChildOf(parent)).id();
+        let sibling = world.spawn(ChildOf(parent)).id();
+        // The parent opts in to bubbling the moves it receives.
+        world
+            .entity_mut(parent)
+            .observe(|mut event: On<Pointer<Move>>| {
+                event.propagate(true);
+            });
+        let camera = world.spawn(Camera::default()).id();
+        world.resource_mut::<PreviousHoverMap>().clear();
+        let hover = |app: &mut App, entity: Entity| {
+            let world = app.world_mut();
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]),
+            );
+            world.resource_mut::<Received>().0.clear();
+            send_moves(app, 1);
+        };
+
+        hover(&mut app, child);
+        assert_eq!(
+            received(&app, PointerEventKind::Over),
+            vec![child, parent, root]
+        );
+        assert_eq!(received(&app, PointerEventKind::Move), vec![child]);
+        hover(&mut app, parent);
+        assert_eq!(received(&app, PointerEventKind::Move), vec![parent, root]);
+
+        // The defaults can be overridden in both directions.
+        app.insert_resource(
+            PointerEventPropagation::default()
+                .with_propagation(PointerEventKind::Move, true)
+                .with_propagation(PointerEventKind::Over, false),
+        );
+        hover(&mut app, sibling);
+        assert_eq!(received(&app, PointerEventKind::Over), vec![sibling]);
+        assert_eq!(
+            received(&app, PointerEventKind::Move),
+            vec![sibling, parent, root]
+        );
+
+        // Resetting an override restores the default of the event type.
+        app.world_mut()
+            .resource_mut::<PointerEventPropagation>()
+            .reset(PointerEventKind::Move);
+        hover(&mut app, child);
+        assert_eq!(received(&app, PointerEventKind::Over), vec![child]);
+        assert_eq!(received(&app, PointerEventKind::Move), vec![child]);
+    }
+
+    #[test]
+    fn adapter_consumes_clicks_before_the_world() {
+        let (mut app, entities) = hovering_app(1);
+        record::<Press>(&mut app);
+        record::<Click>(&mut app);
+        let ui_clicks = Arc::new(Mutex::new(Vec::new()));
+        let forwarded = ui_clicks.clone();
+        app.insert_resource(PointerEventAdapter::new(move |event| {
+            if event.event.try_downcast_ref::<Click>().is_none() {
+                return PointerEventHandling::Ignored;
+            }
+            forwarded.lock().unwrap().push(event.entity);
+            PointerEventHandling::Consumed
+        }));
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        assert_eq!(*ui_clicks.lock().unwrap(), entities);
+        assert!(received(&app, PointerEventKind::Click).is_empty());
+        assert!(app
+            .world()
+            .resource::<Messages<Pointer<Click>>>()
+            .is_empty());
+        assert_eq!(received(&app, PointerEventKind::Press), entities);
+        let adapter = app.world().resource::<PointerEventAdapter>();
+        assert_eq!(adapter.consumed(PointerEventKind::Click), 1);
+        assert_eq!(adapter.consumed(PointerEventKind::Press), 0);
+    }
+
+    #[test]
+    fn first_interaction_fires_once_per_entity() {
+        let (mut app, entities) = hovering_app(1);
+        record::<FirstInteraction>(&mut app);
+        let click = |app: &mut App| {
+            for action in [
+                PointerAction::Press(PointerButton::Primary),
+                PointerAction::Release(PointerButton::Primary),
+            ] {
+                send_input(app, Vec2::ZERO, action);
+            }
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        click(&mut app);
+        click(&mut app);
+        assert_eq!(received(&app, PointerEventKind::FirstInteraction), entities);
+
+        app.world_mut().despawn(entities[0]);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert!(app.world().resource::<FirstInteractions>().is_empty());
+    }
+
+    #[test]
+    fn hover_enter_and_exit_fire_once_per_subtree() {
+        let (mut app, _) = hovering_app(0);
+        record::<HoverEnter>(&mut app);
+        record::<HoverExit>(&mut app);
+        let world = app.world_mut();
+        let camera = world.spawn(Camera::default()).id();
+        let root = world.spawn_empty().id();
+        let list = world.spawn(ChildOf(root)).id();
+        let items = [
+            world.spawn(ChildOf(list)).id(),
+            world.spawn(ChildOf(list)).id(),
+        ];
+        // Moves the pointer over `entities`, and returns the entities that received each event.
+        let hover = |app: &mut App, entities: &[Entity]| {
+            let world = app.world_mut();
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                entities
+                    .iter()
+                    .map(|entity| (*entity, HitData::new(camera, 0.0, None, None)))
+                    .collect(),
+            );
+            world.run_system_cached(pointer_events).unwrap();
+            let received = (
+                received(app, PointerEventKind::HoverEnter),
+                received(app, PointerEventKind::HoverExit),
+            );
+            app.world_mut().resource_mut::<Received>().0.clear();
+            received
+        };
 
-    // If the entity was hovered by a specific pointer last frame...
-    for (pointer_id, hovered_entity, hit) in previous_hover_map
-        .iter()
-        .flat_map(|(id, hashmap)| hashmap.iter().map(|data| (*id, *data.0, data.1.clone())))
-    {
-        // ...but is now not being hovered by that same pointer...
-        if !hover_map
-            .get(&pointer_id)
+        // Entering an item enters its list and the root too, bubbling from the item.
+        assert_eq!(
+            hover(&mut app, &[items[0]]),
+            (vec![items[0], list, root], vec![])
+        );
+        // Moving between items doesn't leave or enter the list again.
+        assert_eq!(
+            hover(&mut app, &[items[1]]),
+            (vec![items[1]], vec![items[0]])
+        );
+        assert_eq!(hover(&mut app, &[list]), (vec![], vec![items[1]]));
+        assert_eq!(hover(&mut app, &[]), (vec![], vec![list, root]));
+        // Hovering both items at once enters the list only once.
+        let (mut entered, exited) = hover(&mut app, &items);
+        entered.sort_unstable();
+        let mut expected = vec![items[0], items[1], list, root];
+        expected.sort_unstable();
+        assert_eq!((entered, exited), (expected, vec![]));
+        // Leaving both items leaves the list, but not the root that is still hovered.
+        let (entered, mut exited) = hover(&mut app, &[root]);
+        exited.sort_unstable();
+        let mut expected = vec![items[0], items[1], list];
+        expected.sort_unstable();
+        assert_eq!((entered, exited), (vec![], expected));
+    }
+
+    #[test]
+    fn hover_exit_reaches_ancestors_of_despawned_children() {
+        let (mut app, _) = hovering_app(0);
+        record::<HoverExit>(&mut app);
+        let world = app.world_mut();
+        let camera = world.spawn(Camera::default()).id();
+        let root = world.spawn_empty().id();
+        let list = world.spawn(ChildOf(root)).id();
+        let item = world.spawn(ChildOf(list)).id();
+        let hover = |app: &mut App, entities: &[Entity]| {
+            let world = app.world_mut();
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                entities
+                    .iter()
+                    .map(|entity| (*entity, HitData::new(camera, 1.0, None, None)))
+                    .collect(),
+            );
+            world.run_system_cached(pointer_events).unwrap();
+        };
+
+        hover(&mut app, &[item]);
+        app.world_mut().despawn(item);
+        hover(&mut app, &[]);
+        // The despawned item is exited on its own, and its former ancestors are still exited.
+        assert_eq!(
+            received(&app, PointerEventKind::HoverExit),
+            vec![item, list, root]
+        );
+        let exit = app
+            .world()
+            .resource::<Messages<Pointer<HoverExit>>>()
+            .iter_current_update_messages()
+            .find(|exit| exit.entity == list)
+            .unwrap();
+        assert_eq!(exit.outermost, root);
+        assert_eq!(exit.hit, HitData::new(camera, 1.0, None, None));
+    }
+
+    #[test]
+    fn held_press_repeats_after_delay_then_every_interval() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual())
+            .insert_resource(RepeatSettings {
+                delay: Duration::from_millis(500),
+                interval: Duration::from_millis(100),
+            });
+        let advance = |app: &mut App, millis| {
+            let world = app.world_mut();
+            world
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(millis));
+            world.run_system_cached(pointer_events).unwrap();
+        };
+        let repeats = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<PressRepeat>>>()
+                .iter_current_update_messages()
+                .map(|repeat| (repeat.entity, repeat.count))
+                .collect::<Vec<_>>()
+        };
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        advance(&mut app, 0);
+        advance(&mut app, 499);
+        assert!(repeats(&app).is_empty());
+
+        advance(&mut app, 1);
+        assert_eq!(repeats(&app), vec![(entities[0], 1)]);
+        advance(&mut app, 99);
+        assert_eq!(repeats(&app).len(), 1);
+        advance(&mut app, 1);
+        advance(&mut app, 100);
+        assert_eq!(
+            repeats(&app),
+            vec![(entities[0], 1), (entities[0], 2), (entities[0], 3)]
+        );
+
+        // Releasing the button stops the repeats.
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        advance(&mut app, 1000);
+        assert_eq!(repeats(&app).len(), 3);
+
+        // So does moving off the entity.
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        advance(&mut app, 0);
+        app.world_mut().resource_mut::<HoverMap>().clear();
+        advance(&mut app, 1000);
+        assert_eq!(repeats(&app).len(), 3);
+    }
+
+    #[test]
+    fn press_repeats_are_scheduled_from_the_press_input() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual())
+            .insert_resource(RepeatSettings {
+                delay: Duration::from_millis(500),
+                interval: Duration::from_millis(100),
+            });
+        let pressed = app.world().resource::<PickingClock>().now();
+        app.world_mut().write_message(
+            PointerInput::new(
+                PointerId::Mouse,
+                location(Vec2::ZERO),
+                PointerAction::Press(PointerButton::Primary),
+            )
+            .with_time(pressed),
+        );
+        // The press is read 400ms after it happened, so the first repeat is due 100ms later.
+        let advance = |app: &mut App, millis| {
+            let world = app.world_mut();
+            world
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(millis));
+            world.run_system_cached(pointer_events).unwrap();
+        };
+        advance(&mut app, 400);
+        advance(&mut app, 99);
+        let repeats = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<PressRepeat>>>()
+                .iter_current_update_messages()
+                .map(|repeat| (repeat.entity, repeat.count))
+                .collect::<Vec<_>>()
+        };
+        assert!(repeats(&app).is_empty());
+        advance(&mut app, 1);
+        assert_eq!(repeats(&app), vec![(entities[0], 1)]);
+    }
+
+    #[test]
+    fn long_press_fires_once_for_presses_held_in_place() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual());
+        let advance = |app: &mut App, millis| {
+            let world = app.world_mut();
+            world
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(millis));
+            world.run_system_cached(pointer_events).unwrap();
+        };
+        let long_presses = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<LongPress>>>()
+                .iter_current_update_messages()
+                .map(|long_press| (long_press.entity, long_press.duration))
+                .collect::<Vec<_>>()
+        };
+        let press = |app: &mut App, action| {
+            send_input(app, Vec2::ZERO, action);
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        press(&mut app, PointerAction::Press(PointerButton::Primary));
+        advance(&mut app, 499);
+        assert!(long_presses(&app).is_empty());
+        advance(&mut app, 1);
+        advance(&mut app, 1000);
+        assert_eq!(
+            long_presses(&app),
+            vec![(entities[0], Duration::from_millis(500))]
+        );
+
+        // Releasing the button, canceling the pointer, or dragging prevent the long press.
+        press(&mut app, PointerAction::Release(PointerButton::Primary));
+        press(&mut app, PointerAction::Press(PointerButton::Primary));
+        press(&mut app, PointerAction::Release(PointerButton::Primary));
+        advance(&mut app, 1000);
+        press(&mut app, PointerAction::Press(PointerButton::Primary));
+        press(&mut app, PointerAction::Cancel);
+        advance(&mut app, 1000);
+        press(&mut app, PointerAction::Press(PointerButton::Primary));
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        advance(&mut app, 1000);
+        assert_eq!(long_presses(&app).len(), 1);
+
+        // The duration is configurable.
+        press(&mut app, PointerAction::Release(PointerButton::Primary));
+        app.insert_resource(PressSettings {
+            long_press_duration: Duration::from_millis(100),
+            ..Default::default()
+        });
+        press(&mut app, PointerAction::Press(PointerButton::Primary));
+        advance(&mut app, 100);
+        assert_eq!(long_presses(&app).len(), 2);
+    }
+
+    #[test]
+    fn long_press_tolerates_presses_stamped_after_now() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual());
+        let now = app.world().resource::<PickingClock>().now();
+        app.world_mut().write_message(
+            PointerInput::new(
+                PointerId::Mouse,
+                location(Vec2::ZERO),
+                PointerAction::Press(PointerButton::Primary),
+            )
+            .with_time(now + Duration::from_millis(100)),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        app.world_mut()
+            .resource_mut::<PickingClock>()
+            .advance(Duration::from_millis(600));
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let long_presses: Vec<_> = app
+            .world()
+            .resource::<Messages<Pointer<LongPress>>>()
+            .iter_current_update_messages()
+            .map(|long_press| (long_press.entity, long_press.duration))
+            .collect();
+        assert_eq!(
+            long_presses,
+            vec![(entities[0], Duration::from_millis(500))]
+        );
+    }
+
+    #[test]
+    fn batched_triggers_keep_observer_order() {
+        let (mut app, _) = hovering_app(0);
+        app.add_message::<AnyPointerEvent>();
+        record::<Out>(&mut app);
+        record::<Over>(&mut app);
+        record::<Press>(&mut app);
+        record::<Move>(&mut app);
+        // Commands queued by an observer must run before the next event is triggered, as they
+        // did when every event was triggered with its own command.
+        app.world_mut()
+            .add_observer(|_: On<Pointer<Over>>, mut commands: Commands| {
+                commands.queue(|world: &mut World| {
+                    let marker = (PointerEventKind::Cancel, Entity::PLACEHOLDER);
+                    world.resource_mut::<Received>().0.push(marker);
+                });
+            });
+        let world = app.world_mut();
+        let camera = world.spawn(Camera::default()).id();
+        let old = world.spawn_empty().id();
+        let new = world.spawn_empty().id();
+        let hit = |entity| HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]);
+        world
+            .resource_mut::<PreviousHoverMap>()
+            .insert(PointerId::Mouse, hit(old));
+        world
+            .resource_mut::<HoverMap>()
+            .insert(PointerId::Mouse, hit(new));
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::ONE,
+            PointerAction::Move { delta: Vec2::ONE },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        assert_eq!(
+            app.world().resource::<Received>().0,
+            vec![
+                (PointerEventKind::Out, old),
+                (PointerEventKind::Over, new),
+                (PointerEventKind::Cancel, Entity::PLACEHOLDER),
+                (PointerEventKind::Press, new),
+                (PointerEventKind::Move, new),
+            ]
+        );
+
+        // Every recorded event written is triggered exactly once, in the order it was written.
+        let recorded = [
+            PointerEventKind::Out,
+            PointerEventKind::Over,
+            PointerEventKind::Press,
+            PointerEventKind::Move,
+        ];
+        let triggered: Vec<_> = app
+            .world()
+            .resource::<Received>()
+            .0
             .iter()
-            .any(|e| e.contains_key(&hovered_entity))
-        {
-            let Some(location) = pointer_location(pointer_id) else {
-                debug!(
-                    "Unable to get location for pointer {:?} during pointer out",
-                    pointer_id
+            .filter(|(_, entity)| *entity != Entity::PLACEHOLDER)
+            .copied()
+            .collect();
+        let written: Vec<_> = app
+            .world()
+            .resource::<Messages<AnyPointerEvent>>()
+            .iter_current_update_messages()
+            .map(|event| (event.kind(), event.entity()))
+            .filter(|(kind, _)| recorded.contains(kind))
+            .collect();
+        assert_eq!(triggered, written);
+
+        // The buffers of the batch are kept for the next frame.
+        let buffers = &app.world().resource::<TriggerBuffers>().0;
+        assert!(buffers.order.is_empty());
+        assert!(buffers.order.capacity() >= written.len());
+        assert!(buffers.over_events.capacity() > 0);
+    }
+
+    #[test]
+    fn drag_handle_drags_its_target() {
+        let (mut app, _) = hovering_app(0);
+        record::<DragStart>(&mut app);
+        let world = app.world_mut();
+        let window = world.spawn_empty().id();
+        let handle = world.spawn((DragHandle::Parent, ChildOf(window))).id();
+        let camera = world.spawn(Camera::default()).id();
+        let hover = |world: &mut World, entity| {
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]),
+            );
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+        };
+        let press_and_move = |app: &mut App| {
+            send_input(
+                app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
+            );
+            send_input(
+                app,
+                Vec2::new(5.0, 0.0),
+                PointerAction::Move {
+                    delta: Vec2::new(5.0, 0.0),
+                },
+            );
+            send_input(
+                app,
+                Vec2::new(5.0, 0.0),
+                PointerAction::Release(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        hover(app.world_mut(), handle);
+        press_and_move(&mut app);
+        assert_eq!(received(&app, PointerEventKind::DragStart), vec![window]);
+
+        // Pressing the window itself doesn't start a drag.
+        hover(app.world_mut(), window);
+        press_and_move(&mut app);
+        assert_eq!(received(&app, PointerEventKind::DragStart), vec![window]);
+    }
+
+    #[test]
+    fn pressed_and_dragged_entities_are_found_across_pointers() {
+        let pressed = Entity::from_raw_u32(1).unwrap();
+        let dragged = Entity::from_raw_u32(2).unwrap();
+        let idle = Entity::from_raw_u32(3).unwrap();
+        let touch = PointerId::Touch(0);
+        let press = |entity| {
+            (
+                entity,
+                (
+                    location(Vec2::ZERO),
+                    Instant::now(),
+                    HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+                ),
+            )
+        };
+
+        let mut pointer_state = PointerState::default();
+        pointer_state
+            .get_mut(PointerId::Mouse, PointerButton::Primary)
+            .pressing
+            .extend([press(pressed)]);
+        pointer_state
+            .get_mut(PointerId::Mouse, PointerButton::Secondary)
+            .pressing
+            .extend([press(pressed)]);
+        let touch_state = pointer_state.get_mut(touch, PointerButton::Primary);
+        touch_state
+            .pressing
+            .extend([press(pressed), press(dragged)]);
+        touch_state.dragging.insert(
+            dragged,
+            DragEntry {
+                start_pos: Vec2::ZERO,
+                latest_pos: Vec2::ONE,
+                latest_time: Instant::now(),
+                velocity: Vec2::ZERO,
+                lock: None,
+                camera: Entity::PLACEHOLDER,
+            },
+        );
+        // A button that pressed nothing.
+        pointer_state.get_mut(touch, PointerButton::Middle);
+
+        assert!(pointer_state.is_pressed(pressed));
+        assert!(pointer_state.is_pressed(dragged));
+        assert!(!pointer_state.is_pressed(idle));
+        assert!(pointer_state.is_dragged(dragged));
+        assert!(!pointer_state.is_dragged(pressed));
+        assert!(!pointer_state.is_dragged(idle));
+
+        let mut pressing: Vec<_> = pointer_state.pressing_pointers(pressed).collect();
+        pressing.sort();
+        assert_eq!(
+            pressing,
+            vec![
+                (PointerId::Mouse, PointerButton::Primary),
+                (PointerId::Mouse, PointerButton::Secondary),
+                (touch, PointerButton::Primary),
+            ]
+        );
+        assert_eq!(
+            pointer_state.pressing_pointers(dragged).collect::<Vec<_>>(),
+            vec![(touch, PointerButton::Primary)]
+        );
+        assert_eq!(pointer_state.pressing_pointers(idle).count(), 0);
+
+        pointer_state.clear(touch);
+        assert!(!pointer_state.is_dragged(dragged));
+        assert!(!pointer_state.is_pressed(dragged));
+        assert!(pointer_state.is_pressed(pressed));
+    }
+
+    #[test]
+    fn press_hit_is_the_hit_of_the_press() {
+        #[derive(Resource, Default)]
+        struct PressHits(Vec<HitData>);
+
+        let (mut app, entities) = hovering_app(1);
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        let hit = HitData::new(camera, 2.0, Some(Vec3::new(1.0, 2.0, 3.0)), Some(Vec3::Z));
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .insert(PointerId::Mouse, HashMap::from_iter([(entities[0], hit)]));
+        app.init_resource::<PressHits>();
+        app.world_mut()
+            .add_observer(|press: On<Pointer<Press>>, mut hits: ResMut<PressHits>| {
+                hits.0.push(press.hit.clone());
+            });
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let pointer_state = app.world().resource::<PointerState>();
+        let press_hit =
+            pointer_state.press_hit(PointerId::Mouse, PointerButton::Primary, entities[0]);
+        assert_eq!(press_hit, app.world().resource::<PressHits>().0.first());
+        assert!(press_hit.is_some());
+        assert_eq!(
+            pointer_state.press_hit(PointerId::Mouse, PointerButton::Secondary, entities[0]),
+            None
+        );
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(
+            app.world().resource::<PointerState>().press_hit(
+                PointerId::Mouse,
+                PointerButton::Primary,
+                entities[0]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn despawned_drag_targets_are_cleared_from_the_pointer_state() {
+        let (mut app, entities) = hovering_app(2);
+        let [dragged, over] = entities[..] else {
+            unreachable!()
+        };
+        record::<DragEnd>(&mut app);
+        record::<DragDrop>(&mut app);
+        record::<DragOver>(&mut app);
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        app.world_mut().resource_mut::<PointerState>().capture(
+            PointerId::Mouse,
+            PointerButton::Primary,
+            dragged,
+        );
+        let state = app.world().resource::<PointerState>();
+        assert!(state.is_dragged(dragged));
+        assert!(state.is_pressed(over));
+
+        app.world_mut().despawn(dragged);
+        app.world_mut().despawn(over);
+        app.world_mut().resource_mut::<HoverMap>().clear();
+        app.world_mut()
+            .run_system_cached(clear_despawned_pointer_state)
+            .unwrap();
+        let state = app
+            .world()
+            .resource::<PointerState>()
+            .get(PointerId::Mouse, PointerButton::Primary)
+            .unwrap();
+        assert!(state.pressing.is_empty());
+        assert!(state.dragging.is_empty());
+        assert!(state.dragging_over.is_empty());
+        assert!(state.repeating.is_empty());
+        assert!(state.long_pressed.is_empty());
+        assert_eq!(state.captured, None);
+
+        // No events are triggered on the despawned entities afterwards.
+        app.world_mut().resource_mut::<Received>().0.clear();
+        send_input(
+            &mut app,
+            Vec2::new(6.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(1.0, 0.0),
+            },
+        );
+        send_input(
+            &mut app,
+            Vec2::new(6.0, 0.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert!(received(&app, PointerEventKind::DragEnd).is_empty());
+        assert!(received(&app, PointerEventKind::DragDrop).is_empty());
+        assert!(received(&app, PointerEventKind::DragOver).is_empty());
+    }
+
+    #[test]
+    fn removed_pointers_leave_everything_they_hovered() {
+        let (mut app, entities) = hovering_app(1);
+        let dragged = entities[0];
+        record::<Out>(&mut app);
+        record::<DragEnd>(&mut app);
+        record::<DragLeave>(&mut app);
+        record::<HoverExit>(&mut app);
+        let target = app.world_mut().spawn_empty().id();
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let hit = HitData::new(Entity::PLACEHOLDER, 1.0, None, None);
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(target, hit);
+        send_moves(&mut app, 1);
+
+        // The pointer is removed mid-hover, and its entries vanish from the hover map.
+        let world = app.world_mut();
+        let hovered = world.resource::<HoverMap>().0.clone();
+        world.resource_mut::<PreviousHoverMap>().0 = hovered;
+        world.resource_mut::<HoverMap>().clear();
+        let pointer = world.resource::<PointerMap>().get_entity(PointerId::Mouse);
+        world.despawn(pointer.unwrap());
+        world.run_system_cached(update_pointer_map).unwrap();
+        world.resource_mut::<Received>().0.clear();
+        world.run_system_cached(pointer_events).unwrap();
+
+        let mut out = received(&app, PointerEventKind::Out);
+        out.sort_unstable();
+        let mut expected = vec![dragged, target];
+        expected.sort_unstable();
+        assert_eq!(out, expected);
+        assert_eq!(received(&app, PointerEventKind::DragEnd), vec![dragged]);
+        assert!(received(&app, PointerEventKind::DragLeave).contains(&target));
+        assert_eq!(received(&app, PointerEventKind::HoverExit).len(), 2);
+        let state = app.world().resource::<PointerState>();
+        assert!(!state.is_pressed(dragged));
+        assert!(!state.is_dragged(dragged));
+        // Nothing is kept about the removed pointer.
+        assert!(state.pointer_buttons.is_empty());
+        assert!(state.reported_captures.is_empty());
+        assert!(state.hovered_subtrees.is_empty());
+        assert!(state.modifiers.is_empty());
+        assert!(state.last_locations.is_empty());
+
+        // The pointer is only flushed once.
+        let world = app.world_mut();
+        world.resource_mut::<PreviousHoverMap>().clear();
+        world.resource_mut::<Received>().0.clear();
+        world.run_system_cached(pointer_events).unwrap();
+        assert!(world.resource::<Received>().0.is_empty());
+    }
+
+    #[test]
+    fn drags_report_world_vectors_through_the_pressed_camera() {
+        let (mut app, entities) = hovering_app(1);
+        let dragged = entities[0];
+        let world = app.world_mut();
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(800, 600),
+            scale_factor: 1.0,
+        });
+        // One world unit per pixel, with the y axis pointing up.
+        camera.computed.clip_from_view =
+            Mat4::orthographic_rh(-400.0, 400.0, -300.0, 300.0, 0.0, 1000.0);
+        let camera = world
+            .spawn((camera, GlobalTransform::from_xyz(10.0, 20.0, 0.0)))
+            .id();
+        world.resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            [(dragged, HitData::new(camera, 0.0, None, None))].into(),
+        );
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        send_input(
+            &mut app,
+            Vec2::new(6.0, 8.0),
+            PointerAction::Move {
+                delta: Vec2::new(6.0, 8.0),
+            },
+        );
+        send_input(
+            &mut app,
+            Vec2::new(10.0, 4.0),
+            PointerAction::Move {
+                delta: Vec2::new(4.0, -4.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let drags: Vec<_> = app
+            .world()
+            .resource::<Messages<Pointer<Drag>>>()
+            .iter_current_update_messages()
+            .map(|drag| (drag.world_delta, drag.world_distance))
+            .collect();
+        assert_eq!(
+            drags,
+            vec![
+                (Some(Vec2::new(6.0, -8.0)), Some(Vec2::new(6.0, -8.0))),
+                (Some(Vec2::new(4.0, 4.0)), Some(Vec2::new(10.0, -4.0))),
+            ]
+        );
+
+        // Without a camera the drag still runs, but can't be projected.
+        app.world_mut().despawn(camera);
+        send_input(
+            &mut app,
+            Vec2::new(12.0, 4.0),
+            PointerAction::Move {
+                delta: Vec2::new(2.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let drag = app
+            .world()
+            .resource::<Messages<Pointer<Drag>>>()
+            .iter_current_update_messages()
+            .last()
+            .unwrap();
+        assert_eq!(drag.delta, Vec2::new(2.0, 0.0));
+        assert_eq!((drag.world_delta, drag.world_distance), (None, None));
+    }
+
+    #[test]
+    fn drag_over_continues_while_the_pointer_holds_still() {
+        let (mut app, entities) = hovering_app(1);
+        let dragged = entities[0];
+        record::<DragOver>(&mut app);
+        let target = app.world_mut().spawn_empty().id();
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let hit = HitData::new(Entity::PLACEHOLDER, 1.0, None, None);
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(target, hit);
+        send_moves(&mut app, 1);
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![target]);
+
+        // Without the setting, holding still doesn't drag over anything.
+        app.world_mut().resource_mut::<Received>().0.clear();
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![]);
+
+        app.insert_resource(DragSettings {
+            continuous_drag_over: true,
+            ..Default::default()
+        });
+        // The target moved beneath the pointer, which is reported by the next DragOver.
+        let hit = HitData::new(Entity::PLACEHOLDER, 2.0, None, None);
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(target, hit.clone());
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![target]);
+        let drag_over = app
+            .world()
+            .resource::<Messages<Pointer<DragOver>>>()
+            .iter_current_update_messages()
+            .last()
+            .unwrap();
+        assert_eq!(drag_over.dragged, dragged);
+        assert_eq!(drag_over.hit, hit);
+
+        // Moving frames aren't doubled up.
+        app.world_mut().resource_mut::<Received>().0.clear();
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(4.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![target]);
+    }
+
+    #[test]
+    fn dragged_entity_doesnt_hide_the_target_beneath_it() {
+        use crate::{
+            backend::PointerHits,
+            hover::{generate_hovermap, DragIgnoresSelf},
+        };
+
+        let (mut app, _) = hovering_app(0);
+        app.add_message::<PointerHits>()
+            .insert_resource(DragIgnoresSelf { descendants: true });
+        record::<DragOver>(&mut app);
+        let world = app.world_mut();
+        let card = world.spawn_empty().id();
+        let badge = world.spawn(ChildOf(card)).id();
+        let target = world.spawn_empty().id();
+        let frame = |app: &mut App, hits: &[Entity], x: f32, action: PointerAction| {
+            let hit = |(depth, entity): (usize, &Entity)| {
+                (
+                    *entity,
+                    HitData::new(Entity::PLACEHOLDER, depth as f32, None, None),
+                )
+            };
+            app.world_mut().write_message(PointerHits::new(
+                PointerId::Mouse,
+                hits.iter().enumerate().map(hit).collect(),
+                0.0,
+            ));
+            send_input(app, Vec2::new(x, 0.0), action);
+            let world = app.world_mut();
+            world.resource_mut::<Received>().0.clear();
+            world.run_system_cached(generate_hovermap).unwrap();
+            world.run_system_cached(pointer_events).unwrap();
+        };
+        let moved = PointerAction::Move { delta: Vec2::X };
+
+        frame(
+            &mut app,
+            &[card, target],
+            0.0,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        // The drag starts on this frame, so the hovered badge of the card is still dragged over.
+        frame(&mut app, &[badge, card, target], 10.0, moved);
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![badge]);
+
+        // From then on, the card and its badge are skipped, and the target beneath is hovered.
+        frame(&mut app, &[badge, card, target], 11.0, moved);
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![target]);
+        let hovered = &app.world().resource::<HoverMap>()[&PointerId::Mouse];
+        assert_eq!(hovered.keys().copied().collect::<Vec<_>>(), vec![target]);
+
+        // Without descendants, the badge of the card hides the target again.
+        app.insert_resource(DragIgnoresSelf { descendants: false });
+        frame(&mut app, &[badge, card, target], 12.0, moved);
+        assert_eq!(received(&app, PointerEventKind::DragOver), vec![badge]);
+    }
+
+    #[test]
+    fn drop_observers_read_the_payload_of_the_dropped_entity() {
+        #[derive(Resource, Default)]
+        struct Payloads(Vec<(PointerEventKind, Option<&'static str>)>);
+
+        let (mut app, entities) = hovering_app(1);
+        let dragged = entities[0];
+        app.init_resource::<Payloads>();
+        let world = app.world_mut();
+        world.entity_mut(dragged).insert(DragPayload("card"));
+        let target = world.spawn_empty().id();
+        world.entity_mut(target).observe(
+            |enter: On<Pointer<DragEnter>>,
+             payloads: Query<&DragPayload<&'static str>>,
+             mut received: ResMut<Payloads>| {
+                let payload = enter.payload(&payloads).copied();
+                received.0.push((PointerEventKind::DragEnter, payload));
+            },
+        );
+        // The observer despawns the dropped entity, after reading its payload.
+        world.entity_mut(target).observe(
+            |drop: On<Pointer<DragDrop>>,
+             payloads: Query<&DragPayload<&'static str>>,
+             mut received: ResMut<Payloads>,
+             mut commands: Commands| {
+                let payload = drop.payload(&payloads).copied();
+                received.0.push((PointerEventKind::DragDrop, payload));
+                commands.entity(drop.dragged()).despawn();
+            },
+        );
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        // The target is only hovered once the drag has started.
+        let hit = HitData::new(Entity::PLACEHOLDER, 0.0, None, None);
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(target, hit);
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        assert_eq!(
+            app.world().resource::<Payloads>().0,
+            vec![
+                (PointerEventKind::DragEnter, Some("card")),
+                (PointerEventKind::DragDrop, Some("card")),
+            ]
+        );
+        assert!(app.world().get_entity(dragged).is_err());
+    }
+
+    #[test]
+    fn events_report_the_modifiers_of_their_input() {
+        let (mut app, _) = hovering_app(1);
+        let shift = PointerModifiers {
+            shift: true,
+            ..Default::default()
+        };
+        let send = |app: &mut App, action: PointerAction, modifiers: PointerModifiers| {
+            app.world_mut().write_message(
+                PointerInput::new(PointerId::Mouse, location(Vec2::ZERO), action)
+                    .with_modifiers(modifiers),
+            );
+        };
+        fn modifiers<E: PickingEvent>(app: &App) -> Vec<PointerModifiers> {
+            app.world()
+                .resource::<Messages<Pointer<E>>>()
+                .iter_current_update_messages()
+                .map(|event| event.modifiers)
+                .collect()
+        }
+
+        send(
+            &mut app,
+            PointerAction::Press(PointerButton::Primary),
+            PointerModifiers::default(),
+        );
+        send(
+            &mut app,
+            PointerAction::Release(PointerButton::Primary),
+            shift,
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(modifiers::<Press>(&app), vec![PointerModifiers::default()]);
+        assert_eq!(modifiers::<Click>(&app), vec![shift]);
+
+        // Events without an input report the modifiers of the latest input of the pointer.
+        let entered = app.world_mut().spawn_empty().id();
+        let hit = HitData::new(Entity::PLACEHOLDER, 0.0, None, None);
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(entered, hit);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(modifiers::<Over>(&app), vec![shift]);
+    }
+
+    #[test]
+    fn release_reports_how_long_the_button_was_held() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual());
+        let entered = app.world_mut().spawn_empty().id();
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        // The second entity is hovered after the press, so it wasn't pressed.
+        let hit = HitData::new(Entity::PLACEHOLDER, 0.0, None, None);
+        app.world_mut()
+            .resource_mut::<PreviousHoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .insert(entered, hit);
+        app.world_mut()
+            .resource_mut::<PickingClock>()
+            .advance(Duration::from_millis(250));
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let mut durations: Vec<_> = app
+            .world()
+            .resource::<Messages<Pointer<Release>>>()
+            .iter_current_update_messages()
+            .map(|release| (release.entity, release.duration))
+            .collect();
+        durations.sort_unstable_by_key(|(entity, _)| *entity);
+        let mut expected = vec![
+            (entities[0], Some(Duration::from_millis(250))),
+            (entered, None),
+        ];
+        expected.sort_unstable_by_key(|(entity, _)| *entity);
+        assert_eq!(durations, expected);
+    }
+
+    #[test]
+    fn clicks_are_timed_by_their_inputs() {
+        let (mut app, entities) = hovering_app(1);
+        app.insert_resource(PickingClock::manual());
+        let pressed = app.world().resource::<PickingClock>().now();
+        let released = pressed + Duration::from_millis(150);
+        for (action, time) in [
+            (PointerAction::Press(PointerButton::Primary), pressed),
+            (PointerAction::Release(PointerButton::Primary), released),
+        ] {
+            app.world_mut().write_message(
+                PointerInput::new(PointerId::Mouse, location(Vec2::ZERO), action).with_time(time),
+            );
+        }
+        // Both inputs are read in the same run, long after they were produced.
+        app.world_mut()
+            .resource_mut::<PickingClock>()
+            .advance(Duration::from_secs(1));
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let world = app.world();
+        let press = world
+            .resource::<Messages<Pointer<Press>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap();
+        assert_eq!(press.pointer_time, pressed);
+        let click = world
+            .resource::<Messages<Pointer<Click>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap();
+        assert_eq!(click.entity, entities[0]);
+        assert_eq!(click.pointer_time, released);
+        assert_eq!(click.duration, Duration::from_millis(150));
+    }
+
+    #[test]
+    fn clicks_within_the_tolerance_coexist_with_tiny_drags() {
+        let (mut app, entities) = hovering_app(1);
+        let entity = entities[0];
+        app.insert_resource(DragSettings {
+            drag_threshold: 2.0,
+            ..Default::default()
+        })
+        .insert_resource(ClickSettings {
+            click_tolerance: 8.0,
+            ..Default::default()
+        });
+        record::<DragStart>(&mut app);
+        record::<Click>(&mut app);
+        record::<Release>(&mut app);
+        record::<DragEnd>(&mut app);
+        let press_and_release = |app: &mut App, path: &[f32]| {
+            app.world_mut().resource_mut::<Received>().0.clear();
+            send_input(
+                app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
+            );
+            let mut previous = 0.0;
+            for &x in path {
+                send_input(
+                    app,
+                    Vec2::new(x, 0.0),
+                    PointerAction::Move {
+                        delta: Vec2::new(x - previous, 0.0),
+                    },
                 );
-                continue;
+                previous = x;
+            }
+            send_input(
+                app,
+                Vec2::new(previous, 0.0),
+                PointerAction::Release(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            app.world().resource::<Received>().0.clone()
+        };
+
+        // A drift past the drag threshold, but within the tolerance, drags and clicks.
+        assert_eq!(
+            press_and_release(&mut app, &[5.0]),
+            vec![
+                (PointerEventKind::DragStart, entity),
+                (PointerEventKind::Click, entity),
+                (PointerEventKind::Release, entity),
+                (PointerEventKind::DragEnd, entity),
+            ]
+        );
+
+        // Moving past the tolerance doesn't click, even if the pointer comes back.
+        assert_eq!(
+            press_and_release(&mut app, &[12.0, 0.0]),
+            vec![
+                (PointerEventKind::DragStart, entity),
+                (PointerEventKind::Release, entity),
+                (PointerEventKind::DragEnd, entity),
+            ]
+        );
+
+        // Without a tolerance, any drag clicks.
+        app.insert_resource(ClickSettings::default());
+        assert!(
+            press_and_release(&mut app, &[12.0, 0.0]).contains(&(PointerEventKind::Click, entity))
+        );
+    }
+
+    #[test]
+    fn canceled_drags_end_with_drag_cancel_and_no_drop() {
+        let (mut app, entities) = hovering_app(1);
+        let dragged = entities[0];
+        record::<Click>(&mut app);
+        record::<DragEnd>(&mut app);
+        record::<DragCancel>(&mut app);
+        record::<DragLeave>(&mut app);
+        record::<DragDrop>(&mut app);
+        let target = app.world_mut().spawn_empty().id();
+        let start_drag = |app: &mut App| {
+            send_input(
+                app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            // The target is only hovered once the drag has started.
+            let hit = HitData::new(Entity::PLACEHOLDER, 0.0, None, None);
+            app.world_mut()
+                .resource_mut::<HoverMap>()
+                .get_mut(&PointerId::Mouse)
+                .unwrap()
+                .insert(target, hit);
+            send_input(
+                app,
+                Vec2::new(5.0, 0.0),
+                PointerAction::Move {
+                    delta: Vec2::new(5.0, 0.0),
+                },
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            app.world_mut().resource_mut::<Received>().0.clear();
+        };
+        let assert_ended_with_drag_cancel = |app: &App| {
+            assert_eq!(received(app, PointerEventKind::DragCancel), vec![dragged]);
+            assert!(received(app, PointerEventKind::DragLeave).contains(&target));
+            for kind in [
+                PointerEventKind::DragEnd,
+                PointerEventKind::DragDrop,
+                PointerEventKind::Click,
+            ] {
+                assert!(received(app, kind).is_empty());
+            }
+        };
+
+        // Canceling the pointer aborts its drag.
+        start_drag(&mut app);
+        send_input(&mut app, Vec2::new(5.0, 0.0), PointerAction::Cancel);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_ended_with_drag_cancel(&app);
+        let drag_cancel = app
+            .world()
+            .resource::<Messages<Pointer<DragCancel>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap();
+        assert_eq!(drag_cancel.distance, Vec2::new(5.0, 0.0));
+
+        // So does canceling the drag, after which releasing the button neither drops nor clicks.
+        app.world_mut()
+            .resource_mut::<HoverMap>()
+            .get_mut(&PointerId::Mouse)
+            .unwrap()
+            .remove(&target);
+        start_drag(&mut app);
+        app.world_mut()
+            .resource_mut::<PointerState>()
+            .cancel_drag(PointerId::Mouse, PointerButton::Primary);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_ended_with_drag_cancel(&app);
+    }
+
+    #[test]
+    fn drag_lock_zeroes_the_locked_axis_for_the_whole_drag() {
+        let (mut app, entities) = hovering_app(1);
+        app.world_mut().entity_mut(entities[0]).insert(DragLock::X);
+        let move_to = |app: &mut App, step: f32| {
+            send_input(
+                app,
+                Vec2::splat(step),
+                PointerAction::Move { delta: Vec2::ONE },
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        move_to(&mut app, 1.0);
+        // Removing the lock mid-drag doesn't unlock the drag.
+        app.world_mut().entity_mut(entities[0]).remove::<DragLock>();
+        move_to(&mut app, 2.0);
+        move_to(&mut app, 3.0);
+        send_input(
+            &mut app,
+            Vec2::splat(3.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let drags: Vec<_> = app
+            .world()
+            .resource::<Messages<Pointer<Drag>>>()
+            .iter_current_update_messages()
+            .map(|drag| (drag.delta, drag.distance))
+            .collect();
+        assert_eq!(
+            drags,
+            vec![
+                (Vec2::X, Vec2::X),
+                (Vec2::X, Vec2::X * 2.0),
+                (Vec2::X, Vec2::X * 3.0),
+            ]
+        );
+        let drag_end = app
+            .world()
+            .resource::<Messages<Pointer<DragEnd>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap();
+        assert_eq!(drag_end.distance, Vec2::X * 3.0);
+    }
+
+    #[test]
+    fn drag_velocity_is_the_delta_over_the_time_between_moves() {
+        let (mut app, _) = hovering_app(1);
+        app.insert_resource(PickingClock::manual());
+        let advance = |app: &mut App, millis: u64| {
+            app.world_mut()
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(millis));
+        };
+        let move_to = |app: &mut App, x: f32, previous: f32| {
+            send_input(
+                app,
+                Vec2::new(x, 0.0),
+                PointerAction::Move {
+                    delta: Vec2::new(x - previous, 0.0),
+                },
+            );
+        };
+        let velocities = |app: &mut App| {
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            let mut drags = app.world_mut().resource_mut::<Messages<Pointer<Drag>>>();
+            let velocities: Vec<_> = drags.drain().map(|drag| drag.velocity.length()).collect();
+            velocities
+        };
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        // 10 pixels in 20 milliseconds, then a second move at the same instant keeps the velocity.
+        advance(&mut app, 20);
+        move_to(&mut app, 10.0, 0.0);
+        move_to(&mut app, 12.0, 10.0);
+        assert_eq!(velocities(&mut app), vec![500.0, 500.0]);
+
+        // 10 pixels in 10 milliseconds.
+        advance(&mut app, 10);
+        move_to(&mut app, 22.0, 12.0);
+        assert_eq!(velocities(&mut app), vec![1000.0]);
+    }
+
+    #[test]
+    fn over_reports_the_velocity_of_the_approach() {
+        let (mut app, _) = hovering_app(0);
+        app.insert_resource(PickingClock::manual())
+            .init_resource::<VelocitySettings>();
+        record::<Over>(&mut app);
+        let target = app.world_mut().spawn_empty().id();
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        let pointer = app
+            .world()
+            .resource::<PointerMap>()
+            .get_entity(PointerId::Mouse)
+            .unwrap();
+        let frame = |app: &mut App, x: f32| {
+            app.world_mut()
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(10));
+            app.world_mut()
+                .entity_mut(pointer)
+                .insert(PointerLocation::new(location(Vec2::new(x, 0.0))));
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+        let velocity = |app: &App| {
+            app.world()
+                .resource::<PointerState>()
+                .velocity(PointerId::Mouse)
+        };
+        app.world_mut().resource_mut::<PreviousHoverMap>().clear();
+
+        // The pointer approaches at 1000 pixels per second, then stops on the target.
+        for step in 0..10 {
+            frame(&mut app, step as f32 * 10.0);
+        }
+        assert!(received(&app, PointerEventKind::Over).is_empty());
+        app.world_mut().resource_mut::<HoverMap>().insert(
+            PointerId::Mouse,
+            HashMap::from_iter([(target, HitData::new(camera, 0.0, None, None))]),
+        );
+        frame(&mut app, 100.0);
+
+        assert_eq!(received(&app, PointerEventKind::Over), vec![target]);
+        let over = app
+            .world()
+            .resource::<Messages<Pointer<Over>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap()
+            .velocity
+            .unwrap();
+        assert!(over.abs_diff_eq(Vec2::new(1000.0, 0.0), 1e-2), "{over}");
+
+        // Once it has stopped for longer than the window, the pointer is still.
+        for _ in 0..11 {
+            frame(&mut app, 100.0);
+        }
+        assert_eq!(velocity(&app), Some(Vec2::ZERO));
+
+        // Without settings, the velocity isn't tracked.
+        app.world_mut().remove_resource::<VelocitySettings>();
+        app.world_mut()
+            .resource_mut::<PointerState>()
+            .motion
+            .clear();
+        frame(&mut app, 150.0);
+        assert_eq!(velocity(&app), None);
+    }
+
+    #[test]
+    fn drags_start_past_the_threshold_from_the_press() {
+        let (mut app, entities) = hovering_app(1);
+        record::<DragStart>(&mut app);
+        app.insert_resource(DragSettings {
+            drag_threshold: 10.0,
+            ..Default::default()
+        });
+        let drags = |app: &App| {
+            app.world()
+                .resource::<Messages<Pointer<Drag>>>()
+                .iter_current_update_messages()
+                .map(|drag| (drag.delta, drag.distance))
+                .collect::<Vec<_>>()
+        };
+        let move_to = |app: &mut App, position: Vec2, previous: Vec2| {
+            send_input(
+                app,
+                position,
+                PointerAction::Move {
+                    delta: position - previous,
+                },
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        move_to(&mut app, Vec2::new(3.0, 0.0), Vec2::ZERO);
+        move_to(&mut app, Vec2::new(6.0, 8.0), Vec2::new(3.0, 0.0));
+        assert!(received(&app, PointerEventKind::DragStart).is_empty());
+        assert!(drags(&app).is_empty());
+
+        // Past the threshold, the drag covers the whole movement since the press.
+        move_to(&mut app, Vec2::new(9.0, 9.0), Vec2::new(6.0, 8.0));
+        assert_eq!(received(&app, PointerEventKind::DragStart), entities);
+        assert_eq!(
+            drags(&app),
+            vec![(Vec2::new(9.0, 9.0), Vec2::new(9.0, 9.0))]
+        );
+
+        // Moving back within the threshold keeps dragging.
+        move_to(&mut app, Vec2::new(1.0, 0.0), Vec2::new(9.0, 9.0));
+        assert_eq!(drags(&app)[1], (Vec2::new(-8.0, -9.0), Vec2::new(1.0, 0.0)));
+    }
+
+    #[test]
+    fn drags_resume_after_a_gap_according_to_the_policy() {
+        #[derive(Resource, Default)]
+        struct Drags(Vec<(Vec2, Vec2)>);
+
+        let drag_after_gap = |policy| {
+            let (mut app, _) = hovering_app(1);
+            record::<DragStart>(&mut app);
+            record::<DragEnd>(&mut app);
+            record::<DragCancel>(&mut app);
+            app.init_resource::<Drags>()
+                .insert_resource(PickingClock::manual())
+                .insert_resource(DragResumeSettings {
+                    policy,
+                    max_gap: Duration::from_millis(250),
+                    max_jump: 100.0,
+                });
+            app.world_mut()
+                .add_observer(|drag: On<Pointer<Drag>>, mut drags: ResMut<Drags>| {
+                    drags.0.push((drag.delta, drag.distance));
+                });
+            let move_to = |app: &mut App, x: f32, previous_x: f32| {
+                send_input(
+                    app,
+                    Vec2::new(x, 0.0),
+                    PointerAction::Move {
+                        delta: Vec2::new(x - previous_x, 0.0),
+                    },
+                );
+                app.world_mut().run_system_cached(pointer_events).unwrap();
             };
 
-            // Always send Out events
-            let out_event = Pointer::new(
-                pointer_id,
-                location.clone(),
-                Out { hit: hit.clone() },
-                hovered_entity,
+            send_input(
+                &mut app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
             );
-            commands.trigger(out_event.clone());
-            message_writers.out_events.write(out_event);
+            move_to(&mut app, 10.0, 0.0);
+            // The window is minimized, and the pointer moves a long way before input resumes.
+            app.world_mut()
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_secs(1));
+            move_to(&mut app, 510.0, 10.0);
+            move_to(&mut app, 520.0, 510.0);
+            let drags = app.world().resource::<Drags>().0.clone();
+            (app, drags)
+        };
 
-            // Possibly send DragLeave events
-            for button in PointerButton::iter() {
-                let state = pointer_state.get_mut(pointer_id, button);
-                state.dragging_over.remove(&hovered_entity);
-                for drag_target in state.dragging.keys() {
-                    let drag_leave_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        DragLeave {
-                            button,
-                            dragged: *drag_target,
-                            hit: hit.clone(),
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(drag_leave_event.clone());
-                    message_writers.drag_leave_events.write(drag_leave_event);
-                }
+        let (_, drags) = drag_after_gap(DragResumePolicy::Clamp);
+        assert_eq!(
+            drags,
+            vec![
+                (Vec2::new(10.0, 0.0), Vec2::new(10.0, 0.0)),
+                (Vec2::new(100.0, 0.0), Vec2::new(110.0, 0.0)),
+                (Vec2::new(10.0, 0.0), Vec2::new(120.0, 0.0)),
+            ]
+        );
+
+        let (_, drags) = drag_after_gap(DragResumePolicy::Reset);
+        assert_eq!(
+            drags,
+            vec![
+                (Vec2::new(10.0, 0.0), Vec2::new(10.0, 0.0)),
+                (Vec2::new(10.0, 0.0), Vec2::new(20.0, 0.0)),
+            ]
+        );
+
+        let (app, drags) = drag_after_gap(DragResumePolicy::Cancel);
+        assert_eq!(drags, vec![(Vec2::new(10.0, 0.0), Vec2::new(10.0, 0.0))]);
+        assert_eq!(received(&app, PointerEventKind::DragCancel).len(), 1);
+        assert!(
+            received(&app, PointerEventKind::DragEnd).is_empty(),
+            "a canceled drag shouldn't look like a completed one"
+        );
+        assert_eq!(
+            received(&app, PointerEventKind::DragStart).len(),
+            1,
+            "a canceled drag shouldn't restart until the button is pressed again"
+        );
+    }
+
+    #[test]
+    fn simultaneous_drag_enters_are_ordered_by_entity() {
+        let (mut app, _) = hovering_app(0);
+        record::<DragEnter>(&mut app);
+        let world = app.world_mut();
+        let dragged = world.spawn_empty().id();
+        let targets: Vec<_> = (0..8).map(|_| world.spawn_empty().id()).collect();
+        let camera = world.spawn(Camera::default()).id();
+        let hit = HitData::new(camera, 1.0, None, None);
+        let hover = |world: &mut World, entities: &[Entity]| {
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                entities
+                    .iter()
+                    .map(|entity| (*entity, hit.clone()))
+                    .collect(),
+            );
+        };
+
+        hover(app.world_mut(), &[dragged]);
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert!(received(&app, PointerEventKind::DragEnter).is_empty());
+
+        hover(app.world_mut(), &[&[dragged], targets.as_slice()].concat());
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        // The dragged entity is still under the pointer, so it is entered as well.
+        let mut entered = [&[dragged], targets.as_slice()].concat();
+        entered.sort();
+        assert_eq!(received(&app, PointerEventKind::DragEnter), entered);
+    }
+
+    #[test]
+    fn button_observer_ignores_other_buttons() {
+        #[derive(Resource, Default)]
+        struct RightClicks(usize);
+
+        let mut world = World::new();
+        world.init_resource::<RightClicks>();
+        let entity = world
+            .spawn_empty()
+            .observe_button(
+                PointerButton::Secondary,
+                |_: On<Pointer<Click>>, mut clicks: ResMut<RightClicks>| clicks.0 += 1,
+            )
+            .id();
+        let click = |button| {
+            Pointer::new(
+                PointerId::Mouse,
+                location(Vec2::ZERO),
+                Click {
+                    button,
+                    hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+                    duration: Duration::ZERO,
+                    count: 1,
+                },
+                entity,
+            )
+        };
+
+        world.trigger(click(PointerButton::Primary));
+        assert_eq!(world.resource::<RightClicks>().0, 0);
+        world.trigger(click(PointerButton::Secondary));
+        assert_eq!(world.resource::<RightClicks>().0, 1);
+        world.trigger(click(PointerButton::Middle));
+        assert_eq!(world.resource::<RightClicks>().0, 1);
+    }
+
+    #[test]
+    fn event_order_is_reproducible() {
+        let run = |reverse: bool| {
+            let (mut app, mut entities) = hovering_app(16);
+            record::<Over>(&mut app);
+            record::<Press>(&mut app);
+            record::<DragStart>(&mut app);
+            record::<Move>(&mut app);
+            record::<Release>(&mut app);
+            let world = app.world_mut();
+            let camera = world.spawn(Camera::default()).id();
+            // Build the hover map in a different order on each run.
+            if reverse {
+                entities.reverse();
+            }
+            let mut hits = HashMap::default();
+            for entity in &entities {
+                hits.insert(*entity, HitData::new(camera, 0.0, None, None));
             }
+            world.resource_mut::<PreviousHoverMap>().clear();
+            world
+                .resource_mut::<HoverMap>()
+                .insert(PointerId::Mouse, hits.clone());
+            send_input(
+                &mut app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+
+            app.world_mut()
+                .resource_mut::<PreviousHoverMap>()
+                .insert(PointerId::Mouse, hits);
+            send_input(&mut app, Vec2::X, PointerAction::Move { delta: Vec2::X });
+            send_input(
+                &mut app,
+                Vec2::X,
+                PointerAction::Release(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            app.world_mut().remove_resource::<Received>().unwrap().0
+        };
+
+        let first = run(false);
+        assert_eq!(first, run(true));
+        assert_eq!(first, run(false));
+        // Within each kind, entities receive their events in ascending order.
+        for kind in [
+            PointerEventKind::Over,
+            PointerEventKind::Press,
+            PointerEventKind::DragStart,
+            PointerEventKind::Move,
+            PointerEventKind::Release,
+        ] {
+            let entities: Vec<_> = first
+                .iter()
+                .filter(|(received, _)| *received == kind)
+                .map(|(_, entity)| *entity)
+                .collect();
+            assert_eq!(entities.len(), 16);
+            assert!(entities.is_sorted());
         }
     }
 
-    // Iterate all currently hovered entities for each pointer
-    for (pointer_id, hovered_entity, hit) in hover_map
-        .iter()
-        .flat_map(|(id, hashmap)| hashmap.iter().map(|data| (*id, *data.0, data.1.clone())))
-    {
-        // Continue if the pointer does not have a valid location.
-        let Some(location) = pointer_location(pointer_id) else {
-            debug!(
-                "Unable to get location for pointer {:?} during pointer over",
-                pointer_id
+    #[test]
+    fn occluded_pressed_entity_is_still_released() {
+        let (mut app, entities) = hovering_app(1);
+        record::<Release>(&mut app);
+        record::<Click>(&mut app);
+        let button = entities[0];
+        let world = app.world_mut();
+        let camera = world.spawn(Camera::default()).id();
+        let front = world.spawn_empty().id();
+        let press_then_occlude_and_release = |app: &mut App| {
+            app.world_mut().resource_mut::<Received>().0.clear();
+            let hits =
+                |entity| HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]);
+            app.world_mut()
+                .resource_mut::<PreviousHoverMap>()
+                .insert(PointerId::Mouse, hits(button));
+            send_input(
+                app,
+                Vec2::ZERO,
+                PointerAction::Press(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+
+            // Something moves in front of the button and blocks it before the release.
+            app.world_mut()
+                .resource_mut::<PreviousHoverMap>()
+                .insert(PointerId::Mouse, hits(front));
+            send_input(
+                app,
+                Vec2::ZERO,
+                PointerAction::Release(PointerButton::Primary),
+            );
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+
+        // By default, only the hovered entity is released.
+        press_then_occlude_and_release(&mut app);
+        assert_eq!(received(&app, PointerEventKind::Release), vec![front]);
+
+        app.insert_resource(PressSettings {
+            release_pressed_entities: true,
+            ..Default::default()
+        });
+        press_then_occlude_and_release(&mut app);
+        assert_eq!(
+            received(&app, PointerEventKind::Release),
+            vec![front, button]
+        );
+        assert!(received(&app, PointerEventKind::Click).is_empty());
+    }
+
+    #[test]
+    fn consecutive_clicks_on_the_same_entity_are_counted() {
+        let (mut app, entities) = hovering_app(2);
+        app.insert_resource(PickingClock::manual());
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        // Clicks `entity` after `millis`, returning the counts of the clicks it generated.
+        let click = |app: &mut App, entity: Entity, millis| {
+            let world = app.world_mut();
+            world.resource_mut::<Messages<Pointer<Click>>>().clear();
+            world
+                .resource_mut::<PickingClock>()
+                .advance(Duration::from_millis(millis));
+            let hits = HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]);
+            world
+                .resource_mut::<HoverMap>()
+                .insert(PointerId::Mouse, hits.clone());
+            world
+                .resource_mut::<PreviousHoverMap>()
+                .insert(PointerId::Mouse, hits);
+            for action in [
+                PointerAction::Press(PointerButton::Primary),
+                PointerAction::Release(PointerButton::Primary),
+            ] {
+                send_input(app, Vec2::ZERO, action);
+            }
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            app.world()
+                .resource::<Messages<Pointer<Click>>>()
+                .iter_current_update_messages()
+                .map(|click| click.count)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(click(&mut app, entities[0], 0), vec![1]);
+        assert_eq!(click(&mut app, entities[0], 200), vec![2]);
+        assert_eq!(click(&mut app, entities[0], 300), vec![3]);
+        // Too late.
+        assert_eq!(click(&mut app, entities[0], 301), vec![1]);
+        assert_eq!(click(&mut app, entities[0], 100), vec![2]);
+
+        // Clicking another entity in between starts over.
+        assert_eq!(click(&mut app, entities[1], 100), vec![1]);
+        assert_eq!(click(&mut app, entities[0], 100), vec![1]);
+
+        // So does canceling the pointer.
+        send_input(&mut app, Vec2::ZERO, PointerAction::Cancel);
+        assert_eq!(click(&mut app, entities[0], 100), vec![1]);
+
+        // The window is configurable.
+        app.insert_resource(ClickSettings {
+            multi_click_window: Duration::from_millis(500),
+            ..Default::default()
+        });
+        assert_eq!(click(&mut app, entities[0], 400), vec![2]);
+    }
+
+    #[test]
+    fn frame_summary_lists_hovers_presses_and_drags() {
+        let (mut app, mut entities) = hovering_app(2);
+        entities.sort_unstable();
+        let camera = app.world_mut().spawn(Camera::default()).id();
+        let mut hover_map = app.world_mut().resource_mut::<HoverMap>();
+        hover_map.insert(
+            PointerId::Touch(1),
+            HashMap::from_iter([(entities[1], HitData::new(camera, 0.0, None, None))]),
+        );
+        hover_map.insert(PointerId::Touch(2), HashMap::default());
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Secondary),
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Move {
+                delta: Vec2::new(5.0, 0.0),
+            },
+        );
+        send_input(
+            &mut app,
+            Vec2::new(5.0, 0.0),
+            PointerAction::Release(PointerButton::Secondary),
+        );
+        let world = app.world_mut();
+        world.run_system_cached(pointer_events).unwrap();
+        world.run_system_cached(summarize_interactions).unwrap();
+
+        let summaries: Vec<_> = app
+            .world()
+            .resource::<Messages<PickingFrameSummary>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect();
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(
+            summary.get(PointerId::Mouse),
+            Some(&PointerInteractionSummary {
+                pointer: PointerId::Mouse,
+                hovered: entities.clone(),
+                pressed: entities
+                    .iter()
+                    .map(|entity| (PointerButton::Primary, *entity))
+                    .collect(),
+                dragged: entities
+                    .iter()
+                    .map(|entity| (PointerButton::Primary, *entity))
+                    .collect(),
+            })
+        );
+        assert_eq!(
+            summary.get(PointerId::Touch(1)),
+            Some(&PointerInteractionSummary {
+                pointer: PointerId::Touch(1),
+                hovered: vec![entities[1]],
+                ..Default::default()
+            })
+        );
+        // Pointers without any interaction are left out.
+        assert_eq!(summary.pointers.len(), 2);
+    }
+
+    #[test]
+    fn hovering_nothing_is_reported_once() {
+        #[derive(Resource, Default)]
+        struct Triggered(usize);
+
+        let (mut app, _) = hovering_app(2);
+        app.init_resource::<Triggered>();
+        app.world_mut().add_observer(
+            |_: On<PointerHoverNothing>, mut triggered: ResMut<Triggered>| {
+                triggered.0 += 1;
+            },
+        );
+        let hover = |app: &mut App, hits: HashMap<Entity, HitData>| {
+            let world = app.world_mut();
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world
+                .resource_mut::<HoverMap>()
+                .insert(PointerId::Mouse, hits);
+            world.run_system_cached(pointer_hover_transitions).unwrap();
+        };
+        let messages = |app: &App| {
+            (
+                app.world()
+                    .resource::<Messages<PointerHoverNothing>>()
+                    .iter_current_update_messages()
+                    .copied()
+                    .collect::<Vec<_>>(),
+                app.world()
+                    .resource::<Messages<PointerHoverStarted>>()
+                    .iter_current_update_messages()
+                    .copied()
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        // Moving off both entities at once only reports a single transition.
+        hover(&mut app, HashMap::default());
+        hover(&mut app, HashMap::default());
+        assert_eq!(
+            messages(&app),
+            (
+                vec![PointerHoverNothing {
+                    pointer: PointerId::Mouse
+                }],
+                vec![]
+            )
+        );
+        assert_eq!(app.world().resource::<Triggered>().0, 1);
+
+        let camera = app.world_mut().spawn_empty().id();
+        let entity = app.world_mut().spawn_empty().id();
+        hover(
+            &mut app,
+            HashMap::from_iter([(entity, HitData::new(camera, 0.0, None, None))]),
+        );
+        assert_eq!(
+            messages(&app).1,
+            vec![PointerHoverStarted {
+                pointer: PointerId::Mouse
+            }]
+        );
+    }
+
+    #[test]
+    fn mirrored_events_keep_their_dispatch_order_and_payload() {
+        let (mut app, entities) = hovering_app(1);
+        app.add_plugins(crate::PointerEventMirrorPlugin);
+        let entity = entities[0];
+        // Enters the hovered subtree before the inputs are sent.
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        app.world_mut()
+            .resource_mut::<Messages<AnyPointerEvent>>()
+            .clear();
+
+        let position = Vec2::new(3.0, 4.0);
+        send_input(&mut app, position, PointerAction::Move { delta: position });
+        send_input(
+            &mut app,
+            position,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send_input(
+            &mut app,
+            position,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let mirrored: Vec<_> = app
+            .world()
+            .resource::<Messages<AnyPointerEvent>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect();
+        assert_eq!(
+            mirrored
+                .iter()
+                .map(AnyPointerEvent::kind)
+                .collect::<Vec<_>>(),
+            vec![
+                PointerEventKind::Move,
+                PointerEventKind::Press,
+                PointerEventKind::FirstInteraction,
+                PointerEventKind::Click,
+                PointerEventKind::Release,
+            ]
+        );
+        assert!(mirrored.iter().all(|event| event.entity() == entity
+            && event.pointer_id() == PointerId::Mouse
+            && event.pointer_location().position == position));
+        let AnyPointerEvent::Click(click) = &mirrored[3] else {
+            panic!("expected a click, got {:?}", mirrored[3]);
+        };
+        let written = app
+            .world()
+            .resource::<Messages<Pointer<Click>>>()
+            .iter_current_update_messages()
+            .next()
+            .unwrap();
+        assert_eq!(click, written);
+    }
+
+    #[test]
+    fn processed_inputs_list_the_events_they_generated() {
+        let (mut app, entities) = hovering_app(1);
+        app.add_message::<ProcessedPointerInput>();
+        record::<Move>(&mut app);
+        record::<Press>(&mut app);
+        record::<FirstInteraction>(&mut app);
+
+        let delta = Vec2::new(1.0, 0.0);
+        send_input(&mut app, delta, PointerAction::Move { delta });
+        send_input(&mut app, delta, PointerAction::Move { delta: Vec2::ZERO });
+        send_input(
+            &mut app,
+            delta,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let processed: Vec<_> = app
+            .world()
+            .resource::<Messages<ProcessedPointerInput>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect();
+        assert_eq!(processed.len(), 3);
+        assert!(matches!(
+            processed[0].input.action,
+            PointerAction::Move { .. }
+        ));
+        assert_eq!(
+            processed[0].events,
+            vec![(PointerEventKind::Move, entities[0])]
+        );
+        // A move without movement generates nothing.
+        assert!(!processed[1].contributed());
+        assert!(matches!(
+            processed[2].input.action,
+            PointerAction::Press(PointerButton::Primary)
+        ));
+        assert_eq!(
+            processed[2].events[0],
+            (PointerEventKind::Press, entities[0])
+        );
+
+        // Together, the annotations account for every event that was emitted, in order.
+        let annotated: Vec<_> = processed
+            .iter()
+            .flat_map(|processed| processed.events.iter().copied())
+            .collect();
+        assert_eq!(annotated, app.world().resource::<Received>().0);
+    }
+
+    #[test]
+    fn topmost_transitions_between_overlapping_entities() {
+        let (mut app, _) = hovering_app(0);
+        record::<BecameTopmost>(&mut app);
+        record::<LostTopmost>(&mut app);
+        let world = app.world_mut();
+        let back = world.spawn_empty().id();
+        let front = world.spawn_empty().id();
+        let camera = world.spawn(Camera::default()).id();
+        let hover = |app: &mut App, entities: &[(Entity, f32)]| {
+            let world = app.world_mut();
+            world.resource_mut::<PreviousHoverMap>().0 = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<HoverMap>().insert(
+                PointerId::Mouse,
+                entities
+                    .iter()
+                    .map(|(entity, depth)| (*entity, HitData::new(camera, *depth, None, None)))
+                    .collect(),
             );
-            continue;
+            world.run_system_cached(pointer_events).unwrap();
+        };
+        let transitions = |app: &mut App| {
+            let transitions = (
+                received(app, PointerEventKind::LostTopmost),
+                received(app, PointerEventKind::BecameTopmost),
+            );
+            app.world_mut().resource_mut::<Received>().0.clear();
+            transitions
         };
 
-        // For each button update its `dragging_over` state and possibly emit DragEnter events.
-        for button in PointerButton::iter() {
-            let state = pointer_state.get_mut(pointer_id, button);
+        hover(&mut app, &[(back, 2.0)]);
+        assert_eq!(transitions(&mut app), (vec![], vec![back]));
 
-            // Only update the `dragging_over` state if there is at least one entity being dragged.
-            // Only emit DragEnter events for this `hovered_entity`, if it had no previous `dragging_over` state.
-            if !state.dragging.is_empty()
-                && state
-                    .dragging_over
-                    .insert(hovered_entity, hit.clone())
-                    .is_none()
-            {
-                for drag_target in state.dragging.keys() {
-                    let drag_enter_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        DragEnter {
-                            button,
-                            dragged: *drag_target,
-                            hit: hit.clone(),
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(drag_enter_event.clone());
-                    message_writers.drag_enter_events.write(drag_enter_event);
-                }
-            }
-        }
+        hover(&mut app, &[(back, 2.0), (front, 1.0)]);
+        assert_eq!(transitions(&mut app), (vec![back], vec![front]));
 
-        // Emit an Over event if the `hovered_entity` was not hovered by the same pointer the previous frame.
-        if !previous_hover_map
-            .get(&pointer_id)
-            .iter()
-            .any(|e| e.contains_key(&hovered_entity))
-        {
-            let over_event = Pointer::new(
-                pointer_id,
-                location.clone(),
-                Over { hit: hit.clone() },
-                hovered_entity,
-            );
-            commands.trigger(over_event.clone());
-            message_writers.over_events.write(over_event);
-        }
+        hover(&mut app, &[(back, 2.0), (front, 1.0)]);
+        assert_eq!(transitions(&mut app), (vec![], vec![]));
+
+        hover(&mut app, &[(back, 2.0)]);
+        assert_eq!(transitions(&mut app), (vec![front], vec![back]));
+
+        hover(&mut app, &[]);
+        assert_eq!(transitions(&mut app), (vec![back], vec![]));
     }
 
-    // Dispatch input events...
-    for PointerInput {
-        pointer_id,
-        location,
-        action,
-    } in input_events.read().cloned()
-    {
-        match action {
-            PointerAction::Press(button) => {
-                let state = pointer_state.get_mut(pointer_id, button);
+    #[test]
+    fn idle_duration_resets_on_new_input() {
+        let (mut app, _) = hovering_app(0);
+        let idle = |app: &App, after: Duration| {
+            app.world()
+                .resource::<PointerActivity>()
+                .idle_duration(PointerId::Mouse, Instant::now() + after)
+        };
+        assert_eq!(idle(&app, Duration::ZERO), Duration::MAX);
 
-                // If it's a press, emit a Pressed event and mark the hovered entities as pressed
-                for (hovered_entity, hit) in hover_map
-                    .get(&pointer_id)
-                    .iter()
-                    .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.clone())))
-                {
-                    let pressed_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        Press {
-                            button,
-                            hit: hit.clone(),
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(pressed_event.clone());
-                    message_writers.pressed_events.write(pressed_event);
-                    // Also insert the press into the state
-                    state
-                        .pressing
-                        .insert(hovered_entity, (location.clone(), now, hit));
-                }
-            }
-            PointerAction::Release(button) => {
-                let state = pointer_state.get_mut(pointer_id, button);
+        send_input(
+            &mut app,
+            Vec2::ONE,
+            PointerAction::Move { delta: Vec2::ONE },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        let later = Duration::from_secs(5);
+        assert!(idle(&app, later) >= later);
 
-                // Emit Click and Release events on all the previously hovered entities.
-                for (hovered_entity, hit) in previous_hover_map
-                    .get(&pointer_id)
-                    .iter()
-                    .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.clone())))
-                {
-                    // If this pointer previously pressed the hovered entity, emit a Click event
-                    if let Some((_, press_instant, _)) = state.pressing.get(&hovered_entity) {
-                        let click_event = Pointer::new(
-                            pointer_id,
-                            location.clone(),
-                            Click {
-                                button,
-                                hit: hit.clone(),
-                                duration: now - *press_instant,
-                            },
-                            hovered_entity,
-                        );
-                        commands.trigger(click_event.clone());
-                        message_writers.click_events.write(click_event);
-                    }
-                    // Always send the Release event
-                    let released_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        Release {
-                            button,
-                            hit: hit.clone(),
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(released_event.clone());
-                    message_writers.released_events.write(released_event);
-                }
+        send_input(
+            &mut app,
+            Vec2::ONE,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert!(idle(&app, Duration::ZERO) < later);
 
-                // Then emit the drop events.
-                for (drag_target, drag) in state.dragging.drain() {
-                    // Emit DragDrop
-                    for (dragged_over, hit) in state.dragging_over.iter() {
-                        let drag_drop_event = Pointer::new(
-                            pointer_id,
-                            location.clone(),
-                            DragDrop {
-                                button,
-                                dropped: drag_target,
-                                hit: hit.clone(),
-                            },
-                            *dragged_over,
-                        );
-                        commands.trigger(drag_drop_event.clone());
-                        message_writers.drag_drop_events.write(drag_drop_event);
-                    }
-                    // Emit DragEnd
-                    let drag_end_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        DragEnd {
-                            button,
-                            distance: drag.latest_pos - drag.start_pos,
-                        },
-                        drag_target,
-                    );
-                    commands.trigger(drag_end_event.clone());
-                    message_writers.drag_end_events.write(drag_end_event);
-                    // Emit DragLeave
-                    for (dragged_over, hit) in state.dragging_over.iter() {
-                        let drag_leave_event = Pointer::new(
-                            pointer_id,
-                            location.clone(),
-                            DragLeave {
-                                button,
-                                dragged: drag_target,
-                                hit: hit.clone(),
-                            },
-                            *dragged_over,
-                        );
-                        commands.trigger(drag_leave_event.clone());
-                        message_writers.drag_leave_events.write(drag_leave_event);
-                    }
-                }
+        let last_input = |app: &App| {
+            app.world()
+                .resource::<PointerActivity>()
+                .last_input(PointerId::Mouse)
+        };
+        let pressed_at = last_input(&app);
+        send_input(&mut app, Vec2::ONE, PointerAction::Cancel);
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+        assert_eq!(last_input(&app), pressed_at);
+    }
 
-                // Finally, we can clear the state of everything relating to presses or drags.
-                state.clear();
-            }
-            // Moved
-            PointerAction::Move { delta } => {
-                if delta == Vec2::ZERO {
-                    continue; // If delta is zero, the following events will not be triggered.
-                }
-                // Triggers during movement even if not over an entity
-                for button in PointerButton::iter() {
-                    let state = pointer_state.get_mut(pointer_id, button);
+    #[test]
+    fn captured_entity_keeps_receiving_events_after_the_pointer_leaves() {
+        let (mut app, entities) = hovering_app(1);
+        let slider = entities[0];
+        record::<Move>(&mut app);
+        record::<Drag>(&mut app);
+        record::<Release>(&mut app);
+        record::<Click>(&mut app);
+        app.world_mut().add_observer(
+            |press: On<Pointer<Press>>, mut state: ResMut<PointerState>| {
+                state.capture(press.pointer_id, press.button, press.entity);
+            },
+        );
+        let other = app.world_mut().spawn_empty().id();
+        let hover = |app: &mut App, entity: Option<Entity>| {
+            let hits = entity
+                .map(|entity| (entity, HitData::new(Entity::PLACEHOLDER, 0.0, None, None)))
+                .into_iter()
+                .collect();
+            let world = app.world_mut();
+            let previous = world.resource::<HoverMap>().0.clone();
+            world.resource_mut::<PreviousHoverMap>().0 = previous;
+            world
+                .resource_mut::<HoverMap>()
+                .insert(PointerId::Mouse, hits);
+            world.resource_mut::<Received>().0.clear();
+        };
+        let step = |app: &mut App, x: f32, action: PointerAction| {
+            send_input(app, Vec2::new(x, 0.0), action);
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+        };
+        let moved = |delta: f32| PointerAction::Move {
+            delta: Vec2::new(delta, 0.0),
+        };
 
-                    // Emit DragEntry and DragStart the first time we move while pressing an entity
-                    for (press_target, (location, _, hit)) in state.pressing.iter() {
-                        if state.dragging.contains_key(press_target) {
-                            continue; // This entity is already logged as being dragged
-                        }
-                        state.dragging.insert(
-                            *press_target,
-                            DragEntry {
-                                start_pos: location.position,
-                                latest_pos: location.position,
-                            },
-                        );
-                        let drag_start_event = Pointer::new(
-                            pointer_id,
-                            location.clone(),
-                            DragStart {
-                                button,
-                                hit: hit.clone(),
-                            },
-                            *press_target,
-                        );
+        step(&mut app, 0.0, PointerAction::Press(PointerButton::Primary));
+        // The pointer leaves the slider, and even the window, while dragging its thumb.
+        hover(&mut app, None);
+        step(&mut app, 50.0, moved(50.0));
+        hover(&mut app, Some(other));
+        step(&mut app, 2000.0, moved(1950.0));
+        assert_eq!(received(&app, PointerEventKind::Move), vec![slider]);
+        assert_eq!(received(&app, PointerEventKind::Drag), vec![slider]);
 
-                        commands.trigger(drag_start_event.clone());
-                        message_writers.drag_start_events.write(drag_start_event);
+        step(
+            &mut app,
+            2000.0,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        assert_eq!(received(&app, PointerEventKind::Release), vec![slider]);
+        assert_eq!(received(&app, PointerEventKind::Click), vec![slider]);
+        let state = app.world().resource::<PointerState>();
+        assert_eq!(
+            state.captured(PointerId::Mouse, PointerButton::Primary),
+            None
+        );
 
-                        // Insert dragging over state and emit DragEnter for hovered entities.
-                        for (hovered_entity, hit) in hover_map
-                            .get(&pointer_id)
-                            .iter()
-                            .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.to_owned())))
-                            .filter(|(hovered_entity, _)| *hovered_entity != *press_target)
-                        {
-                            // Inserting the `dragging_over` state here ensures the `DragEnter` event won't be dispatched twice.
-                            state.dragging_over.insert(hovered_entity, hit.clone());
-                            let drag_enter_event = Pointer::new(
-                                pointer_id,
-                                location.clone(),
-                                DragEnter {
-                                    button,
-                                    dragged: *press_target,
-                                    hit: hit.clone(),
-                                },
-                                hovered_entity,
-                            );
-                            commands.trigger(drag_enter_event.clone());
-                            message_writers.drag_enter_events.write(drag_enter_event);
-                        }
-                    }
+        // Moves follow the hover again once the capture is released.
+        hover(&mut app, Some(other));
+        step(&mut app, 2010.0, moved(10.0));
+        assert_eq!(received(&app, PointerEventKind::Move), vec![other]);
 
-                    // Emit Drag events to the entities we are dragging
-                    for (drag_target, drag) in state.dragging.iter_mut() {
-                        let delta = location.position - drag.latest_pos;
-                        if delta == Vec2::ZERO {
-                            continue; // No need to emit a Drag event if there is no movement
-                        }
-                        let drag_event = Pointer::new(
-                            pointer_id,
-                            location.clone(),
-                            Drag {
-                                button,
-                                distance: location.position - drag.start_pos,
-                                delta,
-                            },
-                            *drag_target,
-                        );
-                        commands.trigger(drag_event.clone());
-                        message_writers.drag_events.write(drag_event);
+        // A retained capture outlives the release of the button.
+        step(
+            &mut app,
+            2010.0,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        app.world_mut()
+            .resource_mut::<PointerState>()
+            .capture_retained(PointerId::Mouse, PointerButton::Primary, slider);
+        step(
+            &mut app,
+            2010.0,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        hover(&mut app, Some(other));
+        step(&mut app, 2020.0, moved(10.0));
+        assert_eq!(received(&app, PointerEventKind::Move), vec![slider]);
+        app.world_mut()
+            .resource_mut::<PointerState>()
+            .release_capture(PointerId::Mouse, PointerButton::Primary);
+        hover(&mut app, Some(other));
+        step(&mut app, 2030.0, moved(10.0));
+        assert_eq!(received(&app, PointerEventKind::Move), vec![other]);
+    }
 
-                        // Update drag position
-                        drag.latest_pos = location.position;
+    #[test]
+    fn capture_changes_are_reported_in_pairs() {
+        #[derive(Resource, Default)]
+        struct Changes(Vec<Option<Entity>>);
 
-                        // Emit corresponding DragOver to the hovered entities
-                        for (hovered_entity, hit) in hover_map
-                            .get(&pointer_id)
-                            .iter()
-                            .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.to_owned())))
-                            .filter(|(hovered_entity, _)| *hovered_entity != *drag_target)
-                        {
-                            let drag_over_event = Pointer::new(
-                                pointer_id,
-                                location.clone(),
-                                DragOver {
-                                    button,
-                                    dragged: *drag_target,
-                                    hit: hit.clone(),
-                                },
-                                hovered_entity,
-                            );
-                            commands.trigger(drag_over_event.clone());
-                            message_writers.drag_over_events.write(drag_over_event);
-                        }
-                    }
-                }
+        let (mut app, entities) = hovering_app(1);
+        let entity = entities[0];
+        let world = app.world_mut();
+        world.init_resource::<Changes>();
+        world.add_observer(
+            |change: On<PointerCaptureChanged>, mut changes: ResMut<Changes>| {
+                assert_eq!(change.pointer, PointerId::Mouse);
+                assert_eq!(change.button, PointerButton::Primary);
+                changes.0.push(change.captured);
+            },
+        );
+        world.add_observer(
+            |press: On<Pointer<Press>>, mut state: ResMut<PointerState>| {
+                state.capture(press.pointer_id, press.button, press.entity);
+            },
+        );
+        let run = |app: &mut App| {
+            app.world_mut().run_system_cached(pointer_events).unwrap();
+            core::mem::take(&mut app.world_mut().resource_mut::<Changes>().0)
+        };
 
-                for (hovered_entity, hit) in hover_map
-                    .get(&pointer_id)
-                    .iter()
-                    .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.to_owned())))
-                {
-                    // Emit Move events to the entities we are hovering
-                    let move_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        Move {
-                            hit: hit.clone(),
-                            delta,
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(move_event.clone());
-                    message_writers.move_events.write(move_event);
-                }
-            }
-            PointerAction::Scroll { x, y, unit } => {
-                for (hovered_entity, hit) in hover_map
-                    .get(&pointer_id)
-                    .iter()
-                    .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.clone())))
-                {
-                    // Emit Scroll events to the entities we are hovering
-                    let scroll_event = Pointer::new(
-                        pointer_id,
-                        location.clone(),
-                        Scroll {
-                            unit,
-                            x,
-                            y,
-                            hit: hit.clone(),
-                        },
-                        hovered_entity,
-                    );
-                    commands.trigger(scroll_event.clone());
-                    message_writers.scroll_events.write(scroll_event);
-                }
-            }
-            // Canceled
-            PointerAction::Cancel => {
-                // Emit a Cancel to the hovered entity.
-                for (hovered_entity, hit) in hover_map
-                    .get(&pointer_id)
-                    .iter()
-                    .flat_map(|h| h.iter().map(|(entity, data)| (*entity, data.to_owned())))
-                {
-                    let cancel_event =
-                        Pointer::new(pointer_id, location.clone(), Cancel { hit }, hovered_entity);
-                    commands.trigger(cancel_event.clone());
-                    message_writers.cancel_events.write(cancel_event);
-                }
-                // Clear the state for the canceled pointer
-                pointer_state.clear(pointer_id);
-            }
-        }
+        // The capture made by the `Press` observer is reported on the next run.
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        assert_eq!(run(&mut app), vec![]);
+        assert_eq!(run(&mut app), vec![Some(entity)]);
+        assert_eq!(
+            app.world()
+                .resource::<PointerState>()
+                .captured(PointerId::Mouse, PointerButton::Primary),
+            Some(entity)
+        );
+
+        // Releasing the button releases the capture.
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Release(PointerButton::Primary),
+        );
+        assert_eq!(run(&mut app), vec![None]);
+        assert_eq!(run(&mut app), vec![]);
+
+        // Captures can also be released explicitly.
+        app.world_mut().resource_mut::<PointerState>().capture(
+            PointerId::Mouse,
+            PointerButton::Primary,
+            entity,
+        );
+        assert_eq!(run(&mut app), vec![Some(entity)]);
+        let released = app
+            .world_mut()
+            .resource_mut::<PointerState>()
+            .release_capture(PointerId::Mouse, PointerButton::Primary);
+        assert_eq!(released, Some(entity));
+        assert_eq!(run(&mut app), vec![None]);
+        assert_eq!(
+            app.world()
+                .resource::<Messages<PointerCaptureChanged>>()
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn scroll_reaches_every_hovered_entity_and_bubbles() {
+        let (mut app, entities) = hovering_app(2);
+        record::<Scroll>(&mut app);
+        let parent = app.world_mut().spawn_empty().id();
+        app.world_mut()
+            .entity_mut(entities[1])
+            .insert(ChildOf(parent));
+
+        send_input(
+            &mut app,
+            Vec2::ZERO,
+            PointerAction::Scroll {
+                delta: Vec2::new(0.0, -3.0),
+                unit: ScrollUnit::Line,
+            },
+        );
+        app.world_mut().run_system_cached(pointer_events).unwrap();
+
+        let mut scrolled = received(&app, PointerEventKind::Scroll);
+        scrolled.sort();
+        let mut expected = vec![entities[0], entities[1], parent];
+        expected.sort();
+        assert_eq!(scrolled, expected);
+        let scrolls = app.world().resource::<Messages<Pointer<Scroll>>>();
+        assert!(scrolls
+            .iter_current_update_messages()
+            .all(|scroll| scroll.delta.y == -3.0 && scroll.unit == ScrollUnit::Line));
+        assert_eq!(scrolls.len(), 2);
     }
 }
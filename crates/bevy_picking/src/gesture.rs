@@ -0,0 +1,904 @@
+//! An extension point for recognizing higher-level gestures from the pointer event stream.
+//!
+//! Gestures such as swipes, pinches, or double taps are built out of the same [`PointerInput`]
+//! stream and hover state that drive [`pointer_events`](crate::events::pointer_events). Rather
+//! than adding each gesture to the core dispatcher, implement [`GestureRecognizer`] and register
+//! it with [`GestureAppExt::add_gesture_recognizer`]. Every registered recognizer is fed each
+//! pointer input, in order, after the core pointer events for the frame have been dispatched.
+//!
+//! Recognizers emit their own events through [`Commands`], usually as [`Pointer<E>`] so that they
+//! bubble like the built-in events. [`SwipeRecognizer`] is provided as a reference implementation,
+//! and [`PinchRotateRecognizer`] recognizes two-finger touch gestures. Events that should also be
+//! read with a [`MessageReader`] must be written as messages too. The built-in events, which
+//! include the [`Pinch`] and [`Rotate`] events of [`PinchRotateRecognizer`], can instead be sent
+//! with [`GestureContext::send`] to be dispatched like the events of
+//! [`pointer_events`](crate::events::pointer_events).
+//!
+//! ```
+//! # use bevy_app::App;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::{gesture::*, prelude::*};
+//! # let mut app = App::new();
+//! # app.add_plugins((PickingPlugin, InteractionPlugin));
+//! app.add_gesture_recognizer(SwipeRecognizer::default());
+//! app.world_mut()
+//!     .spawn_empty()
+//!     .observe(|swipe: On<Pointer<Swipe>>| {
+//!         println!("Swiped {:?}", swipe.direction);
+//!     });
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::{cell::RefCell, time::Duration};
+
+use bevy_app::App;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_platform::collections::HashMap;
+use bevy_platform::time::Instant;
+use bevy_reflect::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    events::{
+        topmost_hit, AnyPointerEvent, GestureEventDispatcher, PickingClock, Pointer, PointerState,
+    },
+    hover::HoverMap,
+    pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput, PointerLocation},
+};
+
+/// Shared picking state made available to a [`GestureRecognizer`] for each input it processes.
+pub struct GestureContext<'a> {
+    /// The entities currently hovered by each pointer.
+    pub hover_map: &'a HoverMap,
+    /// The press and drag state maintained by [`pointer_events`](crate::events::pointer_events).
+    pub pointer_state: &'a PointerState,
+    /// The time of the input being processed: its [`PointerInput::time`] if it has one, or else
+    /// the time of the frame, as read from the [`PickingClock`].
+    pub now: Instant,
+    /// The events sent with [`GestureContext::send`], with the time of their input.
+    events: RefCell<Vec<(Instant, AnyPointerEvent)>>,
+}
+
+impl GestureContext<'_> {
+    /// Sends a built-in [`Pointer`] event, such as a [`Pinch`], once every recognizer has processed
+    /// the inputs of the frame.
+    ///
+    /// Unlike events triggered through [`Commands`], these are dispatched like the events of
+    /// [`pointer_events`](crate::events::pointer_events): they are both triggered and written as
+    /// messages, and respect the [`PickingLimits`](crate::events::PickingLimits),
+    /// [`PickEventMask`](crate::events::PickEventMask)s,
+    /// [`PointerEventMask`](crate::events::PointerEventMask)s,
+    /// [`PointerEventAdapter`](crate::events::PointerEventAdapter) and
+    /// [`PointerEventPropagation`](crate::events::PointerEventPropagation).
+    pub fn send(&self, event: impl Into<AnyPointerEvent>) {
+        self.events.borrow_mut().push((self.now, event.into()));
+    }
+
+    /// Returns an iterator over the entities hovered by the given pointer.
+    pub fn hovered(&self, pointer_id: PointerId) -> impl Iterator<Item = Entity> + '_ {
+        self.hover_map
+            .get(&pointer_id)
+            .into_iter()
+            .flat_map(|hovered| hovered.keys().copied())
+    }
+}
+
+/// Consumes the pointer input stream and emits custom gesture events.
+///
+/// Implementations track whatever per-pointer state they need, and use `commands` to trigger or
+/// write the events they recognize.
+pub trait GestureRecognizer: Send + Sync + 'static {
+    /// Processes a single [`PointerInput`]. Inputs are delivered in the order they were received.
+    fn process(&mut self, input: &PointerInput, context: &GestureContext, commands: &mut Commands);
+}
+
+/// The registry of [`GestureRecognizer`]s run by [`recognize_gestures`].
+#[derive(Resource, Default)]
+pub struct GestureRecognizers {
+    recognizers: Vec<Box<dyn GestureRecognizer>>,
+}
+
+impl GestureRecognizers {
+    /// Registers a recognizer. Recognizers are run in the order they were added.
+    pub fn add(&mut self, recognizer: impl GestureRecognizer) {
+        self.recognizers.push(Box::new(recognizer));
+    }
+
+    /// Returns the number of registered recognizers.
+    pub fn len(&self) -> usize {
+        self.recognizers.len()
+    }
+
+    /// Returns `true` if no recognizers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.recognizers.is_empty()
+    }
+}
+
+/// Extension trait for registering [`GestureRecognizer`]s on an [`App`].
+pub trait GestureAppExt {
+    /// Registers a [`GestureRecognizer`] to be fed the pointer input stream.
+    fn add_gesture_recognizer(&mut self, recognizer: impl GestureRecognizer) -> &mut Self;
+}
+
+impl GestureAppExt for App {
+    fn add_gesture_recognizer(&mut self, recognizer: impl GestureRecognizer) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GestureRecognizers>()
+            .add(recognizer);
+        self
+    }
+}
+
+/// Feeds this frame's [`PointerInput`]s to every registered [`GestureRecognizer`].
+///
+/// Runs in [`PickingSystems::Hover`](crate::PickingSystems::Hover), after
+/// [`pointer_events`](crate::events::pointer_events).
+pub fn recognize_gestures(
+    mut input_events: MessageReader<PointerInput>,
+    mut recognizers: ResMut<GestureRecognizers>,
+    hover_map: Res<HoverMap>,
+    pointer_state: Res<PointerState>,
+    clock: Option<Res<PickingClock>>,
+    mut commands: Commands,
+    dispatcher: GestureEventDispatcher,
+) {
+    if recognizers.is_empty() {
+        input_events.clear();
+        return;
+    }
+
+    let now = clock
+        .as_deref()
+        .map_or_else(Instant::now, PickingClock::now);
+    let mut context = GestureContext {
+        hover_map: &hover_map,
+        pointer_state: &pointer_state,
+        now,
+        events: RefCell::default(),
+    };
+
+    for input in input_events.read() {
+        context.now = input.time.unwrap_or(now);
+        for recognizer in recognizers.recognizers.iter_mut() {
+            recognizer.process(input, &context, &mut commands);
+        }
+    }
+
+    dispatcher.dispatch(&pointer_state, now, context.events.into_inner());
+}
+
+/// The dominant direction of a [`Swipe`], in screen space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum SwipeDirection {
+    /// Towards the left edge of the render target.
+    Left,
+    /// Towards the right edge of the render target.
+    Right,
+    /// Towards the top edge of the render target.
+    Up,
+    /// Towards the bottom edge of the render target.
+    Down,
+}
+
+impl SwipeDirection {
+    /// Returns the dominant direction of a screen-space displacement, where `+y` points down.
+    pub fn from_delta(delta: Vec2) -> Self {
+        if delta.x.abs() >= delta.y.abs() {
+            if delta.x < 0.0 {
+                Self::Left
+            } else {
+                Self::Right
+            }
+        } else if delta.y < 0.0 {
+            Self::Up
+        } else {
+            Self::Down
+        }
+    }
+}
+
+/// Fires when a pointer presses, moves quickly in one direction, and releases.
+///
+/// Emitted by [`SwipeRecognizer`] on each entity that was hovered when the button was pressed.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct Swipe {
+    /// Pointer button pressed to perform the swipe.
+    pub button: PointerButton,
+    /// The dominant direction of the swipe.
+    pub direction: SwipeDirection,
+    /// The screen-space displacement from press to release.
+    pub delta: Vec2,
+    /// Duration between the press and the release.
+    pub duration: Duration,
+}
+
+/// A [`GestureRecognizer`] that emits [`Pointer<Swipe>`] events.
+#[derive(Debug, Clone)]
+pub struct SwipeRecognizer {
+    /// The minimum distance, in logical pixels, between press and release.
+    pub min_distance: f32,
+    /// The maximum time between press and release.
+    pub max_duration: Duration,
+    presses: HashMap<(PointerId, PointerButton), (Location, Instant, Vec<Entity>)>,
+}
+
+impl Default for SwipeRecognizer {
+    fn default() -> Self {
+        Self::new(50.0, Duration::from_millis(500))
+    }
+}
+
+impl SwipeRecognizer {
+    /// Creates a recognizer with the given distance and duration limits.
+    pub fn new(min_distance: f32, max_duration: Duration) -> Self {
+        Self {
+            min_distance,
+            max_duration,
+            presses: HashMap::default(),
+        }
+    }
+}
+
+impl GestureRecognizer for SwipeRecognizer {
+    fn process(&mut self, input: &PointerInput, context: &GestureContext, commands: &mut Commands) {
+        match input.action {
+            PointerAction::Press(button) => {
+                let hovered = context.hovered(input.pointer_id).collect();
+                self.presses.insert(
+                    (input.pointer_id, button),
+                    (input.location.clone(), context.now, hovered),
+                );
+            }
+            PointerAction::Release(button) => {
+                let Some((start, pressed_at, targets)) =
+                    self.presses.remove(&(input.pointer_id, button))
+                else {
+                    return;
+                };
+                if start.target != input.location.target {
+                    return;
+                }
+                let delta = input.location.position - start.position;
+                let duration = context.now.saturating_duration_since(pressed_at);
+                if delta.length() < self.min_distance || duration > self.max_duration {
+                    return;
+                }
+                let swipe = Swipe {
+                    button,
+                    direction: SwipeDirection::from_delta(delta),
+                    delta,
+                    duration,
+                };
+                for target in targets {
+                    commands.trigger(Pointer::new(
+                        input.pointer_id,
+                        input.location.clone(),
+                        swipe.clone(),
+                        target,
+                    ));
+                }
+            }
+            PointerAction::Cancel => {
+                self.presses
+                    .retain(|(pointer_id, _), _| *pointer_id != input.pointer_id);
+            }
+            PointerAction::Move { .. } | PointerAction::Scroll { .. } => {}
+        }
+    }
+}
+
+/// Fires while two touches pinch the [target entity](EntityEvent::event_target), each time either
+/// of them moves.
+///
+/// Emitted by [`PinchRotateRecognizer`] alongside a [`Rotate`] event.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct Pinch {
+    /// The distance between the touches relative to when the gesture started, greater than `1.0`
+    /// when they spread apart.
+    pub scale: f32,
+    /// The distance between the touches relative to the previous [`Pinch`] of the gesture.
+    pub delta_scale: f32,
+}
+
+/// Fires while two touches twist around the [target entity](EntityEvent::event_target), each time
+/// either of them moves.
+///
+/// Emitted by [`PinchRotateRecognizer`] alongside a [`Pinch`] event. Angles are measured in screen
+/// space, where `+y` points down, so positive angles turn clockwise on screen.
+#[derive(Clone, PartialEq, Debug, Reflect)]
+#[reflect(Clone, PartialEq)]
+pub struct Rotate {
+    /// The angle the touches turned by since the gesture started.
+    pub radians: f32,
+    /// The angle the touches turned by since the previous [`Rotate`] of the gesture.
+    pub delta_radians: f32,
+}
+
+/// A [`GestureRecognizer`] that emits [`Pointer<Pinch>`] and [`Pointer<Rotate>`] events for
+/// two-finger touch gestures.
+///
+/// The gesture is formed by the two earliest active touches, and ends as soon as either of them
+/// lifts or is canceled. If other touches are still active, the earliest two of them start a new
+/// gesture, measured from their positions at that time.
+///
+/// The events target the topmost entity under the centroid of the touches. To find it, the
+/// recognizer spawns a [virtual pointer](crate::pointer::VirtualPointers) with the
+/// [`centroid_pointer`](Self::centroid_pointer) id at the centroid when the gesture starts, which
+/// is hit tested by the picking backends like any other pointer, and despawns it when the gesture
+/// ends. The target is the first entity this pointer hovers, usually from the frame after the
+/// gesture starts, and no events are emitted until then. They are reported at the centroid, for
+/// the first touch.
+///
+/// The events are sent with [`GestureContext::send`], so they are dispatched like the built-in
+/// pointer events.
+#[derive(Debug, Clone)]
+pub struct PinchRotateRecognizer {
+    /// The active touches, in the order they were pressed.
+    touches: Vec<(PointerId, Location)>,
+    gesture: Option<TwoFingerGesture>,
+    centroid_pointer: PointerId,
+    /// The entity of the [`centroid_pointer`](Self::centroid_pointer), while there is a gesture.
+    centroid_entity: Option<Entity>,
+}
+
+impl Default for PinchRotateRecognizer {
+    fn default() -> Self {
+        Self {
+            touches: Vec::new(),
+            gesture: None,
+            centroid_pointer: PointerId::Custom(Uuid::new_v4()),
+            centroid_entity: None,
+        }
+    }
+}
+
+/// The state of the gesture formed by the first two [`PinchRotateRecognizer::touches`].
+#[derive(Debug, Clone)]
+struct TwoFingerGesture {
+    /// The offset from the first touch to the second when the gesture started.
+    start: Vec2,
+    /// The offset from the first touch to the second when the events were last emitted.
+    previous: Vec2,
+    /// The topmost entity under the centroid, once the centroid pointer has been hit tested.
+    target: Option<Entity>,
+}
+
+impl PinchRotateRecognizer {
+    /// The id of the virtual pointer kept at the centroid of the touches during a gesture.
+    ///
+    /// Entities under the centroid receive the usual hover events from it, such as
+    /// [`Over`](crate::events::Over), which can be told apart by this id.
+    pub fn centroid_pointer(&self) -> PointerId {
+        self.centroid_pointer
+    }
+
+    /// Returns the offset from the first touch to the second, if two touches are active.
+    fn offset(&self) -> Option<Vec2> {
+        match self.touches.as_slice() {
+            [(_, first), (_, second), ..] => Some(second.position - first.position),
+            _ => None,
+        }
+    }
+
+    /// Starts a gesture between the first two touches, if there are two apart from each other.
+    fn start(&mut self) {
+        let Some(offset) = self.offset().filter(|offset| *offset != Vec2::ZERO) else {
+            return;
+        };
+        self.gesture = Some(TwoFingerGesture {
+            start: offset,
+            previous: offset,
+            target: None,
+        });
+    }
+
+    /// Moves the centroid pointer to the centroid of the gesture, spawning it when a gesture
+    /// starts and despawning it when there is none, so that it is only hit tested during one.
+    fn move_centroid_pointer(&mut self, commands: &mut Commands) {
+        let location = self.gesture.as_ref().and(self.offset()).map(|offset| {
+            let first = &self.touches[0].1;
+            Location {
+                target: first.target.clone(),
+                position: first.position + offset / 2.0,
+            }
+        });
+        match (self.centroid_entity, location) {
+            (Some(entity), Some(location)) => {
+                commands
+                    .entity(entity)
+                    .try_insert(PointerLocation::new(location));
+            }
+            (Some(entity), None) => {
+                commands.entity(entity).try_despawn();
+                self.centroid_entity = None;
+            }
+            (None, Some(location)) => {
+                self.centroid_entity = Some(
+                    commands
+                        .spawn((self.centroid_pointer, PointerLocation::new(location)))
+                        .id(),
+                );
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+impl GestureRecognizer for PinchRotateRecognizer {
+    fn process(&mut self, input: &PointerInput, context: &GestureContext, commands: &mut Commands) {
+        if !input.pointer_id.is_touch() {
+            return;
+        }
+        let index = self
+            .touches
+            .iter()
+            .position(|(pointer_id, _)| *pointer_id == input.pointer_id);
+        match (input.action, index) {
+            (PointerAction::Press(_), None) => {
+                self.touches
+                    .push((input.pointer_id, input.location.clone()));
+                if self.gesture.is_none() {
+                    self.start();
+                    self.move_centroid_pointer(commands);
+                }
+            }
+            (PointerAction::Move { .. }, Some(index)) => {
+                self.touches[index].1 = input.location.clone();
+                if self.gesture.is_none() {
+                    self.start();
+                    self.move_centroid_pointer(commands);
+                    return;
+                }
+                let Some(offset) = self.offset().filter(|_| index < 2) else {
+                    return;
+                };
+                self.move_centroid_pointer(commands);
+                let Some(gesture) = self.gesture.as_mut() else {
+                    return;
+                };
+                if gesture.target.is_none() {
+                    gesture.target = topmost_hit(context.hover_map.get(&self.centroid_pointer))
+                        .map(|(entity, _)| entity);
+                }
+                // The touches met, so the gesture has no scale or angle until they part again.
+                if offset == Vec2::ZERO {
+                    return;
+                }
+                let pinch = Pinch {
+                    scale: offset.length() / gesture.start.length(),
+                    delta_scale: offset.length() / gesture.previous.length(),
+                };
+                let rotate = Rotate {
+                    radians: gesture.start.angle_to(offset),
+                    delta_radians: gesture.previous.angle_to(offset),
+                };
+                gesture.previous = offset;
+                let Some(target) = gesture.target else {
+                    return;
+                };
+                let (pointer_id, first) = &self.touches[0];
+                let location = Location {
+                    target: first.target.clone(),
+                    position: first.position + offset / 2.0,
+                };
+                context.send(Pointer::new(*pointer_id, location.clone(), pinch, target));
+                context.send(Pointer::new(*pointer_id, location, rotate, target));
+            }
+            (PointerAction::Release(_) | PointerAction::Cancel, Some(index)) => {
+                self.touches.remove(index);
+                if index < 2 {
+                    self.gesture = None;
+                    self.start();
+                    self.move_centroid_pointer(commands);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::{Camera, NormalizedRenderTarget};
+
+    use super::*;
+    use crate::{
+        backend::HitData,
+        events::{PickEventMask, PointerEventKind, PointerEventMask},
+        InteractionPlugin,
+    };
+
+    fn location(x: f32, y: f32) -> Location {
+        Location {
+            target: NormalizedRenderTarget::None {
+                width: 800,
+                height: 600,
+            },
+            position: Vec2::new(x, y),
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Swipes(Vec<(Entity, Swipe)>);
+
+    fn setup() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(InteractionPlugin)
+            .add_message::<PointerInput>()
+            .init_resource::<Swipes>();
+        let world = app.world_mut();
+        let mut recognizers = GestureRecognizers::default();
+        recognizers.add(SwipeRecognizer::default());
+        world.insert_resource(recognizers);
+
+        let camera = world.spawn(Camera::default()).id();
+        let target = world.spawn_empty().id();
+        world.add_observer(|swipe: On<Pointer<Swipe>>, mut swipes: ResMut<Swipes>| {
+            swipes.0.push((swipe.entity, swipe.event.clone()));
+        });
+
+        let mut hover_map = HoverMap::default();
+        hover_map.insert(
+            PointerId::Mouse,
+            HashMap::from_iter([(
+                target,
+                HitData {
+                    depth: 0.0,
+                    camera,
+                    position: None,
+                    normal: None,
+                },
+            )]),
+        );
+        world.insert_resource(hover_map);
+        (app, target)
+    }
+
+    fn send(world: &mut World, position: Vec2, action: PointerAction) {
+        world.write_message(PointerInput::new(
+            PointerId::Mouse,
+            location(position.x, position.y),
+            action,
+        ));
+        world.run_system_cached(recognize_gestures).unwrap();
+    }
+
+    #[test]
+    fn swipe_emitted_on_fast_release() {
+        let (mut app, target) = setup();
+        let world = app.world_mut();
+
+        send(
+            world,
+            Vec2::new(100.0, 100.0),
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send(
+            world,
+            Vec2::new(40.0, 110.0),
+            PointerAction::Move {
+                delta: Vec2::new(-60.0, 10.0),
+            },
+        );
+        send(
+            world,
+            Vec2::new(20.0, 110.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+
+        let swipes = &world.resource::<Swipes>().0;
+        assert_eq!(swipes.len(), 1);
+        let (entity, swipe) = &swipes[0];
+        assert_eq!(*entity, target);
+        assert_eq!(swipe.direction, SwipeDirection::Left);
+        assert_eq!(swipe.delta, Vec2::new(-80.0, 10.0));
+    }
+
+    #[test]
+    fn short_drag_is_not_a_swipe() {
+        let (mut app, _) = setup();
+        let world = app.world_mut();
+
+        send(
+            world,
+            Vec2::new(100.0, 100.0),
+            PointerAction::Press(PointerButton::Primary),
+        );
+        send(
+            world,
+            Vec2::new(110.0, 100.0),
+            PointerAction::Release(PointerButton::Primary),
+        );
+
+        assert!(world.resource::<Swipes>().0.is_empty());
+    }
+
+    #[test]
+    fn swipe_duration_is_read_from_the_picking_clock() {
+        let (mut app, _) = setup();
+        let world = app.world_mut();
+        world.insert_resource(PickingClock::manual());
+        let press = PointerAction::Press(PointerButton::Primary);
+        let release = PointerAction::Release(PointerButton::Primary);
+
+        send(world, Vec2::new(100.0, 100.0), press);
+        world
+            .resource_mut::<PickingClock>()
+            .advance(Duration::from_millis(100));
+        send(world, Vec2::new(300.0, 100.0), release);
+        let swipes = &world.resource::<Swipes>().0;
+        assert_eq!(swipes.len(), 1);
+        assert_eq!(swipes[0].1.duration, Duration::from_millis(100));
+
+        // Too slow by the clock, however long the test actually took.
+        send(world, Vec2::new(100.0, 100.0), press);
+        world
+            .resource_mut::<PickingClock>()
+            .advance(Duration::from_millis(600));
+        send(world, Vec2::new(300.0, 100.0), release);
+        assert_eq!(world.resource::<Swipes>().0.len(), 1);
+
+        // Timestamped inputs are timed by their timestamp instead.
+        let pressed_at = world.resource::<PickingClock>().now();
+        for (x, action, time) in [
+            (100.0, press, pressed_at),
+            (300.0, release, pressed_at + Duration::from_millis(250)),
+        ] {
+            world.write_message(
+                PointerInput::new(PointerId::Mouse, location(x, 100.0), action).with_time(time),
+            );
+            world.run_system_cached(recognize_gestures).unwrap();
+        }
+        let swipes = &world.resource::<Swipes>().0;
+        assert_eq!(swipes.len(), 2);
+        assert_eq!(swipes[1].1.duration, Duration::from_millis(250));
+    }
+
+    #[derive(Resource, Default)]
+    struct TwoFingerEvents(Vec<(Entity, PointerId, Vec2, Pinch, Rotate)>);
+
+    fn touch(world: &mut World, id: u64, x: f32, y: f32, action: PointerAction) {
+        world.write_message(PointerInput::new(
+            PointerId::Touch(id),
+            location(x, y),
+            action,
+        ));
+        world.run_system_cached(recognize_gestures).unwrap();
+    }
+
+    #[test]
+    fn two_touches_pinch_and_rotate_until_one_lifts() {
+        let (mut app, touched) = setup();
+        let world = app.world_mut();
+        let recognizer = PinchRotateRecognizer::default();
+        let centroid_pointer = recognizer.centroid_pointer();
+        let mut recognizers = GestureRecognizers::default();
+        recognizers.add(recognizer);
+        world.insert_resource(recognizers);
+        world.init_resource::<TwoFingerEvents>();
+        world.add_observer(
+            |pinch: On<Pointer<Pinch>>, mut events: ResMut<TwoFingerEvents>| {
+                events.0.push((
+                    pinch.entity,
+                    pinch.pointer_id,
+                    pinch.pointer_location.position,
+                    pinch.event.clone(),
+                    Rotate {
+                        radians: f32::NAN,
+                        delta_radians: f32::NAN,
+                    },
+                ));
+            },
+        );
+        world.add_observer(
+            |rotate: On<Pointer<Rotate>>, mut events: ResMut<TwoFingerEvents>| {
+                events.0.last_mut().unwrap().4 = rotate.event.clone();
+            },
+        );
+        let hits = world.resource::<HoverMap>()[&PointerId::Mouse].clone();
+        let mut hover_map = world.resource_mut::<HoverMap>();
+        for id in 0..3 {
+            hover_map.insert(PointerId::Touch(id), hits.clone());
+        }
+
+        let press = PointerAction::Press(PointerButton::Primary);
+        let moved = PointerAction::Move { delta: Vec2::ZERO };
+        let release = PointerAction::Release(PointerButton::Primary);
+        touch(world, 0, 100.0, 100.0, press);
+        touch(world, 1, 200.0, 100.0, press);
+        touch(world, 2, 500.0, 500.0, press);
+        // The third touch isn't part of the gesture.
+        touch(world, 2, 100.0, 500.0, moved);
+        assert!(world.resource::<TwoFingerEvents>().0.is_empty());
+
+        // Nothing is known to be under the centroid until its pointer has been hit tested.
+        touch(world, 1, 200.0, 100.0, moved);
+        assert!(world.resource::<TwoFingerEvents>().0.is_empty());
+        let mut pointers = world.query::<(&PointerId, &PointerLocation)>();
+        let (_, centroid) = pointers
+            .iter(world)
+            .find(|(pointer_id, _)| **pointer_id == centroid_pointer)
+            .unwrap();
+        assert_eq!(
+            centroid.location().unwrap().position,
+            Vec2::new(150.0, 100.0)
+        );
+
+        // The entity under the centroid is targeted, not the one under the touches.
+        let camera = hits.values().next().unwrap().camera;
+        let target = world.spawn_empty().id();
+        world.resource_mut::<HoverMap>().insert(
+            centroid_pointer,
+            HashMap::from_iter([(target, HitData::new(camera, 0.0, None, None))]),
+        );
+
+        // Spread the second touch to twice the distance, a quarter turn clockwise.
+        touch(world, 1, 100.0, 200.0, moved);
+        touch(world, 1, 100.0, 300.0, moved);
+        let events = &world.resource::<TwoFingerEvents>().0;
+        assert_eq!(events.len(), 2);
+        let (entity, pointer_id, centroid, pinch, rotate) = &events[1];
+        assert_ne!(*entity, touched);
+        assert_eq!(*entity, target);
+        assert_eq!(*pointer_id, PointerId::Touch(0));
+        assert_eq!(*centroid, Vec2::new(100.0, 200.0));
+        assert_eq!(pinch.scale, 2.0);
+        assert_eq!(pinch.delta_scale, 2.0);
+        assert!((rotate.radians - core::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert!(rotate.delta_radians.abs() < 1e-6);
+
+        // The events are also written as messages.
+        let pinches = world.resource::<Messages<Pointer<Pinch>>>();
+        let rotations = world.resource::<Messages<Pointer<Rotate>>>();
+        assert_eq!(pinches.len(), 2);
+        assert_eq!(rotations.len(), 2);
+        assert!(pinches
+            .iter_current_update_messages()
+            .all(|pinch| pinch.entity == target));
+
+        // Lifting a finger ends the gesture, and the remaining two start a new one.
+        touch(world, 0, 100.0, 100.0, release);
+        world.resource_mut::<TwoFingerEvents>().0.clear();
+        touch(world, 2, 100.0, 600.0, moved);
+        let events = &world.resource::<TwoFingerEvents>().0;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].3.scale, 1.5);
+        assert_eq!(events[0].4.radians, 0.0);
+
+        // A lone touch never pinches.
+        touch(world, 1, 100.0, 300.0, release);
+        world.resource_mut::<TwoFingerEvents>().0.clear();
+        touch(world, 2, 100.0, 700.0, moved);
+        assert!(world.resource::<TwoFingerEvents>().0.is_empty());
+    }
+
+    /// Starts a pinch between two touches, with `target` under their centroid.
+    fn pinching(world: &mut World, target: Entity) -> PointerId {
+        let recognizer = PinchRotateRecognizer::default();
+        let centroid_pointer = recognizer.centroid_pointer();
+        let mut recognizers = GestureRecognizers::default();
+        recognizers.add(recognizer);
+        world.insert_resource(recognizers);
+        let press = PointerAction::Press(PointerButton::Primary);
+        touch(world, 0, 100.0, 100.0, press);
+        touch(world, 1, 200.0, 100.0, press);
+        let camera = world.spawn(Camera::default()).id();
+        world.resource_mut::<HoverMap>().insert(
+            centroid_pointer,
+            HashMap::from_iter([(target, HitData::new(camera, 0.0, None, None))]),
+        );
+        centroid_pointer
+    }
+
+    #[test]
+    fn centroid_pointer_is_despawned_when_the_gesture_ends() {
+        let (mut app, target) = setup();
+        let world = app.world_mut();
+        let centroid_pointer = pinching(world, target);
+        let mut pointers = world.query::<&PointerId>();
+        let centroid_pointers = |world: &mut World, pointers: &mut QueryState<&PointerId>| {
+            pointers
+                .iter(world)
+                .filter(|pointer_id| **pointer_id == centroid_pointer)
+                .count()
+        };
+        assert_eq!(centroid_pointers(world, &mut pointers), 1);
+
+        let release = PointerAction::Release(PointerButton::Primary);
+        touch(world, 1, 200.0, 100.0, release);
+        assert_eq!(centroid_pointers(world, &mut pointers), 0);
+
+        // A new gesture gets a new pointer, which a cancel despawns as well.
+        touch(
+            world,
+            1,
+            200.0,
+            100.0,
+            PointerAction::Press(PointerButton::Primary),
+        );
+        assert_eq!(centroid_pointers(world, &mut pointers), 1);
+        touch(world, 0, 100.0, 100.0, PointerAction::Cancel);
+        assert_eq!(centroid_pointers(world, &mut pointers), 0);
+    }
+
+    #[test]
+    fn pinch_and_rotate_are_dispatched_like_pointer_events() {
+        let (mut app, _) = setup();
+        app.add_message::<AnyPointerEvent>();
+        let world = app.world_mut();
+        let parent = world.spawn_empty().id();
+        // The target only wants rotations, and masks them so that its parent handles them.
+        let target = world
+            .spawn((
+                ChildOf(parent),
+                PointerEventMask::new([PointerEventKind::Rotate]),
+                PickEventMask::new([PointerEventKind::Rotate]),
+            ))
+            .id();
+        world.init_resource::<TwoFingerEvents>();
+        world.add_observer(
+            |pinch: On<Pointer<Pinch>>, mut events: ResMut<TwoFingerEvents>| {
+                events.0.push((
+                    pinch.entity,
+                    pinch.pointer_id,
+                    pinch.pointer_location.position,
+                    pinch.event.clone(),
+                    Rotate {
+                        radians: f32::NAN,
+                        delta_radians: f32::NAN,
+                    },
+                ));
+            },
+        );
+        world.add_observer(
+            |rotate: On<Pointer<Rotate>>, mut events: ResMut<TwoFingerEvents>| {
+                events.0.push((
+                    rotate.entity,
+                    rotate.pointer_id,
+                    rotate.pointer_location.position,
+                    Pinch {
+                        scale: f32::NAN,
+                        delta_scale: f32::NAN,
+                    },
+                    rotate.event.clone(),
+                ));
+            },
+        );
+        pinching(world, target);
+
+        touch(
+            world,
+            1,
+            100.0,
+            200.0,
+            PointerAction::Move { delta: Vec2::ZERO },
+        );
+        let triggered: Vec<_> = world
+            .resource::<TwoFingerEvents>()
+            .0
+            .iter()
+            .map(|(entity, ..)| *entity)
+            .collect();
+        assert_eq!(triggered, [parent]);
+        assert!(world.resource::<TwoFingerEvents>().0[0].4.radians > 0.0);
+
+        // Both are still written as messages for their target, and mirrored.
+        let pinches = world.resource::<Messages<Pointer<Pinch>>>();
+        assert!(pinches
+            .iter_current_update_messages()
+            .all(|pinch| pinch.entity == target));
+        assert_eq!(pinches.len(), 1);
+        let kinds: Vec<_> = world
+            .resource::<Messages<AnyPointerEvent>>()
+            .iter_current_update_messages()
+            .map(AnyPointerEvent::kind)
+            .collect();
+        assert_eq!(kinds, [PointerEventKind::Pinch, PointerEventKind::Rotate]);
+    }
+}
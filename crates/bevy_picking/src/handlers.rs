@@ -0,0 +1,251 @@
+//! Retained callbacks for pointer events, stored on the entities they handle.
+//!
+//! Observers are the usual way to react to [`Pointer`] events, but they are registered once and
+//! can't be inspected or replaced afterwards. A [`PickHandlers`] component instead holds at most one
+//! boxed callback per event type, which can be swapped at any time, such as by a data-driven UI
+//! that rebuilds its handler tables at runtime.
+//!
+//! The handlers are run by the [`run_pick_handlers`] observer, for the entity each event was
+//! triggered on. The observers of the built-in events are only added once the first
+//! [`PickHandlers`] is, so apps that don't use handlers don't pay for them. Since they are run as the events are triggered, they see the same events as
+//! observers do: events redirected by a [`PickEventMask`](crate::events::PickEventMask) run the
+//! handlers of the entity they were redirected to, and events unwanted by a
+//! [`PointerEventMask`](crate::events::PointerEventMask) don't run any. Unlike observers, they
+//! don't see the events bubbling up from descendants.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::{handlers::PickHandlers, prelude::*};
+//! # let mut world = World::default();
+//! world.spawn(PickHandlers::default().with(
+//!     |click: &Pointer<Click>, commands: &mut Commands| {
+//!         commands.entity(click.entity).despawn();
+//!     },
+//! ));
+//! ```
+
+use alloc::boxed::Box;
+use core::{
+    any::{Any, TypeId},
+    fmt::Debug,
+};
+
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::Reflect;
+
+use crate::{
+    events::*,
+    gesture::{Pinch, Rotate},
+};
+
+/// A callback run for each [`Pointer<E>`] event triggered on the entity holding it, see
+/// [`PickHandlers`].
+pub type PickHandler<E> = Box<dyn FnMut(&Pointer<E>, &mut Commands) + Send + Sync>;
+
+/// Holds at most one [`PickHandler`] per event type, run on the [`Pointer`] events triggered on
+/// this entity.
+///
+/// See the [module docs](self).
+#[derive(Component, Default)]
+#[component(on_add = add_pick_handler_observers)]
+pub struct PickHandlers {
+    handlers: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl PickHandlers {
+    /// Sets the handler of the [`Pointer<E>`] events, replacing the previous one.
+    pub fn with<E: Debug + Clone + Reflect>(
+        mut self,
+        handler: impl FnMut(&Pointer<E>, &mut Commands) + Send + Sync + 'static,
+    ) -> Self {
+        self.set(handler);
+        self
+    }
+
+    /// Sets the handler of the [`Pointer<E>`] events, replacing the previous one.
+    pub fn set<E: Debug + Clone + Reflect>(
+        &mut self,
+        handler: impl FnMut(&Pointer<E>, &mut Commands) + Send + Sync + 'static,
+    ) {
+        let handler: PickHandler<E> = Box::new(handler);
+        self.handlers.insert(TypeId::of::<E>(), Box::new(handler));
+    }
+
+    /// Removes the handler of the [`Pointer<E>`] events, returning it.
+    pub fn remove<E: Debug + Clone + Reflect>(&mut self) -> Option<PickHandler<E>> {
+        self.handlers
+            .remove(&TypeId::of::<E>())
+            .and_then(|handler| handler.downcast().ok())
+            .map(|handler| *handler)
+    }
+
+    /// Returns `true` if the [`Pointer<E>`] events are handled.
+    pub fn contains<E: Debug + Clone + Reflect>(&self) -> bool {
+        self.handlers.contains_key(&TypeId::of::<E>())
+    }
+
+    fn get_mut<E: Debug + Clone + Reflect>(&mut self) -> Option<&mut PickHandler<E>> {
+        self.handlers
+            .get_mut(&TypeId::of::<E>())
+            .and_then(|handler| handler.downcast_mut())
+    }
+}
+
+/// Marks the worlds the [`run_pick_handlers`] observers of the built-in events were added to.
+#[derive(Resource)]
+struct PickHandlerObservers;
+
+/// Adds the [`run_pick_handlers`] observers of the built-in events when the first [`PickHandlers`]
+/// is added to the world.
+fn add_pick_handler_observers(mut world: DeferredWorld, _: HookContext) {
+    if world.contains_resource::<PickHandlerObservers>() {
+        return;
+    }
+    world.commands().queue(|world: &mut World| {
+        // Several handlers may have been added before this command is applied.
+        if world.contains_resource::<PickHandlerObservers>() {
+            return;
+        }
+        world.insert_resource(PickHandlerObservers);
+        world.add_observer(run_pick_handlers::<Cancel>);
+        world.add_observer(run_pick_handlers::<Click>);
+        world.add_observer(run_pick_handlers::<Press>);
+        world.add_observer(run_pick_handlers::<PressRepeat>);
+        world.add_observer(run_pick_handlers::<LongPress>);
+        world.add_observer(run_pick_handlers::<FirstInteraction>);
+        world.add_observer(run_pick_handlers::<DragDrop>);
+        world.add_observer(run_pick_handlers::<DragEnd>);
+        world.add_observer(run_pick_handlers::<DragCancel>);
+        world.add_observer(run_pick_handlers::<DragEnter>);
+        world.add_observer(run_pick_handlers::<Drag>);
+        world.add_observer(run_pick_handlers::<DragLeave>);
+        world.add_observer(run_pick_handlers::<DragOver>);
+        world.add_observer(run_pick_handlers::<DragStart>);
+        world.add_observer(run_pick_handlers::<Move>);
+        world.add_observer(run_pick_handlers::<FallbackMove>);
+        world.add_observer(run_pick_handlers::<Out>);
+        world.add_observer(run_pick_handlers::<Over>);
+        world.add_observer(run_pick_handlers::<Release>);
+        world.add_observer(run_pick_handlers::<Scroll>);
+        world.add_observer(run_pick_handlers::<BecameTopmost>);
+        world.add_observer(run_pick_handlers::<LostTopmost>);
+        world.add_observer(run_pick_handlers::<HoverEnter>);
+        world.add_observer(run_pick_handlers::<HoverExit>);
+        world.add_observer(run_pick_handlers::<Pinch>);
+        world.add_observer(run_pick_handlers::<Rotate>);
+    });
+}
+
+/// An observer running the [`PickHandlers`] of the entity each [`Pointer<E>`] event is triggered
+/// on.
+///
+/// It is added for every built-in event when the first [`PickHandlers`] is added to the world.
+/// Add it with [`App::add_observer`](bevy_app::App::add_observer) for custom events.
+pub fn run_pick_handlers<E: Debug + Clone + Reflect>(
+    event: On<Pointer<E>>,
+    mut handlers: Query<&mut PickHandlers>,
+    mut commands: Commands,
+) {
+    // Skip the entities the event bubbles up to.
+    if event.entity != event.original_event_target() {
+        return;
+    }
+    let Ok(mut handlers) = handlers.get_mut(event.entity) else {
+        return;
+    };
+    if let Some(handler) = handlers.bypass_change_detection().get_mut::<E>() {
+        handler(&event, &mut commands);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+    use core::time::Duration;
+
+    use bevy_app::App;
+    use bevy_camera::NormalizedRenderTarget;
+    use bevy_math::Vec2;
+
+    use super::*;
+    use crate::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    };
+
+    #[derive(Resource, Default)]
+    struct Handled(Vec<(&'static str, Entity)>);
+
+    fn click(entity: Entity) -> Pointer<Click> {
+        Pointer::new(
+            PointerId::Mouse,
+            Location {
+                target: NormalizedRenderTarget::None {
+                    width: 800,
+                    height: 600,
+                },
+                position: Vec2::ZERO,
+            },
+            Click {
+                button: PointerButton::Primary,
+                hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+                duration: Duration::ZERO,
+                count: 1,
+            },
+            entity,
+        )
+    }
+
+    fn record(name: &'static str) -> impl FnMut(&Pointer<Click>, &mut Commands) {
+        move |click, commands| {
+            let entity = click.entity;
+            commands.queue(move |world: &mut World| {
+                world.resource_mut::<Handled>().0.push((name, entity));
+            });
+        }
+    }
+
+    #[test]
+    fn click_handler_runs_and_can_be_swapped() {
+        let mut app = App::new();
+        app.init_resource::<Handled>();
+        // The observers are only added along with the first handlers.
+        assert!(!app.world().contains_resource::<PickHandlerObservers>());
+        let button = app
+            .world_mut()
+            .spawn(PickHandlers::default().with(record("first")))
+            .id();
+        let plain = app.world_mut().spawn_empty().id();
+        // Events bubbling up from descendants don't run the handlers.
+        let child = app.world_mut().spawn(ChildOf(button)).id();
+        // Adding more handlers doesn't add the observers again.
+        app.world_mut().spawn(PickHandlers::default());
+        app.world_mut().flush();
+        assert!(app.world().contains_resource::<PickHandlerObservers>());
+
+        app.world_mut().trigger(click(button));
+        app.world_mut().trigger(click(plain));
+        app.world_mut().trigger(click(child));
+        app.update();
+        assert_eq!(app.world().resource::<Handled>().0, vec![("first", button)]);
+
+        app.world_mut()
+            .get_mut::<PickHandlers>(button)
+            .unwrap()
+            .set(record("second"));
+        app.world_mut().trigger(click(button));
+        app.update();
+        assert_eq!(
+            app.world().resource::<Handled>().0,
+            vec![("first", button), ("second", button)]
+        );
+
+        let mut handlers = app.world_mut().get_mut::<PickHandlers>(button).unwrap();
+        assert!(handlers.remove::<Click>().is_some());
+        assert!(!handlers.contains::<Click>());
+        app.world_mut().trigger(click(button));
+        app.update();
+        assert_eq!(app.world().resource::<Handled>().0.len(), 2);
+    }
+}
@@ -9,15 +9,26 @@ use std::collections::HashSet;
 
 use crate::{
     backend::{self, HitData},
-    pointer::{PointerAction, PointerId, PointerInput, PointerInteraction, PointerPress},
+    events::{topmost_hit, PointerState},
+    pointer::{
+        PointerAction, PointerButton, PointerId, PointerInput, PointerInteraction, PointerLocation,
+        PointerPress,
+    },
     Pickable,
 };
 
+use bevy_camera::{
+    primitives::Aabb,
+    visibility::{RenderLayers, ViewVisibility},
+    Camera, RenderTarget,
+};
 use bevy_derive::{Deref, DerefMut};
-use bevy_ecs::{entity::EntityHashSet, prelude::*};
-use bevy_math::FloatOrd;
+use bevy_ecs::{entity::EntityHashSet, prelude::*, system::SystemParam};
+use bevy_math::{FloatOrd, Rect, Vec2, Vec3, Vec3A};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::prelude::*;
+use bevy_transform::prelude::GlobalTransform;
+use bevy_window::PrimaryWindow;
 
 type DepthSortedHits = Vec<(Entity, HitData)>;
 
@@ -60,6 +71,69 @@ pub struct HoverMap(pub HashMap<PointerId, HashMap<Entity, HitData>>);
 #[derive(Debug, Deref, DerefMut, Default, Resource)]
 pub struct PreviousHoverMap(pub HashMap<PointerId, HashMap<Entity, HitData>>);
 
+/// A [`SystemParam`] for looking up which pointers are hovering a given entity.
+///
+/// Where [`Hovered`] and [`DirectlyHovered`] only say *whether* an entity is hovered, this reports
+/// every pointer over it, along with where each one is hitting it. This is useful for multi-touch
+/// widgets that react to several fingers at once.
+///
+/// It also reports the [`hover_stack`](Self::hover_stack) of a pointer, so that an observer can
+/// tell whether its entity is in front of the others under the pointer:
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::{events::{Over, Pointer}, hover::PointerQuery};
+/// fn show_tooltip(over: On<Pointer<Over>>, pointers: PointerQuery) {
+///     if pointers.is_frontmost(over.pointer_id, over.entity) {
+///         // Only the entity in front shows its tooltip.
+///     }
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct PointerQuery<'w> {
+    hover_map: Res<'w, HoverMap>,
+}
+
+impl PointerQuery<'_> {
+    /// Returns an iterator over the pointers directly hovering `entity`, with their [`HitData`].
+    pub fn pointers_over(&self, entity: Entity) -> impl Iterator<Item = (PointerId, HitData)> + '_ {
+        self.hover_map
+            .iter()
+            .filter_map(move |(pointer_id, hovered)| {
+                hovered.get(&entity).map(|hit| (*pointer_id, hit.clone()))
+            })
+    }
+
+    /// Returns `true` if any pointer is directly hovering `entity`.
+    pub fn is_hovered(&self, entity: Entity) -> bool {
+        self.hover_map
+            .values()
+            .any(|hovered| hovered.contains_key(&entity))
+    }
+
+    /// Returns the entities hovered by `pointer_id`, with their [`HitData`], from front to back.
+    ///
+    /// The entities are sorted by [`HitData::depth`], with ties broken by [`Entity`] order, so the
+    /// first one is the [topmost](crate::events::BecameTopmost) entity.
+    pub fn hover_stack(&self, pointer_id: PointerId) -> Vec<(Entity, HitData)> {
+        let mut stack: Vec<_> = self
+            .hover_map
+            .get(&pointer_id)
+            .into_iter()
+            .flatten()
+            .map(|(entity, hit)| (*entity, hit.clone()))
+            .collect();
+        stack.sort_unstable_by_key(|(entity, hit)| (FloatOrd(hit.depth), *entity));
+        stack
+    }
+
+    /// Returns `true` if `entity` is the frontmost entity hovered by `pointer_id`, the first of
+    /// its [`hover_stack`](Self::hover_stack).
+    pub fn is_frontmost(&self, pointer_id: PointerId, entity: Entity) -> bool {
+        topmost_hit(self.hover_map.get(&pointer_id)).is_some_and(|(topmost, _)| topmost == entity)
+    }
+}
+
 /// Coalesces all data from inputs and backends to generate a map of the currently hovered entities.
 /// This is the final focusing step to determine which entity the pointer is hovering over.
 pub fn generate_hovermap(
@@ -68,6 +142,8 @@ pub fn generate_hovermap(
     pointers: Query<&PointerId>,
     mut pointer_hits_reader: MessageReader<backend::PointerHits>,
     mut pointer_input_reader: MessageReader<PointerInput>,
+    touch_slop: TouchSlopTargets,
+    dragged: DraggedEntities,
     // Local
     mut over_map: Local<OverMap>,
     // Output
@@ -85,7 +161,8 @@ pub fn generate_hovermap(
         &mut over_map,
         &mut pointer_input_reader,
     );
-    build_hover_map(&pointers, pickable, &over_map, &mut hover_map);
+    build_hover_map(&pointers, &pickable, &dragged, &over_map, &mut hover_map);
+    touch_slop.resolve(&dragged, &mut hover_map);
 }
 
 /// Clear non-empty local maps, reusing allocated memory.
@@ -153,9 +230,12 @@ fn build_over_map(
 /// Build an unsorted set of hovered entities, accounting for depth, layer, and [`Pickable`]. Note
 /// that unlike the pointer map, this uses [`Pickable`] to determine if lower entities receive hover
 /// focus. Often, only a single entity per pointer will be hovered.
+///
+/// Entities ignored by [`DragIgnoresSelf`] are skipped entirely, as if the backends hadn't hit them.
 fn build_hover_map(
     pointers: &Query<&PointerId>,
-    pickable: Query<&Pickable>,
+    pickable: &Query<&Pickable>,
+    dragged: &DraggedEntities,
     over_map: &Local<OverMap>,
     // Output
     hover_map: &mut HoverMap,
@@ -165,6 +245,9 @@ fn build_hover_map(
         if let Some(layer_map) = over_map.get(pointer_id) {
             // Note we reverse here to start from the highest layer first.
             for (entity, pick_data) in layer_map.values().rev().flatten() {
+                if dragged.is_ignored(*pointer_id, *entity) {
+                    continue;
+                }
                 if let Ok(pickable) = pickable.get(*entity) {
                     if pickable.is_hoverable {
                         pointer_entity_set.insert(*entity, pick_data.clone());
@@ -181,6 +264,222 @@ fn build_hover_map(
     }
 }
 
+/// Removes the entities being dragged by a pointer from the entities it hovers.
+///
+/// While an entity is dragged, it usually stays under the pointer and hides what is beneath it,
+/// such as the drop target. With this resource, the dragged entity is skipped when building the
+/// [`HoverMap`] of the pointers dragging it, as if the backends hadn't hit it. The entities
+/// underneath then receive [`DragOver`](crate::events::DragOver) and
+/// [`DragDrop`](crate::events::DragDrop) even when the dragged entity blocks lower entities.
+///
+/// As it is no longer hovered, the dragged entity receives an [`Out`](crate::events::Out) on the
+/// frame after the drag starts, and doesn't receive the [`Release`](crate::events::Release) ending
+/// it. Other pointers still hover it as usual.
+///
+/// This resource is not inserted by default.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default, Debug, PartialEq, Clone)]
+pub struct DragIgnoresSelf {
+    /// Whether the descendants of the dragged entity are ignored too, such as the icon and label
+    /// of a dragged card.
+    pub descendants: bool,
+}
+
+/// The [`SystemParam`] used by [`generate_hovermap`] to skip the entities ignored by
+/// [`DragIgnoresSelf`].
+#[derive(SystemParam)]
+pub struct DraggedEntities<'w, 's> {
+    drag_ignores_self: Option<Res<'w, DragIgnoresSelf>>,
+    pointer_state: Option<Res<'w, PointerState>>,
+    parents: Query<'w, 's, &'static ChildOf>,
+}
+
+impl DraggedEntities<'_, '_> {
+    /// Returns `true` if `entity` shouldn't be hovered by `pointer_id`, because the pointer is
+    /// dragging it or, if enabled, one of its ancestors.
+    fn is_ignored(&self, pointer_id: PointerId, entity: Entity) -> bool {
+        let Some(drag_ignores_self) = self.drag_ignores_self.as_deref() else {
+            return false;
+        };
+        let Some(pointer_state) = self.pointer_state.as_deref() else {
+            return false;
+        };
+        let is_dragged = |entity: Entity| {
+            PointerButton::iter().any(|button| {
+                pointer_state
+                    .get(pointer_id, button)
+                    .is_some_and(|state| state.dragging.contains_key(&entity))
+            })
+        };
+        if drag_ignores_self.descendants {
+            core::iter::once(entity)
+                .chain(self.parents.iter_ancestors(entity))
+                .any(is_dragged)
+        } else {
+            is_dragged(entity)
+        }
+    }
+}
+
+/// Widens hover resolution for touch pointers, making small entities easier to hit with a finger.
+///
+/// When a [touch pointer](PointerId::Touch) isn't hovering anything after the backends' hits have
+/// been resolved, every [hoverable](Pickable::is_hoverable) entity with a [`Pickable`] and an
+/// [`Aabb`] whose on-screen bounds lie within `radius` logical pixels of the touch point is
+/// considered a candidate, if it is visible and shares [`RenderLayers`] with the camera. The
+/// candidate whose projected center is nearest to the touch point becomes hovered.
+///
+/// Since only entities with a [`Pickable`] are candidates, backends that pick entities without one
+/// don't get near-misses for them.
+///
+/// The default radius of `0.0` disables this, as does removing the resource.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Resource, Default, Debug, PartialEq, Clone)]
+pub struct TouchSlop {
+    /// How far, in logical pixels, a touch may land outside an entity and still hover it.
+    pub radius: f32,
+}
+
+/// The [`SystemParam`] used by [`generate_hovermap`] to resolve [`TouchSlop`] near-misses.
+#[derive(SystemParam)]
+pub struct TouchSlopTargets<'w, 's> {
+    touch_slop: Option<Res<'w, TouchSlop>>,
+    pointers: Query<'w, 's, (&'static PointerId, &'static PointerLocation)>,
+    cameras: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Camera,
+            &'static RenderTarget,
+            &'static GlobalTransform,
+            Option<&'static RenderLayers>,
+        ),
+    >,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    targets: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Pickable,
+            &'static Aabb,
+            &'static GlobalTransform,
+            &'static ViewVisibility,
+            Option<&'static RenderLayers>,
+        ),
+    >,
+}
+
+impl TouchSlopTargets<'_, '_> {
+    /// Hovers the nearest entity within the [`TouchSlop`] radius of every touch pointer that isn't
+    /// already hovering something.
+    fn resolve(&self, dragged: &DraggedEntities, hover_map: &mut HoverMap) {
+        let radius = self
+            .touch_slop
+            .as_deref()
+            .copied()
+            .unwrap_or_default()
+            .radius;
+        if radius <= 0.0 {
+            return;
+        }
+        let primary_window = self.primary_window.single().ok();
+
+        for (pointer_id, pointer_location) in &self.pointers {
+            let Some(location) = pointer_location.location() else {
+                continue;
+            };
+            if !pointer_id.is_touch() || hover_map.get(pointer_id).is_some_and(|h| !h.is_empty()) {
+                continue;
+            }
+
+            let mut nearest: Option<(FloatOrd, Entity, HitData)> = None;
+            for (camera_entity, camera, render_target, camera_transform, camera_layers) in
+                &self.cameras
+            {
+                if !camera.is_active
+                    || render_target
+                        .normalize(primary_window)
+                        .is_none_or(|target| target != location.target)
+                {
+                    continue;
+                }
+
+                for (entity, pickable, aabb, transform, visibility, layers) in &self.targets {
+                    if !visibility.get()
+                        || !pickable.is_hoverable
+                        || !camera_layers
+                            .unwrap_or_default()
+                            .intersects(layers.unwrap_or_default())
+                        || dragged.is_ignored(*pointer_id, entity)
+                    {
+                        continue;
+                    }
+                    let center = transform.transform_point(aabb.center.into());
+                    let Ok(projected_center) =
+                        camera.world_to_viewport_with_depth(camera_transform, center)
+                    else {
+                        continue;
+                    };
+                    let Some(bounds) = project_aabb(camera, camera_transform, transform, aabb)
+                    else {
+                        continue;
+                    };
+
+                    let outside = (bounds.min - location.position)
+                        .max(location.position - bounds.max)
+                        .max(Vec2::ZERO);
+                    if outside.length() > radius {
+                        continue;
+                    }
+
+                    let distance =
+                        FloatOrd(projected_center.truncate().distance(location.position));
+                    if nearest.as_ref().is_none_or(|(best, ..)| distance < *best) {
+                        let hit =
+                            HitData::new(camera_entity, projected_center.z, Some(center), None);
+                        nearest = Some((distance, entity, hit));
+                    }
+                }
+            }
+
+            if let Some((_, entity, hit)) = nearest {
+                hover_map
+                    .entry(*pointer_id)
+                    .or_default()
+                    .insert(entity, hit);
+            }
+        }
+    }
+}
+
+/// Projects the corners of a world-space [`Aabb`] into the camera's viewport, returning their
+/// screen-space bounds.
+fn project_aabb(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+    aabb: &Aabb,
+) -> Option<Rect> {
+    let mut bounds = Rect {
+        min: Vec2::INFINITY,
+        max: Vec2::NEG_INFINITY,
+    };
+    for x in [-1.0, 1.0] {
+        for y in [-1.0, 1.0] {
+            for z in [-1.0, 1.0] {
+                let local = aabb.center + aabb.half_extents * Vec3A::new(x, y, z);
+                let corner: Vec3 = transform.transform_point(local.into());
+                let projected = camera.world_to_viewport(camera_transform, corner).ok()?;
+                bounds.min = bounds.min.min(projected);
+                bounds.max = bounds.max.max(projected);
+            }
+        }
+    }
+    Some(bounds)
+}
+
 /// A component that aggregates picking interaction state of this entity across all pointers.
 ///
 /// Unlike bevy's `Interaction` component, this is an aggregate of the state of all pointers
@@ -409,6 +708,8 @@ pub fn update_is_directly_hovered(
 #[cfg(test)]
 mod tests {
     use bevy_camera::Camera;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec3;
 
     use super::*;
 
@@ -564,4 +865,164 @@ mod tests {
         assert!(!hover.get());
         assert!(hover.is_changed());
     }
+
+    #[test]
+    fn pointers_over_reports_every_pointer() {
+        let mut world = World::default();
+        let camera = world.spawn(Camera::default()).id();
+        let key = world.spawn_empty().id();
+        let other = world.spawn_empty().id();
+
+        let hit = |x: f32| HitData::new(camera, x, Some(Vec3::new(x, 0.0, 0.0)), None);
+        let first_touch = PointerId::Touch(0);
+        let second_touch = PointerId::Touch(1);
+        let mut hover_map = HoverMap::default();
+        hover_map.insert(first_touch, HashMap::from_iter([(key, hit(1.0))]));
+        hover_map.insert(
+            second_touch,
+            HashMap::from_iter([(key, hit(2.0)), (other, hit(3.0))]),
+        );
+        hover_map.insert(PointerId::Mouse, HashMap::from_iter([(other, hit(4.0))]));
+        world.insert_resource(hover_map);
+
+        let (pointers, hovered) = world
+            .run_system_once(move |pointers: PointerQuery| {
+                (
+                    pointers.pointers_over(key).collect::<Vec<_>>(),
+                    pointers.is_hovered(key),
+                )
+            })
+            .unwrap();
+
+        assert!(hovered);
+        assert_eq!(pointers.len(), 2);
+        assert!(pointers.contains(&(first_touch, hit(1.0))));
+        assert!(pointers.contains(&(second_touch, hit(2.0))));
+    }
+
+    #[test]
+    fn hover_stack_is_ordered_front_to_back() {
+        let mut world = World::default();
+        let camera = world.spawn_empty().id();
+        let back = world.spawn_empty().id();
+        let front = world.spawn_empty().id();
+        let middle = world.spawn_empty().id();
+
+        let hit = |depth: f32| HitData::new(camera, depth, None, None);
+        let mut hover_map = HoverMap::default();
+        hover_map.insert(
+            PointerId::Mouse,
+            HashMap::from_iter([(back, hit(3.0)), (front, hit(1.0)), (middle, hit(2.0))]),
+        );
+        world.insert_resource(hover_map);
+
+        let (stack, frontmost) = world
+            .run_system_once(move |pointers: PointerQuery| {
+                (
+                    pointers.hover_stack(PointerId::Mouse),
+                    [back, front, middle, camera]
+                        .map(|entity| pointers.is_frontmost(PointerId::Mouse, entity)),
+                )
+            })
+            .unwrap();
+
+        assert_eq!(
+            stack,
+            vec![(front, hit(1.0)), (middle, hit(2.0)), (back, hit(3.0))]
+        );
+        assert_eq!(frontmost, [false, true, false, false]);
+    }
+
+    #[test]
+    fn touch_slop_hovers_nearest_small_entity() {
+        use crate::pointer::Location;
+        use bevy_camera::{visibility::SetViewVisibility, ComputedCameraValues, RenderTargetInfo};
+        use bevy_ecs::message::Messages;
+        use bevy_math::{Mat4, UVec2};
+
+        let mut world = World::default();
+        world.init_resource::<HoverMap>();
+        world.init_resource::<PreviousHoverMap>();
+        world.init_resource::<Messages<backend::PointerHits>>();
+        world.init_resource::<Messages<PointerInput>>();
+
+        let size = UVec2::new(800, 600);
+        let camera = world
+            .spawn((
+                Camera {
+                    computed: ComputedCameraValues {
+                        clip_from_view: Mat4::orthographic_rh(
+                            -400.0, 400.0, -300.0, 300.0, 1000.0, -1000.0,
+                        ),
+                        target_info: Some(RenderTargetInfo {
+                            physical_size: size,
+                            scale_factor: 1.0,
+                        }),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                RenderTarget::None { size },
+                GlobalTransform::IDENTITY,
+            ))
+            .id();
+
+        // Two 10x10 entities, drawn at viewport x 495..505 and 520..530.
+        let mut spawn_target = |x: f32| {
+            let mut entity = world.spawn((
+                Pickable::default(),
+                Aabb {
+                    center: Vec3A::ZERO,
+                    half_extents: Vec3A::new(5.0, 5.0, 0.0),
+                },
+                GlobalTransform::from_translation(Vec3::new(x, 0.0, 0.0)),
+                ViewVisibility::HIDDEN,
+            ));
+            entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+            entity.id()
+        };
+        let near = spawn_target(100.0);
+        let far = spawn_target(125.0);
+        // Even nearer, but not pickable, or not rendered by the camera.
+        let unpickable = spawn_target(110.0);
+        let other_layer = spawn_target(112.0);
+        world.entity_mut(unpickable).remove::<Pickable>();
+        world.entity_mut(other_layer).insert(RenderLayers::layer(1));
+
+        let touch = PointerId::Touch(0);
+        world.spawn((
+            touch,
+            PointerLocation::new(Location {
+                target: RenderTarget::None { size }.normalize(None).unwrap(),
+                position: Vec2::new(512.0, 300.0),
+            }),
+        ));
+
+        let hovered = |world: &mut World| {
+            world.run_system_once(generate_hovermap).unwrap();
+            let hover_map = world.resource::<HoverMap>();
+            hover_map[&touch].keys().copied().collect::<Vec<_>>()
+        };
+
+        // Without slop, the touch lands between the entities and misses both. Neither the
+        // `TouchSlop` nor the `PointerState` resources are required.
+        assert!(hovered(&mut world).is_empty());
+
+        // Both entities are within the slop radius; the one with the nearer center wins.
+        world.insert_resource(TouchSlop { radius: 10.0 });
+        assert_eq!(hovered(&mut world), vec![near]);
+        let hit = &world.resource::<HoverMap>()[&touch][&near];
+        assert_eq!(hit.camera, camera);
+        assert_eq!(hit.position, Some(Vec3::new(100.0, 0.0, 0.0)));
+
+        // Moving the touch past the first entity's slop leaves only the second one in reach.
+        world
+            .run_system_once(|mut pointers: Query<&mut PointerLocation>| {
+                for mut pointer in &mut pointers {
+                    pointer.location.as_mut().unwrap().position.x = 518.0;
+                }
+            })
+            .unwrap();
+        assert_eq!(hovered(&mut world), vec![far]);
+    }
 }
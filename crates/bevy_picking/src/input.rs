@@ -21,16 +21,20 @@ use bevy_input::{
     ButtonState,
 };
 use bevy_math::Vec2;
-use bevy_platform::collections::{HashMap, HashSet};
+use bevy_platform::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 use bevy_reflect::prelude::*;
 use bevy_window::{PrimaryWindow, WindowEvent, WindowRef};
 use tracing::debug;
 
 use crate::pointer::{
     Location, PointerAction, PointerButton, PointerId, PointerInput, PointerLocation,
+    PointerModifiers,
 };
 
-use crate::PickingSystems;
+use crate::{events::PickingClock, PickingSystems};
 
 /// The picking input prelude.
 ///
@@ -122,12 +126,19 @@ pub fn mouse_pick_events(
     // Input
     mut window_events: MessageReader<WindowEvent>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
+    clock: Option<Res<PickingClock>>,
     // Locals
     mut cursor_last: Local<Vec2>,
+    mut held_keys: Local<HashSet<KeyCode>>,
     // Output
     mut pointer_inputs: MessageWriter<PointerInput>,
 ) {
     for window_event in window_events.read() {
+        track_held_keys(&mut held_keys, window_event);
+        let modifiers = PointerModifiers::from_pressed(|key| held_keys.contains(&key));
+        let time = clock
+            .as_deref()
+            .map_or_else(Instant::now, PickingClock::now);
         match window_event {
             // Handle cursor movement events
             WindowEvent::CursorMoved(event) => {
@@ -140,13 +151,17 @@ pub fn mouse_pick_events(
                     },
                     position: event.position,
                 };
-                pointer_inputs.write(PointerInput::new(
-                    PointerId::Mouse,
-                    location,
-                    PointerAction::Move {
-                        delta: event.position - *cursor_last,
-                    },
-                ));
+                pointer_inputs.write(
+                    PointerInput::new(
+                        PointerId::Mouse,
+                        location,
+                        PointerAction::Move {
+                            delta: event.position - *cursor_last,
+                        },
+                    )
+                    .with_modifiers(modifiers)
+                    .with_time(time),
+                );
                 *cursor_last = event.position;
             }
             // Handle mouse button press events
@@ -170,7 +185,11 @@ pub fn mouse_pick_events(
                     ButtonState::Pressed => PointerAction::Press(button),
                     ButtonState::Released => PointerAction::Release(button),
                 };
-                pointer_inputs.write(PointerInput::new(PointerId::Mouse, location, action));
+                pointer_inputs.write(
+                    PointerInput::new(PointerId::Mouse, location, action)
+                        .with_modifiers(modifiers)
+                        .with_time(time),
+                );
             }
             WindowEvent::MouseWheel(event) => {
                 let MouseWheel { unit, x, y, window } = *event;
@@ -185,9 +204,16 @@ pub fn mouse_pick_events(
                     position: *cursor_last,
                 };
 
-                let action = PointerAction::Scroll { x, y, unit };
+                let action = PointerAction::Scroll {
+                    delta: Vec2::new(x, y),
+                    unit: unit.into(),
+                };
 
-                pointer_inputs.write(PointerInput::new(PointerId::Mouse, location, action));
+                pointer_inputs.write(
+                    PointerInput::new(PointerId::Mouse, location, action)
+                        .with_modifiers(modifiers)
+                        .with_time(time),
+                );
             }
             _ => {}
         }
@@ -199,13 +225,20 @@ pub fn touch_pick_events(
     // Input
     mut window_events: MessageReader<WindowEvent>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
+    clock: Option<Res<PickingClock>>,
     // Locals
     mut touch_cache: Local<HashMap<u64, TouchInput>>,
+    mut held_keys: Local<HashSet<KeyCode>>,
     // Output
     mut commands: Commands,
     mut pointer_inputs: MessageWriter<PointerInput>,
 ) {
     for window_event in window_events.read() {
+        track_held_keys(&mut held_keys, window_event);
+        let modifiers = PointerModifiers::from_pressed(|key| held_keys.contains(&key));
+        let time = clock
+            .as_deref()
+            .map_or_else(Instant::now, PickingClock::now);
         if let WindowEvent::TouchInput(touch) = window_event {
             let pointer = PointerId::Touch(touch.id);
             let location = Location {
@@ -222,11 +255,15 @@ pub fn touch_pick_events(
                     debug!("Spawning pointer {:?}", pointer);
                     commands.spawn((pointer, PointerLocation::new(location.clone())));
 
-                    pointer_inputs.write(PointerInput::new(
-                        pointer,
-                        location,
-                        PointerAction::Press(PointerButton::Primary),
-                    ));
+                    pointer_inputs.write(
+                        PointerInput::new(
+                            pointer,
+                            location,
+                            PointerAction::Press(PointerButton::Primary),
+                        )
+                        .with_modifiers(modifiers)
+                        .with_time(time),
+                    );
 
                     touch_cache.insert(touch.id, *touch);
                 }
@@ -236,30 +273,38 @@ pub fn touch_pick_events(
                         if last_touch == touch {
                             continue;
                         }
-                        pointer_inputs.write(PointerInput::new(
-                            pointer,
-                            location,
-                            PointerAction::Move {
-                                delta: touch.position - last_touch.position,
-                            },
-                        ));
+                        pointer_inputs.write(
+                            PointerInput::new(
+                                pointer,
+                                location,
+                                PointerAction::Move {
+                                    delta: touch.position - last_touch.position,
+                                },
+                            )
+                            .with_modifiers(modifiers)
+                            .with_time(time),
+                        );
                     }
                     touch_cache.insert(touch.id, *touch);
                 }
                 TouchPhase::Ended => {
-                    pointer_inputs.write(PointerInput::new(
-                        pointer,
-                        location,
-                        PointerAction::Release(PointerButton::Primary),
-                    ));
+                    pointer_inputs.write(
+                        PointerInput::new(
+                            pointer,
+                            location,
+                            PointerAction::Release(PointerButton::Primary),
+                        )
+                        .with_modifiers(modifiers)
+                        .with_time(time),
+                    );
                     touch_cache.remove(&touch.id);
                 }
                 TouchPhase::Canceled => {
-                    pointer_inputs.write(PointerInput::new(
-                        pointer,
-                        location,
-                        PointerAction::Cancel,
-                    ));
+                    pointer_inputs.write(
+                        PointerInput::new(pointer, location, PointerAction::Cancel)
+                            .with_modifiers(modifiers)
+                            .with_time(time),
+                    );
                     touch_cache.remove(&touch.id);
                 }
             }
@@ -267,6 +312,23 @@ pub fn touch_pick_events(
     }
 }
 
+/// Updates the keys held from a keyboard event, so that each [`PointerInput`] reports the
+/// [`PointerModifiers`] held when its window event was produced.
+fn track_held_keys(held_keys: &mut HashSet<KeyCode>, window_event: &WindowEvent) {
+    match window_event {
+        WindowEvent::KeyboardInput(input) => match input.state {
+            ButtonState::Pressed => {
+                held_keys.insert(input.key_code);
+            }
+            ButtonState::Released => {
+                held_keys.remove(&input.key_code);
+            }
+        },
+        WindowEvent::KeyboardFocusLost(_) => held_keys.clear(),
+        _ => {}
+    }
+}
+
 /// Deactivates unused touch pointers.
 ///
 /// Because each new touch gets assigned a new ID, we need to remove the pointers associated with
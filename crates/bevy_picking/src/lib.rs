@@ -38,7 +38,10 @@
 //!
 //! When events are generated, they bubble up the entity hierarchy starting from their target, until
 //! they reach the root or bubbling is halted with a call to
-//! [`On::propagate`](bevy_ecs::observer::On::propagate). See [`Observer`] for details.
+//! [`On::propagate`](bevy_ecs::observer::On::propagate). See [`Observer`] for details. The
+//! high-frequency [`Move`](events::Move) and [`DragOver`](events::DragOver) events are the exception:
+//! they stop at their target unless told otherwise, see
+//! [`PointerEventPropagation`](events::PointerEventPropagation).
 //!
 //! This allows you to run callbacks when any children of an entity are interacted with, and leads
 //! to succinct, expressive code:
@@ -156,12 +159,18 @@
 
 extern crate alloc;
 
+#[cfg(feature = "async_events")]
+pub mod async_events;
 pub mod backend;
+pub mod drag_path;
 pub mod events;
+pub mod gesture;
+pub mod handlers;
 pub mod hover;
 pub mod input;
 #[cfg(feature = "mesh_picking")]
 pub mod mesh_picking;
+pub mod navigation;
 pub mod pointer;
 pub mod window;
 
@@ -178,12 +187,14 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::mesh_picking::{
         ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastBackfaces, RayCastVisibility},
-        MeshPickingCamera, MeshPickingPlugin, MeshPickingSettings,
+        AlphaHitTest, MeshPickingCamera, MeshPickingPlugin, MeshPickingSettings,
     };
     #[doc(hidden)]
     pub use crate::{
-        events::*, input::PointerInputPlugin, pointer::PointerButton, DefaultPickingPlugins,
-        InteractionPlugin, Pickable, PickingPlugin,
+        events::*,
+        input::PointerInputPlugin,
+        pointer::{PointerButton, ScrollUnit},
+        DefaultPickingPlugins, InteractionPlugin, Pickable, PickingPlugin,
     };
 }
 
@@ -418,32 +429,71 @@ impl Plugin for InteractionPlugin {
 
         app.init_resource::<hover::HoverMap>()
             .init_resource::<hover::PreviousHoverMap>()
+            .init_resource::<hover::TouchSlop>()
             .init_resource::<PointerState>()
+            .init_resource::<PointerActivity>()
+            .init_resource::<PickingClock>()
+            .init_resource::<FirstInteractions>()
+            .init_resource::<gesture::GestureRecognizers>()
             .add_message::<Pointer<Cancel>>()
             .add_message::<Pointer<Click>>()
             .add_message::<Pointer<Press>>()
+            .add_message::<Pointer<PressRepeat>>()
+            .add_message::<Pointer<LongPress>>()
+            .add_message::<Pointer<FirstInteraction>>()
             .add_message::<Pointer<DragDrop>>()
             .add_message::<Pointer<DragEnd>>()
+            .add_message::<Pointer<DragCancel>>()
             .add_message::<Pointer<DragEnter>>()
             .add_message::<Pointer<Drag>>()
             .add_message::<Pointer<DragLeave>>()
             .add_message::<Pointer<DragOver>>()
             .add_message::<Pointer<DragStart>>()
             .add_message::<Pointer<Move>>()
+            .add_message::<Pointer<FallbackMove>>()
             .add_message::<Pointer<Out>>()
             .add_message::<Pointer<Over>>()
             .add_message::<Pointer<Release>>()
             .add_message::<Pointer<Scroll>>()
+            .add_message::<PointerCaptureChanged>()
+            .add_message::<PointerHoverNothing>()
+            .add_message::<PointerHoverStarted>()
+            .add_message::<PickingFrameSummary>()
+            .add_message::<Pointer<BecameTopmost>>()
+            .add_message::<Pointer<LostTopmost>>()
+            .add_message::<Pointer<HoverEnter>>()
+            .add_message::<Pointer<HoverExit>>()
+            .add_message::<Pointer<gesture::Pinch>>()
+            .add_message::<Pointer<gesture::Rotate>>()
             .add_systems(
                 PreUpdate,
                 (
                     generate_hovermap,
                     update_interactions,
                     (update_is_hovered, update_is_directly_hovered),
+                    clear_despawned_pointer_state,
                     pointer_events,
+                    pointer_hover_transitions,
+                    summarize_interactions,
+                    gesture::recognize_gestures,
                 )
                     .chain()
                     .in_set(PickingSystems::Hover),
-            );
+            )
+            .add_observer(stop_at_outermost::<HoverEnter>)
+            .add_observer(stop_at_outermost::<HoverExit>);
+    }
+}
+
+/// Mirrors every [`Pointer`](events::Pointer) event written by the [`InteractionPlugin`] into a
+/// single stream of [`AnyPointerEvent`](events::AnyPointerEvent) messages.
+///
+/// This doubles the number of pointer event messages, so it isn't added by default.
+#[derive(Default)]
+pub struct PointerEventMirrorPlugin;
+
+impl Plugin for PointerEventMirrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<events::AnyPointerEvent>();
     }
 }
@@ -13,6 +13,8 @@
 //!
 //! - The `position` reported in `HitData` is in world space. The `normal` is a vector pointing
 //!   away from the face, it is not guaranteed to be normalized for scaled meshes.
+//! - Entities with an [`AlphaHitTest`] are only hit where their texture is opaque enough, which
+//!   requires the texture to be readable on the CPU.
 
 pub mod ray_cast;
 
@@ -22,8 +24,11 @@ use crate::{
     PickingSystems,
 };
 use bevy_app::prelude::*;
+use bevy_asset::{Assets, Handle};
 use bevy_camera::{visibility::RenderLayers, Camera};
 use bevy_ecs::prelude::*;
+use bevy_image::Image;
+use bevy_math::Vec2;
 use bevy_reflect::prelude::*;
 use ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastVisibility};
 
@@ -34,6 +39,49 @@ use ray_cast::{MeshRayCast, MeshRayCastSettings, RayCastVisibility};
 #[reflect(Debug, Default, Component)]
 pub struct MeshPickingCamera;
 
+/// An optional component that makes the [`MeshPickingPlugin`] ignore hits on transparent parts of
+/// an entity's texture.
+///
+/// Without this component, the whole mesh is hittable. With it, a hit only counts if the alpha of
+/// `texture`, sampled at the UV coordinate of the hit, is greater than `threshold`. This is useful
+/// for sprite-like 2D meshes whose texture has transparent regions.
+///
+/// The test is skipped, and the hit is kept, if the mesh has no UVs, the texture is not loaded, or
+/// the texture data is not available on the CPU (see
+/// [`RenderAssetUsages::MAIN_WORLD`](bevy_asset::RenderAssetUsages::MAIN_WORLD)).
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Debug, Clone)]
+pub struct AlphaHitTest {
+    /// Hits where the sampled alpha is less than or equal to this value are ignored.
+    pub threshold: f32,
+    /// The texture sampled to determine the alpha at the hit location. This is usually the same
+    /// texture used by the entity's material.
+    pub texture: Handle<Image>,
+}
+
+impl AlphaHitTest {
+    /// Creates a new [`AlphaHitTest`] sampling `texture` with the given alpha `threshold`.
+    pub fn new(texture: Handle<Image>, threshold: f32) -> Self {
+        Self { threshold, texture }
+    }
+
+    /// Returns `true` if the alpha of `image` at `uv` is greater than the threshold.
+    ///
+    /// If the image data can't be read, this returns `true` so that the hit is kept.
+    pub fn is_hit(&self, image: &Image, uv: Vec2) -> bool {
+        let size = image.size();
+        if size.x == 0 || size.y == 0 {
+            return true;
+        }
+        let x = (uv.x.clamp(0.0, 1.0) * size.x as f32) as u32;
+        let y = (uv.y.clamp(0.0, 1.0) * size.y as f32) as u32;
+        match image.get_color_at(x.min(size.x - 1), y.min(size.y - 1)) {
+            Ok(color) => color.to_linear().alpha > self.threshold,
+            Err(_) => true,
+        }
+    }
+}
+
 /// Runtime settings for the [`MeshPickingPlugin`].
 #[derive(Resource, Reflect)]
 #[reflect(Resource, Default)]
@@ -81,6 +129,8 @@ pub fn update_hits(
     pickables: Query<&Pickable>,
     marked_targets: Query<&Pickable>,
     layers: Query<&RenderLayers>,
+    alpha_hit_tests: Query<&AlphaHitTest>,
+    images: Option<Res<Assets<Image>>>,
     mut ray_cast: MeshRayCast,
     mut pointer_hits_writer: MessageWriter<PointerHits>,
 ) {
@@ -109,14 +159,27 @@ pub fn update_hits(
                 marker_requirement && render_layers_match && is_pickable
             },
             early_exit_test: &|entity_hit| {
-                pickables
-                    .get(entity_hit)
-                    .is_ok_and(|pickable| pickable.should_block_lower)
+                // Hits on alpha tested entities may be discarded below, so they can't end the
+                // ray cast early.
+                !alpha_hit_tests.contains(entity_hit)
+                    && pickables
+                        .get(entity_hit)
+                        .is_ok_and(|pickable| pickable.should_block_lower)
             },
         };
         let picks = ray_cast
             .cast_ray(ray, &settings)
             .iter()
+            .filter(|(entity, hit)| {
+                let (Ok(alpha_hit_test), Some(uv), Some(images)) =
+                    (alpha_hit_tests.get(*entity), hit.uv, images.as_ref())
+                else {
+                    return true;
+                };
+                images
+                    .get(&alpha_hit_test.texture)
+                    .is_none_or(|image| alpha_hit_test.is_hit(image, uv))
+            })
             .map(|(entity, hit)| {
                 let hit_data = HitData::new(
                     ray_id.camera,
@@ -133,3 +196,104 @@ pub fn update_hits(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::{
+        primitives::MeshAabb,
+        visibility::{InheritedVisibility, ViewVisibility},
+        NormalizedRenderTarget,
+    };
+    use bevy_math::{primitives::Rectangle, Dir3, Ray3d, Vec3};
+    use bevy_mesh::{Mesh, Mesh3d};
+
+    use super::*;
+    use crate::{
+        backend::ray::RayId,
+        events::{Over, Pointer},
+        pointer::{
+            update_pointer_map, Location, PointerId, PointerInput, PointerLocation, PointerMap,
+        },
+        InteractionPlugin,
+    };
+
+    /// A 2x1 image: the left texel is fully transparent, the right one is opaque.
+    fn half_transparent_image() -> Image {
+        let mut image = Image::default();
+        image.texture_descriptor.size.width = 2;
+        image.data = Some(vec![255, 255, 255, 0, 255, 255, 255, 255]);
+        image
+    }
+
+    #[test]
+    fn alpha_hit_test_ignores_transparent_texels() {
+        let image = half_transparent_image();
+        let alpha_hit_test = AlphaHitTest::new(Handle::default(), 0.5);
+        assert!(!alpha_hit_test.is_hit(&image, Vec2::new(0.25, 0.5)));
+        assert!(alpha_hit_test.is_hit(&image, Vec2::new(0.75, 0.5)));
+    }
+
+    #[derive(Resource, Default)]
+    struct Overs(Vec<Entity>);
+
+    #[test]
+    fn transparent_texels_do_not_hover() {
+        let mut app = App::new();
+        app.add_plugins((InteractionPlugin, MeshPickingPlugin))
+            .add_message::<PointerInput>()
+            .add_message::<PointerHits>()
+            .init_resource::<PointerMap>()
+            .init_resource::<RayMap>()
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<Image>>()
+            .init_resource::<Overs>()
+            .insert_resource(MeshPickingSettings {
+                ray_cast_visibility: RayCastVisibility::Any,
+                ..Default::default()
+            });
+        let world = app.world_mut();
+        let location = Location {
+            target: NormalizedRenderTarget::None {
+                width: 800,
+                height: 600,
+            },
+            position: Vec2::ZERO,
+        };
+        world.spawn((PointerId::Mouse, PointerLocation::new(location)));
+        world.run_system_cached(update_pointer_map).unwrap();
+        world.add_observer(|event: On<Pointer<Over>>, mut overs: ResMut<Overs>| {
+            overs.0.push(event.entity);
+        });
+
+        // A 2x2 quad facing the camera, textured so its left half is transparent.
+        let mesh = Mesh::from(Rectangle::new(2.0, 2.0));
+        let aabb = mesh.compute_aabb().unwrap();
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        let texture = world
+            .resource_mut::<Assets<Image>>()
+            .add(half_transparent_image());
+        let quad = world
+            .spawn((
+                Mesh3d(mesh),
+                aabb,
+                InheritedVisibility::VISIBLE,
+                ViewVisibility::default(),
+                AlphaHitTest::new(texture, 0.5),
+            ))
+            .id();
+        let camera = world.spawn(Camera::default()).id();
+
+        let hover_at = |app: &mut App, x: f32| {
+            let ray = Ray3d::new(Vec3::new(x, 0.0, 5.0), Dir3::NEG_Z);
+            app.world_mut()
+                .resource_mut::<RayMap>()
+                .map
+                .insert(RayId::new(camera, PointerId::Mouse), ray);
+            app.update();
+            core::mem::take(&mut app.world_mut().resource_mut::<Overs>().0)
+        };
+
+        assert_eq!(hover_at(&mut app, -0.5), Vec::<Entity>::new());
+        assert_eq!(hover_at(&mut app, 0.5), vec![quad]);
+    }
+}
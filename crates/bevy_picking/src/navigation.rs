@@ -0,0 +1,346 @@
+//! Spatial navigation between pickable entities, for gamepad and keyboard driven interfaces.
+//!
+//! [`SpatialNavigation::spatial_navigate`] finds the entity nearest to another one in a
+//! [`NavDirection`], using the positions of both on screen. [`SpatialNavigationPlugin`] builds on
+//! it to drive a focus pointer: each [`NavigationInput`] moves the [`NavigationFocus`] to the
+//! neighbor in its direction and moves the [`FOCUS_POINTER_ID`] pointer onto it, or presses and
+//! releases the pointer on the focused entity. Since the focus pointer is an ordinary pointer, the
+//! focused entity is hovered and pressed through the usual [`Pointer`](crate::events::Pointer)
+//! events.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::navigation::{NavDirection, NavigationInput};
+//! fn navigate_with_arrows(mut navigation: MessageWriter<NavigationInput>) {
+//!     navigation.write(NavigationInput::Move(NavDirection::Right));
+//! }
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_camera::{primitives::Aabb, visibility::ViewVisibility, Camera, RenderTarget};
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_math::{FloatOrd, Vec2};
+use bevy_reflect::prelude::*;
+use bevy_transform::components::GlobalTransform;
+use bevy_window::PrimaryWindow;
+use uuid::Uuid;
+
+use crate::{
+    pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput},
+    Pickable, PickingSystems,
+};
+
+/// The pointer driven by [`SpatialNavigationPlugin`].
+pub const FOCUS_POINTER_ID: PointerId =
+    PointerId::Custom(Uuid::from_u128(0x6f3c_51a2_9d0e_4b7f_8c16_2e4a_d9b0_73f5));
+
+/// A direction to navigate in, on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub enum NavDirection {
+    /// Towards the top edge of the render target.
+    Up,
+    /// Towards the bottom edge of the render target.
+    Down,
+    /// Towards the left edge of the render target.
+    Left,
+    /// Towards the right edge of the render target.
+    Right,
+}
+
+impl NavDirection {
+    /// The unit vector of this direction in viewport space, where `+y` points down.
+    pub fn as_vec2(self) -> Vec2 {
+        match self {
+            Self::Up => Vec2::NEG_Y,
+            Self::Down => Vec2::Y,
+            Self::Left => Vec2::NEG_X,
+            Self::Right => Vec2::X,
+        }
+    }
+}
+
+/// Returns the candidate that is the best neighbor of `origin` in `direction`, given the
+/// viewport positions of the candidates.
+///
+/// Only candidates strictly ahead of `origin` in `direction` are considered. Of those, the one
+/// with the smallest distance along the direction plus twice its distance across it is picked, so
+/// that an entity in the same row or column is preferred over a slightly closer diagonal one.
+/// Ties are broken by the lowest [`Entity`], to keep the result deterministic.
+pub fn best_neighbor(
+    origin: Vec2,
+    direction: NavDirection,
+    candidates: impl IntoIterator<Item = (Entity, Vec2)>,
+) -> Option<Entity> {
+    let axis = direction.as_vec2();
+    candidates
+        .into_iter()
+        .filter_map(|(entity, position)| {
+            let offset = position - origin;
+            let along = offset.dot(axis);
+            if along <= f32::EPSILON {
+                return None;
+            }
+            let across = offset.perp_dot(axis).abs();
+            Some((FloatOrd(along + 2.0 * across), entity))
+        })
+        .min()
+        .map(|(_, entity)| entity)
+}
+
+/// A [`SystemParam`] for moving focus spatially between the visible, hoverable entities on screen.
+///
+/// Entities are positioned at the center of their [`Aabb`], or at their translation if they don't
+/// have one, as seen by the highest-order active camera that has them in view.
+#[derive(SystemParam)]
+pub struct SpatialNavigation<'w, 's> {
+    cameras: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static RenderTarget,
+            &'static GlobalTransform,
+        ),
+    >,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    targets: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static ViewVisibility,
+            Option<&'static Aabb>,
+            Option<&'static Pickable>,
+        ),
+    >,
+}
+
+impl SpatialNavigation<'_, '_> {
+    /// Returns the entity to move focus to when navigating from `from` in `direction`, or `None`
+    /// if there is no suitable entity in that direction.
+    ///
+    /// Only entities on the same render target as `from` are considered. See [`best_neighbor`]
+    /// for how the neighbor is chosen.
+    pub fn spatial_navigate(&self, from: Entity, direction: NavDirection) -> Option<Entity> {
+        let origin = self.location(from)?;
+        let candidates = self.targets.iter().filter_map(|(entity, ..)| {
+            if entity == from {
+                return None;
+            }
+            let location = self.location(entity)?;
+            (location.target == origin.target).then_some((entity, location.position))
+        });
+        best_neighbor(origin.position, direction, candidates)
+    }
+
+    /// Returns where `entity` is on screen, or `None` if it isn't a visible, hoverable entity in
+    /// view of an active camera.
+    pub fn location(&self, entity: Entity) -> Option<Location> {
+        let (_, transform, visibility, aabb, pickable) = self.targets.get(entity).ok()?;
+        if !visibility.get() || pickable.is_some_and(|pickable| !pickable.is_hoverable) {
+            return None;
+        }
+        let center = match aabb {
+            Some(aabb) => transform.transform_point(aabb.center.into()),
+            None => transform.translation(),
+        };
+        let primary_window = self.primary_window.single().ok();
+        self.cameras
+            .iter()
+            .filter(|(camera, ..)| camera.is_active)
+            .filter_map(|(camera, render_target, camera_transform)| {
+                let position = camera.world_to_viewport(camera_transform, center).ok()?;
+                let target = render_target.normalize(primary_window)?;
+                Some((camera.order, Location { target, position }))
+            })
+            .max_by_key(|(order, _)| *order)
+            .map(|(_, location)| location)
+    }
+}
+
+/// An input to the focus pointer driven by [`SpatialNavigationPlugin`].
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Debug, Clone, PartialEq)]
+pub enum NavigationInput {
+    /// Moves the focus to the neighbor in this direction, if there is one.
+    Move(NavDirection),
+    /// Presses the primary button of the focus pointer on the focused entity.
+    Press,
+    /// Releases the primary button of the focus pointer.
+    Release,
+}
+
+/// The entity focused by the [`FOCUS_POINTER_ID`] pointer.
+///
+/// Set this to choose the entity navigation starts from. Whenever it changes, the focus pointer is
+/// moved onto the newly focused entity.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Debug, Default, Clone, PartialEq)]
+pub struct NavigationFocus {
+    /// The focused entity, if any.
+    pub focused: Option<Entity>,
+}
+
+/// Drives the [`FOCUS_POINTER_ID`] pointer from [`NavigationInput`]s, see the
+/// [module docs](self).
+pub struct SpatialNavigationPlugin;
+
+impl Plugin for SpatialNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavigationFocus>()
+            .add_message::<NavigationInput>()
+            .add_systems(Startup, spawn_focus_pointer)
+            .add_systems(First, navigate_focus.in_set(PickingSystems::Input));
+    }
+}
+
+/// Spawns the pointer driven by [`navigate_focus`].
+pub fn spawn_focus_pointer(mut commands: Commands) {
+    commands.spawn(FOCUS_POINTER_ID);
+}
+
+/// Moves the [`NavigationFocus`] according to this frame's [`NavigationInput`]s, and writes the
+/// matching [`PointerInput`]s for the [`FOCUS_POINTER_ID`] pointer.
+pub fn navigate_focus(
+    mut inputs: MessageReader<NavigationInput>,
+    mut focus: ResMut<NavigationFocus>,
+    navigation: SpatialNavigation,
+    mut last_location: Local<Option<Location>>,
+    mut pointer_inputs: MessageWriter<PointerInput>,
+) {
+    let move_to_focus = |focused: Option<Entity>, last_location: &mut Option<Location>| {
+        let location = navigation.location(focused?)?;
+        let delta = last_location
+            .as_ref()
+            .map_or(Vec2::ZERO, |last| location.position - last.position);
+        *last_location = Some(location.clone());
+        Some(PointerInput::new(
+            FOCUS_POINTER_ID,
+            location,
+            PointerAction::Move { delta },
+        ))
+    };
+
+    // The focus was set directly.
+    if focus.is_changed() {
+        pointer_inputs.write_batch(move_to_focus(focus.focused, &mut last_location));
+    }
+
+    for input in inputs.read() {
+        match *input {
+            NavigationInput::Move(direction) => {
+                let Some(next) = focus
+                    .focused
+                    .and_then(|focused| navigation.spatial_navigate(focused, direction))
+                else {
+                    continue;
+                };
+                focus.bypass_change_detection().focused = Some(next);
+                pointer_inputs.write_batch(move_to_focus(Some(next), &mut last_location));
+            }
+            NavigationInput::Press | NavigationInput::Release => {
+                let Some(location) = last_location.clone() else {
+                    continue;
+                };
+                let action = match input {
+                    NavigationInput::Press => PointerAction::Press(PointerButton::Primary),
+                    _ => PointerAction::Release(PointerButton::Primary),
+                };
+                pointer_inputs.write(PointerInput::new(FOCUS_POINTER_ID, location, action));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::{visibility::SetViewVisibility, ComputedCameraValues, RenderTargetInfo};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::{Mat4, UVec2, Vec3};
+
+    use super::*;
+
+    #[test]
+    fn navigation_picks_the_neighbor_in_each_direction() {
+        let mut world = World::default();
+        let size = UVec2::new(800, 600);
+        world.spawn((
+            Camera {
+                computed: ComputedCameraValues {
+                    clip_from_view: Mat4::orthographic_rh(
+                        -400.0, 400.0, -300.0, 300.0, 1000.0, -1000.0,
+                    ),
+                    target_info: Some(RenderTargetInfo {
+                        physical_size: size,
+                        scale_factor: 1.0,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            RenderTarget::None { size },
+            GlobalTransform::IDENTITY,
+        ));
+
+        // A 3x3 grid with 100 units between cells, indexed by row and column. In world space, +y
+        // points up, so the first row is at the top of the screen.
+        let grid: Vec<Vec<Entity>> = (0..3)
+            .map(|row| {
+                (0..3)
+                    .map(|column| {
+                        let translation =
+                            Vec3::new(column as f32 * 100.0, row as f32 * -100.0, 0.0);
+                        let mut entity = world.spawn((
+                            GlobalTransform::from_translation(translation),
+                            ViewVisibility::HIDDEN,
+                        ));
+                        entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+                        entity.id()
+                    })
+                    .collect()
+            })
+            .collect();
+        let center = grid[1][1];
+
+        let navigate = |world: &mut World, from: Entity, direction: NavDirection| {
+            world
+                .run_system_once(move |navigation: SpatialNavigation| {
+                    navigation.spatial_navigate(from, direction)
+                })
+                .unwrap()
+        };
+        assert_eq!(
+            navigate(&mut world, center, NavDirection::Up),
+            Some(grid[0][1])
+        );
+        assert_eq!(
+            navigate(&mut world, center, NavDirection::Down),
+            Some(grid[2][1])
+        );
+        assert_eq!(
+            navigate(&mut world, center, NavDirection::Left),
+            Some(grid[1][0])
+        );
+        assert_eq!(
+            navigate(&mut world, center, NavDirection::Right),
+            Some(grid[1][2])
+        );
+
+        // Nothing is further up than the top row.
+        assert_eq!(navigate(&mut world, grid[0][0], NavDirection::Up), None);
+        // From a corner, the entity in the same row wins over the closer-looking diagonal.
+        assert_eq!(
+            navigate(&mut world, grid[2][0], NavDirection::Right),
+            Some(grid[2][1])
+        );
+
+        // Entities that can't be hovered are skipped.
+        world.entity_mut(center).insert(Pickable::IGNORE);
+        assert_eq!(
+            navigate(&mut world, grid[2][1], NavDirection::Up),
+            Some(grid[0][1])
+        );
+    }
+}
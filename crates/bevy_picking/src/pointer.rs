@@ -3,17 +3,17 @@
 //! The picking system is built around the concept of a 'Pointer', which is an
 //! abstract representation of a user input with a specific screen location. The cursor
 //! and touch input is provided under [`input`](`crate::input`), but you can also implement
-//! your own custom pointers by supplying a unique ID.
+//! your own custom pointers by supplying a unique ID, or drive them with [`VirtualPointers`].
 //!
 //! The purpose of this module is primarily to provide a common interface that can be
 //! driven by lower-level input devices and consumed by higher-level interaction systems.
 
 use bevy_camera::NormalizedRenderTarget;
 use bevy_camera::{Camera, RenderTarget};
-use bevy_ecs::prelude::*;
-use bevy_input::mouse::MouseScrollUnit;
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_input::{keyboard::KeyCode, mouse::MouseScrollUnit};
 use bevy_math::Vec2;
-use bevy_platform::collections::HashMap;
+use bevy_platform::{collections::HashMap, time::Instant};
 use bevy_reflect::prelude::*;
 use bevy_window::PrimaryWindow;
 
@@ -27,7 +27,7 @@ use crate::backend::HitData;
 ///
 /// This component is needed because pointers can be spawned and despawned, but they need to have a
 /// stable ID that persists regardless of the Entity they are associated with.
-#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Component, Reflect)]
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Component, Reflect)]
 #[require(PointerLocation, PointerPress, PointerInteraction)]
 #[reflect(Component, Default, Debug, Hash, PartialEq, Clone)]
 pub enum PointerId {
@@ -63,6 +63,79 @@ impl PointerId {
             None
         }
     }
+
+    /// Returns the kind of the pointer.
+    pub fn kind(&self) -> PointerKind {
+        match self {
+            PointerId::Mouse => PointerKind::Mouse,
+            PointerId::Touch(_) => PointerKind::Touch,
+            PointerId::Custom(_) => PointerKind::Custom,
+        }
+    }
+}
+
+/// The kind of a pointer, one for each variant of [`PointerId`].
+///
+/// Use [`PointerFilter`] to scope systems to the pointers of one kind, or
+/// [`Pointer::pointer_kind`](crate::events::Pointer::pointer_kind) to tell them apart in observers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Reflect)]
+#[reflect(Debug, Hash, PartialEq, Clone)]
+pub enum PointerKind {
+    /// The [`PointerId::Mouse`] pointer.
+    Mouse,
+    /// A [`PointerId::Touch`] pointer.
+    Touch,
+    /// A [`PointerId::Custom`] pointer, such as one spawned with [`VirtualPointers`].
+    Custom,
+}
+
+impl From<PointerId> for PointerKind {
+    fn from(pointer_id: PointerId) -> Self {
+        pointer_id.kind()
+    }
+}
+
+/// A [`SystemParam`] for finding the pointers of a [`PointerKind`].
+///
+/// Touch pointers only exist while the screen is touched, so [`PointerFilter::exists`] also makes
+/// a run condition that scopes a system to touch interactions:
+///
+/// ```
+/// # use bevy_app::{App, Update};
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_picking::pointer::{PointerFilter, PointerKind};
+/// fn pan_camera(touches: PointerFilter) {
+///     for (pointer, pointer_id) in touches.iter(PointerKind::Touch) {
+///         // ...
+///     }
+/// }
+///
+/// # let mut app = App::new();
+/// app.add_systems(Update, pan_camera.run_if(PointerFilter::exists(PointerKind::Touch)));
+/// ```
+#[derive(SystemParam)]
+pub struct PointerFilter<'w, 's> {
+    pointers: Query<'w, 's, (Entity, &'static PointerId)>,
+}
+
+impl PointerFilter<'_, '_> {
+    /// Returns the entities and ids of the pointers of the `kind`.
+    pub fn iter(&self, kind: PointerKind) -> impl Iterator<Item = (Entity, PointerId)> + '_ {
+        self.pointers
+            .iter()
+            .filter(move |(_, pointer_id)| pointer_id.kind() == kind)
+            .map(|(entity, pointer_id)| (entity, *pointer_id))
+    }
+
+    /// Returns `true` if a pointer of the `kind` exists.
+    pub fn any(&self, kind: PointerKind) -> bool {
+        self.iter(kind).next().is_some()
+    }
+
+    /// Returns a run condition that is `true` while a pointer of the `kind` exists.
+    pub fn exists(kind: PointerKind) -> impl FnMut(PointerFilter) -> bool + Clone {
+        move |filter: PointerFilter| filter.any(kind)
+    }
 }
 
 /// Holds a list of entities this pointer is currently interacting with, sorted from nearest to
@@ -155,7 +228,7 @@ pub enum PressDirection {
 }
 
 /// The button that was just pressed or released
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash, Reflect)]
 #[reflect(Clone, PartialEq)]
 pub enum PointerButton {
     /// The primary pointer button
@@ -258,17 +331,77 @@ pub enum PointerAction {
     },
     /// Scroll the pointer
     Scroll {
-        /// The mouse scroll unit.
-        unit: MouseScrollUnit,
-        /// The horizontal scroll value.
-        x: f32,
-        /// The vertical scroll value.
-        y: f32,
+        /// How far the pointer scrolled, in `unit`s.
+        delta: Vec2,
+        /// Whether `delta` is measured in lines or pixels.
+        unit: ScrollUnit,
     },
     /// Cancel the pointer. Often used for touch events.
     Cancel,
 }
 
+/// The unit of the `delta` of a [`PointerAction::Scroll`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Clone, PartialEq, Hash)]
+pub enum ScrollUnit {
+    /// The delta is a number of lines, as reported by most mouse wheels.
+    Line,
+    /// The delta is a number of pixels, as reported by touchpads and precise mouse wheels.
+    Pixel,
+}
+
+impl From<MouseScrollUnit> for ScrollUnit {
+    fn from(unit: MouseScrollUnit) -> Self {
+        match unit {
+            MouseScrollUnit::Line => ScrollUnit::Line,
+            MouseScrollUnit::Pixel => ScrollUnit::Pixel,
+        }
+    }
+}
+
+/// The modifier keys held when a [`PointerInput`] was produced, such as for Shift-click
+/// multi-selection.
+///
+/// Each modifier is held if the key on either side of the keyboard is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Debug, Default, Clone, PartialEq, Hash)]
+pub struct PointerModifiers {
+    /// Whether a Shift key is held.
+    pub shift: bool,
+    /// Whether a Control key is held.
+    pub control: bool,
+    /// Whether an Alt key is held.
+    pub alt: bool,
+    /// Whether a Super key, such as Command or Windows, is held.
+    pub super_key: bool,
+}
+
+impl PointerModifiers {
+    /// Reads the modifiers from `pressed`, which returns whether a key is held.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_input::prelude::*;
+    /// # use bevy_picking::pointer::PointerModifiers;
+    /// fn modifiers(keys: Res<ButtonInput<KeyCode>>) -> PointerModifiers {
+    ///     PointerModifiers::from_pressed(|key| keys.pressed(key))
+    /// }
+    /// ```
+    pub fn from_pressed(pressed: impl Fn(KeyCode) -> bool) -> Self {
+        Self {
+            shift: pressed(KeyCode::ShiftLeft) || pressed(KeyCode::ShiftRight),
+            control: pressed(KeyCode::ControlLeft) || pressed(KeyCode::ControlRight),
+            alt: pressed(KeyCode::AltLeft) || pressed(KeyCode::AltRight),
+            super_key: pressed(KeyCode::SuperLeft) || pressed(KeyCode::SuperRight),
+        }
+    }
+
+    /// Returns `true` if any modifier is held.
+    pub fn any(&self) -> bool {
+        self.shift || self.control || self.alt || self.super_key
+    }
+}
+
 /// An input event effecting a pointer.
 #[derive(Message, Debug, Clone, Reflect)]
 #[reflect(Clone)]
@@ -279,10 +412,20 @@ pub struct PointerInput {
     pub location: Location,
     /// The action that the event describes.
     pub action: PointerAction,
+    /// The modifier keys held when the input was produced.
+    ///
+    /// Defaults to none held, for pointers that don't supply them.
+    pub modifiers: PointerModifiers,
+    /// When the input was produced, read from the same clock as
+    /// [`PickingClock`](crate::events::PickingClock).
+    ///
+    /// When `None`, [`pointer_events`](crate::events::pointer_events) times the input when it reads
+    /// it, so all untimed inputs of a frame share a timestamp.
+    pub time: Option<Instant>,
 }
 
 impl PointerInput {
-    /// Creates a new pointer input event.
+    /// Creates a new pointer input event, with no modifier keys held and no timestamp.
     ///
     /// Note that `location` refers to the position of the pointer *after* the event occurred.
     pub fn new(pointer_id: PointerId, location: Location, action: PointerAction) -> PointerInput {
@@ -290,9 +433,23 @@ impl PointerInput {
             pointer_id,
             location,
             action,
+            modifiers: PointerModifiers::default(),
+            time: None,
         }
     }
 
+    /// Sets the modifier keys held when the input was produced.
+    pub fn with_modifiers(mut self, modifiers: PointerModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Sets the time the input was produced at.
+    pub fn with_time(mut self, time: Instant) -> Self {
+        self.time = Some(time);
+        self
+    }
+
     /// Returns true if the `target_button` of this pointer was just pressed.
     #[inline]
     pub fn button_just_pressed(&self, target_button: PointerButton) -> bool {
@@ -358,3 +515,288 @@ impl PointerInput {
         }
     }
 }
+
+/// Drives virtual pointers from gameplay code, such as AI, scripted sequences, or networked
+/// players.
+///
+/// A virtual pointer is an entity with a [`PointerId::Custom`] id. Like the mouse, it is hit tested
+/// by the picking backends and goes through the whole picking state machine, so entities receive
+/// the same [`Pointer`](crate::events::Pointer) events from it. Rather than by a device, it is
+/// driven by the [`PointerInput`]s written by this parameter.
+///
+/// A pointer can be driven from the frame after it is spawned. It must be moved to a [`Location`]
+/// before pressing or releasing its buttons, which happen at its current location.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_camera::NormalizedRenderTarget;
+/// # use bevy_math::Vec2;
+/// # use bevy_picking::pointer::{Location, PointerButton, PointerId, VirtualPointers};
+/// #[derive(Resource)]
+/// struct Bot(PointerId);
+///
+/// fn spawn_bot(mut pointers: VirtualPointers, mut commands: Commands) {
+///     commands.insert_resource(Bot(pointers.spawn()));
+/// }
+///
+/// fn click_button(bot: Res<Bot>, mut pointers: VirtualPointers) {
+///     let location = Location {
+///         target: NormalizedRenderTarget::None {
+///             width: 800,
+///             height: 600,
+///         },
+///         position: Vec2::new(400.0, 300.0),
+///     };
+///     pointers.move_to(bot.0, location);
+///     pointers.press(bot.0, PointerButton::Primary);
+///     pointers.release(bot.0, PointerButton::Primary);
+/// }
+/// ```
+#[derive(SystemParam)]
+pub struct VirtualPointers<'w, 's> {
+    commands: Commands<'w, 's>,
+    pointers: Query<'w, 's, (&'static PointerId, &'static mut PointerLocation)>,
+    inputs: MessageWriter<'w, PointerInput>,
+}
+
+impl VirtualPointers<'_, '_> {
+    /// Spawns a virtual pointer with a new random [`PointerId::Custom`] id, and returns the id.
+    pub fn spawn(&mut self) -> PointerId {
+        let pointer_id = PointerId::Custom(Uuid::new_v4());
+        self.commands.spawn(pointer_id);
+        pointer_id
+    }
+
+    /// Moves the pointer to `location`.
+    pub fn move_to(&mut self, pointer_id: PointerId, location: Location) {
+        let delta = match self.pointer_location(pointer_id) {
+            Some(mut pointer_location) => {
+                let delta = pointer_location
+                    .location()
+                    .filter(|previous| previous.target == location.target)
+                    .map_or(Vec2::ZERO, |previous| location.position - previous.position);
+                // Updated right away, so that buttons can be pressed at the new location in the
+                // same frame.
+                pointer_location.location = Some(location.clone());
+                delta
+            }
+            None => Vec2::ZERO,
+        };
+        self.inputs.write(PointerInput::new(
+            pointer_id,
+            location,
+            PointerAction::Move { delta },
+        ));
+    }
+
+    /// Presses `button` at the current location of the pointer.
+    ///
+    /// Returns `false`, without pressing, if the pointer has no location yet.
+    pub fn press(&mut self, pointer_id: PointerId, button: PointerButton) -> bool {
+        self.act(pointer_id, PointerAction::Press(button))
+    }
+
+    /// Releases `button` at the current location of the pointer.
+    ///
+    /// Returns `false`, without releasing, if the pointer has no location yet.
+    pub fn release(&mut self, pointer_id: PointerId, button: PointerButton) -> bool {
+        self.act(pointer_id, PointerAction::Release(button))
+    }
+
+    fn act(&mut self, pointer_id: PointerId, action: PointerAction) -> bool {
+        let Some(location) = self
+            .pointer_location(pointer_id)
+            .and_then(|pointer_location| pointer_location.location.clone())
+        else {
+            return false;
+        };
+        self.inputs
+            .write(PointerInput::new(pointer_id, location, action));
+        true
+    }
+
+    fn pointer_location(&mut self, pointer_id: PointerId) -> Option<Mut<'_, PointerLocation>> {
+        self.pointers
+            .iter_mut()
+            .find(|(id, _)| **id == pointer_id)
+            .map(|(_, location)| location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, PreUpdate};
+    use bevy_camera::Camera;
+
+    use super::*;
+    use crate::{
+        backend::PointerHits,
+        events::{Click, Over, Pointer, Press},
+        InteractionPlugin, PickingPlugin, PickingSystems,
+    };
+
+    #[derive(Resource)]
+    struct Button(Entity);
+
+    /// A backend that hits the button wherever a pointer is to the left of `x = 100`.
+    fn hit_button(
+        button: Res<Button>,
+        cameras: Query<Entity, With<Camera>>,
+        pointers: Query<(&PointerId, &PointerLocation)>,
+        mut hits: MessageWriter<PointerHits>,
+    ) {
+        let camera = cameras.single().unwrap();
+        for (pointer_id, pointer_location) in &pointers {
+            if pointer_location
+                .location()
+                .is_some_and(|location| location.position.x < 100.0)
+            {
+                hits.write(PointerHits::new(
+                    *pointer_id,
+                    vec![(button.0, HitData::new(camera, 0.0, None, None))],
+                    0.0,
+                ));
+            }
+        }
+    }
+
+    #[derive(Resource, Default)]
+    struct Received(Vec<&'static str>);
+
+    #[test]
+    fn virtual_pointer_drives_picking_events() {
+        let mut app = App::new();
+        app.add_plugins((PickingPlugin, InteractionPlugin))
+            .init_resource::<Received>()
+            .add_systems(PreUpdate, hit_button.in_set(PickingSystems::Backend));
+        let world = app.world_mut();
+        world.spawn(Camera::default());
+        let button = world
+            .spawn_empty()
+            .observe(|_: On<Pointer<Over>>, mut received: ResMut<Received>| {
+                received.0.push("over");
+            })
+            .observe(|_: On<Pointer<Press>>, mut received: ResMut<Received>| {
+                received.0.push("press");
+            })
+            .observe(|_: On<Pointer<Click>>, mut received: ResMut<Received>| {
+                received.0.push("click");
+            })
+            .id();
+        world.insert_resource(Button(button));
+
+        let pointer_id = world
+            .run_system_cached(|mut pointers: VirtualPointers| pointers.spawn())
+            .unwrap();
+        assert!(pointer_id.is_custom());
+        app.update();
+
+        let drive = move |app: &mut App, x: f32, button: Option<PointerAction>| {
+            app.world_mut()
+                .run_system_cached_with(
+                    |In((pointer_id, x, action)): In<(PointerId, f32, Option<PointerAction>)>,
+                     mut pointers: VirtualPointers| {
+                        pointers.move_to(
+                            pointer_id,
+                            Location {
+                                target: NormalizedRenderTarget::None {
+                                    width: 800,
+                                    height: 600,
+                                },
+                                position: Vec2::new(x, 0.0),
+                            },
+                        );
+                        match action {
+                            Some(PointerAction::Press(button)) => {
+                                assert!(pointers.press(pointer_id, button));
+                            }
+                            Some(PointerAction::Release(button)) => {
+                                assert!(pointers.release(pointer_id, button));
+                            }
+                            _ => {}
+                        }
+                    },
+                    (pointer_id, x, button),
+                )
+                .unwrap();
+            app.update();
+        };
+
+        // Away from the button, nothing happens.
+        drive(&mut app, 200.0, None);
+        assert!(app.world().resource::<Received>().0.is_empty());
+
+        drive(&mut app, 50.0, None);
+        drive(
+            &mut app,
+            50.0,
+            Some(PointerAction::Press(PointerButton::Primary)),
+        );
+        drive(
+            &mut app,
+            50.0,
+            Some(PointerAction::Release(PointerButton::Primary)),
+        );
+        assert_eq!(
+            app.world().resource::<Received>().0,
+            vec!["over", "press", "click"]
+        );
+    }
+
+    #[derive(Resource, Default)]
+    struct Kinds(Vec<PointerKind>);
+
+    #[test]
+    fn events_report_the_kind_of_their_pointer() {
+        let mut app = App::new();
+        app.add_plugins((PickingPlugin, InteractionPlugin))
+            .init_resource::<Kinds>()
+            .init_resource::<Received>()
+            .add_systems(PreUpdate, hit_button.in_set(PickingSystems::Backend))
+            .add_systems(
+                PreUpdate,
+                (|mut received: ResMut<Received>| received.0.push("touching"))
+                    .run_if(PointerFilter::exists(PointerKind::Touch)),
+            );
+        let world = app.world_mut();
+        world.spawn(Camera::default());
+        let button = world
+            .spawn_empty()
+            .observe(|press: On<Pointer<Press>>, mut kinds: ResMut<Kinds>| {
+                kinds.0.push(press.pointer_kind());
+            })
+            .id();
+        world.insert_resource(Button(button));
+        let location = Location {
+            target: NormalizedRenderTarget::None {
+                width: 800,
+                height: 600,
+            },
+            position: Vec2::new(50.0, 0.0),
+        };
+        world.spawn((PointerId::Mouse, PointerLocation::new(location.clone())));
+        app.update();
+        assert!(app.world().resource::<Received>().0.is_empty());
+
+        let touch = app
+            .world_mut()
+            .spawn((PointerId::Touch(0), PointerLocation::new(location.clone())))
+            .id();
+        for pointer_id in [PointerId::Mouse, PointerId::Touch(0)] {
+            app.world_mut().write_message(PointerInput::new(
+                pointer_id,
+                location.clone(),
+                PointerAction::Press(PointerButton::Primary),
+            ));
+        }
+        app.update();
+        let mut kinds = app.world().resource::<Kinds>().0.clone();
+        kinds.sort_by_key(|kind| *kind as u8);
+        assert_eq!(kinds, vec![PointerKind::Mouse, PointerKind::Touch]);
+        assert_eq!(app.world().resource::<Received>().0, vec!["touching"]);
+
+        app.world_mut().despawn(touch);
+        app.update();
+        assert_eq!(app.world().resource::<Received>().0, vec!["touching"]);
+    }
+}
@@ -81,5 +81,8 @@ pub use bevy_material::{
         ComputePipelineDescriptor, FragmentState, PipelineDescriptor, RenderPipelineDescriptor,
         VertexState,
     },
-    specialize::SpecializedMeshPipelineError,
+    specialize::{
+        ColorTargetCountMismatchError, ColorTargetFormatMismatchError, DepthFormatMismatchError,
+        SpecializedMeshPipelineError, StorageBuffersUnsupportedError,
+    },
 };
@@ -17,14 +17,20 @@ use bevy_ecs::{
 };
 use bevy_log::error;
 use bevy_platform::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_platform::hash::FixedHasher;
 use bevy_shader::{
     CachedPipelineId, Shader, ShaderCache, ShaderCacheError, ShaderCacheSource, ShaderDefVal,
     ValidateShader,
 };
 use bevy_tasks::Task;
 use bevy_utils::default;
+#[cfg(not(target_arch = "wasm32"))]
+use core::hash::BuildHasher;
 use core::{future::Future, mem};
 use std::sync::{Mutex, PoisonError};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, path::Path};
 use wgpu::{PipelineCompilationOptions, VertexBufferLayout as RawVertexBufferLayout};
 
 /// A pipeline defining the data layout and shader logic for a specific GPU task.
@@ -208,6 +214,8 @@ pub struct PipelineCache {
     waiting_pipelines: HashSet<CachedPipelineId>,
     new_pipelines: Mutex<Vec<CachedPipeline>>,
     global_shader_defs: Vec<ShaderDefVal>,
+    /// The driver-level cache pipelines are compiled with, if any.
+    driver_cache: Option<Arc<WgpuWrapper<wgpu::PipelineCache>>>,
     /// If `true`, disables asynchronous pipeline compilation.
     /// This has no effect on macOS, wasm, or without the `multi_threaded` feature.
     synchronous_pipeline_compilation: bool,
@@ -260,10 +268,78 @@ impl PipelineCache {
             new_pipelines: default(),
             pipelines: default(),
             global_shader_defs,
+            driver_cache: None,
             synchronous_pipeline_compilation,
         }
     }
 
+    /// Sets the driver-level cache that pipelines created from now on are compiled with.
+    ///
+    /// Drivers look up compiled pipelines in this cache and add the ones they compile to it, so
+    /// seeding it with the [data](PipelineCache::driver_cache_data) of a previous run lets them skip
+    /// most of the compilation. See [`RenderDevice::create_pipeline_cache`].
+    pub fn set_driver_cache(&mut self, cache: wgpu::PipelineCache) {
+        self.driver_cache = Some(Arc::new(WgpuWrapper::new(cache)));
+    }
+
+    /// Returns the current contents of the driver-level cache, to be stored for the next run.
+    ///
+    /// Returns `None` if no cache was [set](PipelineCache::set_driver_cache) or the backend
+    /// doesn't support retrieving its data.
+    pub fn driver_cache_data(&self) -> Option<Vec<u8>> {
+        self.driver_cache.as_ref()?.get_data()
+    }
+
+    /// Seeds the driver-level cache with the file written to `path` by
+    /// [`PipelineCache::save_driver_cache`], and compiles pipelines with it from now on.
+    ///
+    /// A missing, corrupt, or outdated file starts an empty cache instead. Returns `false`, and
+    /// leaves the pipeline cache unchanged, if the device doesn't support driver-level caches.
+    ///
+    /// Caches are only valid for the adapter they were created on, so `path` should include the
+    /// adapter's [`pipeline_cache_key`](crate::renderer::RenderAdapterInfo::pipeline_cache_key).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_driver_cache(&mut self, path: &Path) -> bool {
+        let bytes = fs::read(path).ok();
+        let data = bytes.as_deref().and_then(decode_driver_cache);
+        // SAFETY: The checksum of `data` matches the one written by `save_driver_cache`, so it is
+        // the unmodified output of `get_data`. wgpu checks that it belongs to this adapter.
+        let Some(cache) = (unsafe { self.device.create_pipeline_cache(data) }) else {
+            return false;
+        };
+        self.set_driver_cache(cache);
+        true
+    }
+
+    /// Writes the current contents of the driver-level cache to `path`, to be loaded with
+    /// [`PipelineCache::load_driver_cache`] on the next run.
+    ///
+    /// Returns `Ok(false)` if there is no data to write.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_driver_cache(&self, path: &Path) -> std::io::Result<bool> {
+        let Some(data) = self.driver_cache_data() else {
+            return Ok(false);
+        };
+        Self::write_driver_cache(path, &data)?;
+        Ok(true)
+    }
+
+    /// Writes `data`, as returned by [`PipelineCache::driver_cache_data`], to `path`, to be loaded
+    /// with [`PipelineCache::load_driver_cache`] on the next run.
+    ///
+    /// Unlike [`PipelineCache::save_driver_cache`], this doesn't need access to the pipeline
+    /// cache, so it can run on another thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_driver_cache(path: &Path, data: &[u8]) -> std::io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        // Write to a temporary file first, so that a crash can't leave a truncated cache behind.
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, encode_driver_cache(data))?;
+        fs::rename(&temporary, path)
+    }
+
     /// Get the state of a cached render pipeline.
     ///
     /// See [`PipelineCache::queue_render_pipeline()`].
@@ -466,6 +542,7 @@ impl PipelineCache {
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let driver_cache = self.driver_cache.clone();
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -561,7 +638,7 @@ impl PipelineCache {
                             // TODO: Should this be the same as the vertex compilation options?
                             compilation_options,
                         }),
-                    cache: None,
+                    cache: driver_cache.as_deref().map(|cache| &**cache),
                 };
 
                 Ok(Pipeline::RenderPipeline(
@@ -580,6 +657,7 @@ impl PipelineCache {
         let device = self.device.clone();
         let shader_cache = self.shader_cache.clone();
         let layout_cache = self.layout_cache.clone();
+        let driver_cache = self.driver_cache.clone();
         let mut bindgroup_layout_cache = self.bindgroup_layout_cache.lock().unwrap();
         let bind_group_layout = descriptor
             .layout
@@ -623,7 +701,7 @@ impl PipelineCache {
                         zero_initialize_workgroup_memory: descriptor
                             .zero_initialize_workgroup_memory,
                     },
-                    cache: None,
+                    cache: driver_cache.as_deref().map(|cache| &**cache),
                 };
 
                 Ok(Pipeline::ComputePipeline(
@@ -793,6 +871,28 @@ fn pipeline_error_context(cached_pipeline: &CachedPipeline) -> String {
     }
 }
 
+/// Identifies the files written by [`PipelineCache::save_driver_cache`].
+#[cfg(not(target_arch = "wasm32"))]
+const DRIVER_CACHE_MAGIC: &[u8; 8] = b"BEVYPLC1";
+
+/// Prefixes driver cache data with a checksum, so that it can be verified when loaded.
+#[cfg(not(target_arch = "wasm32"))]
+fn encode_driver_cache(data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(DRIVER_CACHE_MAGIC.len() + 8 + data.len());
+    bytes.extend_from_slice(DRIVER_CACHE_MAGIC);
+    bytes.extend_from_slice(&FixedHasher.hash_one(data).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+/// Returns the driver cache data encoded with [`encode_driver_cache`], if its checksum matches.
+#[cfg(not(target_arch = "wasm32"))]
+fn decode_driver_cache(bytes: &[u8]) -> Option<&[u8]> {
+    let bytes = bytes.strip_prefix(DRIVER_CACHE_MAGIC)?;
+    let (checksum, data) = bytes.split_first_chunk::<8>()?;
+    (u64::from_le_bytes(*checksum) == FixedHasher.hash_one(data)).then_some(data)
+}
+
 #[cfg(all(
     not(target_arch = "wasm32"),
     not(target_os = "macos"),
@@ -211,9 +211,19 @@ impl<S: SpecializedMeshPipeline> SpecializedMeshPipelines<S> {
             let descriptor = specialize_pipeline
                 .specialize(key.clone(), layout)
                 .map_err(|mut err| {
-                    {
-                        let SpecializedMeshPipelineError::MissingVertexAttribute(err) = &mut err;
-                        err.pipeline_type = Some(core::any::type_name::<S>());
+                    match &mut err {
+                        SpecializedMeshPipelineError::MissingVertexAttribute(err) => {
+                            err.pipeline_type = Some(core::any::type_name::<S>());
+                        }
+                        SpecializedMeshPipelineError::ColorTargetFormatMismatch(err) => {
+                            err.pipeline_type = Some(core::any::type_name::<S>());
+                        }
+                        SpecializedMeshPipelineError::ColorTargetCountMismatch(err) => {
+                            err.pipeline_type = Some(core::any::type_name::<S>());
+                        }
+                        SpecializedMeshPipelineError::StorageBuffersUnsupported(err) => {
+                            err.pipeline_type = Some(core::any::type_name::<S>());
+                        }
                     }
                     err
                 })?;
@@ -126,6 +126,16 @@ pub struct RenderInstance(pub Arc<WgpuWrapper<Instance>>);
 #[derive(Resource, Clone, Deref, DerefMut)]
 pub struct RenderAdapterInfo(pub WgpuWrapper<AdapterInfo>);
 
+impl RenderAdapterInfo {
+    /// A name identifying the driver of this adapter, to keep the driver-level pipeline caches of
+    /// different adapters apart. Returns `None` if the backend doesn't support pipeline caches.
+    ///
+    /// See [`RenderDevice::create_pipeline_cache`].
+    pub fn pipeline_cache_key(&self) -> Option<String> {
+        wgpu::util::pipeline_cache_key(&self.0)
+    }
+}
+
 const GPU_NOT_FOUND_ERROR_MESSAGE: &str = if cfg!(target_os = "linux") {
     "Unable to find a GPU! Make sure you have installed required drivers! For extra information, see: https://github.com/bevyengine/bevy/blob/latest/docs/linux_dependencies.md"
 } else {
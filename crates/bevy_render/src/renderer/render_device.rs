@@ -245,6 +245,30 @@ impl RenderDevice {
         Sampler::from(wgpu_sampler)
     }
 
+    /// Creates a driver-level [`wgpu::PipelineCache`], seeded with `data` from a previous run.
+    ///
+    /// Returns `None` if the device doesn't support [`wgpu::Features::PIPELINE_CACHE`]. Invalid or
+    /// outdated `data` is discarded, and an empty cache is created instead.
+    ///
+    /// # Safety
+    ///
+    /// `data` must have been returned by [`wgpu::PipelineCache::get_data`], if not `None`. See
+    /// [`wgpu::util::pipeline_cache_key`] for how to keep caches of different adapters apart.
+    pub unsafe fn create_pipeline_cache(&self, data: Option<&[u8]>) -> Option<wgpu::PipelineCache> {
+        if !self.features().contains(wgpu::Features::PIPELINE_CACHE) {
+            return None;
+        }
+        // SAFETY: The caller guarantees that `data` was produced by `get_data`.
+        Some(unsafe {
+            self.device
+                .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: Some("pipeline_cache"),
+                    data,
+                    fallback: true,
+                })
+        })
+    }
+
     /// Initializes [`Surface`](wgpu::Surface) for presentation.
     ///
     /// # Panics
@@ -35,7 +35,7 @@ pub use tilemap_chunk::*;
 
 use bevy_app::prelude::*;
 use bevy_asset::{embedded_asset, AssetEventSystems};
-use bevy_core_pipeline::core_2d::{AlphaMask2d, Opaque2d, Transparent2d};
+use bevy_core_pipeline::core_2d::{AlphaMask2d, Opaque2d, Prepass2d, Transparent2d};
 use bevy_ecs::prelude::*;
 use bevy_image::{prelude::*, TextureAtlasPlugin};
 use bevy_mesh::Mesh2d;
@@ -118,6 +118,7 @@ impl Plugin for SpriteRenderPlugin {
                         prepare_sprite_view_bind_groups.in_set(RenderSystems::PrepareBindGroups),
                         sort_binned_render_phase::<Opaque2d>.in_set(RenderSystems::PhaseSort),
                         sort_binned_render_phase::<AlphaMask2d>.in_set(RenderSystems::PhaseSort),
+                        sort_binned_render_phase::<Prepass2d>.in_set(RenderSystems::PhaseSort),
                     ),
                 );
         };
@@ -38,6 +38,11 @@ impl Plugin for ColorMaterialPlugin {
 #[uniform(0, ColorMaterialUniform)]
 pub struct ColorMaterial {
     pub color: Color,
+    /// Color added on top of the shaded color, after alpha handling.
+    ///
+    /// Channel values above `1.0` are not clamped, so in an HDR view they will feed
+    /// effects like bloom. Defaults to [`LinearRgba::BLACK`], which adds nothing.
+    pub emissive: LinearRgba,
     pub alpha_mode: AlphaMode2d,
     pub uv_transform: Affine2,
     #[texture(1)]
@@ -56,6 +61,7 @@ impl Default for ColorMaterial {
     fn default() -> Self {
         ColorMaterial {
             color: Color::WHITE,
+            emissive: LinearRgba::BLACK,
             uv_transform: Affine2::default(),
             texture: None,
             // TODO should probably default to AlphaMask once supported?
@@ -113,6 +119,7 @@ impl ColorMaterialFlags {
 #[derive(Clone, Default, ShaderType)]
 pub struct ColorMaterialUniform {
     pub color: Vec4,
+    pub emissive: Vec4,
     pub uv_transform: Mat3,
     pub flags: u32,
     pub alpha_cutoff: f32,
@@ -133,10 +140,16 @@ impl AsBindGroupShaderType<ColorMaterialUniform> for ColorMaterial {
                 alpha_cutoff = c;
                 flags |= ColorMaterialFlags::ALPHA_MODE_MASK;
             }
-            AlphaMode2d::Blend => flags |= ColorMaterialFlags::ALPHA_MODE_BLEND,
+            AlphaMode2d::Blend
+            | AlphaMode2d::Premultiplied
+            | AlphaMode2d::Additive
+            | AlphaMode2d::Multiply => {
+                flags |= ColorMaterialFlags::ALPHA_MODE_BLEND;
+            }
         };
         ColorMaterialUniform {
             color: LinearRgba::from(self.color).to_f32_array().into(),
+            emissive: self.emissive.to_f32_array().into(),
             uv_transform: self.uv_transform.into(),
             flags: flags.bits(),
             alpha_cutoff,
@@ -155,3 +168,31 @@ impl Material2d for ColorMaterial {
         self.alpha_mode
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::Vec3;
+
+    #[test]
+    fn emissive_is_written_to_uniform_unclamped() {
+        let material = ColorMaterial {
+            color: Color::WHITE,
+            emissive: LinearRgba::rgb(4.0, 2.0, 0.0),
+            ..Default::default()
+        };
+        let uniform: ColorMaterialUniform =
+            material.as_bind_group_shader_type(&RenderAssets::<GpuImage>::default());
+
+        assert_eq!(uniform.color, Vec4::ONE);
+        assert_eq!(uniform.emissive, Vec4::new(4.0, 2.0, 0.0, 1.0));
+        assert!(uniform.emissive.max_element() > uniform.color.max_element());
+    }
+
+    #[test]
+    fn default_emissive_adds_nothing() {
+        let uniform: ColorMaterialUniform = ColorMaterial::default()
+            .as_bind_group_shader_type(&RenderAssets::<GpuImage>::default());
+        assert_eq!(uniform.emissive.truncate(), Vec3::ZERO);
+    }
+}
@@ -1,5 +1,5 @@
 #import bevy_sprite::{
-    mesh2d_vertex_output::VertexOutput,
+    mesh2d_vertex_output::{VertexOutput, FragmentOutput},
     mesh2d_view_bindings::view,
 }
 
@@ -9,6 +9,7 @@
 
 struct ColorMaterial {
     color: vec4<f32>,
+    emissive: vec4<f32>,
     uv_transform: mat3x3<f32>,
     // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
     flags: u32,
@@ -28,12 +29,15 @@ const COLOR_MATERIAL_FLAGS_ALPHA_MODE_BLEND: u32         = 2147483648u; // (2u32
 @fragment
 fn fragment(
     mesh: VertexOutput,
-) -> @location(0) vec4<f32> {
+) -> FragmentOutput {
     var output_color: vec4<f32> = material.color;
 
 #ifdef VERTEX_COLORS
     output_color = output_color * mesh.color;
 #endif
+#ifdef MESH2D_TINT
+    output_color = output_color * mesh.tint;
+#endif
 
     let uv = (material.uv_transform * vec3(mesh.uv, 1.0)).xy;
 
@@ -43,10 +47,16 @@ fn fragment(
 
     output_color = alpha_discard(material, output_color);
 
+    // Emissive is added unclamped so HDR views can pick it up for bloom.
+    output_color = vec4(output_color.rgb + material.emissive.rgb, output_color.a);
+
 #ifdef TONEMAP_IN_SHADER
     output_color = tonemapping::tone_mapping(output_color, view.color_grading);
 #endif
-    return output_color;
+
+    var out: FragmentOutput;
+    out.color = output_color;
+    return out;
 }
 
 fn alpha_discard(material: ColorMaterial, output_color: vec4<f32>) -> vec4<f32> {
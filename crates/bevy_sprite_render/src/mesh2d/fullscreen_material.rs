@@ -0,0 +1,651 @@
+use crate::{
+    alpha_mode_blend_state, alpha_mode_pipeline_key, replace_color_target_states,
+    validate_color_target_formats, Material2d, Material2dKey, Material2dPipeline,
+    Mesh2dPipelineKey, PreparedMaterial2d, SetMesh2dViewBindGroup, ViewKeyCache,
+};
+use bevy_app::{App, Plugin};
+use bevy_asset::{AsAssetId, AssetId, Handle};
+use bevy_camera::visibility::{InheritedVisibility, Visibility};
+use bevy_core_pipeline::{core_2d::Transparent2d, FullscreenShader};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::SRes, SystemParamItem},
+};
+use bevy_image::BevyDefault;
+use bevy_math::FloatOrd;
+use bevy_mesh::{MeshVertexBufferLayout, MeshVertexBufferLayoutRef, MeshVertexBufferLayouts};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::{prelude::ReflectDefault, Reflect};
+use bevy_render::{
+    render_asset::{prepare_assets, RenderAssets},
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+        RenderCommandResult, SetItemPipeline, TrackedRenderPass, ViewSortedRenderPhases,
+    },
+    render_resource::{
+        CachedRenderPipelineId, ColorTargetState, ColorWrites, CompareFunction, DepthStencilState,
+        FragmentState, MultisampleState, PipelineCache, RenderPipelineDescriptor,
+        SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        TextureFormat,
+    },
+    sync_world::{MainEntity, MainEntityHashMap, RenderEntity, SyncToRenderWorld},
+    view::{ExtractedView, ViewTarget},
+    Extract, ExtractSchedule, Render, RenderApp, RenderStartup, RenderSystems,
+};
+use bevy_shader::ShaderDefVal;
+use core::{hash::Hash, marker::PhantomData};
+use derive_more::derive::From;
+use tracing::{error, warn};
+
+/// The bind group index of the material in a [`FullscreenMaterial2d`] draw.
+///
+/// Fullscreen draws have no mesh bind group, so the material directly follows the view. The
+/// `MATERIAL_BIND_GROUP` shader def is set to this index.
+pub const FULLSCREEN_MATERIAL_2D_BIND_GROUP_INDEX: usize = 1;
+
+/// Draws a [`Material2d`] over the whole viewport of every 2D camera, without a mesh.
+///
+/// A single triangle covering the viewport is generated in the vertex shader, so no vertex
+/// buffer is bound. The material's fragment shader receives a
+/// `bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput` and the `FULLSCREEN_MATERIAL2D`
+/// shader def is set. Materials without a [`Material2d::fragment_shader`] are not drawn.
+///
+/// The draw is queued once per view in the [`Transparent2d`] phase and sorted among the other
+/// transparent items by [`Material2d::depth_bias`], as if it were a mesh at `z = 0`.
+///
+/// Requires the [`FullscreenMaterial2dPlugin`] for the material type.
+#[derive(Component, Clone, Debug, Deref, DerefMut, Reflect, From)]
+#[reflect(Component, Default, Clone)]
+#[require(Visibility, SyncToRenderWorld)]
+pub struct FullscreenMaterial2d<M: Material2d>(pub Handle<M>);
+
+impl<M: Material2d> Default for FullscreenMaterial2d<M> {
+    fn default() -> Self {
+        Self(Handle::default())
+    }
+}
+
+impl<M: Material2d> AsAssetId for FullscreenMaterial2d<M> {
+    type Asset = M;
+
+    fn as_asset_id(&self) -> AssetId<Self::Asset> {
+        self.id()
+    }
+}
+
+/// Adds support for drawing the given [`Material2d`] with [`FullscreenMaterial2d`].
+///
+/// The [`Material2dPlugin`](crate::Material2dPlugin) for the material must be added as well.
+pub struct FullscreenMaterial2dPlugin<M: Material2d>(PhantomData<M>);
+
+impl<M: Material2d> Default for FullscreenMaterial2dPlugin<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<M: Material2d> Plugin for FullscreenMaterial2dPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.register_type::<FullscreenMaterial2d<M>>();
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .add_render_command::<Transparent2d, DrawFullscreenMaterial2d<M>>()
+                .init_resource::<RenderFullscreenMaterial2dInstances<M>>()
+                .init_resource::<SpecializedFullscreenMaterial2dDraws<M>>()
+                .init_resource::<SpecializedMeshPipelines<FullscreenMaterial2dPipeline<M>>>()
+                .add_systems(
+                    RenderStartup,
+                    init_fullscreen_material_2d_pipeline::<M>
+                        .after(crate::init_material_2d_pipeline::<M>),
+                )
+                .add_systems(ExtractSchedule, extract_fullscreen_materials_2d::<M>)
+                .add_systems(
+                    Render,
+                    (
+                        specialize_fullscreen_materials_2d::<M>
+                            .in_set(RenderSystems::PrepareMeshes)
+                            .after(prepare_assets::<PreparedMaterial2d<M>>),
+                        queue_fullscreen_materials_2d::<M>.in_set(RenderSystems::QueueMeshes),
+                    ),
+                );
+        }
+    }
+}
+
+/// A [`FullscreenMaterial2d`] extracted to the render world.
+pub struct RenderFullscreenMaterial2dInstance<M: Material2d> {
+    /// The render world entity the draw is queued for.
+    pub render_entity: Entity,
+    /// The material to bind.
+    pub material: AssetId<M>,
+}
+
+/// The visible [`FullscreenMaterial2d`] entities, keyed by their main world entity.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RenderFullscreenMaterial2dInstances<M: Material2d>(
+    MainEntityHashMap<RenderFullscreenMaterial2dInstance<M>>,
+);
+
+impl<M: Material2d> Default for RenderFullscreenMaterial2dInstances<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+pub fn extract_fullscreen_materials_2d<M: Material2d>(
+    mut instances: ResMut<RenderFullscreenMaterial2dInstances<M>>,
+    query: Extract<
+        Query<(
+            Entity,
+            &RenderEntity,
+            &InheritedVisibility,
+            &FullscreenMaterial2d<M>,
+        )>,
+    >,
+) {
+    instances.clear();
+    for (entity, render_entity, visibility, material) in &query {
+        if !visibility.get() {
+            continue;
+        }
+        instances.insert(
+            entity.into(),
+            RenderFullscreenMaterial2dInstance {
+                render_entity: render_entity.id(),
+                material: material.id(),
+            },
+        );
+    }
+}
+
+/// Render pipeline data for drawing a [`Material2d`] with [`FullscreenMaterial2d`].
+#[derive(Resource)]
+pub struct FullscreenMaterial2dPipeline<M: Material2d> {
+    pub material2d_pipeline: Material2dPipeline<M>,
+    pub fullscreen_shader: FullscreenShader,
+    /// The layout handed to [`Material2d::specialize`]. It has no attributes, since fullscreen
+    /// draws don't bind a vertex buffer.
+    pub vertex_buffer_layout: MeshVertexBufferLayoutRef,
+}
+
+impl<M: Material2d> Clone for FullscreenMaterial2dPipeline<M> {
+    fn clone(&self) -> Self {
+        Self {
+            material2d_pipeline: self.material2d_pipeline.clone(),
+            fullscreen_shader: self.fullscreen_shader.clone(),
+            vertex_buffer_layout: self.vertex_buffer_layout.clone(),
+        }
+    }
+}
+
+impl<M: Material2d> SpecializedMeshPipeline for FullscreenMaterial2dPipeline<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    type Key = Material2dKey<M>;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayoutRef,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let material_bind_group = ShaderDefVal::UInt(
+            "MATERIAL_BIND_GROUP".into(),
+            FULLSCREEN_MATERIAL_2D_BIND_GROUP_INDEX as u32,
+        );
+        let mut shader_defs = vec!["FULLSCREEN_MATERIAL2D".into(), material_bind_group];
+        if key.mesh_key.contains(Mesh2dPipelineKey::MAY_DISCARD) {
+            shader_defs.push("MAY_DISCARD".into());
+        }
+
+        let mut vertex = self.fullscreen_shader.to_vertex_state();
+        vertex.shader_defs = shader_defs.clone();
+
+        let format = match key.mesh_key.contains(Mesh2dPipelineKey::HDR) {
+            true => ViewTarget::TEXTURE_FORMAT_HDR,
+            false => TextureFormat::bevy_default(),
+        };
+        let blend = key.blend_state.or(alpha_mode_blend_state(key.mesh_key));
+
+        let mut descriptor = RenderPipelineDescriptor {
+            label: Some("fullscreen_material2d_pipeline".into()),
+            layout: vec![
+                self.material2d_pipeline.mesh2d_pipeline.view_layout.clone(),
+                self.material2d_pipeline.material2d_layout.clone(),
+            ],
+            vertex,
+            fragment: Some(FragmentState {
+                shader: self
+                    .material2d_pipeline
+                    .fragment_shader
+                    .clone()
+                    .unwrap_or_default(),
+                shader_defs,
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend,
+                    write_mask: ColorWrites::ALL,
+                })],
+                ..Default::default()
+            }),
+            // Fullscreen draws cover the viewport regardless of what has been drawn before them.
+            depth_stencil: Some(DepthStencilState {
+                format: key.mesh_key.depth_format(),
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::Always,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: MultisampleState {
+                count: key.mesh_key.msaa_samples(),
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            ..Default::default()
+        };
+
+        // Like mesh draws, the draw writes to the extra color targets of the view as declared by
+        // the material.
+        replace_color_target_states(&mut descriptor, key.mesh_key, M::color_target_states())?;
+        validate_color_target_formats(&descriptor, M::color_target_formats())?;
+
+        M::specialize(&mut descriptor, layout, key)?;
+        Ok(descriptor)
+    }
+}
+
+pub fn init_fullscreen_material_2d_pipeline<M: Material2d>(
+    mut commands: Commands,
+    material2d_pipeline: Res<Material2dPipeline<M>>,
+    fullscreen_shader: Res<FullscreenShader>,
+    mut mesh_vertex_buffer_layouts: ResMut<MeshVertexBufferLayouts>,
+) {
+    if material2d_pipeline.fragment_shader.is_none() {
+        warn!(
+            "{} has no fragment shader, so it can't be drawn with `FullscreenMaterial2d`",
+            core::any::type_name::<M>()
+        );
+    }
+    commands.insert_resource(FullscreenMaterial2dPipeline::<M> {
+        material2d_pipeline: material2d_pipeline.clone(),
+        fullscreen_shader: fullscreen_shader.clone(),
+        vertex_buffer_layout: mesh_vertex_buffer_layouts
+            .insert(MeshVertexBufferLayout::new(Vec::new(), Default::default())),
+    });
+}
+
+/// A fullscreen draw specialized for a view, waiting to be queued.
+#[derive(Clone, Copy, Debug)]
+pub struct SpecializedFullscreenMaterial2dDraw {
+    pub entity: (Entity, MainEntity),
+    pub pipeline: CachedRenderPipelineId,
+    pub sort_key: FloatOrd,
+}
+
+/// The fullscreen draws of each view for the current frame, keyed by the view's main entity.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SpecializedFullscreenMaterial2dDraws<M> {
+    #[deref]
+    map: MainEntityHashMap<Vec<SpecializedFullscreenMaterial2dDraw>>,
+    marker: PhantomData<M>,
+}
+
+impl<M> Default for SpecializedFullscreenMaterial2dDraws<M> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+pub fn specialize_fullscreen_materials_2d<M: Material2d>(
+    fullscreen_pipeline: Res<FullscreenMaterial2dPipeline<M>>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<FullscreenMaterial2dPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_materials: Res<RenderAssets<PreparedMaterial2d<M>>>,
+    instances: Res<RenderFullscreenMaterial2dInstances<M>>,
+    views: Query<&MainEntity, With<ExtractedView>>,
+    view_key_cache: Res<ViewKeyCache>,
+    mut draws: ResMut<SpecializedFullscreenMaterial2dDraws<M>>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    draws.clear();
+    if instances.is_empty()
+        || fullscreen_pipeline
+            .material2d_pipeline
+            .fragment_shader
+            .is_none()
+    {
+        return;
+    }
+
+    for view_entity in &views {
+        let Some(view_key) = view_key_cache.get(view_entity) else {
+            continue;
+        };
+        for (main_entity, instance) in instances.iter() {
+            let Some(material_2d) = render_materials.get(instance.material) else {
+                continue;
+            };
+            let mut mesh_key =
+                *view_key | alpha_mode_pipeline_key(material_2d.properties.alpha_mode);
+            mesh_key.remove(Mesh2dPipelineKey::MOTION_VECTOR_PREPASS);
+
+            let pipeline_id = pipelines.specialize(
+                &pipeline_cache,
+                &fullscreen_pipeline,
+                Material2dKey {
+                    mesh_key,
+                    bind_group_data: material_2d.key.clone(),
+                    blend_state: material_2d.properties.blend_state,
+                    depth_write_enabled: material_2d.properties.depth_write_enabled,
+                    depth_compare: material_2d.properties.depth_compare,
+                },
+                &fullscreen_pipeline.vertex_buffer_layout,
+            );
+            let pipeline_id = match pipeline_id {
+                Ok(id) => id,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            };
+
+            draws
+                .entry(*view_entity)
+                .or_default()
+                .push(SpecializedFullscreenMaterial2dDraw {
+                    entity: (instance.render_entity, *main_entity),
+                    pipeline: pipeline_id,
+                    sort_key: FloatOrd(material_2d.properties.depth_bias),
+                });
+        }
+    }
+}
+
+/// Queues one [`Transparent2d`] item per view for each specialized fullscreen draw.
+pub fn queue_fullscreen_materials_2d<M: Material2d>(
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    draws: Res<SpecializedFullscreenMaterial2dDraws<M>>,
+    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
+    views: Query<(&MainEntity, &ExtractedView)>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    if draws.is_empty() {
+        return;
+    }
+    let draw_function = draw_functions.read().id::<DrawFullscreenMaterial2d<M>>();
+
+    for (view_entity, view) in &views {
+        let Some(view_draws) = draws.get(view_entity) else {
+            continue;
+        };
+        let Some(transparent_phase) = transparent_render_phases.get_mut(&view.retained_view_entity)
+        else {
+            continue;
+        };
+        for draw in view_draws {
+            transparent_phase.add(Transparent2d {
+                entity: draw.entity,
+                draw_function,
+                pipeline: draw.pipeline,
+                sort_key: draw.sort_key,
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::None,
+                extracted_index: usize::MAX,
+                indexed: false,
+            });
+        }
+    }
+}
+
+pub type DrawFullscreenMaterial2d<M> = (
+    SetItemPipeline,
+    SetMesh2dViewBindGroup<0>,
+    SetFullscreenMaterial2dBindGroup<M, FULLSCREEN_MATERIAL_2D_BIND_GROUP_INDEX>,
+    DrawFullscreenTriangle,
+);
+
+pub struct SetFullscreenMaterial2dBindGroup<M: Material2d, const I: usize>(PhantomData<M>);
+impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
+    for SetFullscreenMaterial2dBindGroup<M, I>
+{
+    type Param = (
+        SRes<RenderAssets<PreparedMaterial2d<M>>>,
+        SRes<RenderFullscreenMaterial2dInstances<M>>,
+    );
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        _item_query: Option<()>,
+        (materials, instances): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let materials = materials.into_inner();
+        let Some(instance) = instances.into_inner().get(&item.main_entity()) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(material2d) = materials.get(instance.material) else {
+            return RenderCommandResult::Skip;
+        };
+        pass.set_bind_group(I, &material2d.bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Draws the triangle generated by the [`FullscreenShader`].
+pub struct DrawFullscreenTriangle;
+impl<P: PhaseItem> RenderCommand<P> for DrawFullscreenTriangle {
+    type Param = ();
+    type ViewQuery = ();
+    type ItemQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        _item_query: Option<()>,
+        _param: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.draw(0..3, 0..1);
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::TaskPoolPlugin;
+    use bevy_asset::{Asset, AssetApp, AssetPlugin};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_reflect::TypePath;
+    use bevy_render::{render_resource::AsBindGroup, view::RetainedViewEntity, MainWorld};
+    use bevy_shader::Shader;
+
+    use super::*;
+    use crate::mesh2d::{
+        material::tests::test_pipeline as test_material2d_pipeline, mesh::tests::test_view,
+    };
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct VignetteMaterial {}
+
+    impl Material2d for VignetteMaterial {}
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct NormalsMaterial {}
+
+    impl Material2d for NormalsMaterial {
+        fn color_target_states() -> Vec<Option<ColorTargetState>> {
+            [TextureFormat::bevy_default(), TextureFormat::Rgba16Float]
+                .map(|format| {
+                    Some(ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })
+                })
+                .to_vec()
+        }
+    }
+
+    fn test_pipeline<M: Material2d>() -> FullscreenMaterial2dPipeline<M> {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<Shader>()
+            .init_resource::<FullscreenShader>();
+        let mut material2d_pipeline = test_material2d_pipeline();
+        material2d_pipeline.fragment_shader = Some(Handle::default());
+        FullscreenMaterial2dPipeline {
+            material2d_pipeline,
+            fullscreen_shader: app.world().resource::<FullscreenShader>().clone(),
+            vertex_buffer_layout: MeshVertexBufferLayouts::default()
+                .insert(MeshVertexBufferLayout::new(Vec::new(), Default::default())),
+        }
+    }
+
+    #[test]
+    fn fullscreen_pipelines_match_the_view_attachments() {
+        let view_key = Mesh2dPipelineKey::from_depth_format(TextureFormat::Depth24PlusStencil8)
+            .unwrap()
+            | Mesh2dPipelineKey::from_extra_color_targets(1);
+
+        let pipeline = test_pipeline::<NormalsMaterial>();
+        let descriptor = pipeline
+            .specialize(
+                Material2dKey {
+                    mesh_key: view_key,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &pipeline.vertex_buffer_layout,
+            )
+            .unwrap();
+        assert_eq!(
+            descriptor.depth_stencil.unwrap().format,
+            TextureFormat::Depth24PlusStencil8
+        );
+        let formats: Vec<_> = descriptor
+            .fragment
+            .unwrap()
+            .targets
+            .into_iter()
+            .map(|target| target.unwrap().format)
+            .collect();
+        assert_eq!(
+            formats,
+            [TextureFormat::bevy_default(), TextureFormat::Rgba16Float]
+        );
+
+        // A material without targets for the extra attachments can't draw into the view.
+        let pipeline = test_pipeline::<VignetteMaterial>();
+        assert!(pipeline
+            .specialize(
+                Material2dKey {
+                    mesh_key: view_key,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &pipeline.vertex_buffer_layout,
+            )
+            .is_err());
+    }
+
+    fn spawn_view(world: &mut World) -> RetainedViewEntity {
+        let main_entity = MainEntity::from(world.spawn_empty().id());
+        let view = test_view(main_entity);
+        let retained_view_entity = view.retained_view_entity;
+        world.spawn((main_entity, view));
+        world
+            .resource_mut::<ViewSortedRenderPhases<Transparent2d>>()
+            .insert_or_clear(retained_view_entity);
+        retained_view_entity
+    }
+
+    #[test]
+    fn fullscreen_material_is_drawn_once_per_view() {
+        let mut render_app = App::new();
+        render_app
+            .init_resource::<DrawFunctions<Transparent2d>>()
+            .init_resource::<ViewSortedRenderPhases<Transparent2d>>()
+            .init_resource::<RenderFullscreenMaterial2dInstances<VignetteMaterial>>()
+            .init_resource::<SpecializedFullscreenMaterial2dDraws<VignetteMaterial>>()
+            .add_render_command::<Transparent2d, DrawFullscreenMaterial2d<VignetteMaterial>>();
+        let world = render_app.world_mut();
+
+        let material = Handle::<VignetteMaterial>::default();
+        let mut main_world = MainWorld::default();
+        let render_entity = world.spawn_empty().id();
+        let fullscreen = main_world
+            .spawn((
+                FullscreenMaterial2d(material.clone()),
+                RenderEntity::from(render_entity),
+                InheritedVisibility::VISIBLE,
+            ))
+            .id();
+        let hidden_render_entity = world.spawn_empty().id();
+        main_world.spawn((
+            FullscreenMaterial2d(material.clone()),
+            RenderEntity::from(hidden_render_entity),
+            InheritedVisibility::HIDDEN,
+        ));
+        world.insert_resource(main_world);
+        world
+            .run_system_once(extract_fullscreen_materials_2d::<VignetteMaterial>)
+            .unwrap();
+
+        let instances = world.resource::<RenderFullscreenMaterial2dInstances<VignetteMaterial>>();
+        assert_eq!(instances.len(), 1);
+        let instance = &instances[&MainEntity::from(fullscreen)];
+        assert_eq!(instance.render_entity, render_entity);
+        assert_eq!(instance.material, material.id());
+
+        let views = [spawn_view(world), spawn_view(world)];
+
+        // Stand in for `specialize_fullscreen_materials_2d`, which needs a `PipelineCache`.
+        let mut draws = SpecializedFullscreenMaterial2dDraws::<VignetteMaterial>::default();
+        for view in views {
+            draws.insert(
+                view.main_entity,
+                vec![SpecializedFullscreenMaterial2dDraw {
+                    entity: (render_entity, fullscreen.into()),
+                    pipeline: CachedRenderPipelineId::INVALID,
+                    sort_key: FloatOrd(0.0),
+                }],
+            );
+        }
+        world.insert_resource(draws);
+        world
+            .run_system_once(queue_fullscreen_materials_2d::<VignetteMaterial>)
+            .unwrap();
+
+        let draw_function = world
+            .resource::<DrawFunctions<Transparent2d>>()
+            .read()
+            .id::<DrawFullscreenMaterial2d<VignetteMaterial>>();
+        let phases = world.resource::<ViewSortedRenderPhases<Transparent2d>>();
+        for view in views {
+            let items = &phases[&view].items;
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].entity, (render_entity, fullscreen.into()));
+            assert_eq!(items[0].draw_function, draw_function);
+            assert!(!items[0].indexed);
+        }
+    }
+}
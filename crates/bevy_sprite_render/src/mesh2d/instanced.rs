@@ -0,0 +1,416 @@
+//! GPU instancing of the meshes of a [`Material2d`] whose [`Material2d::instanced`] returns
+//! `true`.
+//!
+//! Every frame, [`prepare_material2d_instances`] groups the visible meshes of each view that share
+//! a pipeline, a material bind group and a mesh, and writes one [`Mesh2dInstance`] per mesh into
+//! an instance-step vertex buffer. Only the first mesh of each group is queued, and
+//! [`DrawMeshInstanced2d`] draws the whole group with a single instanced draw call.
+
+use core::{marker::PhantomData, ops::Range};
+
+use bevy_asset::AssetId;
+use bevy_color::ColorToComponents;
+use bevy_ecs::{
+    prelude::*,
+    query::ROQueryItem,
+    system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+};
+use bevy_math::{Affine3Ext, FloatOrd, Vec4};
+use bevy_mesh::{Mesh, Mesh2d, VertexBufferLayout, VertexFormat};
+use bevy_platform::collections::HashMap;
+use bevy_render::{
+    mesh::{allocator::MeshAllocator, RenderMesh, RenderMeshBufferInfo},
+    render_asset::RenderAssets,
+    render_phase::{PhaseItem, RenderCommand, RenderCommandResult, TrackedRenderPass},
+    render_resource::{
+        BindGroupId, BufferUsages, CachedRenderPipelineId, RawBufferVec, VertexStepMode,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    sync_world::MainEntity,
+    view::{ExtractedView, RenderVisibleEntities, RetainedViewEntity},
+};
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    Material2d, PreparedMaterial2d, RenderMaterial2dInstances, RenderMesh2dInstance,
+    RenderMesh2dInstances, SpecializedMaterial2dPipelineCache,
+};
+
+/// The data of one instance of an instanced [`Material2d`], read by the vertex shader from the
+/// instance-step vertex buffer bound to slot 1.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Mesh2dInstance {
+    /// The affine world-from-local transform of the mesh, transposed to 3x4.
+    pub world_from_local: [Vec4; 3],
+    /// The linear [`Mesh2dTint`](crate::Mesh2dTint) of the mesh, white if it has none.
+    pub tint: Vec4,
+}
+
+impl Mesh2dInstance {
+    /// The shader location of the first per-instance attribute.
+    ///
+    /// The rows of [`world_from_local`](Self::world_from_local) are at this location and the two
+    /// following ones, and the [`tint`](Self::tint) at the one after them, leaving the lower
+    /// locations to the attributes of the mesh.
+    pub const FIRST_SHADER_LOCATION: u32 = 10;
+
+    /// Creates the instance data of a mesh.
+    pub fn new(mesh_instance: &RenderMesh2dInstance) -> Self {
+        Self {
+            world_from_local: mesh_instance.transforms.world_from_local.to_transpose(),
+            tint: mesh_instance.tint.to_vec4(),
+        }
+    }
+
+    /// Returns the layout of the instance-step vertex buffer holding the instances.
+    pub fn vertex_buffer_layout() -> VertexBufferLayout {
+        VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Instance,
+            [VertexFormat::Float32x4; 4],
+        )
+        .offset_locations_by(Self::FIRST_SHADER_LOCATION)
+    }
+}
+
+/// The key that the meshes of an instanced [`Material2d`] are grouped by within a view.
+///
+/// Meshes with [`NoAutomaticBatching`](bevy_render::batching::NoAutomaticBatching) are drawn on
+/// their own, keyed by their entity.
+type InstanceGroupKey = (
+    CachedRenderPipelineId,
+    Option<BindGroupId>,
+    AssetId<Mesh>,
+    Option<MainEntity>,
+);
+
+/// The instance buffer of an instanced [`Material2d`], and the range of instances drawn for each
+/// queued mesh.
+#[derive(Resource)]
+pub struct Material2dInstanceBuffer<M: Material2d> {
+    instances: RawBufferVec<Mesh2dInstance>,
+    /// The instances of the group each queued mesh stands for, by view.
+    batches: HashMap<(RetainedViewEntity, MainEntity), Range<u32>>,
+    marker: PhantomData<M>,
+}
+
+impl<M: Material2d> Default for Material2dInstanceBuffer<M> {
+    fn default() -> Self {
+        Self {
+            instances: RawBufferVec::new(BufferUsages::VERTEX),
+            batches: HashMap::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: Material2d> Material2dInstanceBuffer<M> {
+    /// Returns the range of instances drawn for `entity` in `view`, or `None` if the entity
+    /// isn't queued because another mesh of its group draws it.
+    pub fn batch(&self, view: RetainedViewEntity, entity: MainEntity) -> Option<Range<u32>> {
+        self.batches.get(&(view, entity)).cloned()
+    }
+
+    /// Forgets the instances of the previous frame.
+    fn clear(&mut self) {
+        self.instances.clear();
+        self.batches.clear();
+    }
+
+    /// Appends the instances of the meshes visible in `view`, one batch per group.
+    ///
+    /// The instances of a group are ordered back to front by their sort key, see
+    /// [`RenderMesh2dInstance::transparent_sort_key`], and the batch is attributed to the farthest
+    /// one, which is the only mesh of the group to be queued.
+    fn push_view(
+        &mut self,
+        view: RetainedViewEntity,
+        meshes: impl IntoIterator<Item = (InstanceGroupKey, MainEntity, FloatOrd, Mesh2dInstance)>,
+    ) {
+        let mut groups: HashMap<InstanceGroupKey, Vec<(FloatOrd, MainEntity, Mesh2dInstance)>> =
+            HashMap::default();
+        for (key, entity, sort_key, instance) in meshes {
+            groups
+                .entry(key)
+                .or_default()
+                .push((sort_key, entity, instance));
+        }
+        for mut group in groups.into_values() {
+            group.sort_unstable_by_key(|(sort_key, entity, _)| (*sort_key, *entity));
+            let start = self.instances.len() as u32;
+            for (_, _, instance) in &group {
+                self.instances.push(*instance);
+            }
+            let end = self.instances.len() as u32;
+            self.batches.insert((view, group[0].1), start..end);
+        }
+    }
+}
+
+/// Builds the [`Material2dInstanceBuffer`] of an instanced [`Material2d`] from the meshes visible
+/// in each view.
+///
+/// Does nothing for materials that aren't [`instanced`](Material2d::instanced).
+pub fn prepare_material2d_instances<M: Material2d>(
+    mut instance_buffer: ResMut<Material2dInstanceBuffer<M>>,
+    render_mesh_instances: Res<RenderMesh2dInstances>,
+    render_material_instances: Res<RenderMaterial2dInstances<M>>,
+    render_materials: Res<RenderAssets<PreparedMaterial2d<M>>>,
+    specialized_material_pipeline_cache: Res<SpecializedMaterial2dPipelineCache<M>>,
+    views: Query<(&MainEntity, &ExtractedView, &RenderVisibleEntities)>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    if !M::instanced() {
+        return;
+    }
+    instance_buffer.clear();
+
+    for (view_entity, view, visible_entities) in &views {
+        let Some(view_pipelines) = specialized_material_pipeline_cache.get(view_entity) else {
+            continue;
+        };
+        let meshes = visible_entities
+            .iter::<Mesh2d>()
+            .filter_map(|(_, visible_entity)| {
+                let (_, pipeline_id) = view_pipelines.get(visible_entity)?;
+                let material_asset_id = render_material_instances.get(visible_entity)?;
+                let material_2d = render_materials.get(*material_asset_id)?;
+                let mesh_instance = render_mesh_instances.get(visible_entity)?;
+                let key = (
+                    *pipeline_id,
+                    material_2d.get_bind_group_id().0,
+                    mesh_instance.mesh_asset_id,
+                    (!mesh_instance.automatic_batching).then_some(*visible_entity),
+                );
+                let sort_key =
+                    mesh_instance.transparent_sort_key(material_2d.properties.depth_bias);
+                Some((
+                    key,
+                    *visible_entity,
+                    sort_key,
+                    Mesh2dInstance::new(mesh_instance),
+                ))
+            });
+        instance_buffer.push_view(view.retained_view_entity, meshes);
+    }
+
+    instance_buffer
+        .instances
+        .write_buffer(&render_device, &render_queue);
+}
+
+/// Draws all the instances of the group that the phase item stands for, see
+/// [`Material2dInstanceBuffer`].
+pub struct DrawMeshInstanced2d<M>(PhantomData<M>);
+
+impl<P: PhaseItem, M: Material2d> RenderCommand<P> for DrawMeshInstanced2d<M> {
+    type Param = (
+        SRes<RenderAssets<RenderMesh>>,
+        SRes<RenderMesh2dInstances>,
+        SRes<MeshAllocator>,
+        SRes<Material2dInstanceBuffer<M>>,
+    );
+    type ViewQuery = Read<ExtractedView>;
+    type ItemQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        item: &P,
+        view: ROQueryItem<'w, '_, Self::ViewQuery>,
+        _item_query: Option<()>,
+        (meshes, render_mesh2d_instances, mesh_allocator, instance_buffer): SystemParamItem<
+            'w,
+            '_,
+            Self::Param,
+        >,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let meshes = meshes.into_inner();
+        let render_mesh2d_instances = render_mesh2d_instances.into_inner();
+        let mesh_allocator = mesh_allocator.into_inner();
+        let instance_buffer = instance_buffer.into_inner();
+
+        let Some(instances) = instance_buffer.batch(view.retained_view_entity, item.main_entity())
+        else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(buffer) = instance_buffer.instances.buffer() else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(RenderMesh2dInstance { mesh_asset_id, .. }) =
+            render_mesh2d_instances.get(&item.main_entity())
+        else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(gpu_mesh) = meshes.get(*mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+        let Some(vertex_buffer_slice) = mesh_allocator.mesh_vertex_slice(mesh_asset_id) else {
+            return RenderCommandResult::Skip;
+        };
+
+        pass.set_vertex_buffer(0, vertex_buffer_slice.buffer.slice(..));
+        pass.set_vertex_buffer(1, buffer.slice(..));
+
+        match &gpu_mesh.buffer_info {
+            RenderMeshBufferInfo::Indexed {
+                index_format,
+                count,
+            } => {
+                let Some(index_buffer_slice) = mesh_allocator.mesh_index_slice(mesh_asset_id)
+                else {
+                    return RenderCommandResult::Skip;
+                };
+
+                pass.set_index_buffer(index_buffer_slice.buffer.slice(..), *index_format);
+
+                pass.draw_indexed(
+                    index_buffer_slice.range.start..(index_buffer_slice.range.start + count),
+                    vertex_buffer_slice.range.start as i32,
+                    instances,
+                );
+            }
+            RenderMeshBufferInfo::NonIndexed => {
+                pass.draw(vertex_buffer_slice.range, instances);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_color::LinearRgba;
+    use bevy_ecs::entity::Entity;
+    use bevy_math::{Affine3, Vec3};
+
+    use super::*;
+    use crate::{
+        ColorMaterial, Material2dBindGroupId, Mesh2dTransforms, RenderLayer2dOrder, SortLayer2d,
+    };
+
+    fn mesh_instance(z: f32) -> RenderMesh2dInstance {
+        let world_from_local = Affine3::from_translation(Vec3::new(0.0, 0.0, z));
+        RenderMesh2dInstance {
+            transforms: Mesh2dTransforms {
+                world_from_local,
+                previous_world_from_local: world_from_local,
+                previous_world_from_local_index: 0,
+                flags: 0,
+            },
+            mesh_asset_id: AssetId::default(),
+            material_bind_group_id: Material2dBindGroupId::default(),
+            automatic_batching: true,
+            tag: 0,
+            tint: LinearRgba::WHITE,
+            order: RenderLayer2dOrder::default(),
+            sort_layer: None,
+        }
+    }
+
+    #[test]
+    fn meshes_sharing_a_material_are_drawn_in_one_batch() {
+        let view = RetainedViewEntity::new(MainEntity::from(Entity::PLACEHOLDER), None, 0);
+        let key = (
+            CachedRenderPipelineId::INVALID,
+            None,
+            AssetId::default(),
+            None,
+        );
+        let entity = |index: u32| MainEntity::from(Entity::from_raw_u32(index + 1).unwrap());
+        let mut buffer = Material2dInstanceBuffer::<ColorMaterial>::default();
+        buffer.push_view(
+            view,
+            (0..50_000).map(|index| {
+                // Spawned front to back, so that the buffer has to reorder them.
+                let mesh_instance = mesh_instance(-(index as f32));
+                let sort_key = mesh_instance.transparent_sort_key(0.0);
+                (
+                    key,
+                    entity(index),
+                    sort_key,
+                    Mesh2dInstance::new(&mesh_instance),
+                )
+            }),
+        );
+
+        // A single mesh is queued, drawing all the others.
+        assert_eq!(buffer.batches.len(), 1);
+        let queued = (0..50_000)
+            .filter(|&index| buffer.batch(view, entity(index)).is_some())
+            .count();
+        assert_eq!(queued, 1);
+        // The farthest mesh stands for the batch, which holds every instance.
+        assert_eq!(buffer.batch(view, entity(49_999)), Some(0..50_000));
+        assert_eq!(buffer.batch(view, entity(0)), None);
+        assert_eq!(buffer.instances.len(), 50_000);
+        let first = buffer.instances.values().first().unwrap();
+        let last = buffer.instances.values().last().unwrap();
+        assert_eq!(first.world_from_local[2].w, -49_999.0);
+        assert_eq!(last.world_from_local[2].w, 0.0);
+
+        // Meshes opting out of batching are drawn on their own.
+        buffer.clear();
+        let lone = (key.0, key.1, key.2, Some(entity(1)));
+        buffer.push_view(
+            view,
+            [key, lone].into_iter().enumerate().map(|(index, key)| {
+                let mesh_instance = mesh_instance(0.0);
+                let sort_key = mesh_instance.transparent_sort_key(0.0);
+                let entity = entity(index as u32);
+                (key, entity, sort_key, Mesh2dInstance::new(&mesh_instance))
+            }),
+        );
+        assert_eq!(buffer.batches.len(), 2);
+        assert_eq!(buffer.batch(view, entity(1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn instances_are_ordered_like_transparent_meshes() {
+        let view = RetainedViewEntity::new(MainEntity::from(Entity::PLACEHOLDER), None, 0);
+        let key = (
+            CachedRenderPipelineId::INVALID,
+            None,
+            AssetId::default(),
+            None,
+        );
+        let entity = |index: u32| MainEntity::from(Entity::from_raw_u32(index + 1).unwrap());
+
+        // Sorted by z, these would be drawn in reverse: the nearest mesh is drawn first because
+        // of its order, and the farthest one is drawn in front of the one at `z = 0` by its sort
+        // layer, which is itself drawn last because of its depth bias.
+        let mut ordered = mesh_instance(10.0);
+        ordered.order = RenderLayer2dOrder(-1);
+        let mut layered = mesh_instance(-10.0);
+        layered.sort_layer = Some(SortLayer2d::new(20, 0));
+        let biased = mesh_instance(0.0);
+        let meshes = [(ordered, 0.0), (layered, 0.0), (biased, 25.0)];
+
+        let mut buffer = Material2dInstanceBuffer::<ColorMaterial>::default();
+        buffer.push_view(
+            view,
+            meshes
+                .iter()
+                .enumerate()
+                .map(|(index, (mesh_instance, depth_bias))| {
+                    let sort_key = mesh_instance.transparent_sort_key(*depth_bias);
+                    let instance = Mesh2dInstance::new(mesh_instance);
+                    (key, entity(index as u32), sort_key, instance)
+                }),
+        );
+
+        assert_eq!(buffer.batches.len(), 1);
+        assert_eq!(buffer.batch(view, entity(0)), Some(0..3));
+        let z: Vec<_> = buffer
+            .instances
+            .values()
+            .iter()
+            .map(|instance| instance.world_from_local[2].w)
+            .collect();
+        assert_eq!(z, [10.0, -10.0, 0.0]);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{AlphaMode2d, Material2d, Material2dPlugin};
+use bevy_app::{App, Plugin};
+use bevy_asset::{embedded_asset, embedded_path, Asset, AssetApp, AssetPath, Handle};
+use bevy_color::{Color, ColorToComponents, LinearRgba};
+use bevy_image::Image;
+use bevy_math::{Affine2, Mat3, Vec4};
+use bevy_reflect::prelude::*;
+use bevy_render::{render_asset::RenderAssets, render_resource::*, texture::GpuImage};
+use bevy_shader::ShaderRef;
+
+/// Adds the [`MaskedMaterial2d`] material.
+///
+/// This plugin isn't added by the [`SpriteRenderPlugin`](crate::SpriteRenderPlugin), so that apps
+/// only pay for the materials they use.
+#[derive(Default)]
+pub struct MaskedMaterial2dPlugin;
+
+impl Plugin for MaskedMaterial2dPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "masked_material.wgsl");
+
+        app.add_plugins(Material2dPlugin::<MaskedMaterial2d>::default())
+            .register_asset_reflect::<MaskedMaterial2d>();
+    }
+}
+
+/// A [2d material](Material2d) that discards the fragments of a [2d mesh](crate::Mesh2d) where a
+/// grayscale mask texture is below a threshold.
+///
+/// This works like [`AlphaMode2d::Mask`], except that the red channel of [`mask`](Self::mask)
+/// is compared to [`threshold`](Self::threshold) instead of the alpha of the shaded color:
+/// fragments where the mask is below the threshold are discarded, and the others are rendered
+/// fully opaque. Animating the threshold from `0.0` to `1.0` over a gradient mask gives a wipe
+/// transition.
+///
+/// Requires the [`MaskedMaterial2dPlugin`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[reflect(Default, Debug, Clone)]
+#[uniform(0, MaskedMaterial2dUniform)]
+pub struct MaskedMaterial2d {
+    pub color: Color,
+    /// The mask value below which fragments are discarded, between `0.0` and `1.0`.
+    pub threshold: f32,
+    /// Transforms the mesh UVs used to sample both [`texture`](Self::texture) and
+    /// [`mask`](Self::mask).
+    pub uv_transform: Affine2,
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Option<Handle<Image>>,
+    /// The grayscale mask, sampled from its red channel. Without a mask, nothing is discarded.
+    #[texture(3)]
+    #[sampler(4)]
+    pub mask: Option<Handle<Image>>,
+}
+
+impl MaskedMaterial2d {
+    /// Creates a material discarding the fragments where `mask` is below `threshold`.
+    pub fn new(mask: Handle<Image>, threshold: f32) -> Self {
+        Self {
+            mask: Some(mask),
+            threshold,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for MaskedMaterial2d {
+    fn default() -> Self {
+        MaskedMaterial2d {
+            color: Color::WHITE,
+            // Matches the default cutoff of `ColorMaterial`.
+            threshold: 0.5,
+            uv_transform: Affine2::default(),
+            texture: None,
+            mask: None,
+        }
+    }
+}
+
+// NOTE: These must match the bit flags in bevy_sprite_render/src/mesh2d/masked_material.wgsl!
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct MaskedMaterial2dFlags: u32 {
+        const TEXTURE = 1 << 0;
+        const MASK    = 1 << 1;
+        const NONE    = 0;
+    }
+}
+
+/// The GPU representation of the uniform data of a [`MaskedMaterial2d`].
+#[derive(Clone, Default, ShaderType)]
+pub struct MaskedMaterial2dUniform {
+    pub color: Vec4,
+    pub uv_transform: Mat3,
+    pub flags: u32,
+    pub threshold: f32,
+}
+
+impl AsBindGroupShaderType<MaskedMaterial2dUniform> for MaskedMaterial2d {
+    fn as_bind_group_shader_type(
+        &self,
+        _images: &RenderAssets<GpuImage>,
+    ) -> MaskedMaterial2dUniform {
+        let mut flags = MaskedMaterial2dFlags::NONE;
+        if self.texture.is_some() {
+            flags |= MaskedMaterial2dFlags::TEXTURE;
+        }
+        if self.mask.is_some() {
+            flags |= MaskedMaterial2dFlags::MASK;
+        }
+        MaskedMaterial2dUniform {
+            color: LinearRgba::from(self.color).to_f32_array().into(),
+            uv_transform: self.uv_transform.into(),
+            flags: flags.bits(),
+            threshold: self.threshold,
+        }
+    }
+}
+
+impl Material2d for MaskedMaterial2d {
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Path(
+            AssetPath::from_path_buf(embedded_path!("masked_material.wgsl"))
+                .with_source("embedded"),
+        )
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        // Rendered in the alpha mask phase so that the shader is allowed to discard.
+        AlphaMode2d::Mask(self.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_and_threshold_drive_discard() {
+        let images = RenderAssets::<GpuImage>::default();
+
+        let unmasked: MaskedMaterial2dUniform =
+            MaskedMaterial2d::default().as_bind_group_shader_type(&images);
+        assert_eq!(unmasked.flags & MaskedMaterial2dFlags::MASK.bits(), 0);
+
+        let material = MaskedMaterial2d::new(Handle::default(), 0.25);
+        let uniform: MaskedMaterial2dUniform = material.as_bind_group_shader_type(&images);
+        assert_ne!(uniform.flags & MaskedMaterial2dFlags::MASK.bits(), 0);
+        assert_eq!(uniform.threshold, 0.25);
+        // The alpha mask phase is what enables discarding in the fragment shader.
+        assert_eq!(material.alpha_mode(), AlphaMode2d::Mask(0.25));
+
+        // The shader discards against the mask bound next to the base texture, not the alpha.
+        let shader = include_str!("masked_material.wgsl");
+        assert!(shader.contains("@binding(3) var mask_texture: texture_2d<f32>;"));
+        assert!(shader.contains("@binding(4) var mask_sampler: sampler;"));
+        assert!(shader.contains("if mask < material.threshold"));
+    }
+}
@@ -0,0 +1,65 @@
+#import bevy_sprite::{
+    mesh2d_vertex_output::{VertexOutput, FragmentOutput},
+    mesh2d_view_bindings::view,
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct MaskedMaterial2d {
+    color: vec4<f32>,
+    uv_transform: mat3x3<f32>,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+    threshold: f32,
+};
+
+const MASKED_MATERIAL_FLAGS_TEXTURE_BIT: u32 = 1u;
+const MASKED_MATERIAL_FLAGS_MASK_BIT: u32    = 2u;
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: MaskedMaterial2d;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var texture_sampler: sampler;
+@group(#{MATERIAL_BIND_GROUP}) @binding(3) var mask_texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(4) var mask_sampler: sampler;
+
+@fragment
+fn fragment(
+    mesh: VertexOutput,
+) -> FragmentOutput {
+    var output_color: vec4<f32> = material.color;
+
+#ifdef VERTEX_COLORS
+    output_color = output_color * mesh.color;
+#endif
+#ifdef MESH2D_TINT
+    output_color = output_color * mesh.tint;
+#endif
+
+    let uv = (material.uv_transform * vec3(mesh.uv, 1.0)).xy;
+
+    if ((material.flags & MASKED_MATERIAL_FLAGS_TEXTURE_BIT) != 0u) {
+        output_color = output_color * textureSample(texture, texture_sampler, uv);
+    }
+
+    // Sampled unconditionally, as sampling has to happen in uniform control flow.
+    let mask = textureSample(mask_texture, mask_sampler, uv).r;
+#ifdef MAY_DISCARD
+    if ((material.flags & MASKED_MATERIAL_FLAGS_MASK_BIT) != 0u) {
+        if mask < material.threshold {
+            discard;
+        }
+    }
+#endif // MAY_DISCARD
+    // NOTE: Fragments that aren't discarded are rendered fully opaque, like with an alpha mask.
+    output_color.a = 1.0;
+
+#ifdef TONEMAP_IN_SHADER
+    output_color = tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+
+    var out: FragmentOutput;
+    out.color = output_color;
+    return out;
+}
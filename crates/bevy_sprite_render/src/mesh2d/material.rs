@@ -1,16 +1,23 @@
 use crate::{
-    init_mesh_2d_pipeline, DrawMesh2d, Mesh2d, Mesh2dPipeline, Mesh2dPipelineKey,
-    RenderMesh2dInstances, SetMesh2dBindGroup, SetMesh2dViewBindGroup, ViewKeyCache,
-    ViewSpecializationTicks,
+    init_mesh_2d_pipeline, prepare_material2d_instances, DepthFormat2d, DrawMesh2d,
+    DrawMeshInstanced2d, Material2dInstanceBuffer, Mesh2d, Mesh2dInstance, Mesh2dPipeline,
+    Mesh2dPipelineKey, RenderMesh2dInstances, SetMesh2dBindGroup, SetMesh2dViewBindGroup,
+    ViewKeyCache, ViewSpecializationTicks,
 };
-use bevy_app::{App, Plugin, PostUpdate};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    record_material2d_pipelines, save_material2d_pipeline_disk_cache, Material2dPipelineDiskCache,
+};
+use bevy_app::{App, Plugin, PostUpdate, SubApp};
 use bevy_asset::prelude::AssetChanged;
 use bevy_asset::{AsAssetId, Asset, AssetApp, AssetEventSystems, AssetId, AssetServer, Handle};
 use bevy_camera::visibility::ViewVisibility;
 use bevy_core_pipeline::{
     core_2d::{
-        AlphaMask2d, AlphaMask2dBinKey, BatchSetKey2d, Opaque2d, Opaque2dBinKey, Transparent2d,
+        AlphaMask2d, AlphaMask2dBinKey, BatchSetKey2d, Opaque2d, Opaque2dBinKey, Prepass2d,
+        Transparent2d,
     },
+    prepass::MOTION_VECTOR_PREPASS_FORMAT,
     tonemapping::Tonemapping,
 };
 use bevy_derive::{Deref, DerefMut};
@@ -18,10 +25,18 @@ use bevy_ecs::change_detection::Tick;
 use bevy_ecs::system::SystemChangeTick;
 use bevy_ecs::{
     prelude::*,
-    system::{lifetimeless::SRes, SystemParamItem},
+    query::ROQueryItem,
+    system::{
+        lifetimeless::{Read, SRes, SResMut},
+        ReadOnlySystemParam, SystemParamItem,
+    },
+};
+use bevy_image::{ImageFilterMode, ImageSamplerDescriptor};
+use bevy_math::{ops, Mat4, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles};
+use bevy_mesh::{
+    MeshVertexBufferLayout, MeshVertexBufferLayoutRef, MeshVertexBufferLayouts,
+    VertexAttributeDescriptor,
 };
-use bevy_math::FloatOrd;
-use bevy_mesh::MeshVertexBufferLayoutRef;
 use bevy_platform::collections::HashMap;
 use bevy_reflect::{prelude::ReflectDefault, Reflect};
 use bevy_render::render_resource::BindGroupLayoutDescriptor;
@@ -29,7 +44,8 @@ use bevy_render::{
     camera::extract_cameras,
     mesh::RenderMesh,
     render_asset::{
-        prepare_assets, PrepareAssetError, RenderAsset, RenderAssetPlugin, RenderAssets,
+        prepare_assets, ExtractedAssets, PrepareAssetError, RenderAsset, RenderAssetPlugin,
+        RenderAssets,
     },
     render_phase::{
         AddRenderCommand, BinnedRenderPhaseType, DrawFunctionId, DrawFunctions, InputUniformIndex,
@@ -37,22 +53,29 @@ use bevy_render::{
         TrackedRenderPass, ViewBinnedRenderPhases, ViewSortedRenderPhases,
     },
     render_resource::{
-        AsBindGroup, AsBindGroupError, BindGroup, BindGroupId, BindingResources,
-        CachedRenderPipelineId, PipelineCache, RenderPipelineDescriptor, SpecializedMeshPipeline,
-        SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        AsBindGroup, AsBindGroupError, BindGroup, BindGroupEntry, BindGroupId, BindingResources,
+        BindingType, BlendComponent, BlendFactor, BlendOperation, BlendState, BufferBindingType,
+        CachedRenderPipelineId, ColorTargetCountMismatchError, ColorTargetFormatMismatchError,
+        ColorTargetState, ColorWrites, CompareFunction, DepthFormatMismatchError, DownlevelFlags,
+        OwnedBindingResource, OwnedData, PipelineCache, PreparedBindGroup,
+        RenderPipelineDescriptor, SamplerBindingType, ShaderType, SpecializedMeshPipeline,
+        SpecializedMeshPipelineError, SpecializedMeshPipelines, StorageBuffersUnsupportedError,
+        TextureFormat, UnpreparedBindGroup,
     },
-    renderer::RenderDevice,
+    renderer::{RenderAdapter, RenderDevice},
     sync_world::{MainEntity, MainEntityHashMap},
     view::{ExtractedView, RenderVisibleEntities},
     Extract, ExtractSchedule, Render, RenderApp, RenderStartup, RenderSystems,
 };
 use bevy_shader::{Shader, ShaderDefVal, ShaderRef};
-use bevy_utils::Parallel;
+use bevy_utils::{once, Parallel};
 use core::{hash::Hash, marker::PhantomData};
 use derive_more::derive::From;
-use tracing::error;
+use tracing::{error, warn};
 
 pub const MATERIAL_2D_BIND_GROUP_INDEX: usize = 2;
+/// The index of the view bind group declared with [`Material2dViewLayout::Extend`].
+pub const MATERIAL_2D_VIEW_BIND_GROUP_INDEX: usize = 3;
 
 /// Materials are used alongside [`Material2dPlugin`], [`Mesh2d`], and [`MeshMaterial2d`]
 /// to spawn entities that are rendered with a specific [`Material2d`] type. They serve as an easy to use high level
@@ -128,6 +151,20 @@ pub const MATERIAL_2D_BIND_GROUP_INDEX: usize = 2;
 /// @group(2) @binding(1) var color_texture: texture_2d<f32>;
 /// @group(2) @binding(2) var color_sampler: sampler;
 /// ```
+///
+/// If the mesh has [`Mesh::ATTRIBUTE_NORMAL`](bevy_mesh::Mesh::ATTRIBUTE_NORMAL), the
+/// `VERTEX_NORMALS` shader def is set and the default vertex shader writes the normalized
+/// world-space normal to `VertexOutput::world_normal`, which fragment shaders can use for simple
+/// 2D lighting.
+///
+/// Animated shaders can read the elapsed time and the frame count from the `globals` uniform of
+/// the view bind group, which is bound at `@group(0) @binding(1)` for every 2D mesh:
+///
+/// ```wgsl
+/// #import bevy_sprite::mesh2d_view_bindings::globals
+///
+/// let uv = mesh.uv + velocity * globals.time;
+/// ```
 pub trait Material2d: AsBindGroup + Asset + Clone + Sized {
     /// Returns this material's vertex shader. If [`ShaderRef::Default`] is returned, the default mesh vertex shader
     /// will be used.
@@ -141,6 +178,16 @@ pub trait Material2d: AsBindGroup + Asset + Clone + Sized {
         ShaderRef::Default
     }
 
+    /// Returns this material's fragment shader for the depth-only [`Prepass2d`] phase, which is
+    /// drawn when the [`Prepass2dPlugin`](bevy_core_pipeline::core_2d::Prepass2dPlugin) is added.
+    /// If [`ShaderRef::Default`] is returned, the prepass pipeline has no fragment stage and only
+    /// writes the depth of the mesh, or, on views with a motion vector prepass, writes the motion
+    /// vectors of [`writes_motion_vectors`](Self::writes_motion_vectors) materials. Only opaque
+    /// materials are drawn in the prepass.
+    fn prepass_fragment_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
     /// Add a bias to the view depth of the mesh which can be used to force a specific render order.
     #[inline]
     fn depth_bias(&self) -> f32 {
@@ -151,6 +198,311 @@ pub trait Material2d: AsBindGroup + Asset + Clone + Sized {
         AlphaMode2d::Opaque
     }
 
+    /// Returns the blend state of the color target, to blend in ways that [`AlphaMode2d`] doesn't
+    /// offer, such as additive or multiplicative blending for glows and shadows.
+    ///
+    /// When this returns `Some`, the blend state replaces the one of the first color target in
+    /// [`Material2dPipeline::specialize`], after any other blending option of the material. A
+    /// custom blend state implies transparent sorting: the meshes are drawn in the
+    /// [`Transparent2d`] phase, back to front, and don't write depth, whatever the
+    /// [`alpha_mode`](Self::alpha_mode). An [`AlphaMode2d::Mask`] still discards fragments below
+    /// its cutoff.
+    ///
+    /// Defaults to `None`, which blends according to the [`alpha_mode`](Self::alpha_mode).
+    fn blend_state(&self) -> Option<BlendState> {
+        None
+    }
+
+    /// Whether this material writes per-fragment motion vectors in the [`Prepass2d`] phase.
+    ///
+    /// This only takes effect for views with a [`MotionVectorPrepass`], whose prepass binds a
+    /// [`MOTION_VECTOR_PREPASS_FORMAT`] attachment at location 0. In that case the prepass
+    /// pipeline is specialized with the `MOTION_VECTOR_PREPASS` shader def and the vertex output
+    /// carries `previous_world_position`. Without a
+    /// [`prepass_fragment_shader`](Self::prepass_fragment_shader), the default one writes the
+    /// result of `mesh2d_functions::mesh2d_motion_vector`. Only opaque materials are drawn in
+    /// the prepass.
+    ///
+    /// [`MotionVectorPrepass`]: bevy_core_pipeline::prepass::MotionVectorPrepass
+    /// [`MOTION_VECTOR_PREPASS_FORMAT`]: bevy_core_pipeline::prepass::MOTION_VECTOR_PREPASS_FORMAT
+    #[inline]
+    fn writes_motion_vectors(&self) -> bool {
+        false
+    }
+
+    /// Disables clipping of this material's fragments against the near and far planes, so that
+    /// meshes at the extremes of the depth range are clamped rather than cut off.
+    ///
+    /// Requires [`WgpuFeatures::DEPTH_CLIP_CONTROL`](bevy_render::settings::WgpuFeatures::DEPTH_CLIP_CONTROL).
+    /// On devices without it, this has no effect.
+    #[inline]
+    fn unclipped_depth(&self) -> bool {
+        false
+    }
+
+    /// Whether this material ignores depth entirely, so that it always draws on top.
+    ///
+    /// Meant for overlays such as debug markers and badges. The pipeline is specialized to neither
+    /// test nor write depth, and meshes with this material are drawn in the [`Transparent2d`]
+    /// phase whatever their [`AlphaMode2d`], after all opaque and alpha mask meshes. They are
+    /// still sorted by z among the other transparent meshes, so use [`Material2d::depth_bias`] to
+    /// lift them over those.
+    ///
+    /// This takes precedence over [`Material2d::depth_write_enabled`] and
+    /// [`Material2d::depth_compare`], which have no effect on materials that ignore depth.
+    #[inline]
+    fn ignore_depth(&self) -> bool {
+        false
+    }
+
+    /// Whether this material applies the view's [`ColorGradingLut2d`](crate::ColorGradingLut2d).
+    ///
+    /// For views that have one, the pipeline is specialized with the `COLOR_GRADING_LUT` shader
+    /// def. The default fragment shader then grades its output, and custom fragment shaders are
+    /// expected to call `mesh2d_functions::mesh2d_color_grading_lut` on their color. Views
+    /// without a LUT are unaffected.
+    #[inline]
+    fn uses_lut(&self) -> bool {
+        false
+    }
+
+    /// Whether this material dithers its output to reduce banding in smooth gradients.
+    ///
+    /// The pipeline is specialized with the `MATERIAL_DITHER` shader def, independently of the
+    /// view's [`DebandDither`](bevy_core_pipeline::tonemapping::DebandDither) setting, which only
+    /// applies when tonemapping in the shader. The default fragment shader then dithers its output,
+    /// and custom fragment shaders are expected to call `mesh2d_functions::mesh2d_dither` on their
+    /// color with the fragment's `position`.
+    #[inline]
+    fn dither(&self) -> bool {
+        false
+    }
+
+    /// The anisotropic filtering level to sample this material's textures with, for textures
+    /// that are viewed at a grazing angle, such as ground planes in a 2.5D game.
+    ///
+    /// When this returns a level above 1, every filtering sampler of the material is replaced with
+    /// a trilinear sampler with that anisotropy clamp and `ClampToEdge` addressing, regardless of
+    /// the sampler settings of the images. Levels above 16 are clamped, and other levels are
+    /// rounded down to a power of two. Has no effect on adapters without
+    /// [`DownlevelFlags::ANISOTROPIC_FILTERING`].
+    #[inline]
+    fn anisotropy_clamp(&self) -> Option<u16> {
+        None
+    }
+
+    /// Selects how this material's textures are filtered depending on how far each view is
+    /// zoomed, such as nearest filtering for pixel art viewed up close and linear filtering once
+    /// it is zoomed out.
+    ///
+    /// When this returns `Some`, every filtering sampler of the material is replaced, per view,
+    /// with a `ClampToEdge` sampler using the filter mode picked by [`ZoomFilter::filter_for`].
+    /// This takes precedence over [`Material2d::anisotropy_clamp`].
+    #[inline]
+    fn zoom_filter(&self) -> Option<ZoomFilter> {
+        None
+    }
+
+    /// The color target formats this material's fragment shader writes, in location order.
+    ///
+    /// When non-empty, specialization fails with a
+    /// [`ColorTargetFormatMismatchError`](bevy_render::render_resource::ColorTargetFormatMismatchError)
+    /// if the view's color targets differ, instead of surfacing as a validation error when the
+    /// pipeline is created. The check runs after any
+    /// [`color_target_states`](Self::color_target_states) replace the targets of the view, so
+    /// materials writing multiple render targets declare the formats of all of them. Returns an
+    /// empty slice by default, which skips the check.
+    fn color_target_formats() -> &'static [TextureFormat] {
+        &[]
+    }
+
+    /// The color targets this material's fragment shader writes, in location order, for
+    /// materials that write to multiple render targets.
+    ///
+    /// When non-empty, these replace the color targets of the view, including their blend states.
+    /// The view binds its [`ExtraColorTargets2d`] after its view target, and specialization fails
+    /// with a
+    /// [`ColorTargetCountMismatchError`](bevy_render::render_resource::ColorTargetCountMismatchError)
+    /// if the number of targets differs from the number of attachments. Returns an empty `Vec` by
+    /// default, which keeps the targets of the view.
+    ///
+    /// [`ExtraColorTargets2d`]: bevy_core_pipeline::core_2d::ExtraColorTargets2d
+    fn color_target_states() -> Vec<Option<ColorTargetState>> {
+        Vec::new()
+    }
+
+    /// A stand-in for this material, bound while any of its textures are still loading.
+    ///
+    /// By default a material whose textures aren't loaded isn't rendered at all. When this returns
+    /// `Some`, the returned material is prepared instead, and the real material is retried every
+    /// frame until its textures are available, at which point it replaces the fallback. The
+    /// fallback should usually be a clone of `self` with the unloaded textures swapped for a
+    /// placeholder, so that it is specialized into the same pipeline.
+    ///
+    /// The bind group of the fallback is only built once, see [`Material2dFallbackCache`]. Give
+    /// the fallback a [`Material2d::content_hash`] to share it between materials of this type.
+    fn loading_fallback(&self) -> Option<Self> {
+        None
+    }
+
+    /// A hash of everything this material writes to its bind group, used to batch meshes whose
+    /// materials are logically identical.
+    ///
+    /// By default, meshes are only batched together if they share a material asset, and a material
+    /// that is rebuilt gets a new bind group and is no longer batched with draws queued against the
+    /// old one. Materials returning the same hash are batched as if they shared a bind group,
+    /// including across rebuilds, so the hash must cover every field that is uploaded to the GPU.
+    /// Returns `None` by default, which batches by bind group.
+    fn content_hash(&self) -> Option<u64> {
+        None
+    }
+
+    /// The part of this material that selects its shader variant, passed to
+    /// [`Material2d::specialize`] as [`Material2dKey::bind_group_data`].
+    ///
+    /// Meshes whose materials return equal keys share a pipeline, so this should only capture the
+    /// fields that change the pipeline, such as feature toggles mapped to shader defs, and leave out
+    /// values that are only written to the bind group, such as colors or intensities. Declare the
+    /// key type with `#[bind_group_data(MyKey)]` on the [`AsBindGroup`] derive.
+    ///
+    /// Defaults to [`AsBindGroup::bind_group_data`].
+    fn variant_key(&self) -> Self::Data {
+        self.bind_group_data()
+    }
+
+    /// Returns the layout of a view bind group holding screen-space data that the default mesh2d
+    /// view bind group doesn't provide, such as the inverse view-projection matrix or the
+    /// resolution.
+    ///
+    /// The bind group is set by the view render command passed to [`Material2dPlugin`], which runs
+    /// before the mesh is drawn. With [`Material2dViewLayout::Extend`], the shader declares its
+    /// bindings in the `MATERIAL_VIEW_BIND_GROUP` group.
+    ///
+    /// Defaults to `None`, which only binds the default view bind group.
+    fn view_layout() -> Option<Material2dViewLayout> {
+        None
+    }
+
+    /// Returns the binding of a [`VertexDisplacement2d`] uniform in the material bind group, to
+    /// displace the vertices of the mesh with a sine wave, such as for flags, water, or jelly.
+    ///
+    /// The default vertex shader then applies the displacement to each vertex before transforming
+    /// it. Custom vertex shaders see the `VERTEX_DISPLACEMENT` and `VERTEX_DISPLACEMENT_BINDING`
+    /// shader defs, and can apply it with `bevy_sprite::mesh2d_functions::mesh2d_displace_vertex`.
+    ///
+    /// Defaults to `None`, which doesn't displace vertices.
+    fn vertex_displacement_binding() -> Option<u32> {
+        None
+    }
+
+    /// Returns the binding of an [`AdditiveMix2d`] uniform in the material bind group, to blend
+    /// anywhere between alpha blending and additive blending with a single pipeline, such as for
+    /// particles that fade from smoke into fire.
+    ///
+    /// With [`AlphaMode2d::Blend`], the pipeline then blends with premultiplied alpha, and the
+    /// default fragment shader premultiplies its color as [`AdditiveMix2d::premultiply`] does.
+    /// Custom fragment shaders see the `ADDITIVE_MIX` and `ADDITIVE_MIX_BINDING` shader defs, and
+    /// are expected to call `bevy_sprite::mesh2d_functions::mesh2d_additive_mix` on their color.
+    /// Changing the uniform doesn't require a different pipeline.
+    ///
+    /// Defaults to `None`, which blends according to the [`alpha_mode`](Self::alpha_mode) only.
+    fn additive_mix_binding() -> Option<u32> {
+        None
+    }
+
+    /// Overrides whether the pipeline of this material writes depth.
+    ///
+    /// Meshes with a material that doesn't write depth are drawn in the [`Transparent2d`] phase
+    /// whatever their [`AlphaMode2d`], so that they are still sorted back to front rather than
+    /// binned without any order.
+    ///
+    /// Defaults to `None`, which writes depth for opaque and alpha mask materials only. Has no
+    /// effect on materials that [ignore depth](Material2d::ignore_depth).
+    #[inline]
+    fn depth_write_enabled(&self) -> Option<bool> {
+        None
+    }
+
+    /// Overrides the comparison the pipeline of this material tests depth with.
+    ///
+    /// Use [`CompareFunction::Always`] to draw over everything drawn before, while still writing
+    /// depth for what comes after unless [`Material2d::depth_write_enabled`] disables it.
+    ///
+    /// Defaults to `None`, which keeps [`CompareFunction::GreaterEqual`]. Has no effect on
+    /// materials that [ignore depth](Material2d::ignore_depth), which always pass the test.
+    #[inline]
+    fn depth_compare(&self) -> Option<CompareFunction> {
+        None
+    }
+
+    /// Returns `true` to snap the vertices of the mesh to the pixel grid of the view, which keeps
+    /// pixel art from shimmering as it moves by fractions of a pixel.
+    ///
+    /// The default vertex shader then rounds the clip-space position of each vertex to the nearest
+    /// pixel edge of the viewport, as [`pixel_snap_clip_position`] does. Custom vertex shaders see
+    /// the `PIXEL_SNAP` shader def, and can snap positions with
+    /// `bevy_sprite::mesh2d_functions::mesh2d_pixel_snap`.
+    ///
+    /// Defaults to `false`.
+    fn pixel_snap() -> bool {
+        false
+    }
+
+    /// Returns the largest color value this material writes to LDR targets, to sanitize the output
+    /// of shaders that may produce NaN, infinite, or out-of-range values, which would otherwise
+    /// corrupt the target or blow up post-processing such as bloom.
+    ///
+    /// When this returns `Some`, the pipeline is specialized with the `CLAMP_OUTPUT` shader def and
+    /// the `CLAMP_OUTPUT_MAX_BITS` def holding the bits of the largest value: the returned one for
+    /// LDR targets, or the largest finite value of [`ViewTarget::TEXTURE_FORMAT_HDR`](bevy_render::view::ViewTarget::TEXTURE_FORMAT_HDR) for HDR
+    /// targets. The default fragment shader then replaces NaN with zero and clamps its color as
+    /// [`clamp_material_output`] does. Custom fragment shaders are expected to call
+    /// `mesh2d_functions::mesh2d_clamp_output` on their color with
+    /// `bitcast<f32>(#{CLAMP_OUTPUT_MAX_BITS}u)`.
+    ///
+    /// Defaults to `None`, which writes the output unchanged.
+    fn clamp_output() -> Option<f32> {
+        None
+    }
+
+    /// Returns `true` to draw the meshes of this material with GPU instancing, such as for
+    /// thousands of particles or tiles sharing a mesh and a material.
+    ///
+    /// The visible meshes of a view that share a pipeline, a material and a mesh are then drawn
+    /// with a single draw call, by [`DrawMeshInstanced2d`] from the buffer built by
+    /// [`prepare_material2d_instances`], ordered back to front. The pipeline gets an
+    /// instance-step vertex buffer laid out by [`Mesh2dInstance::vertex_buffer_layout`], and the
+    /// default vertex shader reads the transform and tint of each mesh from it. Custom vertex
+    /// shaders see the `INSTANCED` shader def, and can declare the attributes from
+    /// [`Mesh2dInstance::FIRST_SHADER_LOCATION`] on as `mesh2d.wgsl` does.
+    ///
+    /// Within a batch, the meshes are only sorted against each other, so transparent instanced
+    /// meshes are drawn at the depth of the farthest one relative to other transparent meshes.
+    ///
+    /// Defaults to `false`, which draws each mesh with the batching shared by all 2D meshes.
+    fn instanced() -> bool {
+        false
+    }
+
+    /// Returns the mesh vertex attributes this material requires, in addition to those read by
+    /// the default mesh2d pipeline, such as a second set of UVs or a custom attribute.
+    ///
+    /// The attributes are appended to the mesh vertex buffer with
+    /// [`push_mesh2d_vertex_attributes`] before [`Material2d::specialize`] runs, so a custom
+    /// vertex shader can declare them at their shader locations. Locations `0` to `4` are read by
+    /// the default pipeline when the mesh has the matching attribute, so requiring one of them,
+    /// such as [`Mesh::ATTRIBUTE_COLOR`](bevy_mesh::Mesh::ATTRIBUTE_COLOR) at location `4`, only
+    /// makes it mandatory. Locations from [`Mesh2dInstance::FIRST_SHADER_LOCATION`] on are
+    /// reserved for [instancing](Material2d::instanced).
+    ///
+    /// Meshes missing one of the attributes fail to specialize with
+    /// [`SpecializedMeshPipelineError::MissingVertexAttribute`], and are logged and skipped.
+    ///
+    /// Defaults to no attributes.
+    fn vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+        Vec::new()
+    }
+
     /// Customizes the default [`RenderPipelineDescriptor`].
     #[expect(
         unused_variables,
@@ -232,6 +584,102 @@ impl<M: Material2d> AsAssetId for MeshMaterial2d<M> {
     }
 }
 
+/// A view bind group layout declared by a [`Material2d`]. See [`Material2d::view_layout`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Material2dViewLayout {
+    /// Binds the layout in place of the default mesh2d view bind group, at index `0`.
+    ///
+    /// The bind group must then provide everything the shader reads from the view, including the
+    /// bindings used by the `bevy_sprite::mesh2d_view_bindings` imports.
+    Replace(BindGroupLayoutDescriptor),
+    /// Binds the layout in addition to the default mesh2d view bind group, at
+    /// [`MATERIAL_2D_VIEW_BIND_GROUP_INDEX`].
+    Extend(BindGroupLayoutDescriptor),
+}
+
+/// A sine wave displacing the vertices of a 2d mesh, bound by materials that return its binding
+/// from [`Material2d::vertex_displacement_binding`].
+///
+/// Each vertex moves along [`axis`](Self::axis) by up to [`amplitude`](Self::amplitude). The wave
+/// travels across the axis, so that for a vertical axis, vertices at different `x` positions are
+/// displaced by different amounts, like a waving flag.
+#[derive(ShaderType, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Default, Debug, Clone, PartialEq)]
+pub struct VertexDisplacement2d {
+    /// The largest distance a vertex is moved, in local units. No vertex moves if this is `0.0`.
+    pub amplitude: f32,
+    /// The number of radians the wave advances per local unit across the axis.
+    pub frequency: f32,
+    /// The number of radians the wave advances per second.
+    pub speed: f32,
+    /// The direction vertices are moved in, in local space. Should be normalized.
+    pub axis: Vec2,
+}
+
+impl Default for VertexDisplacement2d {
+    fn default() -> Self {
+        Self {
+            amplitude: 0.0,
+            frequency: 1.0,
+            speed: 1.0,
+            axis: Vec2::Y,
+        }
+    }
+}
+
+impl VertexDisplacement2d {
+    /// Returns the local `position` of a vertex after displacement, `time` seconds after startup,
+    /// as computed by the shader.
+    pub fn displace(&self, position: Vec3, time: f32) -> Vec3 {
+        let phase = position.xy().dot(self.axis.perp()) * self.frequency + time * self.speed;
+        position + (self.axis * self.amplitude * ops::sin(phase)).extend(0.0)
+    }
+}
+
+/// How much a 2d material blends additively rather than with its alpha, bound by materials that
+/// return its binding from [`Material2d::additive_mix_binding`].
+///
+/// The color is premultiplied by its alpha, and the alpha it writes is scaled down as the blending
+/// becomes additive, so that the premultiplied alpha blend state covers the whole range.
+#[derive(ShaderType, Reflect, Debug, Clone, Copy, Default, PartialEq)]
+#[reflect(Default, Debug, Clone, PartialEq)]
+pub struct AdditiveMix2d {
+    /// `0.0` for alpha blending, `1.0` for additive blending, and values in between to mix both.
+    pub additive: f32,
+}
+
+impl AdditiveMix2d {
+    /// Returns the premultiplied `color` written by the shader, which the premultiplied alpha
+    /// blend state then adds to the color behind it, scaled by one minus its alpha.
+    pub fn premultiply(&self, color: Vec4) -> Vec4 {
+        let alpha = color.w;
+        (color.xyz() * alpha).extend(alpha * (1.0 - self.additive))
+    }
+}
+
+/// Rounds a `clip_position` to the nearest pixel edge of a viewport of `viewport_size` pixels, as
+/// done by the vertex shader of materials that enable [`Material2d::pixel_snap`].
+pub fn pixel_snap_clip_position(clip_position: Vec4, viewport_size: Vec2) -> Vec4 {
+    let half_size = viewport_size * 0.5;
+    let pixel = (clip_position.xy() / clip_position.w + 1.0) * half_size;
+    let snapped = (pixel.round() / half_size - 1.0) * clip_position.w;
+    Vec4::new(snapped.x, snapped.y, clip_position.z, clip_position.w)
+}
+
+/// The largest finite value of [`ViewTarget::TEXTURE_FORMAT_HDR`](bevy_render::view::ViewTarget::TEXTURE_FORMAT_HDR), which materials that enable
+/// [`Material2d::clamp_output`] clamp their output to on HDR targets.
+const HDR_OUTPUT_MAX: f32 = 65504.0;
+
+/// Sanitizes a `color` as done by the fragment shader of materials that enable
+/// [`Material2d::clamp_output`].
+///
+/// NaN channels are replaced with zero, the color channels are clamped between zero and `max`, and
+/// alpha is clamped between zero and one.
+pub fn clamp_material_output(color: Vec4, max: f32) -> Vec4 {
+    let color = Vec4::select(color.is_nan_mask(), Vec4::ZERO, color);
+    color.clamp(Vec4::ZERO, Vec4::new(max, max, max, 1.0))
+}
+
 /// Sets how a 2d material's base color alpha channel is used for transparency.
 /// Currently, this only works with [`Mesh2d`]. Sprites are always transparent.
 ///
@@ -256,21 +704,94 @@ pub enum AlphaMode2d {
     /// Standard alpha-blending is used to blend the fragment's color
     /// with the color behind it.
     Blend,
+    /// Like [`Blend`](Self::Blend), for colors whose alpha is already multiplied into them, such
+    /// as textures authored with premultiplied alpha.
+    ///
+    /// [`Blend`](Self::Blend) multiplies the color by its alpha while blending, which darkens the
+    /// semi-transparent edges of premultiplied textures a second time, leaving dark fringes. This
+    /// mode adds the color as is, and only scales the color behind it by one minus the alpha.
+    /// This is the equivalent of `AlphaMode::Premultiplied` in 3D.
+    Premultiplied,
+    /// The fragment's color, scaled by its alpha, is added to the color behind it.
+    ///
+    /// Useful for glows, fire, and other light effects that brighten what they overlap.
+    Additive,
+    /// The color behind the fragment is multiplied by the fragment's color.
+    ///
+    /// The alpha of the fragment is ignored: white leaves the color behind unchanged. Useful
+    /// for shadows and tinting.
+    Multiply,
 }
 
 /// Adds the necessary ECS resources and render logic to enable rendering entities using the given [`Material2d`]
 /// asset type (which includes [`Material2d`] types).
-pub struct Material2dPlugin<M: Material2d>(PhantomData<M>);
+///
+/// The optional `C` parameter is a [`RenderCommand`] appended to the material's draw sequence,
+/// after the mesh has been drawn with [`DrawMesh2d`]. It can bind auxiliary state or issue a
+/// follow-up draw for materials built on specialized pipelines. Tuples of commands run in order.
+///
+/// The optional `V` parameter is a [`RenderCommand`] that runs after the material bind group is
+/// set and before the mesh is drawn. It binds the view bind group declared by
+/// [`Material2d::view_layout`].
+///
+/// ```
+/// # use bevy_sprite_render::{ColorMaterial, Material2dPlugin};
+/// # use bevy_render::render_phase::{
+/// #     PhaseItem, RenderCommand, RenderCommandResult, TrackedRenderPass,
+/// # };
+/// # use bevy_ecs::system::SystemParamItem;
+/// struct DrawOutline;
+///
+/// impl<P: PhaseItem> RenderCommand<P> for DrawOutline {
+///     type Param = ();
+///     type ViewQuery = ();
+///     type ItemQuery = ();
+///
+///     fn render<'w>(
+///         _item: &P,
+///         _view: (),
+///         _entity: Option<()>,
+///         _param: SystemParamItem<'w, '_, Self::Param>,
+///         _pass: &mut TrackedRenderPass<'w>,
+///     ) -> RenderCommandResult {
+///         // Issue the follow-up draw here.
+///         RenderCommandResult::Success
+///     }
+/// }
+///
+/// let plugin = Material2dPlugin::<ColorMaterial, DrawOutline>::default();
+/// ```
+pub struct Material2dPlugin<M: Material2d, C = (), V = ()>(PhantomData<fn() -> (M, C, V)>);
 
-impl<M: Material2d> Default for Material2dPlugin<M> {
+impl<M: Material2d, C, V> Default for Material2dPlugin<M, C, V> {
     fn default() -> Self {
         Self(Default::default())
     }
 }
 
-impl<M: Material2d> Plugin for Material2dPlugin<M>
+impl<M: Material2d, C, V> Plugin for Material2dPlugin<M, C, V>
 where
     M::Data: PartialEq + Eq + Hash + Clone,
+    C: RenderCommand<Opaque2d>
+        + RenderCommand<AlphaMask2d>
+        + RenderCommand<Transparent2d>
+        + Send
+        + Sync
+        + 'static,
+    <C as RenderCommand<Opaque2d>>::Param: ReadOnlySystemParam,
+    <C as RenderCommand<AlphaMask2d>>::Param: ReadOnlySystemParam,
+    <C as RenderCommand<Transparent2d>>::Param: ReadOnlySystemParam,
+    V: RenderCommand<Opaque2d>
+        + RenderCommand<AlphaMask2d>
+        + RenderCommand<Transparent2d>
+        + RenderCommand<Prepass2d>
+        + Send
+        + Sync
+        + 'static,
+    <V as RenderCommand<Opaque2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<AlphaMask2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<Transparent2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<Prepass2d>>::Param: ReadOnlySystemParam,
 {
     fn build(&self, app: &mut App) {
         app.init_asset::<M>()
@@ -283,13 +804,17 @@ where
             );
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            add_material2d_render_commands::<M, C, V>(render_app);
             render_app
                 .init_resource::<EntitySpecializationTickPair<M>>()
                 .init_resource::<SpecializedMaterial2dPipelineCache<M>>()
-                .add_render_command::<Opaque2d, DrawMaterial2d<M>>()
-                .add_render_command::<AlphaMask2d, DrawMaterial2d<M>>()
-                .add_render_command::<Transparent2d, DrawMaterial2d<M>>()
+                .init_resource::<Material2dPipelineDepthFormats<M>>()
                 .init_resource::<RenderMaterial2dInstances<M>>()
+                .init_resource::<Material2dLoadingFallbacks<M>>()
+                .init_resource::<Material2dBindGroupIds<M>>()
+                .init_resource::<Material2dFallbackCache<M>>()
+                .init_resource::<Material2dPrewarmQueue<M>>()
+                .init_resource::<Material2dInstanceBuffer<M>>()
                 .init_resource::<SpecializedMeshPipelines<Material2dPipeline<M>>>()
                 .add_systems(
                     RenderStartup,
@@ -305,15 +830,35 @@ where
                 .add_systems(
                     Render,
                     (
+                        retry_material2d_loading_fallbacks::<M>
+                            .in_set(RenderSystems::PrepareAssets)
+                            .before(prepare_assets::<PreparedMaterial2d<M>>),
+                        prewarm_material2d_pipelines::<M>
+                            .in_set(RenderSystems::PrepareMeshes)
+                            .before(specialize_material2d_meshes::<M>),
                         specialize_material2d_meshes::<M>
                             .in_set(RenderSystems::PrepareMeshes)
                             .after(prepare_assets::<PreparedMaterial2d<M>>)
                             .after(prepare_assets::<RenderMesh>),
+                        prepare_material2d_instances::<M>
+                            .in_set(RenderSystems::PrepareMeshes)
+                            .after(specialize_material2d_meshes::<M>),
                         queue_material2d_meshes::<M>
                             .in_set(RenderSystems::QueueMeshes)
                             .after(prepare_assets::<PreparedMaterial2d<M>>),
+                        queue_material2d_prepass::<M>
+                            .in_set(RenderSystems::QueueMeshes)
+                            .after(queue_material2d_meshes::<M>),
                     ),
                 );
+            #[cfg(not(target_arch = "wasm32"))]
+            render_app.add_systems(
+                Render,
+                record_material2d_pipelines::<M>
+                    .in_set(RenderSystems::Cleanup)
+                    .before(save_material2d_pipeline_disk_cache)
+                    .run_if(resource_exists::<Material2dPipelineDiskCache>),
+            );
         }
     }
 }
@@ -382,14 +927,27 @@ pub fn extract_mesh_materials_2d<M: Material2d>(
 pub struct Material2dPipeline<M: Material2d> {
     pub mesh2d_pipeline: Mesh2dPipeline,
     pub material2d_layout: BindGroupLayoutDescriptor,
+    /// The view bind group layout declared by [`Material2d::view_layout`].
+    pub view_layout: Option<Material2dViewLayout>,
     pub vertex_shader: Option<Handle<Shader>>,
     pub fragment_shader: Option<Handle<Shader>>,
+    /// The fragment shader of the depth prepass, see [`Material2d::prepass_fragment_shader`].
+    pub prepass_fragment_shader: Option<Handle<Shader>>,
+    /// Whether the adapter supports anisotropic filtering, which is required to honor
+    /// [`Material2d::anisotropy_clamp`].
+    pub anisotropic_filtering_supported: bool,
     marker: PhantomData<M>,
 }
 
 pub struct Material2dKey<M: Material2d> {
     pub mesh_key: Mesh2dPipelineKey,
     pub bind_group_data: M::Data,
+    /// The [`Material2d::blend_state`] of the material.
+    pub blend_state: Option<BlendState>,
+    /// The [`Material2d::depth_write_enabled`] override of the material.
+    pub depth_write_enabled: Option<bool>,
+    /// The [`Material2d::depth_compare`] override of the material.
+    pub depth_compare: Option<CompareFunction>,
 }
 
 impl<M: Material2d> Eq for Material2dKey<M> where M::Data: PartialEq {}
@@ -399,7 +957,11 @@ where
     M::Data: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.mesh_key == other.mesh_key && self.bind_group_data == other.bind_group_data
+        self.mesh_key == other.mesh_key
+            && self.bind_group_data == other.bind_group_data
+            && self.blend_state == other.blend_state
+            && self.depth_write_enabled == other.depth_write_enabled
+            && self.depth_compare == other.depth_compare
     }
 }
 
@@ -411,6 +973,9 @@ where
         Self {
             mesh_key: self.mesh_key,
             bind_group_data: self.bind_group_data.clone(),
+            blend_state: self.blend_state,
+            depth_write_enabled: self.depth_write_enabled,
+            depth_compare: self.depth_compare,
         }
     }
 }
@@ -422,6 +987,9 @@ where
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.mesh_key.hash(state);
         self.bind_group_data.hash(state);
+        self.blend_state.hash(state);
+        self.depth_write_enabled.hash(state);
+        self.depth_compare.hash(state);
     }
 }
 
@@ -430,8 +998,11 @@ impl<M: Material2d> Clone for Material2dPipeline<M> {
         Self {
             mesh2d_pipeline: self.mesh2d_pipeline.clone(),
             material2d_layout: self.material2d_layout.clone(),
+            view_layout: self.view_layout.clone(),
             vertex_shader: self.vertex_shader.clone(),
             fragment_shader: self.fragment_shader.clone(),
+            prepass_fragment_shader: self.prepass_fragment_shader.clone(),
+            anisotropic_filtering_supported: self.anisotropic_filtering_supported,
             marker: PhantomData,
         }
     }
@@ -459,28 +1030,259 @@ where
                 MATERIAL_2D_BIND_GROUP_INDEX as u32,
             ));
         }
-        if let Some(vertex_shader) = &self.vertex_shader {
-            descriptor.vertex.shader = vertex_shader.clone();
+        match &self.vertex_shader {
+            Some(vertex_shader) => descriptor.vertex.shader = vertex_shader.clone(),
+            // Custom vertex shaders may leave the tint zeroed, which would turn the mesh black.
+            None => {
+                if let Some(ref mut fragment) = descriptor.fragment {
+                    fragment.shader_defs.push("MESH2D_TINT".into());
+                }
+            }
         }
 
         if let Some(fragment_shader) = &self.fragment_shader {
             descriptor.fragment.as_mut().unwrap().shader = fragment_shader.clone();
         }
+        // Mesh uniforms are only batched into a uniform buffer on devices without storage buffers.
+        validate_material2d_storage_buffers(
+            &self.material2d_layout,
+            self.mesh2d_pipeline.per_object_buffer_batch_size.is_none(),
+        )?;
         descriptor.layout = vec![
             self.mesh2d_pipeline.view_layout.clone(),
             self.mesh2d_pipeline.mesh_layout.clone(),
             self.material2d_layout.clone(),
         ];
+        match &self.view_layout {
+            None => {}
+            Some(Material2dViewLayout::Replace(view_layout)) => {
+                descriptor.layout[0] = view_layout.clone();
+            }
+            Some(Material2dViewLayout::Extend(view_layout)) => {
+                descriptor.layout.push(view_layout.clone());
+                let shader_def = ShaderDefVal::UInt(
+                    "MATERIAL_VIEW_BIND_GROUP".into(),
+                    MATERIAL_2D_VIEW_BIND_GROUP_INDEX as u32,
+                );
+                descriptor.vertex.shader_defs.push(shader_def.clone());
+                if let Some(ref mut fragment) = descriptor.fragment {
+                    fragment.shader_defs.push(shader_def);
+                }
+            }
+        }
+        if let Some(binding) = M::vertex_displacement_binding() {
+            descriptor.vertex.shader_defs.extend([
+                "VERTEX_DISPLACEMENT".into(),
+                ShaderDefVal::UInt("VERTEX_DISPLACEMENT_BINDING".into(), binding),
+            ]);
+        }
+        // Materials that ignore depth neither test nor write it, whatever their overrides.
+        if !key.mesh_key.contains(Mesh2dPipelineKey::IGNORE_DEPTH)
+            && let Some(depth_stencil) = descriptor.depth_stencil.as_mut()
+        {
+            if let Some(depth_write_enabled) = key.depth_write_enabled {
+                depth_stencil.depth_write_enabled = depth_write_enabled;
+            }
+            if let Some(depth_compare) = key.depth_compare {
+                depth_stencil.depth_compare = depth_compare;
+            }
+        }
+        if M::pixel_snap() {
+            descriptor.vertex.shader_defs.push("PIXEL_SNAP".into());
+        }
+        if M::instanced() {
+            descriptor.vertex.shader_defs.push("INSTANCED".into());
+            descriptor
+                .vertex
+                .buffers
+                .push(Mesh2dInstance::vertex_buffer_layout());
+        }
+        if let Some(binding) = M::additive_mix_binding()
+            && key.mesh_key.contains(Mesh2dPipelineKey::BLEND_ALPHA)
+            && let Some(ref mut fragment) = descriptor.fragment
+        {
+            fragment.shader_defs.extend([
+                "ADDITIVE_MIX".into(),
+                ShaderDefVal::UInt("ADDITIVE_MIX_BINDING".into(), binding),
+            ]);
+            if let Some(Some(target)) = fragment.targets.first_mut() {
+                target.blend = Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING);
+            }
+        }
+        if let Some(ldr_max) = M::clamp_output()
+            && let Some(ref mut fragment) = descriptor.fragment
+        {
+            let max = if key.mesh_key.contains(Mesh2dPipelineKey::HDR) {
+                HDR_OUTPUT_MAX
+            } else {
+                ldr_max
+            };
+            fragment.shader_defs.extend([
+                "CLAMP_OUTPUT".into(),
+                ShaderDefVal::UInt("CLAMP_OUTPUT_MAX_BITS".into(), max.to_bits()),
+            ]);
+        }
+        if key.mesh_key.intersects(
+            Mesh2dPipelineKey::BLEND_PREMULTIPLIED_ALPHA
+                | Mesh2dPipelineKey::BLEND_ADDITIVE
+                | Mesh2dPipelineKey::BLEND_MULTIPLY,
+        ) && let Some(ref mut fragment) = descriptor.fragment
+            && let Some(Some(target)) = fragment.targets.first_mut()
+        {
+            target.blend = alpha_mode_blend_state(key.mesh_key);
+        }
+        if let Some(blend_state) = key.blend_state
+            && let Some(ref mut fragment) = descriptor.fragment
+            && let Some(Some(target)) = fragment.targets.first_mut()
+        {
+            target.blend = Some(blend_state);
+        }
 
+        replace_color_target_states(&mut descriptor, key.mesh_key, M::color_target_states())?;
+        // Validated after the replacement, so materials writing multiple render targets declare
+        // the formats of all of them.
+        validate_color_target_formats(&descriptor, M::color_target_formats())?;
+
+        push_mesh2d_vertex_attributes(&mut descriptor, layout, &M::vertex_attributes())?;
+        let mesh_key = key.mesh_key;
         M::specialize(&mut descriptor, layout, key)?;
+        let depth_prepass = mesh_key.contains(Mesh2dPipelineKey::DEPTH_PREPASS);
+        if depth_prepass {
+            // The prepass only writes depth and motion vectors. Without a motion vector target, a
+            // material without a prepass fragment shader has nothing to shade.
+            let motion_vector_prepass = mesh_key.contains(Mesh2dPipelineKey::MOTION_VECTOR_PREPASS);
+            let prepass_fragment_shader = self.prepass_fragment_shader.clone().or_else(|| {
+                motion_vector_prepass.then(|| self.mesh2d_pipeline.prepass_shader.clone())
+            });
+            descriptor.fragment = descriptor.fragment.take().zip(prepass_fragment_shader).map(
+                |(mut fragment, shader)| {
+                    fragment.shader = shader;
+                    fragment.targets.clear();
+                    if motion_vector_prepass {
+                        // The target is bound by the view, so materials that don't write motion
+                        // vectors still declare it, without writing to it.
+                        let write_mask =
+                            if mesh_key.contains(Mesh2dPipelineKey::WRITES_MOTION_VECTORS) {
+                                ColorWrites::ALL
+                            } else {
+                                ColorWrites::empty()
+                            };
+                        fragment.targets.push(Some(ColorTargetState {
+                            format: MOTION_VECTOR_PREPASS_FORMAT,
+                            blend: None,
+                            write_mask,
+                        }));
+                    }
+                    fragment
+                },
+            );
+        }
         Ok(descriptor)
     }
 }
 
+/// Checks that the color targets of `descriptor` match the formats declared by a material.
+pub(crate) fn validate_color_target_formats(
+    descriptor: &RenderPipelineDescriptor,
+    expected: &[TextureFormat],
+) -> Result<(), ColorTargetFormatMismatchError> {
+    if expected.is_empty() {
+        return Ok(());
+    }
+    let actual: Vec<Option<TextureFormat>> = descriptor
+        .fragment
+        .iter()
+        .flat_map(|fragment| fragment.targets.iter())
+        .map(|target| target.as_ref().map(|target| target.format))
+        .collect();
+    if actual.len() == expected.len()
+        && actual
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| *actual == Some(*expected))
+    {
+        return Ok(());
+    }
+    Err(ColorTargetFormatMismatchError {
+        pipeline_type: None,
+        expected: expected.to_vec(),
+        actual,
+    })
+}
+
+/// Checks that a material bind group layout only declares storage buffers on devices that
+/// support them, such as those without WebGL2 limits.
+fn validate_material2d_storage_buffers(
+    layout: &BindGroupLayoutDescriptor,
+    storage_buffers_supported: bool,
+) -> Result<(), StorageBuffersUnsupportedError> {
+    if storage_buffers_supported {
+        return Ok(());
+    }
+    let Some(entry) = layout.entries.iter().find(|entry| {
+        matches!(
+            entry.ty,
+            BindingType::Buffer {
+                ty: BufferBindingType::Storage { .. },
+                ..
+            }
+        )
+    }) else {
+        return Ok(());
+    };
+    Err(StorageBuffersUnsupportedError {
+        pipeline_type: None,
+        binding: entry.binding,
+    })
+}
+
+/// Replaces the color targets of `descriptor` with the [`Material2d::color_target_states`] of a
+/// material, after checking that they match the color attachments of the view.
+pub(crate) fn replace_color_target_states(
+    descriptor: &mut RenderPipelineDescriptor,
+    mesh_key: Mesh2dPipelineKey,
+    color_target_states: Vec<Option<ColorTargetState>>,
+) -> Result<(), ColorTargetCountMismatchError> {
+    let Some(fragment) = descriptor.fragment.as_mut() else {
+        return Ok(());
+    };
+    let extra_color_targets = mesh_key.extra_color_targets();
+    if color_target_states.is_empty() && extra_color_targets == 0 {
+        return Ok(());
+    }
+    let attachments = fragment.targets.len() + extra_color_targets;
+    if color_target_states.len() != attachments {
+        return Err(ColorTargetCountMismatchError {
+            pipeline_type: None,
+            expected: color_target_states.len(),
+            actual: attachments,
+        });
+    }
+    fragment.targets = color_target_states;
+    Ok(())
+}
+
+/// Checks that a pipeline of `M` specialized for `pipeline_format` can draw into a view whose
+/// depth attachment is `view_format`.
+pub fn validate_material2d_depth_format<M: Material2d>(
+    pipeline_format: Option<TextureFormat>,
+    view_format: TextureFormat,
+) -> Result<(), DepthFormatMismatchError> {
+    if pipeline_format == Some(view_format) {
+        return Ok(());
+    }
+    Err(DepthFormatMismatchError {
+        pipeline_type: Some(core::any::type_name::<M>()),
+        expected: pipeline_format,
+        actual: view_format,
+    })
+}
+
 pub fn init_material_2d_pipeline<M: Material2d>(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     render_device: Res<RenderDevice>,
+    render_adapter: Res<RenderAdapter>,
     mesh_2d_pipeline: Res<Mesh2dPipeline>,
 ) {
     let material2d_layout = M::bind_group_layout_descriptor(&render_device);
@@ -488,6 +1290,7 @@ pub fn init_material_2d_pipeline<M: Material2d>(
     commands.insert_resource(Material2dPipeline::<M> {
         mesh2d_pipeline: mesh_2d_pipeline.clone(),
         material2d_layout,
+        view_layout: M::view_layout(),
         vertex_shader: match M::vertex_shader() {
             ShaderRef::Default => None,
             ShaderRef::Handle(handle) => Some(handle),
@@ -498,18 +1301,163 @@ pub fn init_material_2d_pipeline<M: Material2d>(
             ShaderRef::Handle(handle) => Some(handle),
             ShaderRef::Path(path) => Some(asset_server.load(path)),
         },
+        prepass_fragment_shader: match M::prepass_fragment_shader() {
+            ShaderRef::Default => None,
+            ShaderRef::Handle(handle) => Some(handle),
+            ShaderRef::Path(path) => Some(asset_server.load(path)),
+        },
+        anisotropic_filtering_supported: render_adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(DownlevelFlags::ANISOTROPIC_FILTERING),
         marker: PhantomData,
     });
 }
 
-pub(super) type DrawMaterial2d<M> = (
+pub(super) type DrawMaterial2d<M, C = (), V = ()> = (
     SetItemPipeline,
     SetMesh2dViewBindGroup<0>,
     SetMesh2dBindGroup<1>,
     SetMaterial2dBindGroup<M, MATERIAL_2D_BIND_GROUP_INDEX>,
+    V,
     DrawMesh2d,
+    C,
 );
 
+/// The draw function of a [`Material2d`] drawn with [`instancing`](Material2d::instanced).
+pub(super) type DrawMaterial2dInstanced<M, C = (), V = ()> = (
+    SetItemPipeline,
+    SetMesh2dViewBindGroup<0>,
+    SetMesh2dBindGroup<1>,
+    SetMaterial2dBindGroup<M, MATERIAL_2D_BIND_GROUP_INDEX>,
+    V,
+    DrawMeshInstanced2d<M>,
+    C,
+);
+
+/// The draw functions of a [`Material2d`] in each 2D phase, including the render command suffix
+/// its [`Material2dPlugin`] was added with.
+#[derive(Resource)]
+pub struct Material2dDrawFunctions<M: Material2d> {
+    pub opaque: DrawFunctionId,
+    pub alpha_mask: DrawFunctionId,
+    pub transparent: DrawFunctionId,
+    /// The draw function of the depth prepass, which doesn't run the render command suffix.
+    pub prepass: DrawFunctionId,
+    marker: PhantomData<M>,
+}
+
+/// Adds the draw functions of `M`, with the view render command `V` and followed by the render
+/// command `C`, to the 2D phases.
+fn add_material2d_render_commands<M: Material2d, C, V>(render_app: &mut SubApp)
+where
+    C: RenderCommand<Opaque2d>
+        + RenderCommand<AlphaMask2d>
+        + RenderCommand<Transparent2d>
+        + Send
+        + Sync
+        + 'static,
+    <C as RenderCommand<Opaque2d>>::Param: ReadOnlySystemParam,
+    <C as RenderCommand<AlphaMask2d>>::Param: ReadOnlySystemParam,
+    <C as RenderCommand<Transparent2d>>::Param: ReadOnlySystemParam,
+    V: RenderCommand<Opaque2d>
+        + RenderCommand<AlphaMask2d>
+        + RenderCommand<Transparent2d>
+        + RenderCommand<Prepass2d>
+        + Send
+        + Sync
+        + 'static,
+    <V as RenderCommand<Opaque2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<AlphaMask2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<Transparent2d>>::Param: ReadOnlySystemParam,
+    <V as RenderCommand<Prepass2d>>::Param: ReadOnlySystemParam,
+{
+    render_app.init_resource::<DrawFunctions<Prepass2d>>();
+    if M::instanced() {
+        add_material2d_draw_functions::<
+            M,
+            DrawMaterial2dInstanced<M, C, V>,
+            DrawMaterial2dInstanced<M, (), V>,
+        >(render_app);
+    } else {
+        add_material2d_draw_functions::<M, DrawMaterial2d<M, C, V>, DrawMaterial2d<M, (), V>>(
+            render_app,
+        );
+    }
+}
+
+/// Adds the render command `D` to the 2D phases, and `P` to the [`Prepass2d`] phase, as the draw
+/// functions of `M`.
+fn add_material2d_draw_functions<M: Material2d, D, P>(render_app: &mut SubApp)
+where
+    D: RenderCommand<Opaque2d>
+        + RenderCommand<AlphaMask2d>
+        + RenderCommand<Transparent2d>
+        + Send
+        + Sync
+        + 'static,
+    <D as RenderCommand<Opaque2d>>::Param: ReadOnlySystemParam,
+    <D as RenderCommand<AlphaMask2d>>::Param: ReadOnlySystemParam,
+    <D as RenderCommand<Transparent2d>>::Param: ReadOnlySystemParam,
+    P: RenderCommand<Prepass2d> + Send + Sync + 'static,
+    <P as RenderCommand<Prepass2d>>::Param: ReadOnlySystemParam,
+{
+    render_app
+        .add_render_command::<Opaque2d, D>()
+        .add_render_command::<AlphaMask2d, D>()
+        .add_render_command::<Transparent2d, D>()
+        .add_render_command::<Prepass2d, P>();
+    let world = render_app.world();
+    let draw_functions = Material2dDrawFunctions::<M> {
+        opaque: world.resource::<DrawFunctions<Opaque2d>>().read().id::<D>(),
+        alpha_mask: world
+            .resource::<DrawFunctions<AlphaMask2d>>()
+            .read()
+            .id::<D>(),
+        transparent: world
+            .resource::<DrawFunctions<Transparent2d>>()
+            .read()
+            .id::<D>(),
+        prepass: world
+            .resource::<DrawFunctions<Prepass2d>>()
+            .read()
+            .id::<P>(),
+        marker: PhantomData,
+    };
+    render_app.insert_resource(draw_functions);
+}
+
+/// Appends the given attributes of the mesh to the mesh vertex buffer of a mesh2d pipeline
+/// descriptor, for [`Material2d::specialize`] implementations whose vertex shader reads more
+/// attributes than the default one. See [`Material2d::vertex_attributes`].
+///
+/// An attribute replaces the one the descriptor already reads at the same shader location.
+/// Returns [`SpecializedMeshPipelineError::MissingVertexAttribute`] if the mesh lacks one of the
+/// attributes.
+pub fn push_mesh2d_vertex_attributes(
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayoutRef,
+    attributes: &[VertexAttributeDescriptor],
+) -> Result<(), SpecializedMeshPipelineError> {
+    if attributes.is_empty() {
+        return Ok(());
+    }
+    let required = layout.0.get_layout(attributes)?;
+    // The mesh vertex buffer is the first one, before any instance buffer.
+    let Some(mesh_buffer) = descriptor.vertex.buffers.first_mut() else {
+        descriptor.vertex.buffers.push(required);
+        return Ok(());
+    };
+    mesh_buffer.attributes.retain(|attribute| {
+        required
+            .attributes
+            .iter()
+            .all(|required| required.shader_location != attribute.shader_location)
+    });
+    mesh_buffer.attributes.extend(required.attributes);
+    Ok(())
+}
+
 pub struct SetMaterial2dBindGroup<M: Material2d, const I: usize>(PhantomData<M>);
 impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
     for SetMaterial2dBindGroup<M, I>
@@ -518,13 +1466,13 @@ impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
         SRes<RenderAssets<PreparedMaterial2d<M>>>,
         SRes<RenderMaterial2dInstances<M>>,
     );
-    type ViewQuery = ();
+    type ViewQuery = Read<ExtractedView>;
     type ItemQuery = ();
 
     #[inline]
     fn render<'w>(
         item: &P,
-        _view: (),
+        view: ROQueryItem<'w, '_, Self::ViewQuery>,
         _item_query: Option<()>,
         (materials, material_instances): SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
@@ -537,7 +1485,13 @@ impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
         let Some(material2d) = materials.get(*material_instance) else {
             return RenderCommandResult::Skip;
         };
-        pass.set_bind_group(I, &material2d.bind_group, &[]);
+        let bind_group = match &material2d.zoom_bind_groups {
+            Some(zoom_bind_groups) => {
+                zoom_bind_groups.get(material2d_view_scale(&view.clip_from_view, view.viewport.z))
+            }
+            None => &material2d.bind_group,
+        };
+        pass.set_bind_group(I, bind_group, &[]);
         RenderCommandResult::Success
     }
 }
@@ -546,10 +1500,51 @@ pub const fn alpha_mode_pipeline_key(alpha_mode: AlphaMode2d) -> Mesh2dPipelineK
     match alpha_mode {
         AlphaMode2d::Blend => Mesh2dPipelineKey::BLEND_ALPHA,
         AlphaMode2d::Mask(_) => Mesh2dPipelineKey::MAY_DISCARD,
+        AlphaMode2d::Premultiplied => Mesh2dPipelineKey::BLEND_PREMULTIPLIED_ALPHA,
+        AlphaMode2d::Additive => Mesh2dPipelineKey::BLEND_ADDITIVE,
+        AlphaMode2d::Multiply => Mesh2dPipelineKey::BLEND_MULTIPLY,
         _ => Mesh2dPipelineKey::NONE,
     }
 }
 
+/// Returns the blend state selected by the blend bits of `mesh_key`, see
+/// [`alpha_mode_pipeline_key`].
+pub const fn alpha_mode_blend_state(mesh_key: Mesh2dPipelineKey) -> Option<BlendState> {
+    if mesh_key.contains(Mesh2dPipelineKey::BLEND_ADDITIVE) {
+        Some(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::Zero,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+        })
+    } else if mesh_key.contains(Mesh2dPipelineKey::BLEND_MULTIPLY) {
+        Some(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::Dst,
+                dst_factor: BlendFactor::Zero,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::Zero,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+        })
+    } else if mesh_key.contains(Mesh2dPipelineKey::BLEND_PREMULTIPLIED_ALPHA) {
+        Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING)
+    } else if mesh_key.contains(Mesh2dPipelineKey::BLEND_ALPHA) {
+        Some(BlendState::ALPHA_BLENDING)
+    } else {
+        None
+    }
+}
+
 pub const fn tonemapping_pipeline_key(tonemapping: Tonemapping) -> Mesh2dPipelineKey {
     match tonemapping {
         Tonemapping::None => Mesh2dPipelineKey::TONEMAP_METHOD_NONE,
@@ -633,6 +1628,26 @@ pub struct SpecializedMaterial2dPipelineCache<M> {
     marker: PhantomData<M>,
 }
 
+/// The depth format each [`Material2dPipeline`] was specialized for.
+///
+/// Recorded by [`specialize_material2d_meshes`] and checked against the [`DepthFormat2d`] of each
+/// view by [`queue_material2d_meshes`].
+#[derive(Resource, Deref, DerefMut)]
+pub struct Material2dPipelineDepthFormats<M> {
+    #[deref]
+    map: HashMap<CachedRenderPipelineId, Option<TextureFormat>>,
+    marker: PhantomData<M>,
+}
+
+impl<M> Default for Material2dPipelineDepthFormats<M> {
+    fn default() -> Self {
+        Self {
+            map: HashMap::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
 /// Stores the cached render pipeline ID for each entity in a single view, as
 /// well as the last time it was changed.
 #[derive(Deref, DerefMut)]
@@ -707,6 +1722,7 @@ pub fn specialize_material2d_meshes<M: Material2d>(
     view_specialization_ticks: Res<ViewSpecializationTicks>,
     ticks: SystemChangeTick,
     mut specialized_material_pipeline_cache: ResMut<SpecializedMaterial2dPipelineCache<M>>,
+    mut depth_formats: ResMut<Material2dPipelineDepthFormats<M>>,
 ) where
     M::Data: PartialEq + Eq + Hash + Clone,
 {
@@ -758,9 +1774,18 @@ pub fn specialize_material2d_meshes<M: Material2d>(
             let Some(mesh) = render_meshes.get(mesh_instance.mesh_asset_id) else {
                 continue;
             };
-            let mesh_key = *view_key
+            let mut mesh_key = *view_key
                 | Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology())
+                | Mesh2dPipelineKey::from_strip_index_format(
+                    mesh.primitive_topology(),
+                    &mesh.buffer_info,
+                )
                 | material_2d.properties.mesh_pipeline_key_bits;
+            // Motion vectors are only written in the prepass.
+            mesh_key.remove(Mesh2dPipelineKey::MOTION_VECTOR_PREPASS);
+            if !material_2d.properties.uses_lut {
+                mesh_key.remove(Mesh2dPipelineKey::COLOR_GRADING_LUT);
+            }
 
             let pipeline_id = pipelines.specialize(
                 &pipeline_cache,
@@ -768,6 +1793,9 @@ pub fn specialize_material2d_meshes<M: Material2d>(
                 Material2dKey {
                     mesh_key,
                     bind_group_data: material_2d.key.clone(),
+                    blend_state: material_2d.properties.blend_state,
+                    depth_write_enabled: material_2d.properties.depth_write_enabled,
+                    depth_compare: material_2d.properties.depth_compare,
                 },
                 &mesh.layout,
             );
@@ -779,6 +1807,13 @@ pub fn specialize_material2d_meshes<M: Material2d>(
                     continue;
                 }
             };
+            depth_formats.entry(pipeline_id).or_insert_with(|| {
+                pipeline_cache
+                    .get_render_pipeline_descriptor(pipeline_id)
+                    .depth_stencil
+                    .as_ref()
+                    .map(|depth_stencil| depth_stencil.format)
+            });
 
             view_specialized_material_pipeline_cache
                 .insert(*visible_entity, (ticks.this_run(), pipeline_id));
@@ -786,18 +1821,128 @@ pub fn specialize_material2d_meshes<M: Material2d>(
     }
 }
 
-pub fn queue_material2d_meshes<M: Material2d>(
-    (render_meshes, render_materials): (
-        Res<RenderAssets<RenderMesh>>,
-        Res<RenderAssets<PreparedMaterial2d<M>>>,
-    ),
-    mut render_mesh_instances: ResMut<RenderMesh2dInstances>,
-    render_material_instances: Res<RenderMaterial2dInstances<M>>,
-    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
-    mut opaque_render_phases: ResMut<ViewBinnedRenderPhases<Opaque2d>>,
+/// Pipelines to compile for a [`Material2d`] before any entity using them is drawn.
+///
+/// Specializing a pipeline the first time a material is used compiles it on the render thread,
+/// which can cause a hitch. Requests pushed here, usually through
+/// [`Material2dPrewarmAppExt::prewarm_material2d`], are specialized by
+/// [`prewarm_material2d_pipelines`] on the next frame. The resulting pipelines are cached in
+/// [`SpecializedMeshPipelines`], so entities with a matching key reuse them.
+#[derive(Resource)]
+pub struct Material2dPrewarmQueue<M: Material2d>(Vec<(Material2dKey<M>, MeshVertexBufferLayout)>);
+
+impl<M: Material2d> Default for Material2dPrewarmQueue<M> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<M: Material2d> Material2dPrewarmQueue<M> {
+    /// Queues the pipeline for `key` and a mesh with the given vertex layout to be compiled.
+    pub fn push(&mut self, key: Material2dKey<M>, layout: MeshVertexBufferLayout) {
+        self.0.push((key, layout));
+    }
+
+    /// Returns the number of queued pipelines.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no pipelines are queued.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Extension trait for compiling [`Material2d`] pipelines ahead of time.
+pub trait Material2dPrewarmAppExt {
+    /// Queues the pipeline for `key` and a mesh with the given vertex layout to be compiled before
+    /// it is first used. The layout can be obtained with [`Mesh::get_mesh_vertex_buffer_layout`].
+    ///
+    /// Does nothing if there is no [`RenderApp`].
+    ///
+    /// [`Mesh::get_mesh_vertex_buffer_layout`]: bevy_mesh::Mesh::get_mesh_vertex_buffer_layout
+    fn prewarm_material2d<M: Material2d>(
+        &mut self,
+        key: Material2dKey<M>,
+        layout: MeshVertexBufferLayout,
+    ) -> &mut Self;
+}
+
+impl Material2dPrewarmAppExt for App {
+    fn prewarm_material2d<M: Material2d>(
+        &mut self,
+        key: Material2dKey<M>,
+        layout: MeshVertexBufferLayout,
+    ) -> &mut Self {
+        if let Some(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app
+                .world_mut()
+                .get_resource_or_init::<Material2dPrewarmQueue<M>>()
+                .push(key, layout);
+        }
+        self
+    }
+}
+
+/// Specializes the pipeline for `key` and `layout`, queuing it for compilation in the
+/// [`PipelineCache`] if it isn't already cached.
+pub fn prewarm_material2d<M: Material2d>(
+    pipeline_cache: &PipelineCache,
+    pipelines: &mut SpecializedMeshPipelines<Material2dPipeline<M>>,
+    material2d_pipeline: &Material2dPipeline<M>,
+    key: Material2dKey<M>,
+    layout: &MeshVertexBufferLayoutRef,
+) -> Result<CachedRenderPipelineId, SpecializedMeshPipelineError>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    pipelines.specialize(pipeline_cache, material2d_pipeline, key, layout)
+}
+
+/// Specializes the pipelines requested through [`Material2dPrewarmQueue`].
+pub fn prewarm_material2d_pipelines<M: Material2d>(
+    mut queue: ResMut<Material2dPrewarmQueue<M>>,
+    material2d_pipeline: Res<Material2dPipeline<M>>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<Material2dPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut layouts: ResMut<MeshVertexBufferLayouts>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    for (key, layout) in queue.0.drain(..) {
+        let layout = layouts.insert(layout);
+        if let Err(err) = prewarm_material2d(
+            &pipeline_cache,
+            &mut pipelines,
+            &material2d_pipeline,
+            key,
+            &layout,
+        ) {
+            error!("{}", err);
+        }
+    }
+}
+
+pub fn queue_material2d_meshes<M: Material2d>(
+    (render_meshes, render_materials): (
+        Res<RenderAssets<RenderMesh>>,
+        Res<RenderAssets<PreparedMaterial2d<M>>>,
+    ),
+    mut render_mesh_instances: ResMut<RenderMesh2dInstances>,
+    render_material_instances: Res<RenderMaterial2dInstances<M>>,
+    mut transparent_render_phases: ResMut<ViewSortedRenderPhases<Transparent2d>>,
+    mut opaque_render_phases: ResMut<ViewBinnedRenderPhases<Opaque2d>>,
     mut alpha_mask_render_phases: ResMut<ViewBinnedRenderPhases<AlphaMask2d>>,
-    views: Query<(&MainEntity, &ExtractedView, &RenderVisibleEntities)>,
+    views: Query<(
+        &MainEntity,
+        &ExtractedView,
+        &RenderVisibleEntities,
+        Option<&DepthFormat2d>,
+    )>,
     specialized_material_pipeline_cache: ResMut<SpecializedMaterial2dPipelineCache<M>>,
+    depth_formats: Res<Material2dPipelineDepthFormats<M>>,
+    instance_buffer: Res<Material2dInstanceBuffer<M>>,
 ) where
     M::Data: PartialEq + Eq + Hash + Clone,
 {
@@ -805,12 +1950,13 @@ pub fn queue_material2d_meshes<M: Material2d>(
         return;
     }
 
-    for (view_entity, view, visible_entities) in &views {
+    for (view_entity, view, visible_entities, view_depth_format) in &views {
         let Some(view_specialized_material_pipeline_cache) =
             specialized_material_pipeline_cache.get(view_entity)
         else {
             continue;
         };
+        let view_depth_format = view_depth_format.copied().unwrap_or_default();
 
         let Some(transparent_phase) = transparent_render_phases.get_mut(&view.retained_view_entity)
         else {
@@ -832,6 +1978,15 @@ pub fn queue_material2d_meshes<M: Material2d>(
                 continue;
             };
 
+            // Instanced meshes are drawn by the first mesh of their group.
+            if M::instanced()
+                && instance_buffer
+                    .batch(view.retained_view_entity, *visible_entity)
+                    .is_none()
+            {
+                continue;
+            }
+
             // Skip the entity if it's cached in a bin and up to date.
             if opaque_phase.validate_cached_entity(*visible_entity, current_change_tick)
                 || alpha_mask_phase.validate_cached_entity(*visible_entity, current_change_tick)
@@ -839,6 +1994,17 @@ pub fn queue_material2d_meshes<M: Material2d>(
                 continue;
             }
 
+            // Drawing with a pipeline built for another depth format fails GPU validation.
+            if let Some(pipeline_depth_format) = depth_formats.get(&pipeline_id)
+                && let Err(err) = validate_material2d_depth_format::<M>(
+                    *pipeline_depth_format,
+                    *view_depth_format,
+                )
+            {
+                once!(warn!("{err}. Skipping {visible_entity:?}."));
+                continue;
+            }
+
             let Some(material_asset_id) = render_material_instances.get(visible_entity) else {
                 continue;
             };
@@ -853,21 +2019,22 @@ pub fn queue_material2d_meshes<M: Material2d>(
             };
 
             mesh_instance.material_bind_group_id = material_2d.get_bind_group_id();
-            let mesh_z = mesh_instance.transforms.world_from_local.translation.z;
 
             // We don't support multidraw yet for 2D meshes, so we use this
             // custom logic to generate the `BinnedRenderPhaseType` instead of
             // `BinnedRenderPhaseType::mesh`, which can return
             // `BinnedRenderPhaseType::MultidrawableMesh` if the hardware
             // supports multidraw.
-            let binned_render_phase_type = if mesh_instance.automatic_batching {
+            // Instanced meshes draw their whole group, so they can't be batched with each other.
+            let binned_render_phase_type = if mesh_instance.automatic_batching && !M::instanced() {
                 BinnedRenderPhaseType::BatchableMesh
             } else {
                 BinnedRenderPhaseType::UnbatchableMesh
             };
 
+            let sorted = material_2d.properties.is_transparent();
             match material_2d.properties.alpha_mode {
-                AlphaMode2d::Opaque => {
+                AlphaMode2d::Opaque if !sorted => {
                     let bin_key = Opaque2dBinKey {
                         pipeline: pipeline_id,
                         draw_function: material_2d.properties.draw_function_id,
@@ -885,7 +2052,7 @@ pub fn queue_material2d_meshes<M: Material2d>(
                         current_change_tick,
                     );
                 }
-                AlphaMode2d::Mask(_) => {
+                AlphaMode2d::Mask(_) if !sorted => {
                     let bin_key = AlphaMask2dBinKey {
                         pipeline: pipeline_id,
                         draw_function: material_2d.properties.draw_function_id,
@@ -903,16 +2070,20 @@ pub fn queue_material2d_meshes<M: Material2d>(
                         current_change_tick,
                     );
                 }
-                AlphaMode2d::Blend => {
+                // Materials that ignore depth or blend in a custom way are drawn after all opaque
+                // and alpha mask meshes.
+                AlphaMode2d::Blend
+                | AlphaMode2d::Premultiplied
+                | AlphaMode2d::Additive
+                | AlphaMode2d::Multiply
+                | AlphaMode2d::Opaque
+                | AlphaMode2d::Mask(_) => {
                     transparent_phase.add(Transparent2d {
                         entity: (*render_entity, *visible_entity),
                         draw_function: material_2d.properties.draw_function_id,
                         pipeline: pipeline_id,
-                        // NOTE: Back-to-front ordering for transparent with ascending sort means far should have the
-                        // lowest sort key and getting closer should increase. As we have
-                        // -z in front of the camera, the largest distance is -far with values increasing toward the
-                        // camera. As such we can just use mesh_z as the distance
-                        sort_key: FloatOrd(mesh_z + material_2d.properties.depth_bias),
+                        sort_key: mesh_instance
+                            .transparent_sort_key(material_2d.properties.depth_bias),
                         // Batching is done in batch_and_prepare_render_phase
                         batch_range: 0..1,
                         extra_index: PhaseItemExtraIndex::None,
@@ -925,10 +2096,168 @@ pub fn queue_material2d_meshes<M: Material2d>(
     }
 }
 
+/// Queues the meshes of `M` that are drawn in the [`Opaque2d`] phase into the depth-only
+/// [`Prepass2d`] phase.
+///
+/// Views only have a [`Prepass2d`] phase when the
+/// [`Prepass2dPlugin`](bevy_core_pipeline::core_2d::Prepass2dPlugin) is added.
+pub fn queue_material2d_prepass<M: Material2d>(
+    material2d_pipeline: Res<Material2dPipeline<M>>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<Material2dPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    (render_meshes, render_materials): (
+        Res<RenderAssets<RenderMesh>>,
+        Res<RenderAssets<PreparedMaterial2d<M>>>,
+    ),
+    render_mesh_instances: Res<RenderMesh2dInstances>,
+    render_material_instances: Res<RenderMaterial2dInstances<M>>,
+    mut prepass_render_phases: ResMut<ViewBinnedRenderPhases<Prepass2d>>,
+    views: Query<(
+        &MainEntity,
+        &ExtractedView,
+        &RenderVisibleEntities,
+        Option<&DepthFormat2d>,
+    )>,
+    view_key_cache: Res<ViewKeyCache>,
+    specialized_material_pipeline_cache: Res<SpecializedMaterial2dPipelineCache<M>>,
+    depth_formats: Res<Material2dPipelineDepthFormats<M>>,
+    instance_buffer: Res<Material2dInstanceBuffer<M>>,
+    draw_functions: Res<Material2dDrawFunctions<M>>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    if render_material_instances.is_empty() {
+        return;
+    }
+
+    for (view_entity, view, visible_entities, view_depth_format) in &views {
+        let Some(prepass_phase) = prepass_render_phases.get_mut(&view.retained_view_entity) else {
+            continue;
+        };
+        let Some(view_specialized_material_pipeline_cache) =
+            specialized_material_pipeline_cache.get(view_entity)
+        else {
+            continue;
+        };
+        let Some(view_key) = view_key_cache.get(view_entity) else {
+            continue;
+        };
+        let view_depth_format = view_depth_format.copied().unwrap_or_default();
+
+        for (render_entity, visible_entity) in visible_entities.iter::<Mesh2d>() {
+            let Some((current_change_tick, pipeline_id)) = view_specialized_material_pipeline_cache
+                .get(visible_entity)
+                .map(|(current_change_tick, pipeline_id)| (*current_change_tick, *pipeline_id))
+            else {
+                continue;
+            };
+
+            // Instanced meshes are drawn by the first mesh of their group.
+            if M::instanced()
+                && instance_buffer
+                    .batch(view.retained_view_entity, *visible_entity)
+                    .is_none()
+            {
+                continue;
+            }
+
+            // The prepass is re-queued whenever the main pipeline of the entity is re-specialized.
+            if prepass_phase.validate_cached_entity(*visible_entity, current_change_tick) {
+                continue;
+            }
+
+            // `queue_material2d_meshes` already warns about entities with a mismatched depth
+            // format.
+            if let Some(pipeline_depth_format) = depth_formats.get(&pipeline_id)
+                && validate_material2d_depth_format::<M>(*pipeline_depth_format, *view_depth_format)
+                    .is_err()
+            {
+                continue;
+            }
+
+            let Some(material_asset_id) = render_material_instances.get(visible_entity) else {
+                continue;
+            };
+            let Some(mesh_instance) = render_mesh_instances.get(visible_entity) else {
+                continue;
+            };
+            let Some(material_2d) = render_materials.get(*material_asset_id) else {
+                continue;
+            };
+            if !material_2d.properties.draws_in_prepass() {
+                continue;
+            }
+            let Some(mesh) = render_meshes.get(mesh_instance.mesh_asset_id) else {
+                continue;
+            };
+
+            let mut mesh_key = *view_key
+                | Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology())
+                | Mesh2dPipelineKey::from_strip_index_format(
+                    mesh.primitive_topology(),
+                    &mesh.buffer_info,
+                )
+                | material_2d.properties.mesh_pipeline_key_bits
+                | Mesh2dPipelineKey::DEPTH_PREPASS;
+            mesh_key.remove(Mesh2dPipelineKey::COLOR_GRADING_LUT);
+            if material_2d.properties.writes_motion_vectors {
+                mesh_key |= Mesh2dPipelineKey::WRITES_MOTION_VECTORS;
+            }
+
+            let prepass_pipeline_id = match pipelines.specialize(
+                &pipeline_cache,
+                &material2d_pipeline,
+                Material2dKey {
+                    mesh_key,
+                    bind_group_data: material_2d.key.clone(),
+                    blend_state: material_2d.properties.blend_state,
+                    depth_write_enabled: material_2d.properties.depth_write_enabled,
+                    depth_compare: material_2d.properties.depth_compare,
+                },
+                &mesh.layout,
+            ) {
+                Ok(id) => id,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            };
+
+            // See `queue_material2d_meshes`.
+            let binned_render_phase_type = if mesh_instance.automatic_batching && !M::instanced() {
+                BinnedRenderPhaseType::BatchableMesh
+            } else {
+                BinnedRenderPhaseType::UnbatchableMesh
+            };
+
+            prepass_phase.add(
+                BatchSetKey2d {
+                    indexed: mesh.indexed(),
+                },
+                Opaque2dBinKey {
+                    pipeline: prepass_pipeline_id,
+                    draw_function: draw_functions.prepass,
+                    asset_id: mesh_instance.mesh_asset_id.into(),
+                    material_bind_group_id: material_2d.get_bind_group_id().0,
+                },
+                (*render_entity, *visible_entity),
+                InputUniformIndex::default(),
+                binned_render_phase_type,
+                current_change_tick,
+            );
+        }
+    }
+}
+
+/// The key that meshes are batched by, derived from their material.
+///
+/// This is the id of the material's bind group, or, for materials with a
+/// [`Material2d::content_hash`], the id shared by every material with that hash.
 #[derive(Component, Clone, Copy, Default, PartialEq, Eq, Deref, DerefMut)]
 pub struct Material2dBindGroupId(pub Option<BindGroupId>);
 
 /// Common [`Material2d`] properties, calculated for a specific material instance.
+#[derive(Clone)]
 pub struct Material2dProperties {
     /// The [`AlphaMode2d`] of this material.
     pub alpha_mode: AlphaMode2d,
@@ -942,86 +2271,2056 @@ pub struct Material2dProperties {
     /// [`queue_material2d_meshes`].
     pub mesh_pipeline_key_bits: Mesh2dPipelineKey,
     pub draw_function_id: DrawFunctionId,
+    /// Whether this material writes motion vectors in the prepass of views with a target for them.
+    pub writes_motion_vectors: bool,
+    /// Whether depth clipping is disabled for this material.
+    pub unclipped_depth: bool,
+    /// Whether this material applies the view's color grading LUT.
+    pub uses_lut: bool,
+    /// Whether this material neither tests nor writes depth.
+    pub ignore_depth: bool,
+    /// The [`Material2d::blend_state`] of this material.
+    pub blend_state: Option<BlendState>,
+    /// The [`Material2d::depth_write_enabled`] override of this material.
+    pub depth_write_enabled: Option<bool>,
+    /// The [`Material2d::depth_compare`] override of this material.
+    pub depth_compare: Option<CompareFunction>,
+}
+
+impl Material2dProperties {
+    /// Whether meshes with this material are also drawn in the depth-only [`Prepass2d`] phase,
+    /// which is the case for the meshes drawn in the [`Opaque2d`] phase.
+    pub fn draws_in_prepass(&self) -> bool {
+        matches!(self.alpha_mode, AlphaMode2d::Opaque) && !self.is_transparent()
+    }
+
+    /// Whether meshes with this material are drawn in the [`Transparent2d`] phase, sorted back to
+    /// front by their `sort_key`, rather than binned in the [`Opaque2d`] or [`AlphaMask2d`] phase.
+    pub fn is_transparent(&self) -> bool {
+        self.ignore_depth
+            || self.blend_state.is_some()
+            || self.depth_write_enabled == Some(false)
+            || matches!(
+                self.alpha_mode,
+                AlphaMode2d::Blend
+                    | AlphaMode2d::Premultiplied
+                    | AlphaMode2d::Additive
+                    | AlphaMode2d::Multiply
+            )
+    }
 }
 
 /// Data prepared for a [`Material2d`] instance.
 pub struct PreparedMaterial2d<T: Material2d> {
     pub bindings: BindingResources,
     pub bind_group: BindGroup,
+    /// The id meshes using this material are batched by, see [`Material2dBindGroupId`].
+    pub batch_id: BindGroupId,
+    /// The per-filter bind groups used in place of `bind_group` when the material has a
+    /// [`ZoomFilter`].
+    pub zoom_bind_groups: Option<Material2dZoomBindGroups>,
     pub key: T::Data,
     pub properties: Material2dProperties,
 }
 
 impl<T: Material2d> PreparedMaterial2d<T> {
     pub fn get_bind_group_id(&self) -> Material2dBindGroupId {
-        Material2dBindGroupId(Some(self.bind_group.id()))
+        Material2dBindGroupId(Some(self.batch_id))
+    }
+
+    /// Returns a copy of this material with the given variant `key`, sharing its GPU resources.
+    fn share(&self, key: T::Data) -> Self {
+        let bindings = self
+            .bindings
+            .iter()
+            .map(|(index, binding)| {
+                let binding = match binding {
+                    OwnedBindingResource::Buffer(buffer) => {
+                        OwnedBindingResource::Buffer(buffer.clone())
+                    }
+                    OwnedBindingResource::TextureView(dimension, view) => {
+                        OwnedBindingResource::TextureView(*dimension, view.clone())
+                    }
+                    OwnedBindingResource::Sampler(ty, sampler) => {
+                        OwnedBindingResource::Sampler(*ty, sampler.clone())
+                    }
+                    OwnedBindingResource::Data(data) => {
+                        OwnedBindingResource::Data(OwnedData(data.0.clone()))
+                    }
+                };
+                (*index, binding)
+            })
+            .collect();
+        Self {
+            bindings: BindingResources(bindings),
+            bind_group: self.bind_group.clone(),
+            batch_id: self.batch_id,
+            zoom_bind_groups: self.zoom_bind_groups.clone(),
+            key,
+            properties: self.properties.clone(),
+        }
     }
 }
 
-impl<M: Material2d> RenderAsset for PreparedMaterial2d<M> {
-    type SourceAsset = M;
+/// Materials whose bind groups are currently built from their
+/// [`Material2d::loading_fallback`], keyed by the asset they stand in for.
+///
+/// Each frame, [`retry_material2d_loading_fallbacks`] queues these materials to be prepared again,
+/// so that the fallback is replaced as soon as the real textures have loaded.
+#[derive(Resource, Deref, DerefMut)]
+pub struct Material2dLoadingFallbacks<M: Material2d>(HashMap<AssetId<M>, M>);
 
-    type Param = (
-        SRes<RenderDevice>,
-        SRes<PipelineCache>,
-        SRes<Material2dPipeline<M>>,
-        SRes<DrawFunctions<Opaque2d>>,
-        SRes<DrawFunctions<AlphaMask2d>>,
-        SRes<DrawFunctions<Transparent2d>>,
-        M::Param,
-    );
+impl<M: Material2d> Default for Material2dLoadingFallbacks<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
 
-    fn prepare_asset(
-        material: Self::SourceAsset,
-        _: AssetId<Self::SourceAsset>,
-        (
-            render_device,
-            pipeline_cache,
-            pipeline,
-            opaque_draw_functions,
-            alpha_mask_draw_functions,
-            transparent_draw_functions,
-            material_param,
-        ): &mut SystemParamItem<Self::Param>,
-        _: Option<&Self>,
-    ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
-        let bind_group_data = material.bind_group_data();
-        match material.as_bind_group(
-            &pipeline.material2d_layout,
-            render_device,
-            pipeline_cache,
-            material_param,
-        ) {
-            Ok(prepared) => {
-                let mut mesh_pipeline_key_bits = Mesh2dPipelineKey::empty();
-                mesh_pipeline_key_bits.insert(alpha_mode_pipeline_key(material.alpha_mode()));
+/// The batching ids of materials with a [`Material2d::content_hash`].
+///
+/// The first material prepared with a given hash lends the id of its bind group to every other
+/// material with the same hash, until all of them have been unloaded or changed to a different hash.
+#[derive(Resource)]
+pub struct Material2dBindGroupIds<M: Material2d> {
+    by_hash: HashMap<u64, (BindGroupId, usize)>,
+    by_asset: HashMap<AssetId<M>, u64>,
+}
 
-                let draw_function_id = match material.alpha_mode() {
-                    AlphaMode2d::Opaque => opaque_draw_functions.read().id::<DrawMaterial2d<M>>(),
-                    AlphaMode2d::Mask(_) => {
-                        alpha_mask_draw_functions.read().id::<DrawMaterial2d<M>>()
-                    }
-                    AlphaMode2d::Blend => {
-                        transparent_draw_functions.read().id::<DrawMaterial2d<M>>()
-                    }
-                };
+impl<M: Material2d> Default for Material2dBindGroupIds<M> {
+    fn default() -> Self {
+        Self {
+            by_hash: Default::default(),
+            by_asset: Default::default(),
+        }
+    }
+}
 
-                Ok(PreparedMaterial2d {
-                    bindings: prepared.bindings,
-                    bind_group: prepared.bind_group,
-                    key: bind_group_data,
-                    properties: Material2dProperties {
-                        depth_bias: material.depth_bias(),
-                        alpha_mode: material.alpha_mode(),
-                        mesh_pipeline_key_bits,
-                        draw_function_id,
-                    },
-                })
+impl<M: Material2d> Material2dBindGroupIds<M> {
+    /// Returns the batching id of the material `id` with the given content `hash`, whose newly
+    /// built bind group has the id `bind_group`.
+    pub fn acquire(&mut self, id: AssetId<M>, hash: u64, bind_group: BindGroupId) -> BindGroupId {
+        if self.by_asset.get(&id) == Some(&hash)
+            && let Some((shared, _)) = self.by_hash.get(&hash)
+        {
+            return *shared;
+        }
+        self.release(id);
+        let (shared, count) = self.by_hash.entry(hash).or_insert((bind_group, 0));
+        *count += 1;
+        self.by_asset.insert(id, hash);
+        *shared
+    }
+
+    /// Stops the material `id` from sharing a batching id with other materials.
+    pub fn release(&mut self, id: AssetId<M>) {
+        let Some(hash) = self.by_asset.remove(&id) else {
+            return;
+        };
+        if let Some((_, count)) = self.by_hash.get_mut(&hash) {
+            *count -= 1;
+            if *count == 0 {
+                self.by_hash.remove(&hash);
             }
-            Err(AsBindGroupError::RetryNextUpdate) => {
-                Err(PrepareAssetError::RetryNextUpdate(material))
+        }
+    }
+}
+
+/// The materials prepared from a [`Material2d::loading_fallback`], so that their bind groups are
+/// built once rather than every frame the real material is retried.
+///
+/// Fallbacks with a [`Material2d::content_hash`] are shared by every material of the type whose
+/// fallback has that hash, others are cached for the asset they stand in for.
+#[derive(Resource)]
+pub struct Material2dFallbackCache<M: Material2d> {
+    shared: HashMap<u64, (PreparedMaterial2d<M>, usize)>,
+    by_asset: HashMap<AssetId<M>, u64>,
+    unique: HashMap<AssetId<M>, PreparedMaterial2d<M>>,
+}
+
+impl<M: Material2d> Default for Material2dFallbackCache<M> {
+    fn default() -> Self {
+        Self {
+            shared: Default::default(),
+            by_asset: Default::default(),
+            unique: Default::default(),
+        }
+    }
+}
+
+impl<M: Material2d> Material2dFallbackCache<M> {
+    /// Returns the cached fallback of the material `id`, whose fallback has the content `hash` and
+    /// the variant `key`.
+    pub fn get(
+        &mut self,
+        id: AssetId<M>,
+        hash: Option<u64>,
+        key: M::Data,
+    ) -> Option<PreparedMaterial2d<M>> {
+        let Some(hash) = hash else {
+            return self.unique.get(&id).map(|prepared| prepared.share(key));
+        };
+        if !self.shared.contains_key(&hash) {
+            return None;
+        }
+        if self.by_asset.get(&id) != Some(&hash) {
+            self.release(id);
+            self.by_asset.insert(id, hash);
+            if let Some((_, count)) = self.shared.get_mut(&hash) {
+                *count += 1;
             }
-            Err(other) => Err(PrepareAssetError::AsBindGroupError(other)),
         }
+        self.shared
+            .get(&hash)
+            .map(|(prepared, _)| prepared.share(key))
+    }
+
+    /// Caches the fallback `prepared` for the material `id`, returning a copy of it.
+    pub fn insert(
+        &mut self,
+        id: AssetId<M>,
+        hash: Option<u64>,
+        prepared: PreparedMaterial2d<M>,
+        key: M::Data,
+    ) -> PreparedMaterial2d<M> {
+        self.release(id);
+        let copy = prepared.share(key);
+        match hash {
+            Some(hash) => {
+                self.shared.entry(hash).or_insert((prepared, 0)).1 += 1;
+                self.by_asset.insert(id, hash);
+            }
+            None => {
+                self.unique.insert(id, prepared);
+            }
+        }
+        copy
+    }
+
+    /// Drops the fallback of the material `id`, once it is ready, changed or unloaded.
+    pub fn release(&mut self, id: AssetId<M>) {
+        self.unique.remove(&id);
+        let Some(hash) = self.by_asset.remove(&id) else {
+            return;
+        };
+        if let Some((_, count)) = self.shared.get_mut(&hash) {
+            *count -= 1;
+            if *count == 0 {
+                self.shared.remove(&hash);
+            }
+        }
+    }
+}
+
+/// Moves materials prepared with a [`Material2d::loading_fallback`] back into
+/// [`ExtractedAssets`], unless the asset was changed or removed this frame.
+pub fn retry_material2d_loading_fallbacks<M: Material2d>(
+    mut fallbacks: ResMut<Material2dLoadingFallbacks<M>>,
+    mut fallback_cache: ResMut<Material2dFallbackCache<M>>,
+    mut extracted_assets: ResMut<ExtractedAssets<PreparedMaterial2d<M>>>,
+) {
+    // A changed material may have a different fallback.
+    for id in extracted_assets
+        .added
+        .iter()
+        .chain(&extracted_assets.modified)
+    {
+        fallback_cache.release(*id);
+    }
+    for (id, material) in fallbacks.drain() {
+        if extracted_assets.removed.contains(&id)
+            || extracted_assets.added.contains(&id)
+            || extracted_assets.modified.contains(&id)
+        {
+            continue;
+        }
+        extracted_assets.extracted.push((id, material));
+    }
+}
+
+/// Chooses a [`Material2d`]'s texture filtering from the scale of the view it is drawn in.
+///
+/// The scale is the number of world units covered by one physical pixel of the view, as computed
+/// by [`material2d_view_scale`]. For a 2D camera on a display with a scale factor of 1, this is
+/// the [`OrthographicProjection::scale`](bevy_camera::OrthographicProjection::scale).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomFilter {
+    /// Views with a scale up to and including this value use [`ZoomFilter::zoomed_in`].
+    pub threshold: f32,
+    /// The filter mode used when zoomed in, at or below the threshold.
+    pub zoomed_in: ImageFilterMode,
+    /// The filter mode used when zoomed out, above the threshold.
+    pub zoomed_out: ImageFilterMode,
+}
+
+impl ZoomFilter {
+    /// Nearest filtering at a scale of one world unit per pixel or closer, and linear filtering
+    /// when zoomed out further.
+    pub const PIXEL_ART: Self = Self {
+        threshold: 1.0,
+        zoomed_in: ImageFilterMode::Nearest,
+        zoomed_out: ImageFilterMode::Linear,
+    };
+
+    /// Returns the filter mode for a view with the given `scale`.
+    pub fn filter_for(&self, scale: f32) -> ImageFilterMode {
+        if scale <= self.threshold {
+            self.zoomed_in
+        } else {
+            self.zoomed_out
+        }
+    }
+}
+
+/// Returns the number of world units covered by one physical pixel of a view, derived from its
+/// projection and the width of its viewport.
+///
+/// This is only meaningful for orthographic projections.
+pub fn material2d_view_scale(clip_from_view: &Mat4, viewport_width: u32) -> f32 {
+    2.0 / (clip_from_view.x_axis.x * viewport_width as f32)
+}
+
+/// The bind groups of a material with a [`ZoomFilter`], one per filter mode.
+#[derive(Clone)]
+pub struct Material2dZoomBindGroups {
+    pub filter: ZoomFilter,
+    pub zoomed_in: BindGroup,
+    pub zoomed_out: BindGroup,
+}
+
+impl Material2dZoomBindGroups {
+    /// Returns the bind group to use for a view with the given `scale`.
+    pub fn get(&self, scale: f32) -> &BindGroup {
+        if scale <= self.filter.threshold {
+            &self.zoomed_in
+        } else {
+            &self.zoomed_out
+        }
+    }
+}
+
+/// Returns the sampler used in place of a material's filtering samplers when it requests
+/// anisotropic filtering, or `None` if it doesn't or the adapter doesn't support it.
+///
+/// The level is clamped to the range `1..=16` accepted by wgpu and rounded down to a power of two.
+pub fn anisotropic_sampler_descriptor(
+    anisotropy_clamp: Option<u16>,
+    supported: bool,
+) -> Option<ImageSamplerDescriptor> {
+    let level = anisotropy_clamp?.min(16);
+    if !supported || level <= 1 {
+        return None;
+    }
+    Some(ImageSamplerDescriptor {
+        anisotropy_clamp: 1 << level.ilog2(),
+        ..ImageSamplerDescriptor::linear()
+    })
+}
+
+fn prepare_material2d<M: Material2d>(
+    material: &M,
+    (
+        render_device,
+        pipeline_cache,
+        pipeline,
+        draw_functions,
+        _,
+        _,
+        _,
+        material_param,
+    ): &mut SystemParamItem<<PreparedMaterial2d<M> as RenderAsset>::Param>,
+) -> Result<PreparedMaterial2d<M>, AsBindGroupError> {
+    let variant_key = material.variant_key();
+    let mut with_sampler = |sampler_descriptor: ImageSamplerDescriptor| {
+        let layout = pipeline_cache.get_bind_group_layout(&pipeline.material2d_layout);
+        let UnpreparedBindGroup { mut bindings } =
+            material.unprepared_bind_group(&layout, render_device, material_param, false)?;
+        let sampler = render_device.create_sampler(&sampler_descriptor.as_wgpu());
+        for (_, binding) in bindings.iter_mut() {
+            if let OwnedBindingResource::Sampler(SamplerBindingType::Filtering, filtering) = binding
+            {
+                *filtering = sampler.clone();
+            }
+        }
+        let entries = bindings
+            .iter()
+            .map(|(index, binding)| BindGroupEntry {
+                binding: *index,
+                resource: binding.get_binding(),
+            })
+            .collect::<Vec<_>>();
+        let bind_group = render_device.create_bind_group(M::label(), &layout, &entries);
+        Ok::<_, AsBindGroupError>(PreparedBindGroup {
+            bindings,
+            bind_group,
+        })
+    };
+    let zoom_bind_groups = match material.zoom_filter() {
+        Some(filter) => {
+            let filtered = |mode| ImageSamplerDescriptor {
+                mag_filter: mode,
+                min_filter: mode,
+                mipmap_filter: mode,
+                ..Default::default()
+            };
+            Some(Material2dZoomBindGroups {
+                filter,
+                zoomed_in: with_sampler(filtered(filter.zoomed_in))?.bind_group,
+                zoomed_out: with_sampler(filtered(filter.zoomed_out))?.bind_group,
+            })
+        }
+        None => None,
+    };
+    let prepared = match anisotropic_sampler_descriptor(
+        material.anisotropy_clamp(),
+        pipeline.anisotropic_filtering_supported,
+    ) {
+        Some(sampler_descriptor) => with_sampler(sampler_descriptor)?,
+        None => material.as_bind_group(
+            &pipeline.material2d_layout,
+            render_device,
+            pipeline_cache,
+            material_param,
+        )?,
+    };
+
+    let mut mesh_pipeline_key_bits = Mesh2dPipelineKey::empty();
+    mesh_pipeline_key_bits.insert(alpha_mode_pipeline_key(material.alpha_mode()));
+    if material.unclipped_depth() {
+        mesh_pipeline_key_bits.insert(Mesh2dPipelineKey::UNCLIPPED_DEPTH);
+    }
+    if material.dither() {
+        mesh_pipeline_key_bits.insert(Mesh2dPipelineKey::MATERIAL_DITHER);
+    }
+    if material.ignore_depth() {
+        mesh_pipeline_key_bits.insert(Mesh2dPipelineKey::IGNORE_DEPTH);
+    }
+    let blend_state = material.blend_state();
+    if blend_state.is_some() {
+        // Custom blending is sorted like alpha blending, and doesn't write depth either.
+        mesh_pipeline_key_bits.insert(Mesh2dPipelineKey::BLEND_ALPHA);
+    }
+
+    let draw_function_id = match material.alpha_mode() {
+        _ if material.ignore_depth()
+            || blend_state.is_some()
+            || material.depth_write_enabled() == Some(false) =>
+        {
+            draw_functions.transparent
+        }
+        AlphaMode2d::Opaque => draw_functions.opaque,
+        AlphaMode2d::Mask(_) => draw_functions.alpha_mask,
+        AlphaMode2d::Blend
+        | AlphaMode2d::Premultiplied
+        | AlphaMode2d::Additive
+        | AlphaMode2d::Multiply => draw_functions.transparent,
+    };
+
+    Ok(PreparedMaterial2d {
+        bindings: prepared.bindings,
+        batch_id: prepared.bind_group.id(),
+        bind_group: prepared.bind_group,
+        zoom_bind_groups,
+        key: variant_key,
+        properties: Material2dProperties {
+            depth_bias: material.depth_bias(),
+            alpha_mode: material.alpha_mode(),
+            mesh_pipeline_key_bits,
+            draw_function_id,
+            writes_motion_vectors: material.writes_motion_vectors(),
+            unclipped_depth: material.unclipped_depth(),
+            uses_lut: material.uses_lut(),
+            ignore_depth: material.ignore_depth(),
+            blend_state,
+            depth_write_enabled: material.depth_write_enabled(),
+            depth_compare: material.depth_compare(),
+        },
+    })
+}
+
+impl<M: Material2d> RenderAsset for PreparedMaterial2d<M> {
+    type SourceAsset = M;
+
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<PipelineCache>,
+        SRes<Material2dPipeline<M>>,
+        SRes<Material2dDrawFunctions<M>>,
+        SResMut<Material2dLoadingFallbacks<M>>,
+        SResMut<Material2dBindGroupIds<M>>,
+        SResMut<Material2dFallbackCache<M>>,
+        M::Param,
+    );
+
+    fn prepare_asset(
+        material: Self::SourceAsset,
+        id: AssetId<Self::SourceAsset>,
+        param: &mut SystemParamItem<Self::Param>,
+        _: Option<&Self>,
+    ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
+        let with_batch_id =
+            |mut prepared: PreparedMaterial2d<M>,
+             hash: Option<u64>,
+             bind_group_ids: &mut Material2dBindGroupIds<M>| {
+                if let Some(hash) = hash {
+                    prepared.batch_id = bind_group_ids.acquire(id, hash, prepared.batch_id);
+                } else {
+                    bind_group_ids.release(id);
+                }
+                prepared
+            };
+        match prepare_material2d(&material, param) {
+            Ok(prepared) => {
+                param.6.release(id);
+                Ok(with_batch_id(
+                    prepared,
+                    material.content_hash(),
+                    &mut param.5,
+                ))
+            }
+            Err(AsBindGroupError::RetryNextUpdate) => {
+                let Some(fallback) = material.loading_fallback() else {
+                    return Err(PrepareAssetError::RetryNextUpdate(material));
+                };
+                let hash = fallback.content_hash();
+                // The fallback is retried along with the real material every frame, so its bind
+                // group is only built the first time.
+                let prepared = match param.6.get(id, hash, fallback.variant_key()) {
+                    Some(prepared) => prepared,
+                    None => match prepare_material2d(&fallback, param) {
+                        Ok(prepared) => param.6.insert(id, hash, prepared, fallback.variant_key()),
+                        Err(AsBindGroupError::RetryNextUpdate) => {
+                            return Err(PrepareAssetError::RetryNextUpdate(material));
+                        }
+                        Err(other) => return Err(PrepareAssetError::AsBindGroupError(other)),
+                    },
+                };
+                param.4.insert(id, material);
+                Ok(with_batch_id(prepared, hash, &mut param.5))
+            }
+            Err(other) => Err(PrepareAssetError::AsBindGroupError(other)),
+        }
+    }
+
+    fn unload_asset(
+        id: AssetId<Self::SourceAsset>,
+        (.., fallbacks, bind_group_ids, fallback_cache, _): &mut SystemParamItem<Self::Param>,
+    ) {
+        fallbacks.remove(&id);
+        bind_group_ids.release(id);
+        fallback_cache.release(id);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use bevy_app::SubApp;
+    use bevy_asset::{Asset, Handle};
+    use bevy_color::LinearRgba;
+    use bevy_mesh::{
+        Mesh, MeshVertexAttribute, MeshVertexBufferLayouts, PrimitiveTopology, VertexFormat,
+    };
+    use bevy_reflect::TypePath;
+    use bevy_render::{
+        batching::gpu_preprocessing::GpuPreprocessingMode,
+        render_resource::{
+            binding_types::{storage_buffer_read_only, uniform_buffer},
+            AsBindGroup, BindGroupLayoutDescriptor, BindGroupLayoutEntries, FilterMode,
+            ShaderStages, VertexStepMode,
+        },
+        storage::ShaderBuffer,
+        view::RetainedViewEntity,
+    };
+
+    use super::*;
+    use crate::mesh2d::mesh::tests::{test_layout, test_pipeline as test_mesh2d_pipeline};
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct RgTargetMaterial {}
+
+    impl Material2d for RgTargetMaterial {
+        fn color_target_formats() -> &'static [TextureFormat] {
+            &[TextureFormat::Rg16Float]
+        }
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    #[bind_group_data(RimLightKey)]
+    struct RimLightMaterial {
+        #[uniform(0)]
+        intensity: f32,
+        rim_light: bool,
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct RimLightKey {
+        rim_light: bool,
+    }
+
+    impl From<&RimLightMaterial> for RimLightKey {
+        fn from(material: &RimLightMaterial) -> Self {
+            Self {
+                rim_light: material.rim_light,
+            }
+        }
+    }
+
+    impl Material2d for RimLightMaterial {
+        fn specialize(
+            descriptor: &mut RenderPipelineDescriptor,
+            _: &MeshVertexBufferLayoutRef,
+            key: Material2dKey<Self>,
+        ) -> Result<(), SpecializedMeshPipelineError> {
+            if key.bind_group_data.rim_light {
+                descriptor
+                    .fragment
+                    .as_mut()
+                    .unwrap()
+                    .shader_defs
+                    .push("RIM_LIGHT".into());
+            }
+            Ok(())
+        }
+    }
+
+    pub(crate) fn test_pipeline<M: Material2d>() -> Material2dPipeline<M> {
+        Material2dPipeline {
+            mesh2d_pipeline: test_mesh2d_pipeline(),
+            material2d_layout: BindGroupLayoutDescriptor::new("material", &[]),
+            view_layout: M::view_layout(),
+            vertex_shader: None,
+            fragment_shader: None,
+            prepass_fragment_shader: None,
+            anisotropic_filtering_supported: false,
+            marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn only_variant_fields_split_pipelines() {
+        let material = |intensity, rim_light| RimLightMaterial {
+            intensity,
+            rim_light,
+        };
+        let key = |material: RimLightMaterial| Material2dKey::<RimLightMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: material.variant_key(),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let dim = key(material(0.5, true));
+        let bright = key(material(2.0, true));
+        let plain = key(material(2.0, false));
+        assert!(dim == bright);
+        assert!(bright != plain);
+
+        let pipeline = test_pipeline::<RimLightMaterial>();
+        let layout = test_layout();
+        let has_rim_light = |key| {
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .fragment
+                .unwrap()
+                .shader_defs
+                .contains(&"RIM_LIGHT".into())
+        };
+        assert!(has_rim_light(bright));
+        assert!(!has_rim_light(plain));
+    }
+
+    #[test]
+    fn vertex_normals_reach_the_fragment_shader() {
+        let pipeline = test_pipeline::<RimLightMaterial>();
+        let material = RimLightMaterial {
+            intensity: 1.0,
+            rim_light: false,
+        };
+        let key = || Material2dKey::<RimLightMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: material.variant_key(),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let has_normals = |descriptor: &RenderPipelineDescriptor| {
+            let def = ShaderDefVal::from("VERTEX_NORMALS");
+            let vertex = descriptor.vertex.shader_defs.contains(&def);
+            let fragment = descriptor
+                .fragment
+                .as_ref()
+                .unwrap()
+                .shader_defs
+                .contains(&def);
+            assert_eq!(vertex, fragment);
+            let normal_attribute = descriptor.vertex.buffers[0]
+                .attributes
+                .iter()
+                .any(|attribute| attribute.shader_location == 1);
+            assert_eq!(vertex, normal_attribute);
+            vertex
+        };
+
+        let plain = pipeline.specialize(key(), &test_layout()).unwrap();
+        assert!(!has_normals(&plain));
+
+        let mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3])
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0f32, 0.0, 1.0]; 3]);
+        let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+        let lit = pipeline.specialize(key(), &layout).unwrap();
+        assert!(has_normals(&lit));
+
+        // The fragment shader reads the normal through the `world_normal` varying.
+        assert!(include_str!("mesh2d_vertex_output.wgsl").contains("world_normal: vec3<f32>"));
+    }
+
+    #[test]
+    fn only_the_default_vertex_shader_tints() {
+        let key = || Material2dKey::<RimLightMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: RimLightKey { rim_light: false },
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let is_tinted = |pipeline: &Material2dPipeline<RimLightMaterial>| {
+            pipeline
+                .specialize(key(), &test_layout())
+                .unwrap()
+                .fragment
+                .unwrap()
+                .shader_defs
+                .contains(&"MESH2D_TINT".into())
+        };
+
+        let mut pipeline = test_pipeline::<RimLightMaterial>();
+        assert!(is_tinted(&pipeline));
+        pipeline.vertex_shader = Some(Handle::default());
+        assert!(!is_tinted(&pipeline));
+        assert!(include_str!("color_material.wgsl")
+            .contains("#ifdef MESH2D_TINT\n    output_color = output_color * mesh.tint;"));
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct WavyMaterial {
+        #[uniform(0)]
+        displacement: VertexDisplacement2d,
+    }
+
+    impl Material2d for WavyMaterial {
+        fn vertex_displacement_binding() -> Option<u32> {
+            Some(0)
+        }
+    }
+
+    #[test]
+    fn vertex_displacement_is_bound_and_moves_vertices() {
+        let key = Material2dKey::<RimLightMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: RimLightKey { rim_light: false },
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let plain = test_pipeline::<RimLightMaterial>()
+            .specialize(key, &test_layout())
+            .unwrap();
+        assert!(!plain
+            .vertex
+            .shader_defs
+            .contains(&"VERTEX_DISPLACEMENT".into()));
+
+        let wavy = test_pipeline::<WavyMaterial>()
+            .specialize(
+                Material2dKey {
+                    mesh_key: Mesh2dPipelineKey::NONE,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            )
+            .unwrap();
+        let shader_defs = &wavy.vertex.shader_defs;
+        assert!(shader_defs.contains(&"VERTEX_DISPLACEMENT".into()));
+        assert!(shader_defs.contains(&ShaderDefVal::UInt("VERTEX_DISPLACEMENT_BINDING".into(), 0)));
+        // The default vertex shader reads the uniform from that binding of the material bind group.
+        assert!(include_str!("mesh2d.wgsl").contains(
+            "@group(#{MATERIAL_BIND_GROUP}) @binding(#{VERTEX_DISPLACEMENT_BINDING}) var<uniform> displacement: VertexDisplacement2d;"
+        ));
+
+        // A vertical wave moves vertices up and down by an amount depending on their `x` position.
+        let displacement = VertexDisplacement2d {
+            amplitude: 2.0,
+            frequency: core::f32::consts::FRAC_PI_2,
+            speed: core::f32::consts::PI,
+            axis: Vec2::Y,
+        };
+        let displaced = |x: f32, time| displacement.displace(Vec3::new(x, 1.0, 0.5), time);
+        assert!(displaced(0.0, 0.0).abs_diff_eq(Vec3::new(0.0, 1.0, 0.5), 1e-6));
+        assert!(displaced(-1.0, 0.0).abs_diff_eq(Vec3::new(-1.0, 3.0, 0.5), 1e-6));
+        assert!(displaced(1.0, 0.0).abs_diff_eq(Vec3::new(1.0, -1.0, 0.5), 1e-6));
+        // Half a second later, the wave has moved on by a quarter turn.
+        assert!(displaced(0.0, 0.5).abs_diff_eq(Vec3::new(0.0, 3.0, 0.5), 1e-6));
+        // Without amplitude, nothing moves.
+        let still = VertexDisplacement2d::default();
+        assert_eq!(
+            still.displace(Vec3::new(1.0, 1.0, 0.0), 0.3),
+            Vec3::new(1.0, 1.0, 0.0)
+        );
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct ParticleMaterial {}
+
+    impl Material2d for ParticleMaterial {
+        fn instanced() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn instanced_pipelines_read_an_instance_buffer() {
+        let layout = test_layout();
+        let key = Material2dKey {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let descriptor = test_pipeline::<ParticleMaterial>()
+            .specialize(key.clone(), &layout)
+            .unwrap();
+        assert!(descriptor.vertex.shader_defs.contains(&"INSTANCED".into()));
+        assert_eq!(descriptor.vertex.buffers.len(), 2);
+        let instances = &descriptor.vertex.buffers[1];
+        assert_eq!(instances.step_mode, VertexStepMode::Instance);
+        assert_eq!(instances.array_stride, size_of::<Mesh2dInstance>() as u64);
+        assert_eq!(
+            instances.attributes[0].shader_location,
+            Mesh2dInstance::FIRST_SHADER_LOCATION
+        );
+
+        let key = Material2dKey::<PixelArtMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let descriptor = test_pipeline::<PixelArtMaterial>()
+            .specialize(key, &layout)
+            .unwrap();
+        assert_eq!(descriptor.vertex.buffers.len(), 1);
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct PixelArtMaterial {}
+
+    impl Material2d for PixelArtMaterial {
+        fn pixel_snap() -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn pixel_snap_quantizes_sub_pixel_positions() {
+        fn key<M: Material2d<Data = ()>>() -> Material2dKey<M> {
+            Material2dKey {
+                mesh_key: Mesh2dPipelineKey::NONE,
+                bind_group_data: (),
+                blend_state: None,
+                depth_write_enabled: None,
+                depth_compare: None,
+            }
+        }
+        let smooth = test_pipeline::<WavyMaterial>()
+            .specialize(key(), &test_layout())
+            .unwrap();
+        assert!(!smooth.vertex.shader_defs.contains(&"PIXEL_SNAP".into()));
+        let snapped = test_pipeline::<PixelArtMaterial>()
+            .specialize(key(), &test_layout())
+            .unwrap();
+        assert!(snapped.vertex.shader_defs.contains(&"PIXEL_SNAP".into()));
+        assert!(include_str!("mesh2d.wgsl")
+            .contains("out.position = mesh_functions::mesh2d_pixel_snap(out.position);"));
+
+        // In an 800x600 viewport, a pixel is 1/400 wide and 1/300 tall in clip space. Moving a
+        // vertex by fractions of a pixel only changes its position once it crosses half a pixel.
+        let viewport = Vec2::new(800.0, 600.0);
+        let pixel = Vec2::new(1.0 / 400.0, 1.0 / 300.0);
+        let snap = |offset: Vec2| {
+            let clip = Vec4::new(0.5 + offset.x, -0.25 + offset.y, 0.3, 1.0);
+            pixel_snap_clip_position(clip, viewport)
+        };
+        let origin = Vec4::new(0.5, -0.25, 0.3, 1.0);
+        assert!(snap(Vec2::ZERO).abs_diff_eq(origin, 1e-6));
+        assert!(snap(pixel * 0.3).abs_diff_eq(origin, 1e-6));
+        assert!(snap(pixel * -0.4).abs_diff_eq(origin, 1e-6));
+        let next = origin + pixel.extend(0.0).extend(0.0);
+        assert!(snap(pixel * 0.7).abs_diff_eq(next, 1e-6));
+
+        // Snapping is done after the perspective divide, and keeps depth untouched.
+        let scaled =
+            pixel_snap_clip_position(Vec4::new(1.0 + 0.6 * pixel.x, -0.5, 0.6, 2.0), viewport);
+        assert!(scaled.abs_diff_eq(Vec4::new(1.0, -0.5, 0.6, 2.0), 1e-6));
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct OverbrightMaterial {}
+
+    impl Material2d for OverbrightMaterial {
+        fn clamp_output() -> Option<f32> {
+            Some(1.0)
+        }
+    }
+
+    #[test]
+    fn clamped_output_is_within_the_target_range() {
+        let specialize = |mesh_key| {
+            test_pipeline::<OverbrightMaterial>()
+                .specialize(
+                    Material2dKey {
+                        mesh_key,
+                        bind_group_data: (),
+                        blend_state: None,
+                        depth_write_enabled: None,
+                        depth_compare: None,
+                    },
+                    &test_layout(),
+                )
+                .unwrap()
+                .fragment
+                .unwrap()
+                .shader_defs
+        };
+        let max_bits = |max: f32| ShaderDefVal::UInt("CLAMP_OUTPUT_MAX_BITS".into(), max.to_bits());
+        let ldr = specialize(Mesh2dPipelineKey::NONE);
+        assert!(ldr.contains(&"CLAMP_OUTPUT".into()));
+        assert!(ldr.contains(&max_bits(1.0)));
+        let hdr = specialize(Mesh2dPipelineKey::HDR);
+        assert!(hdr.contains(&max_bits(HDR_OUTPUT_MAX)));
+        let unclamped = test_pipeline::<PixelArtMaterial>()
+            .specialize(
+                Material2dKey {
+                    mesh_key: Mesh2dPipelineKey::NONE,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            )
+            .unwrap();
+        assert!(!unclamped
+            .fragment
+            .unwrap()
+            .shader_defs
+            .contains(&"CLAMP_OUTPUT".into()));
+        assert!(include_str!("mesh2d.wgsl").contains(
+            "out.color = mesh_functions::mesh2d_clamp_output(out.color, bitcast<f32>(#{CLAMP_OUTPUT_MAX_BITS}u));"
+        ));
+
+        // A material emitting NaN, infinite, negative, and overbright values.
+        let emitted = Vec4::new(f32::NAN, f32::INFINITY, -2.0, 3.0);
+        assert_eq!(
+            clamp_material_output(emitted, 1.0),
+            Vec4::new(0.0, 1.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            clamp_material_output(Vec4::new(4.0, f32::INFINITY, 0.5, f32::NAN), HDR_OUTPUT_MAX),
+            Vec4::new(4.0, HDR_OUTPUT_MAX, 0.5, 0.0)
+        );
+        // Values already within range are unchanged.
+        let color = Vec4::new(0.25, 0.5, 0.75, 0.5);
+        assert_eq!(clamp_material_output(color, 1.0), color);
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct FadingParticleMaterial {
+        #[uniform(0)]
+        mix: AdditiveMix2d,
+    }
+
+    impl Material2d for FadingParticleMaterial {
+        fn alpha_mode(&self) -> AlphaMode2d {
+            AlphaMode2d::Blend
+        }
+
+        fn additive_mix_binding() -> Option<u32> {
+            Some(0)
+        }
+    }
+
+    #[test]
+    fn one_pipeline_mixes_alpha_and_additive_blending() {
+        let materials = [0.0, 0.25, 1.0].map(|additive| FadingParticleMaterial {
+            mix: AdditiveMix2d { additive },
+        });
+        // Every mix specializes the same pipeline.
+        let keys = materials.each_ref().map(|material| Material2dKey {
+            mesh_key: alpha_mode_pipeline_key(material.alpha_mode()),
+            bind_group_data: material.bind_group_data(),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        });
+        assert!(keys.iter().all(|key| *key == keys[0]));
+        let descriptor = test_pipeline::<FadingParticleMaterial>()
+            .specialize(keys[0].clone(), &test_layout())
+            .unwrap();
+        let fragment = descriptor.fragment.unwrap();
+        assert_eq!(
+            fragment.targets[0].as_ref().unwrap().blend,
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING)
+        );
+        assert!(fragment.shader_defs.contains(&"ADDITIVE_MIX".into()));
+        assert!(fragment
+            .shader_defs
+            .contains(&ShaderDefVal::UInt("ADDITIVE_MIX_BINDING".into(), 0)));
+        assert!(include_str!("mesh2d.wgsl").contains(
+            "@group(#{MATERIAL_BIND_GROUP}) @binding(#{ADDITIVE_MIX_BINDING}) var<uniform> additive_mix: AdditiveMix2d;"
+        ));
+
+        // The premultiplied alpha blend state adds the source to the destination scaled by one
+        // minus the source alpha.
+        let blend = |material: &FadingParticleMaterial, color: Vec4, behind: Vec4| {
+            let source = material.mix.premultiply(color);
+            source + behind * (1.0 - source.w)
+        };
+        let color = Vec4::new(1.0, 0.5, 0.0, 0.5);
+        let behind = Vec4::new(0.0, 0.0, 1.0, 1.0);
+        let alpha_blended = (color.xyz() * color.w + behind.xyz() * (1.0 - color.w)).extend(1.0);
+        let added = (color.xyz() * color.w + behind.xyz()).extend(1.0);
+        assert!(blend(&materials[0], color, behind).abs_diff_eq(alpha_blended, 1e-6));
+        assert!(blend(&materials[2], color, behind).abs_diff_eq(added, 1e-6));
+        let mixed = blend(&materials[1], color, behind);
+        assert!(mixed.abs_diff_eq(alpha_blended.lerp(added, 0.25), 1e-6));
+    }
+
+    const ADDITIVE_BLENDING: BlendState = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        },
+        alpha: BlendComponent::OVER,
+    };
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct GlowMaterial {}
+
+    impl Material2d for GlowMaterial {
+        fn blend_state(&self) -> Option<BlendState> {
+            Some(ADDITIVE_BLENDING)
+        }
+    }
+
+    #[test]
+    fn custom_blend_state_overrides_the_color_target() {
+        let material = GlowMaterial {};
+        let key = Material2dKey::<GlowMaterial> {
+            // `prepare_material2d` sorts custom blending like alpha blending.
+            mesh_key: alpha_mode_pipeline_key(material.alpha_mode())
+                | Mesh2dPipelineKey::BLEND_ALPHA,
+            bind_group_data: (),
+            blend_state: material.blend_state(),
+            depth_write_enabled: material.depth_write_enabled(),
+            depth_compare: material.depth_compare(),
+        };
+        let descriptor = test_pipeline::<GlowMaterial>()
+            .specialize(key.clone(), &test_layout())
+            .unwrap();
+        let fragment = descriptor.fragment.unwrap();
+        assert_eq!(
+            fragment.targets[0].as_ref().unwrap().blend,
+            Some(ADDITIVE_BLENDING)
+        );
+        assert!(!descriptor.depth_stencil.unwrap().depth_write_enabled);
+
+        // Without a custom blend state, the alpha mode picks the blend state.
+        let descriptor = test_pipeline::<GlowMaterial>()
+            .specialize(
+                Material2dKey {
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                    ..key
+                },
+                &test_layout(),
+            )
+            .unwrap();
+        assert_eq!(
+            descriptor.fragment.unwrap().targets[0]
+                .as_ref()
+                .unwrap()
+                .blend,
+            Some(BlendState::ALPHA_BLENDING)
+        );
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct SparkMaterial {}
+
+    impl Material2d for SparkMaterial {
+        fn alpha_mode(&self) -> AlphaMode2d {
+            AlphaMode2d::Additive
+        }
+    }
+
+    #[test]
+    fn additive_and_multiply_materials_are_transparent() {
+        let properties = |alpha_mode| Material2dProperties {
+            alpha_mode,
+            depth_bias: 0.0,
+            mesh_pipeline_key_bits: alpha_mode_pipeline_key(alpha_mode),
+            draw_function_id: DrawFunctionId(0),
+            writes_motion_vectors: false,
+            unclipped_depth: false,
+            uses_lut: false,
+            ignore_depth: false,
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        // Queued in the `Transparent2d` phase, and sorted back to front with alpha blended meshes.
+        assert!(properties(SparkMaterial {}.alpha_mode()).is_transparent());
+        assert!(properties(AlphaMode2d::Multiply).is_transparent());
+        assert!(properties(AlphaMode2d::Blend).is_transparent());
+        assert!(!properties(AlphaMode2d::Opaque).is_transparent());
+        assert!(!properties(AlphaMode2d::Mask(0.5)).is_transparent());
+
+        let pipeline = test_pipeline::<SparkMaterial>();
+        let specialize = |alpha_mode| {
+            let descriptor = pipeline
+                .specialize(
+                    Material2dKey {
+                        mesh_key: alpha_mode_pipeline_key(alpha_mode),
+                        bind_group_data: (),
+                        blend_state: None,
+                        depth_write_enabled: None,
+                        depth_compare: None,
+                    },
+                    &test_layout(),
+                )
+                .unwrap();
+            let blend = descriptor.fragment.unwrap().targets[0]
+                .as_ref()
+                .unwrap()
+                .blend
+                .unwrap();
+            (blend, descriptor.depth_stencil.unwrap().depth_write_enabled)
+        };
+        let (additive, depth_write) = specialize(AlphaMode2d::Additive);
+        assert_eq!(additive.color.src_factor, BlendFactor::SrcAlpha);
+        assert_eq!(additive.color.dst_factor, BlendFactor::One);
+        assert!(!depth_write);
+        let (multiply, depth_write) = specialize(AlphaMode2d::Multiply);
+        assert_eq!(multiply.color.src_factor, BlendFactor::Dst);
+        assert_eq!(multiply.color.dst_factor, BlendFactor::Zero);
+        assert!(!depth_write);
+        assert_eq!(
+            specialize(AlphaMode2d::Blend),
+            (BlendState::ALPHA_BLENDING, false)
+        );
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct PremultipliedSpriteMaterial {}
+
+    impl Material2d for PremultipliedSpriteMaterial {
+        fn alpha_mode(&self) -> AlphaMode2d {
+            AlphaMode2d::Premultiplied
+        }
+    }
+
+    #[test]
+    fn premultiplied_materials_emit_premultiplied_blend_factors() {
+        let material = PremultipliedSpriteMaterial {};
+        let mesh_key = alpha_mode_pipeline_key(material.alpha_mode());
+        let descriptor = test_pipeline::<PremultipliedSpriteMaterial>()
+            .specialize(
+                Material2dKey {
+                    mesh_key,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            )
+            .unwrap();
+        assert!(!descriptor.depth_stencil.unwrap().depth_write_enabled);
+        let blend = descriptor.fragment.unwrap().targets[0]
+            .as_ref()
+            .unwrap()
+            .blend
+            .unwrap();
+        // The color is added as is, instead of being multiplied by its alpha like with `Blend`.
+        assert_eq!(
+            blend.color,
+            BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            }
+        );
+        assert_eq!(blend.alpha, BlendComponent::OVER);
+        assert_eq!(
+            BlendState::ALPHA_BLENDING.color.src_factor,
+            BlendFactor::SrcAlpha
+        );
+
+        // Queued in the `Transparent2d` phase like alpha blended materials.
+        assert!(Material2dProperties {
+            alpha_mode: material.alpha_mode(),
+            depth_bias: 0.0,
+            mesh_pipeline_key_bits: mesh_key,
+            draw_function_id: DrawFunctionId(0),
+            writes_motion_vectors: false,
+            unclipped_depth: false,
+            uses_lut: false,
+            ignore_depth: false,
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        }
+        .is_transparent());
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct DepthOnlyMaterial {}
+
+    impl Material2d for DepthOnlyMaterial {}
+
+    #[test]
+    fn prepass_phase_receives_opaque_entities() {
+        let properties = |alpha_mode| Material2dProperties {
+            alpha_mode,
+            depth_bias: 0.0,
+            mesh_pipeline_key_bits: alpha_mode_pipeline_key(alpha_mode),
+            draw_function_id: DrawFunctionId(0),
+            writes_motion_vectors: false,
+            unclipped_depth: false,
+            uses_lut: false,
+            ignore_depth: false,
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let mut ignore_depth = properties(AlphaMode2d::Opaque);
+        ignore_depth.ignore_depth = true;
+        let materials = [
+            properties(AlphaMode2d::Opaque),
+            properties(AlphaMode2d::Mask(0.5)),
+            properties(AlphaMode2d::Blend),
+            ignore_depth,
+        ];
+
+        let view = RetainedViewEntity::new(MainEntity::from(Entity::PLACEHOLDER), None, 0);
+        let mut phases = ViewBinnedRenderPhases::<Prepass2d>::default();
+        phases.prepare_for_new_frame(view, GpuPreprocessingMode::None);
+        let phase = phases.get_mut(&view).unwrap();
+        let tick = Tick::new(1);
+        let entities: Vec<_> = (0..materials.len() as u32)
+            .map(|index| MainEntity::from(Entity::from_raw_u32(index + 1).unwrap()))
+            .collect();
+        // Mirrors `queue_material2d_prepass`.
+        for (entity, properties) in entities.iter().zip(&materials) {
+            if !properties.draws_in_prepass() {
+                continue;
+            }
+            phase.add(
+                BatchSetKey2d { indexed: false },
+                Opaque2dBinKey {
+                    pipeline: CachedRenderPipelineId::INVALID,
+                    draw_function: DrawFunctionId(0),
+                    asset_id: AssetId::<Mesh>::default().untyped(),
+                    material_bind_group_id: None,
+                },
+                (Entity::PLACEHOLDER, *entity),
+                InputUniformIndex::default(),
+                BinnedRenderPhaseType::BatchableMesh,
+                tick,
+            );
+        }
+        let queued: Vec<_> = entities
+            .iter()
+            .map(|entity| phase.validate_cached_entity(*entity, tick))
+            .collect();
+        assert_eq!(queued, [true, false, false, false]);
+
+        // The prepass pipeline writes depth without shading any color.
+        let descriptor = test_pipeline::<DepthOnlyMaterial>()
+            .specialize(
+                Material2dKey {
+                    mesh_key: Mesh2dPipelineKey::DEPTH_PREPASS,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            )
+            .unwrap();
+        assert!(descriptor.fragment.is_none());
+        assert!(descriptor.depth_stencil.unwrap().depth_write_enabled);
+        assert!(descriptor
+            .vertex
+            .shader_defs
+            .contains(&"DEPTH_PREPASS".into()));
+
+        // A prepass fragment shader runs without color targets.
+        let mut pipeline = test_pipeline::<DepthOnlyMaterial>();
+        pipeline.prepass_fragment_shader = Some(Handle::default());
+        let fragment = pipeline
+            .specialize(
+                Material2dKey {
+                    mesh_key: Mesh2dPipelineKey::DEPTH_PREPASS,
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            )
+            .unwrap()
+            .fragment
+            .unwrap();
+        assert!(fragment.targets.is_empty());
+    }
+
+    #[test]
+    fn motion_vectors_are_only_written_in_the_prepass() {
+        let specialize = |mesh_key| {
+            test_pipeline::<DepthOnlyMaterial>()
+                .specialize(
+                    Material2dKey {
+                        mesh_key,
+                        bind_group_data: (),
+                        blend_state: None,
+                        depth_write_enabled: None,
+                        depth_compare: None,
+                    },
+                    &test_layout(),
+                )
+                .unwrap()
+                .fragment
+                .unwrap()
+        };
+
+        // The main passes have no motion vector target.
+        let fragment = specialize(Mesh2dPipelineKey::empty());
+        assert_eq!(fragment.targets.len(), 1);
+        assert!(!fragment
+            .shader_defs
+            .contains(&"MOTION_VECTOR_PREPASS".into()));
+
+        // The default prepass shader writes the motion vectors of materials that write them.
+        let prepass_key =
+            Mesh2dPipelineKey::DEPTH_PREPASS | Mesh2dPipelineKey::MOTION_VECTOR_PREPASS;
+        let fragment = specialize(prepass_key | Mesh2dPipelineKey::WRITES_MOTION_VECTORS);
+        assert!(fragment
+            .shader_defs
+            .contains(&"MOTION_VECTOR_PREPASS".into()));
+        assert_eq!(
+            fragment.targets,
+            [Some(ColorTargetState {
+                format: MOTION_VECTOR_PREPASS_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })]
+        );
+
+        // Other materials still declare the target bound by the view, without writing to it.
+        let fragment = specialize(prepass_key);
+        assert!(!fragment
+            .shader_defs
+            .contains(&"MOTION_VECTOR_PREPASS".into()));
+        assert_eq!(
+            fragment.targets,
+            [Some(ColorTargetState {
+                format: MOTION_VECTOR_PREPASS_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::empty(),
+            })]
+        );
+    }
+
+    const ATTRIBUTE_GRADIENT: MeshVertexAttribute =
+        MeshVertexAttribute::new("Gradient", 988540917, VertexFormat::Float32);
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct GradientMaterial {}
+
+    impl Material2d for GradientMaterial {
+        fn vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+            vec![
+                Mesh::ATTRIBUTE_COLOR.at_shader_location(4),
+                ATTRIBUTE_GRADIENT.at_shader_location(5),
+            ]
+        }
+    }
+
+    #[test]
+    fn meshes_missing_required_vertex_attributes_fail_to_specialize() {
+        let pipeline = test_pipeline::<GradientMaterial>();
+        let key = Material2dKey::<GradientMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+
+        // The test mesh only has positions.
+        let Err(SpecializedMeshPipelineError::MissingVertexAttribute(err)) =
+            pipeline.specialize(key.clone(), &test_layout())
+        else {
+            panic!("expected a missing vertex attribute");
+        };
+        assert!(err.to_string().contains("Vertex_Color"), "{err}");
+
+        let mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3])
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1.0f32; 4]; 3])
+            .with_inserted_attribute(ATTRIBUTE_GRADIENT, vec![0.0f32, 0.5, 1.0]);
+        let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+        let descriptor = pipeline.specialize(key, &layout).unwrap();
+        let mut locations: Vec<_> = descriptor.vertex.buffers[0]
+            .attributes
+            .iter()
+            .map(|attribute| (attribute.shader_location, attribute.format))
+            .collect();
+        locations.sort_by_key(|(location, _)| *location);
+        // The color the default pipeline already reads isn't bound twice.
+        assert_eq!(
+            locations,
+            vec![
+                (0, VertexFormat::Float32x3),
+                (4, VertexFormat::Float32x4),
+                (5, VertexFormat::Float32),
+            ]
+        );
+    }
+
+    #[test]
+    fn mismatched_color_target_format_is_reported() {
+        let pipeline = test_pipeline::<RgTargetMaterial>();
+        let layout = test_layout();
+
+        let key = Material2dKey::<RgTargetMaterial> {
+            mesh_key: Mesh2dPipelineKey::HDR,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let Err(SpecializedMeshPipelineError::ColorTargetFormatMismatch(err)) =
+            pipeline.specialize(key, &layout)
+        else {
+            panic!("expected a color target format mismatch");
+        };
+        assert_eq!(err.expected, vec![TextureFormat::Rg16Float]);
+        assert_eq!(err.actual, vec![Some(TextureFormat::Rgba16Float)]);
+        let message = err.to_string();
+        assert!(message.contains("Rg16Float"), "{message}");
+        assert!(message.contains("Rgba16Float"), "{message}");
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct ColorAndMaskMaterial {}
+
+    impl Material2d for ColorAndMaskMaterial {
+        fn color_target_states() -> Vec<Option<ColorTargetState>> {
+            vec![
+                Some(ColorTargetState {
+                    format: TextureFormat::Rgba16Float,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                }),
+                Some(ColorTargetState {
+                    format: TextureFormat::R8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::RED,
+                }),
+            ]
+        }
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct DeclaredColorAndMaskMaterial {}
+
+    impl Material2d for DeclaredColorAndMaskMaterial {
+        fn color_target_formats() -> &'static [TextureFormat] {
+            &[TextureFormat::Rgba16Float, TextureFormat::R8Unorm]
+        }
+
+        fn color_target_states() -> Vec<Option<ColorTargetState>> {
+            ColorAndMaskMaterial::color_target_states()
+        }
+    }
+
+    #[test]
+    fn multiple_render_targets_validate_their_declared_formats() {
+        let key = |mesh_key| Material2dKey::<DeclaredColorAndMaskMaterial> {
+            mesh_key,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let descriptor = test_pipeline::<DeclaredColorAndMaskMaterial>()
+            .specialize(
+                key(Mesh2dPipelineKey::HDR | Mesh2dPipelineKey::from_extra_color_targets(1)),
+                &test_layout(),
+            )
+            .unwrap();
+        assert_eq!(descriptor.fragment.unwrap().targets.len(), 2);
+    }
+
+    #[test]
+    fn material_writes_multiple_render_targets() {
+        let pipeline = test_pipeline::<ColorAndMaskMaterial>();
+        let key = |mesh_key| Material2dKey::<ColorAndMaskMaterial> {
+            mesh_key,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+
+        let descriptor = pipeline
+            .specialize(
+                key(Mesh2dPipelineKey::HDR | Mesh2dPipelineKey::from_extra_color_targets(1)),
+                &test_layout(),
+            )
+            .unwrap();
+        let formats: Vec<_> = descriptor
+            .fragment
+            .unwrap()
+            .targets
+            .iter()
+            .map(|target| target.as_ref().unwrap().format)
+            .collect();
+        assert_eq!(
+            formats,
+            vec![TextureFormat::Rgba16Float, TextureFormat::R8Unorm]
+        );
+
+        // A view without the extra attachment can't draw the material.
+        let Err(SpecializedMeshPipelineError::ColorTargetCountMismatch(err)) =
+            pipeline.specialize(key(Mesh2dPipelineKey::HDR), &test_layout())
+        else {
+            panic!("expected a color target count mismatch");
+        };
+        assert_eq!((err.expected, err.actual), (2, 1));
+
+        // Neither can a material writing a single target be drawn into it.
+        assert!(matches!(
+            test_pipeline::<DepthOnlyMaterial>().specialize(
+                Material2dKey {
+                    mesh_key: Mesh2dPipelineKey::from_extra_color_targets(1),
+                    bind_group_data: (),
+                    blend_state: None,
+                    depth_write_enabled: None,
+                    depth_compare: None,
+                },
+                &test_layout(),
+            ),
+            Err(SpecializedMeshPipelineError::ColorTargetCountMismatch(_))
+        ));
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct StorageColorsMaterial {
+        #[uniform(0)]
+        color: LinearRgba,
+        #[storage(1, read_only)]
+        colors: Handle<ShaderBuffer>,
+    }
+
+    impl Material2d for StorageColorsMaterial {}
+
+    #[test]
+    fn material_layout_keeps_storage_buffer_bindings() {
+        let mut pipeline = test_pipeline::<StorageColorsMaterial>();
+        pipeline.material2d_layout = BindGroupLayoutDescriptor::new(
+            "storage_colors_material",
+            &BindGroupLayoutEntries::with_indices(
+                ShaderStages::VERTEX_FRAGMENT,
+                (
+                    (0, uniform_buffer::<LinearRgba>(false)),
+                    (1, storage_buffer_read_only::<Vec<LinearRgba>>(false)),
+                ),
+            ),
+        );
+        let key = Material2dKey::<StorageColorsMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+
+        let descriptor = pipeline.specialize(key.clone(), &test_layout()).unwrap();
+        let material_layout = &descriptor.layout[MATERIAL_2D_BIND_GROUP_INDEX];
+        let buffer_types: Vec<_> = material_layout
+            .entries
+            .iter()
+            .map(|entry| match entry.ty {
+                BindingType::Buffer { ty, .. } => (entry.binding, ty),
+                _ => panic!("expected only buffer bindings"),
+            })
+            .collect();
+        assert_eq!(
+            buffer_types,
+            vec![
+                (0, BufferBindingType::Uniform),
+                (1, BufferBindingType::Storage { read_only: true }),
+            ]
+        );
+
+        // Without storage buffer support, the material can't be drawn.
+        pipeline.mesh2d_pipeline.per_object_buffer_batch_size = Some(16);
+        let Err(SpecializedMeshPipelineError::StorageBuffersUnsupported(err)) =
+            pipeline.specialize(key, &test_layout())
+        else {
+            panic!("expected storage buffers to be unsupported");
+        };
+        assert_eq!(err.binding, 1);
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct OverlayMaterial {
+        depth_write_enabled: Option<bool>,
+        depth_compare: Option<CompareFunction>,
+        ignore_depth: bool,
+    }
+
+    impl Material2d for OverlayMaterial {
+        fn ignore_depth(&self) -> bool {
+            self.ignore_depth
+        }
+
+        fn depth_write_enabled(&self) -> Option<bool> {
+            self.depth_write_enabled
+        }
+
+        fn depth_compare(&self) -> Option<CompareFunction> {
+            self.depth_compare
+        }
+    }
+
+    #[test]
+    fn material_overrides_depth_write_and_compare() {
+        // Mirrors how `prepare_material2d` and `specialize_material2d_meshes` key the pipeline.
+        let key = |material: &OverlayMaterial| Material2dKey::<OverlayMaterial> {
+            mesh_key: if material.ignore_depth() {
+                Mesh2dPipelineKey::IGNORE_DEPTH
+            } else {
+                Mesh2dPipelineKey::NONE
+            },
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: material.depth_write_enabled(),
+            depth_compare: material.depth_compare(),
+        };
+        let pipeline = test_pipeline::<OverlayMaterial>();
+        let depth_state = |material: &OverlayMaterial| {
+            let depth_stencil = pipeline
+                .specialize(key(material), &test_layout())
+                .unwrap()
+                .depth_stencil
+                .unwrap();
+            (
+                depth_stencil.depth_write_enabled,
+                depth_stencil.depth_compare,
+            )
+        };
+
+        let overlay = OverlayMaterial {
+            depth_write_enabled: Some(false),
+            depth_compare: Some(CompareFunction::Always),
+            ignore_depth: false,
+        };
+        assert_eq!(depth_state(&overlay), (false, CompareFunction::Always));
+
+        // Instances of the same material without overrides keep the depth state of their alpha
+        // mode, in a pipeline of their own.
+        let plain = OverlayMaterial {
+            depth_write_enabled: None,
+            depth_compare: None,
+            ignore_depth: false,
+        };
+        assert!(key(&plain) != key(&overlay));
+        assert_eq!(depth_state(&plain), (true, CompareFunction::GreaterEqual));
+
+        // Ignoring depth takes precedence over the overrides.
+        let ignored = OverlayMaterial {
+            depth_write_enabled: Some(true),
+            depth_compare: Some(CompareFunction::Less),
+            ignore_depth: true,
+        };
+        assert_eq!(depth_state(&ignored), (false, CompareFunction::Always));
+
+        // Without depth writes, opaque meshes are sorted with the transparent ones.
+        let properties = |depth_write_enabled| Material2dProperties {
+            alpha_mode: AlphaMode2d::Opaque,
+            depth_bias: 0.0,
+            mesh_pipeline_key_bits: Mesh2dPipelineKey::NONE,
+            draw_function_id: DrawFunctionId(0),
+            writes_motion_vectors: false,
+            unclipped_depth: false,
+            uses_lut: false,
+            ignore_depth: false,
+            blend_state: None,
+            depth_write_enabled,
+            depth_compare: None,
+        };
+        assert!(properties(overlay.depth_write_enabled()).is_transparent());
+        assert!(!properties(overlay.depth_write_enabled()).draws_in_prepass());
+        assert!(!properties(Some(true)).is_transparent());
+    }
+
+    #[test]
+    fn identical_materials_keep_their_batch_id_across_rebuilds() {
+        let mut ids = Material2dBindGroupIds::<RimLightMaterial>::default();
+        let a = AssetId::<RimLightMaterial>::Uuid {
+            uuid: bevy_asset::uuid::Uuid::from_u128(1),
+        };
+        let b = AssetId::<RimLightMaterial>::Uuid {
+            uuid: bevy_asset::uuid::Uuid::from_u128(2),
+        };
+
+        let shared = ids.acquire(a, 7, BindGroupId::new());
+        // Rebuilding the material builds a new bind group, but keeps the batching id.
+        assert_eq!(ids.acquire(a, 7, BindGroupId::new()), shared);
+        // A different asset with the same contents batches with it.
+        assert_eq!(ids.acquire(b, 7, BindGroupId::new()), shared);
+
+        // Changing the contents of one stops it from batching with the other.
+        let changed = BindGroupId::new();
+        assert_eq!(ids.acquire(a, 8, changed), changed);
+        assert_eq!(ids.acquire(b, 7, BindGroupId::new()), shared);
+
+        // Once no material uses the hash anymore, its id is released.
+        ids.release(b);
+        let fresh = BindGroupId::new();
+        assert_eq!(ids.acquire(b, 7, fresh), fresh);
+    }
+
+    #[test]
+    fn loading_fallbacks_are_retried_until_replaced() {
+        let mut world = World::new();
+        world.init_resource::<Material2dLoadingFallbacks<RgTargetMaterial>>();
+        world.init_resource::<Material2dFallbackCache<RgTargetMaterial>>();
+        world.init_resource::<ExtractedAssets<PreparedMaterial2d<RgTargetMaterial>>>();
+        let pending = AssetId::<RgTargetMaterial>::invalid();
+        world
+            .resource_mut::<Material2dLoadingFallbacks<RgTargetMaterial>>()
+            .insert(pending, RgTargetMaterial {});
+
+        world
+            .run_system_cached(retry_material2d_loading_fallbacks::<RgTargetMaterial>)
+            .unwrap();
+        let extracted = world.resource::<ExtractedAssets<PreparedMaterial2d<RgTargetMaterial>>>();
+        assert_eq!(extracted.extracted.len(), 1);
+        assert_eq!(extracted.extracted[0].0, pending);
+        assert!(world
+            .resource::<Material2dLoadingFallbacks<RgTargetMaterial>>()
+            .is_empty());
+
+        // A newer version of the asset takes precedence over the retried one.
+        world.insert_resource(ExtractedAssets::<PreparedMaterial2d<RgTargetMaterial>>::default());
+        world
+            .resource_mut::<ExtractedAssets<PreparedMaterial2d<RgTargetMaterial>>>()
+            .modified
+            .insert(pending);
+        world
+            .resource_mut::<Material2dLoadingFallbacks<RgTargetMaterial>>()
+            .insert(pending, RgTargetMaterial {});
+        world
+            .run_system_cached(retry_material2d_loading_fallbacks::<RgTargetMaterial>)
+            .unwrap();
+        assert!(world
+            .resource::<ExtractedAssets<PreparedMaterial2d<RgTargetMaterial>>>()
+            .extracted
+            .is_empty());
+    }
+
+    #[test]
+    fn prewarm_requests_reach_the_render_app() {
+        let mut app = App::new();
+        app.insert_sub_app(RenderApp, SubApp::new());
+
+        let layout = test_layout();
+        app.prewarm_material2d::<RgTargetMaterial>(
+            Material2dKey {
+                mesh_key: Mesh2dPipelineKey::HDR,
+                bind_group_data: (),
+                blend_state: None,
+                depth_write_enabled: None,
+                depth_compare: None,
+            },
+            layout.0.as_ref().clone(),
+        );
+
+        let render_app = app.get_sub_app(RenderApp).unwrap();
+        let queue = render_app
+            .world()
+            .resource::<Material2dPrewarmQueue<RgTargetMaterial>>();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(queue.0[0].0.mesh_key, Mesh2dPipelineKey::HDR);
+        assert_eq!(queue.0[0].1, *layout.0);
+    }
+
+    struct NoOpSuffix;
+
+    impl<P: PhaseItem> RenderCommand<P> for NoOpSuffix {
+        type Param = ();
+        type ViewQuery = ();
+        type ItemQuery = ();
+
+        fn render<'w>(
+            _: &P,
+            _: (),
+            _: Option<()>,
+            _: SystemParamItem<'w, '_, Self::Param>,
+            _: &mut TrackedRenderPass<'w>,
+        ) -> RenderCommandResult {
+            RenderCommandResult::Success
+        }
+    }
+
+    #[test]
+    fn render_command_suffix_runs_after_draw_mesh() {
+        let mut render_app = SubApp::new();
+        render_app.init_resource::<DrawFunctions<Opaque2d>>();
+        render_app.init_resource::<DrawFunctions<AlphaMask2d>>();
+        render_app.init_resource::<DrawFunctions<Transparent2d>>();
+        add_material2d_render_commands::<RgTargetMaterial, NoOpSuffix, ()>(&mut render_app);
+
+        let world = render_app.world();
+        let draw_functions = world.resource::<Material2dDrawFunctions<RgTargetMaterial>>();
+        let registered = world
+            .resource::<DrawFunctions<Transparent2d>>()
+            .read()
+            .get_id::<DrawMaterial2d<RgTargetMaterial, NoOpSuffix>>();
+        assert_eq!(registered, Some(draw_functions.transparent));
+
+        let sequence = core::any::type_name::<DrawMaterial2d<RgTargetMaterial, NoOpSuffix>>();
+        let draw_mesh = sequence.find("DrawMesh2d").unwrap();
+        let suffix = sequence.find("NoOpSuffix").unwrap();
+        assert!(draw_mesh < suffix, "{sequence}");
+    }
+
+    #[derive(Asset, AsBindGroup, TypePath, Clone)]
+    struct ScreenSpaceMaterial {}
+
+    impl Material2d for ScreenSpaceMaterial {
+        fn view_layout() -> Option<Material2dViewLayout> {
+            Some(Material2dViewLayout::Extend(
+                BindGroupLayoutDescriptor::new("screen_space_view", &[]),
+            ))
+        }
+    }
+
+    struct SetScreenSpaceViewBindGroup;
+
+    impl<P: PhaseItem> RenderCommand<P> for SetScreenSpaceViewBindGroup {
+        type Param = ();
+        type ViewQuery = ();
+        type ItemQuery = ();
+
+        fn render<'w>(
+            _: &P,
+            _: (),
+            _: Option<()>,
+            _: SystemParamItem<'w, '_, Self::Param>,
+            _: &mut TrackedRenderPass<'w>,
+        ) -> RenderCommandResult {
+            RenderCommandResult::Success
+        }
+    }
+
+    #[test]
+    fn material_view_layout_extends_the_pipeline_and_draw() {
+        let key = Material2dKey::<ScreenSpaceMaterial> {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: (),
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let descriptor = test_pipeline::<ScreenSpaceMaterial>()
+            .specialize(key.clone(), &test_layout())
+            .unwrap();
+        let labels: Vec<_> = descriptor
+            .layout
+            .iter()
+            .map(|layout| layout.label.as_ref())
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["view", "mesh", "material", "screen_space_view"]
+        );
+        let shader_def = ShaderDefVal::UInt(
+            "MATERIAL_VIEW_BIND_GROUP".into(),
+            MATERIAL_2D_VIEW_BIND_GROUP_INDEX as u32,
+        );
+        assert!(descriptor.vertex.shader_defs.contains(&shader_def));
+        assert!(descriptor
+            .fragment
+            .unwrap()
+            .shader_defs
+            .contains(&shader_def));
+
+        // A replacing layout takes the place of the default view bind group.
+        let mut pipeline = test_pipeline::<ScreenSpaceMaterial>();
+        pipeline.view_layout = Some(Material2dViewLayout::Replace(
+            BindGroupLayoutDescriptor::new("screen_space_view", &[]),
+        ));
+        let descriptor = pipeline.specialize(key, &test_layout()).unwrap();
+        assert_eq!(descriptor.layout.len(), 3);
+        assert_eq!(descriptor.layout[0].label, "screen_space_view");
+
+        // The view bind group is set before the mesh is drawn.
+        let mut render_app = SubApp::new();
+        render_app.init_resource::<DrawFunctions<Opaque2d>>();
+        render_app.init_resource::<DrawFunctions<AlphaMask2d>>();
+        render_app.init_resource::<DrawFunctions<Transparent2d>>();
+        add_material2d_render_commands::<ScreenSpaceMaterial, (), SetScreenSpaceViewBindGroup>(
+            &mut render_app,
+        );
+        let world = render_app.world();
+        let registered = world
+            .resource::<DrawFunctions<Opaque2d>>()
+            .read()
+            .get_id::<DrawMaterial2d<ScreenSpaceMaterial, (), SetScreenSpaceViewBindGroup>>();
+        assert_eq!(
+            registered,
+            Some(
+                world
+                    .resource::<Material2dDrawFunctions<ScreenSpaceMaterial>>()
+                    .opaque
+            )
+        );
+        let sequence = core::any::type_name::<
+            DrawMaterial2d<ScreenSpaceMaterial, (), SetScreenSpaceViewBindGroup>,
+        >();
+        let view = sequence.find("SetScreenSpaceViewBindGroup").unwrap();
+        let draw_mesh = sequence.find("DrawMesh2d").unwrap();
+        assert!(view < draw_mesh, "{sequence}");
+    }
+
+    #[test]
+    fn zoom_filter_follows_view_scale() {
+        use bevy_camera::{CameraProjection, OrthographicProjection};
+
+        let view_scale = |scale| {
+            let mut projection = OrthographicProjection {
+                scale,
+                ..OrthographicProjection::default_2d()
+            };
+            projection.update(800.0, 600.0);
+            material2d_view_scale(&projection.get_clip_from_view(), 800)
+        };
+        let zoomed_in = view_scale(0.5);
+        let zoomed_out = view_scale(4.0);
+        assert!((zoomed_in - 0.5).abs() < 1e-5, "{zoomed_in}");
+        assert!((zoomed_out - 4.0).abs() < 1e-5, "{zoomed_out}");
+
+        let filter = ZoomFilter::PIXEL_ART;
+        assert_eq!(filter.filter_for(zoomed_in), ImageFilterMode::Nearest);
+        assert_eq!(filter.filter_for(zoomed_out), ImageFilterMode::Linear);
+
+        let inverted = ZoomFilter {
+            threshold: 2.0,
+            zoomed_in: ImageFilterMode::Linear,
+            zoomed_out: ImageFilterMode::Nearest,
+        };
+        assert_eq!(inverted.filter_for(zoomed_in), ImageFilterMode::Linear);
+        assert_eq!(inverted.filter_for(zoomed_out), ImageFilterMode::Nearest);
+    }
+
+    #[test]
+    fn anisotropy_is_clamped_and_requires_support() {
+        let level = |requested, supported| {
+            anisotropic_sampler_descriptor(requested, supported)
+                .map(|descriptor| descriptor.anisotropy_clamp)
+        };
+        assert_eq!(level(Some(8), true), Some(8));
+        assert_eq!(level(Some(12), true), Some(8));
+        assert_eq!(level(Some(64), true), Some(16));
+        assert_eq!(level(Some(1), true), None);
+        assert_eq!(level(None, true), None);
+        assert_eq!(level(Some(8), false), None);
+
+        let descriptor = anisotropic_sampler_descriptor(Some(4), true).unwrap();
+        let sampler = descriptor.as_wgpu();
+        assert_eq!(sampler.anisotropy_clamp, 4);
+        assert_eq!(sampler.min_filter, FilterMode::Linear);
+        assert_eq!(sampler.mag_filter, FilterMode::Linear);
+    }
+
+    #[test]
+    fn pipelines_use_the_depth_format_of_the_view() {
+        let pipeline = test_pipeline::<RimLightMaterial>();
+        let key = Material2dKey {
+            mesh_key: Mesh2dPipelineKey::NONE,
+            bind_group_data: RimLightKey { rim_light: false },
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let pipeline_format = pipeline
+            .specialize(key, &test_layout())
+            .unwrap()
+            .depth_stencil
+            .map(|depth_stencil| depth_stencil.format);
+
+        let main_pass = DepthFormat2d::default();
+        assert_eq!(
+            validate_material2d_depth_format::<RimLightMaterial>(pipeline_format, *main_pass),
+            Ok(())
+        );
+
+        let offscreen_pass = DepthFormat2d(TextureFormat::Depth24PlusStencil8);
+        let offscreen_key = Material2dKey {
+            mesh_key: Mesh2dPipelineKey::from_depth_format(*offscreen_pass).unwrap(),
+            bind_group_data: RimLightKey { rim_light: false },
+            blend_state: None,
+            depth_write_enabled: None,
+            depth_compare: None,
+        };
+        let offscreen_format = pipeline
+            .specialize(offscreen_key, &test_layout())
+            .unwrap()
+            .depth_stencil
+            .map(|depth_stencil| depth_stencil.format);
+        assert_eq!(offscreen_format, Some(TextureFormat::Depth24PlusStencil8));
+        assert_eq!(
+            validate_material2d_depth_format::<RimLightMaterial>(offscreen_format, *offscreen_pass),
+            Ok(())
+        );
+        assert!(Mesh2dPipelineKey::from_depth_format(TextureFormat::Rgba8Unorm).is_none());
+        // Stencil-only formats have no depth to test against.
+        assert!(Mesh2dPipelineKey::from_depth_format(TextureFormat::Stencil8).is_none());
+
+        let err =
+            validate_material2d_depth_format::<RimLightMaterial>(pipeline_format, *offscreen_pass)
+                .unwrap_err();
+        assert_eq!(err.expected, Some(TextureFormat::Depth32Float));
+        assert_eq!(err.actual, TextureFormat::Depth24PlusStencil8);
+        let message = err.to_string();
+        assert!(message.contains("Depth32Float"), "{message}");
+        assert!(message.contains("Depth24PlusStencil8"), "{message}");
+        assert!(message.contains("RimLightMaterial"), "{message}");
+
+        assert!(validate_material2d_depth_format::<RimLightMaterial>(None, *main_pass).is_err());
     }
 }
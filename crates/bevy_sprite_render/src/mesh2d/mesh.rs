@@ -1,12 +1,16 @@
 use bevy_app::Plugin;
-use bevy_asset::{embedded_asset, load_embedded_asset, AssetId, AssetServer, Handle};
-use bevy_camera::{visibility::ViewVisibility, Camera2d};
+use bevy_asset::{embedded_asset, load_embedded_asset, AssetId, AssetServer, Assets, Handle};
+use bevy_camera::{visibility::ViewVisibility, Camera2d, RenderTarget};
 use bevy_render::RenderStartup;
 use bevy_shader::{load_shader_library, Shader, ShaderDefVal, ShaderSettings};
 
 use crate::{tonemapping_pipeline_key, Material2dBindGroupId};
+use bevy_color::{Color, ColorToComponents, LinearRgba};
 use bevy_core_pipeline::{
-    core_2d::{AlphaMask2d, Opaque2d, Transparent2d, CORE_2D_DEPTH_FORMAT},
+    core_2d::{
+        AlphaMask2d, ExtraColorTargets2d, Opaque2d, Prepass2d, Transparent2d, CORE_2D_DEPTH_FORMAT,
+    },
+    prepass::MotionVectorPrepass,
     tonemapping::{
         get_lut_bind_group_layout_entries, get_lut_bindings, DebandDither, Tonemapping,
         TonemappingLuts,
@@ -20,9 +24,10 @@ use bevy_ecs::{
     query::ROQueryItem,
     system::{lifetimeless::*, SystemParamItem},
 };
-use bevy_image::BevyDefault;
-use bevy_math::{Affine3, Affine3Ext, Vec4};
+use bevy_image::{BevyDefault, Image};
+use bevy_math::{Affine3, Affine3Ext, FloatOrd, Vec4};
 use bevy_mesh::{Mesh, Mesh2d, MeshTag, MeshVertexBufferLayoutRef};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use bevy_render::prelude::Msaa;
 use bevy_render::RenderSystems::PrepareAssets;
 use bevy_render::{
@@ -34,16 +39,21 @@ use bevy_render::{
         },
         GetBatchData, GetFullBatchData, NoAutomaticBatching,
     },
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
     globals::{GlobalsBuffer, GlobalsUniform},
     mesh::{allocator::MeshAllocator, RenderMesh, RenderMeshBufferInfo},
     render_asset::RenderAssets,
     render_phase::{
-        sweep_old_entities, PhaseItem, PhaseItemExtraIndex, RenderCommand, RenderCommandResult,
-        TrackedRenderPass,
+        sweep_old_entities, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+        RenderCommandResult, TrackedRenderPass, ViewBinnedRenderPhases,
+    },
+    render_resource::{
+        binding_types::{storage_buffer_read_only_sized, uniform_buffer},
+        *,
     },
-    render_resource::{binding_types::uniform_buffer, *},
-    renderer::RenderDevice,
-    sync_world::{MainEntity, MainEntityHashMap},
+    renderer::{RenderDevice, RenderQueue},
+    settings::WgpuFeatures,
+    sync_world::{MainEntity, MainEntityHashMap, RenderEntity},
     texture::{FallbackImage, GpuImage},
     view::{ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
     Extract, ExtractSchedule, Render, RenderApp, RenderSystems,
@@ -65,17 +75,27 @@ impl Plugin for Mesh2dRenderPlugin {
         load_shader_library!(app, "mesh2d_functions.wgsl");
 
         embedded_asset!(app, "mesh2d.wgsl");
+        embedded_asset!(app, "mesh2d_prepass.wgsl");
 
         // These bindings should be loaded as a shader library, but it depends on runtime
         // information, so we will load it in a system.
         embedded_asset!(app, "mesh2d_bindings.wgsl");
 
+        app.add_plugins((
+            ExtractComponentPlugin::<ColorGradingLut2d>::default(),
+            ExtractComponentPlugin::<DepthFormat2d>::default(),
+        ));
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<ViewKeyCache>()
                 .init_resource::<RenderMesh2dInstances>()
                 .init_resource::<SpecializedMeshPipelines<Mesh2dPipeline>>()
                 .init_resource::<ViewSpecializationTicks>()
+                .init_resource::<PreviousMesh2dTransforms>()
+                // Only populated for views when the `Prepass2dPlugin` is added.
+                .init_resource::<DrawFunctions<Prepass2d>>()
+                .init_resource::<ViewBinnedRenderPhases<Prepass2d>>()
                 .add_systems(
                     RenderStartup,
                     (
@@ -84,24 +104,36 @@ impl Plugin for Mesh2dRenderPlugin {
                         load_mesh2d_bindings,
                     ),
                 )
-                .add_systems(ExtractSchedule, extract_mesh2d)
+                .add_systems(
+                    ExtractSchedule,
+                    (extract_mesh2d, extract_target_sample_counts_2d),
+                )
                 .add_systems(
                     Render,
                     (
+                        apply_target_sample_counts_2d
+                            .in_set(PrepareAssets)
+                            .before(check_views_need_specialization),
                         check_views_need_specialization.in_set(PrepareAssets),
+                        prepare_previous_mesh2d_transforms.in_set(PrepareAssets),
                         (
                             sweep_old_entities::<Opaque2d>,
                             sweep_old_entities::<AlphaMask2d>,
+                            sweep_old_entities::<Prepass2d>,
                         )
                             .in_set(RenderSystems::QueueSweep),
                         batch_and_prepare_binned_render_phase::<Opaque2d, Mesh2dPipeline>
                             .in_set(RenderSystems::PrepareResources),
                         batch_and_prepare_binned_render_phase::<AlphaMask2d, Mesh2dPipeline>
                             .in_set(RenderSystems::PrepareResources),
+                        batch_and_prepare_binned_render_phase::<Prepass2d, Mesh2dPipeline>
+                            .in_set(RenderSystems::PrepareResources),
                         batch_and_prepare_sorted_render_phase::<Transparent2d, Mesh2dPipeline>
                             .in_set(RenderSystems::PrepareResources),
                         write_batched_instance_buffer::<Mesh2dPipeline>
                             .in_set(RenderSystems::PrepareResourcesFlush),
+                        write_previous_mesh2d_transforms_buffer
+                            .in_set(RenderSystems::PrepareResourcesFlush),
                         prepare_mesh2d_bind_group.in_set(RenderSystems::PrepareBindGroups),
                         prepare_mesh2d_view_bind_groups.in_set(RenderSystems::PrepareBindGroups),
                         no_gpu_preprocessing::clear_batched_cpu_instance_buffers::<Mesh2dPipeline>
@@ -113,6 +145,180 @@ impl Plugin for Mesh2dRenderPlugin {
     }
 }
 
+/// A 3D lookup table used to color grade the [`Material2d`](crate::Material2d)s drawn by this
+/// 2D camera.
+///
+/// Only materials that opt in with [`Material2d::uses_lut`](crate::Material2d::uses_lut) are
+/// graded. The LUT is bound to the mesh2d view bind group as `color_grading_lut_texture` and
+/// applied with `mesh2d_functions::mesh2d_color_grading_lut`, which maps the red, green, and blue
+/// channels of a color to the three axes of the texture.
+#[derive(Component, Clone, Debug, Default, ExtractComponent, Reflect)]
+#[reflect(Component, Debug, Default, Clone)]
+pub struct ColorGradingLut2d(pub Handle<Image>);
+
+/// A per-instance color passed to the material of a [`Mesh2d`].
+///
+/// The tint is stored in the mesh instance data rather than in the material, so entities sharing
+/// a material and mesh are still batched together while being tinted differently. It reaches the
+/// vertex shader through `mesh2d_functions::get_tint` and the fragment shader as
+/// `VertexOutput::tint`. [`ColorMaterial`](crate::ColorMaterial) multiplies its color by it.
+///
+/// Meshes without this component are tinted white.
+///
+/// Only the default vertex shader sets the tint, which it signals to the fragment shader with the
+/// `MESH2D_TINT` shader def, so the built-in materials ignore the tint of materials with a custom
+/// [`Material2d::vertex_shader`](crate::Material2d::vertex_shader). Custom vertex shaders that set
+/// `tint`, such as with `mesh2d_functions::get_tint`, can add the shader def to the fragment shader
+/// in [`Material2d::specialize`](crate::Material2d::specialize).
+#[derive(Component, Clone, Copy, Debug, PartialEq, Deref, DerefMut, Reflect)]
+#[reflect(Component, Debug, Default, Clone, PartialEq)]
+pub struct Mesh2dTint(pub Color);
+
+impl Default for Mesh2dTint {
+    fn default() -> Self {
+        Self(Color::WHITE)
+    }
+}
+
+/// Orders a transparent [`Mesh2d`] by a sort layer and an order within that layer, instead of by
+/// the z of its [`GlobalTransform`].
+///
+/// Meshes on a higher layer are drawn after, so in front of, those on lower layers, whatever their
+/// order, and meshes with a higher order are drawn after the others of the same layer. Like
+/// [`RenderLayer2dOrder`], this only applies to meshes whose [`Material2d`](crate::Material2d) is
+/// drawn in the [`Transparent2d`] phase, and the transform of the mesh is left as is, so opaque
+/// and alpha mask meshes are still depth tested with their z. Unlike [`RenderLayer2dOrder`], the z
+/// isn't used to sort the mesh at all.
+///
+/// The layer and order replace the z in the sort key, see [`SortLayer2d::z`], which is still
+/// ordered by the [`RenderLayer2dOrder`] of the mesh for layers between `-1000` and `1000`. The
+/// order is clamped between [`SortLayer2d::MIN_ORDER`] and [`SortLayer2d::MAX_ORDER`].
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Debug, Default, Clone, PartialEq)]
+pub struct SortLayer2d {
+    /// The layer of the mesh.
+    pub layer: i32,
+    /// The order of the mesh within its layer.
+    pub order: i32,
+}
+
+impl SortLayer2d {
+    /// The lowest order that is distinguished within a layer.
+    pub const MIN_ORDER: i32 = -511;
+    /// The highest order that is distinguished within a layer.
+    pub const MAX_ORDER: i32 = 511;
+
+    /// Creates a sort layer with the given layer and order.
+    pub const fn new(layer: i32, order: i32) -> Self {
+        Self { layer, order }
+    }
+
+    /// The z that meshes on this layer and with this order are sorted at, in place of the z of
+    /// their transform.
+    pub fn z(&self) -> f32 {
+        let order = self.order.clamp(Self::MIN_ORDER, Self::MAX_ORDER);
+        // Orders step by a power of two, which keeps them exact, and stay within half a layer.
+        self.layer as f32 + order as f32 / 1024.0
+    }
+}
+
+/// Orders a transparent [`Mesh2d`] relative to the others in the [`Transparent2d`] phase,
+/// independently of the z of its [`GlobalTransform`], such as to always draw UI-like meshes over
+/// the world.
+///
+/// Meshes with a higher order are drawn after, so in front of, those with a lower order, whatever
+/// their z. Meshes with the same order are sorted by their z as usual. Unlike [`SortLayer2d`],
+/// the z of the mesh is kept, so this doesn't change how opaque and alpha mask meshes are depth
+/// tested, and only applies to meshes whose [`Material2d`](crate::Material2d) is drawn in the
+/// [`Transparent2d`] phase.
+///
+/// The order is combined with the z into the sort key by [`RenderLayer2dOrder::sort_key`], which
+/// distinguishes orders between [`RenderLayer2dOrder::MIN`] and [`RenderLayer2dOrder::MAX`] for
+/// meshes within the `-1000` to `1000` z range of the default [`Camera2d`]. The further the order
+/// is from `0`, the coarser the z differences that are still told apart.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Deref, DerefMut, Reflect)]
+#[reflect(Component, Debug, Default, Clone, PartialEq)]
+pub struct RenderLayer2dOrder(pub i32);
+
+impl RenderLayer2dOrder {
+    /// The lowest order that is distinguished from the others.
+    pub const MIN: i32 = -128;
+    /// The highest order that is distinguished from the others.
+    pub const MAX: i32 = 127;
+    /// The sort key distance between consecutive orders, which is wider than the z range of the
+    /// default [`Camera2d`].
+    pub const SPACING: f32 = 2048.0;
+
+    /// Returns the sort key of a mesh with this order at the given z.
+    ///
+    /// A mesh with the default order of `0` keeps its z as its sort key.
+    pub fn sort_key(self, z: f32) -> f32 {
+        let order = self.0.clamp(Self::MIN, Self::MAX);
+        // The spacing is a power of two, so the offset of each order is exact.
+        order as f32 * Self::SPACING + z
+    }
+}
+
+/// The format of the depth attachment of a 2D view, for views that don't use
+/// [`CORE_2D_DEPTH_FORMAT`], such as off-screen passes.
+///
+/// 2D mesh pipelines are specialized for this format through [`Mesh2dPipelineKey`].
+/// [`Material2d`](crate::Material2d) pipelines that still end up with another depth format,
+/// because the material overrides it or the view's format isn't a depth format, are skipped with
+/// a warning when queued into the view, rather than failing GPU validation.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, ExtractComponent)]
+pub struct DepthFormat2d(pub TextureFormat);
+
+impl Default for DepthFormat2d {
+    fn default() -> Self {
+        Self(CORE_2D_DEPTH_FORMAT)
+    }
+}
+
+/// The number of samples of the multisampled [`Image`] a 2D camera renders into.
+///
+/// Extracted onto the views of [`Camera2d`]s whose [`RenderTarget`] is a multisampled image, and
+/// applied to the view's [`Msaa`] by [`apply_target_sample_counts_2d`], so that the view target,
+/// the depth texture and the mesh2d and [`Material2d`](crate::Material2d) pipelines all use the
+/// sample count of the image. Single-sampled images keep the view's [`Msaa`], and are resolved
+/// into as usual.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct TargetSampleCount2d(pub u32);
+
+pub fn extract_target_sample_counts_2d(
+    mut commands: Commands,
+    cameras: Extract<Query<(RenderEntity, &RenderTarget), With<Camera2d>>>,
+    images: Extract<Res<Assets<Image>>>,
+) {
+    for (render_entity, target) in &cameras {
+        let sample_count = target
+            .as_image()
+            .and_then(|image| images.get(image))
+            .map(|image| image.texture_descriptor.sample_count)
+            .filter(|sample_count| matches!(sample_count, 2 | 4 | 8));
+        match sample_count {
+            Some(sample_count) => {
+                commands
+                    .entity(render_entity)
+                    .try_insert(TargetSampleCount2d(sample_count));
+            }
+            None => {
+                commands
+                    .entity(render_entity)
+                    .try_remove::<TargetSampleCount2d>();
+            }
+        }
+    }
+}
+
+/// Overrides the [`Msaa`] of views rendering into a multisampled image with its
+/// [`TargetSampleCount2d`], before their view targets and pipeline keys are prepared.
+pub fn apply_target_sample_counts_2d(mut views: Query<(&TargetSampleCount2d, &mut Msaa)>) {
+    for (sample_count, mut msaa) in &mut views {
+        msaa.set_if_neq(Msaa::from_samples(sample_count.0));
+    }
+}
+
 #[derive(Resource, Deref, DerefMut, Default, Debug, Clone)]
 pub struct ViewKeyCache(MainEntityHashMap<Mesh2dPipelineKey>);
 
@@ -128,13 +334,49 @@ pub fn check_views_need_specialization(
         &Msaa,
         Option<&Tonemapping>,
         Option<&DebandDither>,
+        Has<MotionVectorPrepass>,
+        Has<ColorGradingLut2d>,
+        Option<&ExtraColorTargets2d>,
+        Option<&DepthFormat2d>,
     )>,
     ticks: SystemChangeTick,
 ) {
-    for (view_entity, view, msaa, tonemapping, dither) in &views {
+    for (
+        view_entity,
+        view,
+        msaa,
+        tonemapping,
+        dither,
+        motion_vector_prepass,
+        color_grading_lut,
+        extra_color_targets,
+        depth_format,
+    ) in &views
+    {
         let mut view_key = Mesh2dPipelineKey::from_msaa_samples(msaa.samples())
             | Mesh2dPipelineKey::from_hdr(view.hdr);
 
+        // Only the prepass binds the motion vector texture, so this is removed from the keys of
+        // the main passes.
+        if motion_vector_prepass {
+            view_key |= Mesh2dPipelineKey::MOTION_VECTOR_PREPASS;
+        }
+
+        if color_grading_lut {
+            view_key |= Mesh2dPipelineKey::COLOR_GRADING_LUT;
+        }
+
+        if let Some(extra_color_targets) = extra_color_targets {
+            view_key |= Mesh2dPipelineKey::from_extra_color_targets(extra_color_targets.0.len());
+        }
+
+        // Unsupported formats keep the default, and are reported when the view is queued.
+        if let Some(depth_format) =
+            depth_format.and_then(|format| Mesh2dPipelineKey::from_depth_format(format.0))
+        {
+            view_key |= depth_format;
+        }
+
         if !view.hdr {
             if let Some(tonemapping) = tonemapping {
                 view_key |= Mesh2dPipelineKey::TONEMAP_IN_SHADER;
@@ -192,6 +434,13 @@ fn load_mesh2d_bindings(render_device: Res<RenderDevice>, asset_server: Res<Asse
 #[derive(Component)]
 pub struct Mesh2dTransforms {
     pub world_from_local: Affine3,
+    /// The transform of the mesh during the previous frame, used to compute motion vectors.
+    ///
+    /// Equal to `world_from_local` on the first frame a mesh is extracted.
+    pub previous_world_from_local: Affine3,
+    /// The index of `previous_world_from_local` in the [`PreviousMesh2dTransforms`], set while a
+    /// view has a [`MotionVectorPrepass`].
+    pub previous_world_from_local_index: u32,
     pub flags: u32,
 }
 
@@ -207,10 +456,13 @@ pub struct Mesh2dUniform {
     pub local_from_world_transpose_b: f32,
     pub flags: u32,
     pub tag: u32,
+    // Fills the padding before `tint`, so motion vectors don't grow the uniform.
+    pub previous_world_from_local_index: u32,
+    pub tint: Vec4,
 }
 
 impl Mesh2dUniform {
-    fn from_components(mesh_transforms: &Mesh2dTransforms, tag: u32) -> Self {
+    fn from_components(mesh_transforms: &Mesh2dTransforms, tag: u32, tint: LinearRgba) -> Self {
         let (local_from_world_transpose_a, local_from_world_transpose_b) =
             mesh_transforms.world_from_local.inverse_transpose_3x3();
         Self {
@@ -219,6 +471,8 @@ impl Mesh2dUniform {
             local_from_world_transpose_b,
             flags: mesh_transforms.flags,
             tag,
+            previous_world_from_local_index: mesh_transforms.previous_world_from_local_index,
+            tint: tint.to_vec4(),
         }
     }
 }
@@ -238,6 +492,30 @@ pub struct RenderMesh2dInstance {
     pub material_bind_group_id: Material2dBindGroupId,
     pub automatic_batching: bool,
     pub tag: u32,
+    /// The [`Mesh2dTint`] of the mesh, or white if it has none.
+    pub tint: LinearRgba,
+    /// The [`RenderLayer2dOrder`] of the mesh, or `0` if it has none.
+    pub order: RenderLayer2dOrder,
+    /// The [`SortLayer2d`] of the mesh, if any.
+    pub sort_layer: Option<SortLayer2d>,
+}
+
+impl RenderMesh2dInstance {
+    /// The key the mesh is sorted by in the [`Transparent2d`] phase, back to front.
+    ///
+    /// This is the z of the mesh, or of its [`SortLayer2d`], offset by `depth_bias` and ordered by
+    /// its [`RenderLayer2dOrder`].
+    pub fn transparent_sort_key(&self, depth_bias: f32) -> FloatOrd {
+        // NOTE: Back-to-front ordering for transparent with ascending sort means far should have
+        // the lowest sort key and getting closer should increase. As we have -z in front of the
+        // camera, the largest distance is -far with values increasing toward the camera. As such
+        // we can just use the z as the distance.
+        let z = self.sort_layer.as_ref().map_or(
+            self.transforms.world_from_local.translation.z,
+            SortLayer2d::z,
+        );
+        FloatOrd(self.order.sort_key(z + depth_bias))
+    }
 }
 
 #[derive(Default, Resource, Deref, DerefMut)]
@@ -255,38 +533,126 @@ pub fn extract_mesh2d(
             &GlobalTransform,
             &Mesh2d,
             Option<&MeshTag>,
+            Option<&Mesh2dTint>,
+            Option<&SortLayer2d>,
+            Option<&RenderLayer2dOrder>,
             Has<NoAutomaticBatching>,
         )>,
     >,
 ) {
-    render_mesh_instances.clear();
-
-    for (entity, view_visibility, transform, handle, tag, no_automatic_batching) in &query {
+    let previous_instances = core::mem::take(&mut render_mesh_instances.0);
+
+    for (
+        entity,
+        view_visibility,
+        transform,
+        handle,
+        tag,
+        tint,
+        sort_layer,
+        order,
+        no_automatic_batching,
+    ) in &query
+    {
         if !view_visibility.get() {
             continue;
         }
+        let world_from_local: Affine3 = transform.affine().into();
+        let previous_world_from_local = previous_instances
+            .get(&MainEntity::from(entity))
+            .map_or(world_from_local, |instance| {
+                instance.transforms.world_from_local
+            });
         render_mesh_instances.insert(
             entity.into(),
             RenderMesh2dInstance {
                 transforms: Mesh2dTransforms {
-                    world_from_local: transform.affine().into(),
+                    world_from_local,
+                    previous_world_from_local,
+                    previous_world_from_local_index: 0,
                     flags: MeshFlags::empty().bits(),
                 },
                 mesh_asset_id: handle.0.id(),
                 material_bind_group_id: Material2dBindGroupId::default(),
                 automatic_batching: !no_automatic_batching,
                 tag: tag.map_or(0, |i| **i),
+                tint: tint.map_or(LinearRgba::WHITE, |tint| tint.to_linear()),
+                order: order.copied().unwrap_or_default(),
+                sort_layer: sort_layer.copied(),
             },
         );
     }
 }
 
+/// The transforms of the 2D meshes during the previous frame, read by the 2D motion vector
+/// prepass through [`Mesh2dTransforms::previous_world_from_local_index`].
+///
+/// They are only filled while a 2D view has a [`MotionVectorPrepass`], and never on devices
+/// without storage buffers, where meshes have no motion.
+#[derive(Resource, Deref, DerefMut)]
+pub struct PreviousMesh2dTransforms(pub RawBufferVec<[Vec4; 3]>);
+
+impl Default for PreviousMesh2dTransforms {
+    fn default() -> Self {
+        let mut buffer = RawBufferVec::new(BufferUsages::STORAGE);
+        buffer.set_label(Some("previous_mesh2d_transforms"));
+        Self(buffer)
+    }
+}
+
+pub fn prepare_previous_mesh2d_transforms(
+    mut render_mesh_instances: ResMut<RenderMesh2dInstances>,
+    mut previous_transforms: ResMut<PreviousMesh2dTransforms>,
+    mesh2d_pipeline: Res<Mesh2dPipeline>,
+    motion_vector_views: Query<
+        (),
+        (
+            With<ExtractedView>,
+            With<Camera2d>,
+            With<MotionVectorPrepass>,
+        ),
+    >,
+) {
+    previous_transforms.clear();
+    if mesh2d_pipeline.per_object_buffer_batch_size.is_some() {
+        return;
+    }
+
+    if !motion_vector_views.is_empty() {
+        for mesh_instance in render_mesh_instances.values_mut() {
+            let transforms = &mut mesh_instance.transforms;
+            transforms.previous_world_from_local_index = previous_transforms
+                .push(transforms.previous_world_from_local.to_transpose())
+                as u32;
+        }
+    }
+
+    // The buffer is bound even when no view reads it, so it can't be empty.
+    if previous_transforms.is_empty() {
+        previous_transforms.push(Affine3::IDENTITY.to_transpose());
+    }
+}
+
+pub fn write_previous_mesh2d_transforms_buffer(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut previous_transforms: ResMut<PreviousMesh2dTransforms>,
+) {
+    previous_transforms.write_buffer(&render_device, &render_queue);
+}
+
 #[derive(Resource, Clone)]
 pub struct Mesh2dPipeline {
     pub view_layout: BindGroupLayoutDescriptor,
     pub mesh_layout: BindGroupLayoutDescriptor,
     pub shader: Handle<Shader>,
+    /// The fragment shader of the [`Prepass2d`] for materials without a prepass fragment shader,
+    /// on views with a [`MotionVectorPrepass`].
+    pub prepass_shader: Handle<Shader>,
     pub per_object_buffer_batch_size: Option<u32>,
+    /// Whether the device supports [`WgpuFeatures::DEPTH_CLIP_CONTROL`], which is required to
+    /// honor [`Mesh2dPipelineKey::UNCLIPPED_DEPTH`].
+    pub depth_clip_control_supported: bool,
 }
 
 pub fn init_mesh_2d_pipeline(
@@ -304,25 +670,39 @@ pub fn init_mesh_2d_pipeline(
                 uniform_buffer::<GlobalsUniform>(false),
                 tonemapping_lut_entries[0].visibility(ShaderStages::FRAGMENT),
                 tonemapping_lut_entries[1].visibility(ShaderStages::FRAGMENT),
+                tonemapping_lut_entries[0].visibility(ShaderStages::FRAGMENT),
+                tonemapping_lut_entries[1].visibility(ShaderStages::FRAGMENT),
             ),
         ),
     );
 
-    let mesh_layout = BindGroupLayoutDescriptor::new(
-        "mesh2d_layout",
-        &BindGroupLayoutEntries::single(
-            ShaderStages::VERTEX_FRAGMENT,
-            GpuArrayBuffer::<Mesh2dUniform>::binding_layout(&render_device.limits()),
+    let per_object_buffer_batch_size =
+        GpuArrayBuffer::<Mesh2dUniform>::batch_size(&render_device.limits());
+    let mesh_binding = GpuArrayBuffer::<Mesh2dUniform>::binding_layout(&render_device.limits());
+    let mesh_layout = match per_object_buffer_batch_size {
+        Some(_) => BindGroupLayoutDescriptor::new(
+            "mesh2d_layout",
+            &BindGroupLayoutEntries::single(ShaderStages::VERTEX_FRAGMENT, mesh_binding),
         ),
-    );
+        // The `PreviousMesh2dTransforms` are only bound on devices with storage buffers.
+        None => BindGroupLayoutDescriptor::new(
+            "mesh2d_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX_FRAGMENT,
+                (mesh_binding, storage_buffer_read_only_sized(false, None)),
+            ),
+        ),
+    };
 
     commands.insert_resource(Mesh2dPipeline {
         view_layout,
         mesh_layout,
-        per_object_buffer_batch_size: GpuArrayBuffer::<Mesh2dUniform>::batch_size(
-            &render_device.limits(),
-        ),
+        per_object_buffer_batch_size,
         shader: load_embedded_asset!(asset_server.as_ref(), "mesh2d.wgsl"),
+        prepass_shader: load_embedded_asset!(asset_server.as_ref(), "mesh2d_prepass.wgsl"),
+        depth_clip_control_supported: render_device
+            .features()
+            .contains(WgpuFeatures::DEPTH_CLIP_CONTROL),
     });
 }
 
@@ -341,7 +721,11 @@ impl GetBatchData for Mesh2dPipeline {
     ) -> Option<(Self::BufferData, Option<Self::CompareData>)> {
         let mesh_instance = mesh_instances.get(&main_entity)?;
         Some((
-            Mesh2dUniform::from_components(&mesh_instance.transforms, mesh_instance.tag),
+            Mesh2dUniform::from_components(
+                &mesh_instance.transforms,
+                mesh_instance.tag,
+                mesh_instance.tint,
+            ),
             mesh_instance.automatic_batching.then_some((
                 mesh_instance.material_bind_group_id,
                 mesh_instance.mesh_asset_id,
@@ -361,6 +745,7 @@ impl GetFullBatchData for Mesh2dPipeline {
         Some(Mesh2dUniform::from_components(
             &mesh_instance.transforms,
             mesh_instance.tag,
+            mesh_instance.tint,
         ))
     }
 
@@ -429,6 +814,55 @@ bitflags::bitflags! {
         const DEBAND_DITHER                     = 1 << 2;
         const BLEND_ALPHA                       = 1 << 3;
         const MAY_DISCARD                       = 1 << 4;
+        /// Set when the view has a [`MotionVectorPrepass`], whose texture is bound by the
+        /// [`Prepass2d`] phase. Only kept in the keys of [`Mesh2dPipelineKey::DEPTH_PREPASS`]
+        /// pipelines.
+        const MOTION_VECTOR_PREPASS             = 1 << 5;
+        /// Disables clipping of fragments outside the near and far planes, if the device
+        /// supports it. See [`Material2d::unclipped_depth`](crate::Material2d::unclipped_depth).
+        const UNCLIPPED_DEPTH                   = 1 << 6;
+        /// Set when the view has a [`ColorGradingLut2d`] and the material applies it.
+        /// See [`Material2d::uses_lut`](crate::Material2d::uses_lut).
+        const COLOR_GRADING_LUT                 = 1 << 7;
+        /// Set when the material dithers its output.
+        /// See [`Material2d::dither`](crate::Material2d::dither).
+        const MATERIAL_DITHER                   = 1 << 8;
+        /// Disables depth testing and writing.
+        /// See [`Material2d::ignore_depth`](crate::Material2d::ignore_depth).
+        const IGNORE_DEPTH                      = 1 << 9;
+        /// Set for strip meshes with 16-bit indices. See
+        /// [`Mesh2dPipelineKey::from_strip_index_format`].
+        const STRIP_INDEX_UINT16                = 1 << 10;
+        /// Set for strip meshes with 32-bit indices. See
+        /// [`Mesh2dPipelineKey::from_strip_index_format`].
+        const STRIP_INDEX_UINT32                = 1 << 11;
+        /// Adds the color of the material to the color behind it.
+        /// See [`AlphaMode2d::Additive`](crate::AlphaMode2d::Additive).
+        const BLEND_ADDITIVE                    = 1 << 12;
+        /// Multiplies the color behind the material by the color of the material.
+        /// See [`AlphaMode2d::Multiply`](crate::AlphaMode2d::Multiply).
+        const BLEND_MULTIPLY                    = 1 << 13;
+        /// Blends colors with premultiplied alpha with the color behind them.
+        /// See [`AlphaMode2d::Premultiplied`](crate::AlphaMode2d::Premultiplied).
+        const BLEND_PREMULTIPLIED_ALPHA         = 1 << 14;
+        /// Set for pipelines drawn in the depth-only [`Prepass2d`] phase.
+        /// See [`Material2d::prepass_fragment_shader`](crate::Material2d::prepass_fragment_shader).
+        const DEPTH_PREPASS                     = 1 << 15;
+        /// Set for [`Mesh2dPipelineKey::MOTION_VECTOR_PREPASS`] pipelines whose material writes
+        /// motion vectors.
+        /// See [`Material2d::writes_motion_vectors`](crate::Material2d::writes_motion_vectors).
+        const WRITES_MOTION_VECTORS             = 1 << 21;
+        /// Draws the edges of the triangles of the mesh as lines, which requires
+        /// [`WgpuFeatures::POLYGON_MODE_LINE`](bevy_render::settings::WgpuFeatures::POLYGON_MODE_LINE).
+        /// Set by the [`Wireframe2dPlugin`](crate::Wireframe2dPlugin) for the meshes it draws.
+        const WIREFRAME                         = 1 << 22;
+        /// All the bits that make a mesh blend with the color behind it, and not write depth.
+        const BLEND_RESERVED_BITS               = Self::BLEND_ALPHA.bits() | Self::BLEND_ADDITIVE.bits() | Self::BLEND_MULTIPLY.bits() | Self::BLEND_PREMULTIPLIED_ALPHA.bits();
+        /// The number of [`ExtraColorTargets2d`] of the view, see
+        /// [`Mesh2dPipelineKey::from_extra_color_targets`].
+        const EXTRA_COLOR_TARGETS_RESERVED_BITS = Self::EXTRA_COLOR_TARGETS_MASK_BITS << Self::EXTRA_COLOR_TARGETS_SHIFT_BITS;
+        /// The [`DepthFormat2d`] of the view, see [`Mesh2dPipelineKey::from_depth_format`].
+        const DEPTH_FORMAT_RESERVED_BITS        = Self::DEPTH_FORMAT_MASK_BITS << Self::DEPTH_FORMAT_SHIFT_BITS;
         const MSAA_RESERVED_BITS                = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
         const PRIMITIVE_TOPOLOGY_RESERVED_BITS  = Self::PRIMITIVE_TOPOLOGY_MASK_BITS << Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS;
         const TONEMAP_METHOD_RESERVED_BITS      = Self::TONEMAP_METHOD_MASK_BITS << Self::TONEMAP_METHOD_SHIFT_BITS;
@@ -451,6 +885,19 @@ impl Mesh2dPipelineKey {
     const TONEMAP_METHOD_MASK_BITS: u32 = 0b111;
     const TONEMAP_METHOD_SHIFT_BITS: u32 =
         Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS - Self::TONEMAP_METHOD_MASK_BITS.count_ones();
+    const EXTRA_COLOR_TARGETS_MASK_BITS: u32 = 0b11;
+    const EXTRA_COLOR_TARGETS_SHIFT_BITS: u32 = 16;
+    const DEPTH_FORMAT_MASK_BITS: u32 = 0b111;
+    const DEPTH_FORMAT_SHIFT_BITS: u32 = 18;
+    /// The depth formats that can be encoded in the key, indexed by their bits. Formats without
+    /// a depth aspect, such as [`TextureFormat::Stencil8`], can't be depth tested against.
+    const DEPTH_FORMATS: [TextureFormat; 5] = [
+        CORE_2D_DEPTH_FORMAT,
+        TextureFormat::Depth24Plus,
+        TextureFormat::Depth24PlusStencil8,
+        TextureFormat::Depth32FloatStencil8,
+        TextureFormat::Depth16Unorm,
+    ];
 
     pub fn from_msaa_samples(msaa_samples: u32) -> Self {
         let msaa_bits =
@@ -470,6 +917,40 @@ impl Mesh2dPipelineKey {
         1 << ((self.bits() >> Self::MSAA_SHIFT_BITS) & Self::MSAA_MASK_BITS)
     }
 
+    /// Returns the key bits of a view binding `count` [`ExtraColorTargets2d`] after its view
+    /// target. At most three are supported.
+    pub fn from_extra_color_targets(count: usize) -> Self {
+        let count = (count as u32).min(Self::EXTRA_COLOR_TARGETS_MASK_BITS);
+        Self::from_bits_retain(count << Self::EXTRA_COLOR_TARGETS_SHIFT_BITS)
+    }
+
+    /// The number of color attachments the view binds after its view target, as set by
+    /// [`Mesh2dPipelineKey::from_extra_color_targets`].
+    pub fn extra_color_targets(&self) -> usize {
+        ((self.bits() >> Self::EXTRA_COLOR_TARGETS_SHIFT_BITS)
+            & Self::EXTRA_COLOR_TARGETS_MASK_BITS) as usize
+    }
+
+    /// Encodes the depth format of the view, or returns `None` if `format` has no depth aspect.
+    pub fn from_depth_format(format: TextureFormat) -> Option<Self> {
+        let index = Self::DEPTH_FORMATS
+            .iter()
+            .position(|depth_format| *depth_format == format)? as u32;
+        Some(Self::from_bits_retain(
+            index << Self::DEPTH_FORMAT_SHIFT_BITS,
+        ))
+    }
+
+    /// The depth format of the view, as set by [`Mesh2dPipelineKey::from_depth_format`].
+    /// Defaults to [`CORE_2D_DEPTH_FORMAT`].
+    pub fn depth_format(&self) -> TextureFormat {
+        let index = (self.bits() >> Self::DEPTH_FORMAT_SHIFT_BITS) & Self::DEPTH_FORMAT_MASK_BITS;
+        Self::DEPTH_FORMATS
+            .get(index as usize)
+            .copied()
+            .unwrap_or(CORE_2D_DEPTH_FORMAT)
+    }
+
     pub fn from_primitive_topology(primitive_topology: PrimitiveTopology) -> Self {
         let primitive_topology_bits = ((primitive_topology as u32)
             & Self::PRIMITIVE_TOPOLOGY_MASK_BITS)
@@ -489,6 +970,40 @@ impl Mesh2dPipelineKey {
             _ => PrimitiveTopology::default(),
         }
     }
+
+    /// Returns the key bits of the index format of a mesh, if it is an indexed strip.
+    ///
+    /// Indexed strips are split by primitive restart: an index of `u16::MAX` for
+    /// [`IndexFormat::Uint16`], or `u32::MAX` for [`IndexFormat::Uint32`], ends the current strip,
+    /// and the next index starts a new one. This lets a single mesh hold several strips without
+    /// them being connected by degenerate primitives. The pipeline has to know the index format
+    /// to recognize the restart value, so it is part of the key.
+    pub fn from_strip_index_format(
+        primitive_topology: PrimitiveTopology,
+        buffer_info: &RenderMeshBufferInfo,
+    ) -> Self {
+        match buffer_info {
+            RenderMeshBufferInfo::Indexed { index_format, .. } if primitive_topology.is_strip() => {
+                match index_format {
+                    IndexFormat::Uint16 => Mesh2dPipelineKey::STRIP_INDEX_UINT16,
+                    IndexFormat::Uint32 => Mesh2dPipelineKey::STRIP_INDEX_UINT32,
+                }
+            }
+            _ => Mesh2dPipelineKey::NONE,
+        }
+    }
+
+    /// The index format of an indexed strip mesh, as set by
+    /// [`Mesh2dPipelineKey::from_strip_index_format`].
+    pub fn strip_index_format(&self) -> Option<IndexFormat> {
+        if self.contains(Mesh2dPipelineKey::STRIP_INDEX_UINT32) {
+            Some(IndexFormat::Uint32)
+        } else if self.contains(Mesh2dPipelineKey::STRIP_INDEX_UINT16) {
+            Some(IndexFormat::Uint16)
+        } else {
+            None
+        }
+    }
 }
 
 impl SpecializedMeshPipeline for Mesh2dPipeline {
@@ -577,6 +1092,24 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
             shader_defs.push("MAY_DISCARD".into());
         }
 
+        if key.contains(
+            Mesh2dPipelineKey::MOTION_VECTOR_PREPASS | Mesh2dPipelineKey::WRITES_MOTION_VECTORS,
+        ) {
+            shader_defs.push("MOTION_VECTOR_PREPASS".into());
+        }
+
+        if key.contains(Mesh2dPipelineKey::COLOR_GRADING_LUT) {
+            shader_defs.push("COLOR_GRADING_LUT".into());
+        }
+
+        if key.contains(Mesh2dPipelineKey::MATERIAL_DITHER) {
+            shader_defs.push("MATERIAL_DITHER".into());
+        }
+
+        if key.contains(Mesh2dPipelineKey::DEPTH_PREPASS) {
+            shader_defs.push("DEPTH_PREPASS".into());
+        }
+
         let vertex_buffer_layout = layout.0.get_layout(&vertex_attributes)?;
 
         let format = match key.contains(Mesh2dPipelineKey::HDR) {
@@ -585,15 +1118,26 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
         };
 
         let (depth_write_enabled, label, blend);
-        if key.contains(Mesh2dPipelineKey::BLEND_ALPHA) {
+        if key.intersects(Mesh2dPipelineKey::BLEND_RESERVED_BITS) {
             label = "transparent_mesh2d_pipeline";
+            // The blend of the other blend modes is selected by the material pipeline.
             blend = Some(BlendState::ALPHA_BLENDING);
             depth_write_enabled = false;
         } else {
-            label = "opaque_mesh2d_pipeline";
+            label = if key.contains(Mesh2dPipelineKey::DEPTH_PREPASS) {
+                "depth_prepass_mesh2d_pipeline"
+            } else {
+                "opaque_mesh2d_pipeline"
+            };
             blend = None;
             depth_write_enabled = true;
         }
+        let (depth_write_enabled, depth_compare) = if key.contains(Mesh2dPipelineKey::IGNORE_DEPTH)
+        {
+            (false, CompareFunction::Always)
+        } else {
+            (depth_write_enabled, CompareFunction::GreaterEqual)
+        };
 
         Ok(RenderPipelineDescriptor {
             vertex: VertexState {
@@ -616,16 +1160,25 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
             primitive: PrimitiveState {
                 front_face: FrontFace::Ccw,
                 cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: key.contains(Mesh2dPipelineKey::UNCLIPPED_DEPTH)
+                    && self.depth_clip_control_supported,
+                polygon_mode: if key.contains(Mesh2dPipelineKey::WIREFRAME) {
+                    PolygonMode::Line
+                } else {
+                    PolygonMode::Fill
+                },
                 conservative: false,
                 topology: key.primitive_topology(),
-                strip_index_format: None,
+                strip_index_format: key
+                    .primitive_topology()
+                    .is_strip()
+                    .then(|| key.strip_index_format())
+                    .flatten(),
             },
             depth_stencil: Some(DepthStencilState {
-                format: CORE_2D_DEPTH_FORMAT,
+                format: key.depth_format(),
                 depth_write_enabled,
-                depth_compare: CompareFunction::GreaterEqual,
+                depth_compare,
                 stencil: StencilState {
                     front: StencilFaceState::IGNORE,
                     back: StencilFaceState::IGNORE,
@@ -660,16 +1213,30 @@ pub fn prepare_mesh2d_bind_group(
     render_device: Res<RenderDevice>,
     pipeline_cache: Res<PipelineCache>,
     mesh2d_uniforms: Res<BatchedInstanceBuffer<Mesh2dUniform>>,
+    previous_transforms: Res<PreviousMesh2dTransforms>,
 ) {
-    if let Some(binding) = mesh2d_uniforms.instance_data_binding() {
-        commands.insert_resource(Mesh2dBindGroup {
-            value: render_device.create_bind_group(
+    let Some(binding) = mesh2d_uniforms.instance_data_binding() else {
+        return;
+    };
+    let layout = pipeline_cache.get_bind_group_layout(&mesh2d_pipeline.mesh_layout);
+    let value = match mesh2d_pipeline.per_object_buffer_batch_size {
+        Some(_) => render_device.create_bind_group(
+            "mesh2d_bind_group",
+            &layout,
+            &BindGroupEntries::single(binding),
+        ),
+        None => {
+            let Some(previous_transforms) = previous_transforms.binding() else {
+                return;
+            };
+            render_device.create_bind_group(
                 "mesh2d_bind_group",
-                &pipeline_cache.get_bind_group_layout(&mesh2d_pipeline.mesh_layout),
-                &BindGroupEntries::single(binding),
-            ),
-        });
-    }
+                &layout,
+                &BindGroupEntries::sequential((binding, previous_transforms)),
+            )
+        }
+    };
+    commands.insert_resource(Mesh2dBindGroup { value });
 }
 
 #[derive(Component)]
@@ -683,7 +1250,10 @@ pub fn prepare_mesh2d_view_bind_groups(
     pipeline_cache: Res<PipelineCache>,
     mesh2d_pipeline: Res<Mesh2dPipeline>,
     view_uniforms: Res<ViewUniforms>,
-    views: Query<(Entity, &Tonemapping), (With<ExtractedView>, With<Camera2d>)>,
+    views: Query<
+        (Entity, &Tonemapping, Option<&ColorGradingLut2d>),
+        (With<ExtractedView>, With<Camera2d>),
+    >,
     globals_buffer: Res<GlobalsBuffer>,
     tonemapping_luts: Res<TonemappingLuts>,
     images: Res<RenderAssets<GpuImage>>,
@@ -696,9 +1266,12 @@ pub fn prepare_mesh2d_view_bind_groups(
         return;
     };
 
-    for (entity, tonemapping) in &views {
+    for (entity, tonemapping, color_grading_lut) in &views {
         let lut_bindings =
             get_lut_bindings(&images, &tonemapping_luts, tonemapping, &fallback_image);
+        let color_grading_lut = color_grading_lut
+            .and_then(|lut| images.get(&lut.0))
+            .unwrap_or(&fallback_image.d3);
         let view_bind_group = render_device.create_bind_group(
             "mesh2d_view_bind_group",
             &pipeline_cache.get_bind_group_layout(&mesh2d_pipeline.view_layout),
@@ -707,6 +1280,8 @@ pub fn prepare_mesh2d_view_bind_groups(
                 globals.clone(),
                 lut_bindings.0,
                 lut_bindings.1,
+                &color_grading_lut.texture_view,
+                &color_grading_lut.sampler,
             )),
         );
 
@@ -827,3 +1402,545 @@ impl<P: PhaseItem> RenderCommand<P> for DrawMesh2d {
         RenderCommandResult::Success
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use bevy_asset::Handle;
+    use bevy_camera::visibility::SetViewVisibility;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::{Mat4, UVec4, Vec3};
+    use bevy_mesh::{Indices, MeshVertexBufferLayouts, PrimitiveTopology};
+    use bevy_render::{
+        render_phase::{DrawFunctionId, SortedPhaseItem},
+        render_resource::CachedRenderPipelineId,
+        view::{ColorGrading, RetainedViewEntity},
+        MainWorld,
+    };
+
+    use super::*;
+
+    pub(crate) fn test_pipeline() -> Mesh2dPipeline {
+        Mesh2dPipeline {
+            view_layout: BindGroupLayoutDescriptor::new("view", &[]),
+            mesh_layout: BindGroupLayoutDescriptor::new("mesh", &[]),
+            shader: Handle::default(),
+            prepass_shader: Handle::default(),
+            per_object_buffer_batch_size: None,
+            depth_clip_control_supported: false,
+        }
+    }
+
+    pub(crate) fn test_layout() -> MeshVertexBufferLayoutRef {
+        let mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3]);
+        mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default())
+    }
+
+    pub(crate) fn test_view(main_entity: MainEntity) -> ExtractedView {
+        ExtractedView {
+            retained_view_entity: RetainedViewEntity::new(main_entity, None, 0),
+            clip_from_view: Mat4::IDENTITY,
+            world_from_view: GlobalTransform::IDENTITY,
+            clip_from_world: None,
+            hdr: false,
+            viewport: UVec4::ZERO,
+            color_grading: ColorGrading::default(),
+            invert_culling: false,
+        }
+    }
+
+    fn set_translation(render_world: &mut World, entity: Entity, translation: Vec3) {
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        let mut entity = main_world.entity_mut(entity);
+        *entity.get_mut::<GlobalTransform>().unwrap() =
+            GlobalTransform::from_translation(translation);
+        entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+    }
+
+    #[test]
+    fn motion_vector_prepass_reads_previous_transforms() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<RenderMesh2dInstances>();
+        render_world.init_resource::<PreviousMesh2dTransforms>();
+        render_world.insert_resource(test_pipeline());
+        let view_entity = MainEntity::from(Entity::PLACEHOLDER);
+        let view = render_world.spawn((Camera2d, test_view(view_entity))).id();
+        let entity = render_world
+            .resource_mut::<MainWorld>()
+            .spawn((
+                Mesh2d(Handle::default()),
+                GlobalTransform::default(),
+                ViewVisibility::HIDDEN,
+            ))
+            .id();
+        let previous_transform = |render_world: &World| {
+            let instances = render_world.resource::<RenderMesh2dInstances>();
+            let transforms = &instances[&MainEntity::from(entity)].transforms;
+            let uniform = Mesh2dUniform::from_components(transforms, 0, LinearRgba::WHITE);
+            let previous_transforms = render_world.resource::<PreviousMesh2dTransforms>();
+            (
+                uniform.world_from_local,
+                *previous_transforms
+                    .get(uniform.previous_world_from_local_index)
+                    .unwrap(),
+                previous_transforms.len(),
+            )
+        };
+
+        set_translation(&mut render_world, entity, Vec3::new(5.0, 0.0, 0.0));
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+        set_translation(&mut render_world, entity, Vec3::new(10.0, 0.0, 0.0));
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+
+        // Without a motion vector prepass, only the placeholder is uploaded.
+        render_world
+            .run_system_cached(prepare_previous_mesh2d_transforms)
+            .unwrap();
+        let (_, previous_world_from_local, len) = previous_transform(&render_world);
+        assert_eq!(len, 1);
+        assert_eq!(previous_world_from_local, Affine3::IDENTITY.to_transpose());
+
+        render_world.entity_mut(view).insert(MotionVectorPrepass);
+        render_world
+            .run_system_cached(prepare_previous_mesh2d_transforms)
+            .unwrap();
+        let (world_from_local, previous_world_from_local, len) = previous_transform(&render_world);
+        assert_eq!(len, 1);
+        assert_eq!(world_from_local[0].w, 10.0);
+        assert_eq!(previous_world_from_local[0].w, 5.0);
+
+        // A mesh that stops moving has no motion on the next frame.
+        set_translation(&mut render_world, entity, Vec3::new(10.0, 0.0, 0.0));
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+        render_world
+            .run_system_cached(prepare_previous_mesh2d_transforms)
+            .unwrap();
+        let (world_from_local, previous_world_from_local, _) = previous_transform(&render_world);
+        assert_eq!(world_from_local, previous_world_from_local);
+    }
+
+    #[test]
+    fn multisampled_image_targets_override_the_view_msaa() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<ViewKeyCache>();
+        render_world.init_resource::<ViewSpecializationTicks>();
+        let view_entity = MainEntity::from(Entity::PLACEHOLDER);
+        let render_entity = render_world
+            .spawn((view_entity, test_view(view_entity), Msaa::Off))
+            .id();
+
+        let mut image = Image::default();
+        image.texture_descriptor.sample_count = 4;
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        main_world.init_resource::<Assets<Image>>();
+        let handle = main_world.resource_mut::<Assets<Image>>().add(image);
+        main_world.spawn((
+            Camera2d,
+            RenderTarget::Image(handle.into()),
+            RenderEntity::from(render_entity),
+        ));
+
+        render_world
+            .run_system_once(extract_target_sample_counts_2d)
+            .unwrap();
+        render_world
+            .run_system_once(apply_target_sample_counts_2d)
+            .unwrap();
+        render_world
+            .run_system_once(check_views_need_specialization)
+            .unwrap();
+        assert_eq!(
+            render_world.get::<TargetSampleCount2d>(render_entity),
+            Some(&TargetSampleCount2d(4))
+        );
+        // The view target and depth texture are prepared from the view's `Msaa`, so they agree
+        // with the pipelines.
+        assert_eq!(
+            render_world.get::<Msaa>(render_entity),
+            Some(&Msaa::Sample4)
+        );
+        let view_key = render_world.resource::<ViewKeyCache>()[&view_entity];
+        assert_eq!(view_key.msaa_samples(), 4);
+
+        // Material pipelines are specialized from the view key, so they render with 4 samples too.
+        let layout = test_layout();
+        let pipeline = test_pipeline();
+        let descriptor = pipeline.specialize(view_key, &layout).unwrap();
+        assert_eq!(descriptor.multisample.count, 4);
+    }
+
+    #[test]
+    fn single_sampled_image_targets_keep_the_view_msaa() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<ViewKeyCache>();
+        render_world.init_resource::<ViewSpecializationTicks>();
+        let view_entity = MainEntity::from(Entity::PLACEHOLDER);
+        let render_entity = render_world
+            .spawn((view_entity, test_view(view_entity), Msaa::default()))
+            .id();
+
+        // The view renders into a multisampled view target, which is resolved into the image.
+        let image = Image::default();
+        assert_eq!(image.texture_descriptor.sample_count, 1);
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        main_world.init_resource::<Assets<Image>>();
+        let handle = main_world.resource_mut::<Assets<Image>>().add(image);
+        main_world.spawn((
+            Camera2d,
+            RenderTarget::Image(handle.into()),
+            RenderEntity::from(render_entity),
+        ));
+
+        render_world
+            .run_system_once(extract_target_sample_counts_2d)
+            .unwrap();
+        render_world
+            .run_system_once(apply_target_sample_counts_2d)
+            .unwrap();
+        render_world
+            .run_system_once(check_views_need_specialization)
+            .unwrap();
+        assert_eq!(render_world.get::<TargetSampleCount2d>(render_entity), None);
+        assert_eq!(
+            render_world.get::<Msaa>(render_entity),
+            Some(&Msaa::Sample4)
+        );
+        let view_key = render_world.resource::<ViewKeyCache>()[&view_entity];
+        assert_eq!(view_key.msaa_samples(), 4);
+    }
+
+    #[test]
+    fn unclipped_depth_requires_depth_clip_control() {
+        let layout = test_layout();
+        let mut pipeline = test_pipeline();
+        pipeline.depth_clip_control_supported = true;
+
+        let unclipped = |pipeline: &Mesh2dPipeline, key| {
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .primitive
+                .unclipped_depth
+        };
+        assert!(unclipped(&pipeline, Mesh2dPipelineKey::UNCLIPPED_DEPTH));
+        assert!(!unclipped(&pipeline, Mesh2dPipelineKey::NONE));
+
+        pipeline.depth_clip_control_supported = false;
+        assert!(!unclipped(&pipeline, Mesh2dPipelineKey::UNCLIPPED_DEPTH));
+    }
+
+    #[test]
+    fn wireframe_meshes_are_drawn_as_lines() {
+        let pipeline = test_pipeline();
+        let layout = test_layout();
+        let polygon_mode = |key| {
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .primitive
+                .polygon_mode
+        };
+
+        assert_eq!(
+            polygon_mode(Mesh2dPipelineKey::WIREFRAME),
+            PolygonMode::Line
+        );
+        assert_eq!(polygon_mode(Mesh2dPipelineKey::NONE), PolygonMode::Fill);
+    }
+
+    #[test]
+    fn indexed_strips_restart_at_the_index_format_maximum() {
+        // Two quads drawn as separate strips of a single mesh, split by a restart index.
+        let mesh = Mesh::new(PrimitiveTopology::TriangleStrip, Default::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 8])
+            .with_inserted_indices(Indices::U16(vec![0, 1, 2, 3, u16::MAX, 4, 5, 6, 7]));
+        let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+        let pipeline = test_pipeline();
+        let indices = mesh.indices().unwrap();
+        let buffer_info = RenderMeshBufferInfo::Indexed {
+            count: indices.len() as u32,
+            index_format: indices.into(),
+        };
+        let strip_index_format = |topology, buffer_info: &RenderMeshBufferInfo| {
+            let key = Mesh2dPipelineKey::from_primitive_topology(topology)
+                | Mesh2dPipelineKey::from_strip_index_format(topology, buffer_info);
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .primitive
+                .strip_index_format
+        };
+
+        // Without the index format, `u16::MAX` would be read as a vertex, and the last triangle
+        // of the first strip would be connected to the first one of the second.
+        assert_eq!(
+            strip_index_format(PrimitiveTopology::TriangleStrip, &buffer_info),
+            Some(IndexFormat::Uint16)
+        );
+        assert_eq!(
+            strip_index_format(
+                PrimitiveTopology::LineStrip,
+                &RenderMeshBufferInfo::Indexed {
+                    count: 4,
+                    index_format: IndexFormat::Uint32,
+                }
+            ),
+            Some(IndexFormat::Uint32)
+        );
+        // Lists and non-indexed strips have nothing to restart.
+        assert_eq!(
+            strip_index_format(PrimitiveTopology::TriangleList, &buffer_info),
+            None
+        );
+        assert_eq!(
+            strip_index_format(
+                PrimitiveTopology::TriangleStrip,
+                &RenderMeshBufferInfo::NonIndexed
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn ignore_depth_disables_depth_test_and_write() {
+        let layout = test_layout();
+        let pipeline = test_pipeline();
+        let depth_state = |key| {
+            let depth_stencil = pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .depth_stencil
+                .unwrap();
+            (
+                depth_stencil.depth_compare,
+                depth_stencil.depth_write_enabled,
+            )
+        };
+
+        assert_eq!(
+            depth_state(Mesh2dPipelineKey::NONE),
+            (CompareFunction::GreaterEqual, true)
+        );
+        // Never rejected by the depth of what was drawn before, whatever its z.
+        assert_eq!(
+            depth_state(Mesh2dPipelineKey::IGNORE_DEPTH),
+            (CompareFunction::Always, false)
+        );
+        assert_eq!(
+            depth_state(Mesh2dPipelineKey::IGNORE_DEPTH | Mesh2dPipelineKey::BLEND_ALPHA),
+            (CompareFunction::Always, false)
+        );
+    }
+
+    #[test]
+    fn color_grading_lut_adds_shader_def() {
+        let layout = test_layout();
+        let pipeline = test_pipeline();
+
+        let graded = |key| {
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .fragment
+                .unwrap()
+                .shader_defs
+                .contains(&"COLOR_GRADING_LUT".into())
+        };
+        assert!(graded(Mesh2dPipelineKey::COLOR_GRADING_LUT));
+        assert!(!graded(Mesh2dPipelineKey::NONE));
+    }
+
+    #[test]
+    fn material_dither_adds_shader_def_and_breaks_up_bands() {
+        let layout = test_layout();
+        let pipeline = test_pipeline();
+        let dithered = |key| {
+            pipeline
+                .specialize(key, &layout)
+                .unwrap()
+                .fragment
+                .unwrap()
+                .shader_defs
+                .contains(&"MATERIAL_DITHER".into())
+        };
+        assert!(dithered(Mesh2dPipelineKey::MATERIAL_DITHER));
+        assert!(!dithered(Mesh2dPipelineKey::NONE));
+
+        // Mirrors `mesh2d_dither` in mesh2d_functions.wgsl, with the matrix declared there.
+        const MATRIX: [f32; 16] = [
+            0.0, 8.0, 2.0, 10.0, 12.0, 4.0, 14.0, 6.0, 3.0, 11.0, 1.0, 9.0, 15.0, 7.0, 13.0, 5.0,
+        ];
+        let declaration = format!(
+            "const MESH2D_DITHER_MATRIX: array<f32, 16> = array<f32, 16>({});",
+            MATRIX.map(|threshold| format!("{threshold:?}")).join(", ")
+        );
+        assert!(include_str!("mesh2d_functions.wgsl").contains(&declaration));
+        let quantize = |value: f32, x: usize, y: usize| {
+            let threshold = (MATRIX[(y % 4) * 4 + x % 4] + 0.5) / 16.0;
+            ((value + (threshold - 0.5) / 255.0) * 255.0).round() as u8
+        };
+
+        // A value a third of the way between two 8-bit levels.
+        let value = (100.0 + 1.0 / 3.0) / 255.0;
+        let levels: Vec<u8> = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .map(|(x, y)| quantize(value, x, y))
+            .collect();
+        // Without dithering, every pixel would round down to 100.
+        assert!(levels.iter().all(|level| (100..=101).contains(level)));
+        let raised = levels.iter().filter(|level| **level == 101).count();
+        // The share of pixels raised to the next level matches the sub-quantization remainder.
+        assert_eq!(raised, 5);
+    }
+
+    #[test]
+    fn sort_layers_order_meshes_regardless_of_world_z() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<RenderMesh2dInstances>();
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        let mut spawn = |z: f32, sort_layer: Option<SortLayer2d>| {
+            let mut entity = main_world.spawn((
+                Mesh2d(Handle::default()),
+                GlobalTransform::from_xyz(1.0, 2.0, z),
+                ViewVisibility::HIDDEN,
+            ));
+            entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+            if let Some(sort_layer) = sort_layer {
+                entity.insert(sort_layer);
+            }
+            MainEntity::from(entity.id())
+        };
+        let foreground = spawn(-100.0, Some(SortLayer2d::new(1, 0)));
+        let foreground_behind = spawn(900.0, Some(SortLayer2d::new(1, -1)));
+        let background = spawn(500.0, Some(SortLayer2d::new(0, 200)));
+        let unlayered = spawn(-0.5, None);
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+
+        let instances = render_world.resource::<RenderMesh2dInstances>();
+        let mut phase_order = vec![foreground, foreground_behind, background, unlayered];
+        phase_order.sort_by_key(|entity| instances[entity].transparent_sort_key(0.0));
+        assert_eq!(
+            phase_order,
+            vec![unlayered, background, foreground_behind, foreground]
+        );
+
+        // The transform is kept, so opaque meshes are still depth tested with their z.
+        let translation = |entity| instances[&entity].transforms.world_from_local.translation;
+        assert_eq!(translation(foreground), Vec3::new(1.0, 2.0, -100.0));
+        assert_eq!(
+            instances[&unlayered].transparent_sort_key(0.0),
+            FloatOrd(-0.5)
+        );
+    }
+
+    #[test]
+    fn render_layer_order_flips_transparent_draw_order_regardless_of_z() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<RenderMesh2dInstances>();
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        let mut spawn = |z: f32, order: Option<RenderLayer2dOrder>| {
+            let mut entity = main_world.spawn((
+                Mesh2d(Handle::default()),
+                GlobalTransform::from_xyz(0.0, 0.0, z),
+                ViewVisibility::HIDDEN,
+            ));
+            entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+            if let Some(order) = order {
+                entity.insert(order);
+            }
+            MainEntity::from(entity.id())
+        };
+        // The world mesh is in front by z, but the overlay is ordered above it.
+        let world = spawn(900.0, None);
+        let overlay = spawn(-900.0, Some(RenderLayer2dOrder(1)));
+        let world_behind = spawn(-950.0, None);
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+
+        // Sort the meshes like `queue_material2d_meshes` does, back to front.
+        let instances = render_world.resource::<RenderMesh2dInstances>();
+        let mut items: Vec<_> = [world, overlay, world_behind]
+            .into_iter()
+            .map(|entity| {
+                let instance = &instances[&entity];
+                let z = instance.transforms.world_from_local.translation.z;
+                Transparent2d {
+                    sort_key: FloatOrd(instance.order.sort_key(z)),
+                    entity: (Entity::PLACEHOLDER, entity),
+                    pipeline: CachedRenderPipelineId::INVALID,
+                    draw_function: DrawFunctionId(0),
+                    batch_range: 0..1,
+                    extracted_index: usize::MAX,
+                    extra_index: PhaseItemExtraIndex::None,
+                    indexed: false,
+                }
+            })
+            .collect();
+        Transparent2d::sort(&mut items);
+        let draw_order: Vec<_> = items.iter().map(|item| item.entity.1).collect();
+        assert_eq!(draw_order, vec![world_behind, world, overlay]);
+
+        // The default order keeps the z as the sort key, and the order doesn't move the mesh.
+        assert_eq!(RenderLayer2dOrder::default().sort_key(-0.25), -0.25);
+        assert_eq!(
+            instances[&overlay]
+                .transforms
+                .world_from_local
+                .translation
+                .z,
+            -900.0
+        );
+        // Orders out of range are clamped.
+        assert_eq!(
+            RenderLayer2dOrder(i32::MAX).sort_key(0.0),
+            RenderLayer2dOrder(RenderLayer2dOrder::MAX).sort_key(0.0)
+        );
+    }
+
+    #[test]
+    fn batched_meshes_carry_distinct_tints() {
+        let mut render_world = World::new();
+        render_world.init_resource::<MainWorld>();
+        render_world.init_resource::<RenderMesh2dInstances>();
+        let mut main_world = render_world.resource_mut::<MainWorld>();
+        let mut spawn = |tint: Option<Mesh2dTint>| {
+            let mut entity = main_world.spawn((
+                Mesh2d(Handle::default()),
+                GlobalTransform::default(),
+                ViewVisibility::HIDDEN,
+            ));
+            entity.get_mut::<ViewVisibility>().unwrap().set_visible();
+            if let Some(tint) = tint {
+                entity.insert(tint);
+            }
+            MainEntity::from(entity.id())
+        };
+        let red = spawn(Some(Mesh2dTint(Color::srgb(1.0, 0.0, 0.0))));
+        let blue = spawn(Some(Mesh2dTint(Color::srgb(0.0, 0.0, 1.0))));
+        let untinted = spawn(None);
+        render_world.run_system_cached(extract_mesh2d).unwrap();
+
+        let instances = render_world.resource::<RenderMesh2dInstances>();
+        let batch_key = |entity| {
+            let instance: &RenderMesh2dInstance = &instances[&entity];
+            (
+                instance.automatic_batching,
+                instance.material_bind_group_id,
+                instance.mesh_asset_id,
+            )
+        };
+        assert!(batch_key(red) == batch_key(blue));
+
+        let tint = |entity| {
+            let instance: &RenderMesh2dInstance = &instances[&entity];
+            Mesh2dUniform::from_components(&instance.transforms, instance.tag, instance.tint).tint
+        };
+        assert_eq!(tint(red), Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(tint(blue), Vec4::new(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(tint(untinted), Vec4::ONE);
+    }
+}
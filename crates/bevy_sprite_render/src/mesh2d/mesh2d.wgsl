@@ -1,6 +1,6 @@
 #import bevy_sprite::{
     mesh2d_functions as mesh_functions,
-    mesh2d_vertex_output::VertexOutput,
+    mesh2d_vertex_output::{VertexOutput, FragmentOutput},
     mesh2d_view_bindings::view,
 }
 
@@ -8,6 +8,25 @@
 #import bevy_core_pipeline::tonemapping
 #endif
 
+#ifdef INSTANCED
+#import bevy_render::maths::affine3_to_square
+#endif
+
+#ifdef VERTEX_DISPLACEMENT
+#import bevy_sprite::{
+    mesh2d_types::VertexDisplacement2d,
+    mesh2d_view_bindings::globals,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(#{VERTEX_DISPLACEMENT_BINDING}) var<uniform> displacement: VertexDisplacement2d;
+#endif
+
+#ifdef ADDITIVE_MIX
+#import bevy_sprite::mesh2d_types::AdditiveMix2d
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(#{ADDITIVE_MIX_BINDING}) var<uniform> additive_mix: AdditiveMix2d;
+#endif
+
 struct Vertex {
     @builtin(instance_index) instance_index: u32,
 #ifdef VERTEX_POSITIONS
@@ -25,6 +44,13 @@ struct Vertex {
 #ifdef VERTEX_COLORS
     @location(4) color: vec4<f32>,
 #endif
+#ifdef INSTANCED
+    // The `Mesh2dInstance` of the mesh.
+    @location(10) i_world_from_local_0: vec4<f32>,
+    @location(11) i_world_from_local_1: vec4<f32>,
+    @location(12) i_world_from_local_2: vec4<f32>,
+    @location(13) i_tint: vec4<f32>,
+#endif
 };
 
 @vertex
@@ -35,17 +61,48 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #endif
 
 #ifdef VERTEX_POSITIONS
+#ifdef VERTEX_DISPLACEMENT
+    let position = mesh_functions::mesh2d_displace_vertex(vertex.position, displacement, globals.time);
+#else
+    let position = vertex.position;
+#endif
+#ifdef INSTANCED
+    var world_from_local = affine3_to_square(mat3x4<f32>(
+        vertex.i_world_from_local_0,
+        vertex.i_world_from_local_1,
+        vertex.i_world_from_local_2,
+    ));
+#else
     var world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+#endif
     out.world_position = mesh_functions::mesh2d_position_local_to_world(
         world_from_local,
-        vec4<f32>(vertex.position, 1.0)
+        vec4<f32>(position, 1.0)
     );
     out.position = mesh_functions::mesh2d_position_world_to_clip(out.world_position);
+#ifdef PIXEL_SNAP
+    out.position = mesh_functions::mesh2d_pixel_snap(out.position);
+#endif
+#ifdef MOTION_VECTOR_PREPASS
+#ifdef INSTANCED
+    // Instances don't keep their previous transform.
+    out.previous_world_position = out.world_position;
+#else
+    out.previous_world_position = mesh_functions::mesh2d_position_local_to_world(
+        mesh_functions::get_previous_world_from_local(vertex.instance_index),
+        vec4<f32>(position, 1.0)
+    );
+#endif
+#endif
 #endif
 
 #ifdef VERTEX_NORMALS
+#ifdef INSTANCED
+    out.world_normal = mesh_functions::mesh2d_instance_normal_local_to_world(world_from_local, vertex.normal);
+#else
     out.world_normal = mesh_functions::mesh2d_normal_local_to_world(vertex.normal, vertex.instance_index);
 #endif
+#endif
 
 #ifdef VERTEX_TANGENTS
     out.world_tangent = mesh_functions::mesh2d_tangent_local_to_world(
@@ -57,20 +114,47 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #ifdef VERTEX_COLORS
     out.color = vertex.color;
 #endif
+
+#ifdef INSTANCED
+    out.tint = vertex.i_tint;
+#else
+    out.tint = mesh_functions::get_tint(vertex.instance_index);
+#endif
+
+#ifdef INSTANCED
+    // Instances don't carry a `MeshTag`.
+    out.tag = 0u;
+#else
+    out.tag = mesh_functions::get_tag(vertex.instance_index);
+#endif
     return out;
 }
 
 @fragment
 fn fragment(
     in: VertexOutput,
-) -> @location(0) vec4<f32> {
+) -> FragmentOutput {
+    var out: FragmentOutput;
 #ifdef VERTEX_COLORS
-    var color = in.color;
+    out.color = in.color;
 #ifdef TONEMAP_IN_SHADER
-    color = tonemapping::tone_mapping(color, view.color_grading);
+    out.color = tonemapping::tone_mapping(out.color, view.color_grading);
 #endif
-    return color;
 #else
-    return vec4<f32>(1.0, 0.0, 1.0, 1.0);
+    out.color = vec4<f32>(1.0, 0.0, 1.0, 1.0);
+#endif
+#ifdef COLOR_GRADING_LUT
+    out.color = mesh_functions::mesh2d_color_grading_lut(out.color);
+#endif
+#ifdef MATERIAL_DITHER
+    out.color = mesh_functions::mesh2d_dither(out.color, in.position.xy);
 #endif
+#ifdef ADDITIVE_MIX
+    out.color = mesh_functions::mesh2d_additive_mix(out.color, additive_mix);
+#endif
+#ifdef CLAMP_OUTPUT
+    out.color = mesh_functions::mesh2d_clamp_output(out.color, bitcast<f32>(#{CLAMP_OUTPUT_MAX_BITS}u));
+#endif
+    return out;
 }
+
@@ -6,4 +6,16 @@
 @group(1) @binding(0) var<uniform> mesh: array<Mesh2d, #{PER_OBJECT_BUFFER_BATCH_SIZE}u>;
 #else
 @group(1) @binding(0) var<storage> mesh: array<Mesh2d>;
+// Indexed by `Mesh2d::previous_world_from_local_index`.
+@group(1) @binding(1) var<storage> previous_mesh_transforms: array<mat3x4<f32>>;
 #endif // PER_OBJECT_BUFFER_BATCH_SIZE
+
+// The transform of the mesh during the previous frame. Without storage buffers the previous
+// transforms are not bound, and meshes have no motion.
+fn previous_world_from_local(instance_index: u32) -> mat3x4<f32> {
+#ifdef PER_OBJECT_BUFFER_BATCH_SIZE
+    return mesh[instance_index].world_from_local;
+#else
+    return previous_mesh_transforms[mesh[instance_index].previous_world_from_local_index];
+#endif // PER_OBJECT_BUFFER_BATCH_SIZE
+}
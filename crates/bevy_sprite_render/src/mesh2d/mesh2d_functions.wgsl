@@ -1,8 +1,9 @@
 #define_import_path bevy_sprite::mesh2d_functions
 
 #import bevy_sprite::{
-    mesh2d_view_bindings::view,
-    mesh2d_bindings::mesh,
+    mesh2d_view_bindings::{view, color_grading_lut_texture, color_grading_lut_sampler},
+    mesh2d_bindings::{mesh, previous_world_from_local},
+    mesh2d_types::{AdditiveMix2d, VertexDisplacement2d},
 }
 #import bevy_render::maths::{affine3_to_square, mat2x4_f32_to_mat3x3_unpack}
 
@@ -10,6 +11,29 @@ fn get_world_from_local(instance_index: u32) -> mat4x4<f32> {
     return affine3_to_square(mesh[instance_index].world_from_local);
 }
 
+fn get_previous_world_from_local(instance_index: u32) -> mat4x4<f32> {
+    return affine3_to_square(previous_world_from_local(instance_index));
+}
+
+// Moves a local vertex position along the displacement axis by a sine wave traveling across it.
+fn mesh2d_displace_vertex(
+    vertex_position: vec3<f32>,
+    displacement: VertexDisplacement2d,
+    time: f32,
+) -> vec3<f32> {
+    let across = vec2<f32>(-displacement.axis.y, displacement.axis.x);
+    let phase = dot(vertex_position.xy, across) * displacement.frequency + time * displacement.speed;
+    return vertex_position + vec3<f32>(displacement.axis * displacement.amplitude * sin(phase), 0.0);
+}
+
+// Rounds a clip-space position to the nearest pixel edge of the viewport.
+fn mesh2d_pixel_snap(clip_position: vec4<f32>) -> vec4<f32> {
+    let half_size = view.viewport.zw * 0.5;
+    let pixel = (clip_position.xy / clip_position.w + 1.0) * half_size;
+    let snapped = (round(pixel) / half_size - 1.0) * clip_position.w;
+    return vec4<f32>(snapped, clip_position.zw);
+}
+
 fn mesh2d_position_local_to_world(world_from_local: mat4x4<f32>, vertex_position: vec4<f32>) -> vec4<f32> {
     return world_from_local * vertex_position;
 }
@@ -27,10 +51,32 @@ fn mesh2d_position_local_to_clip(world_from_local: mat4x4<f32>, vertex_position:
 }
 
 fn mesh2d_normal_local_to_world(vertex_normal: vec3<f32>, instance_index: u32) -> vec3<f32> {
-    return mat2x4_f32_to_mat3x3_unpack(
-        mesh[instance_index].local_from_world_transpose_a,
-        mesh[instance_index].local_from_world_transpose_b,
-    ) * vertex_normal;
+    // Normalize so that lighting math in 2D materials isn't skewed by non-uniform scale. Invalid
+    // normals are skipped so that they don't become NaN.
+    if any(vertex_normal != vec3<f32>(0.0)) {
+        return normalize(
+            mat2x4_f32_to_mat3x3_unpack(
+                mesh[instance_index].local_from_world_transpose_a,
+                mesh[instance_index].local_from_world_transpose_b,
+            ) * vertex_normal
+        );
+    } else {
+        return vertex_normal;
+    }
+}
+
+// Transforms a normal like `mesh2d_normal_local_to_world`, for instanced meshes that only have
+// their `world_from_local` matrix. The cofactor matrix is the inverse transpose scaled by the
+// determinant, whose sign is kept so that mirrored instances don't flip their normals.
+fn mesh2d_instance_normal_local_to_world(world_from_local: mat4x4<f32>, vertex_normal: vec3<f32>) -> vec3<f32> {
+    if all(vertex_normal == vec3<f32>(0.0)) {
+        return vertex_normal;
+    }
+    let x = world_from_local[0].xyz;
+    let y = world_from_local[1].xyz;
+    let z = world_from_local[2].xyz;
+    let cofactor = mat3x3<f32>(cross(y, z), cross(z, x), cross(x, y));
+    return normalize(cofactor * vertex_normal) * sign(dot(x, cross(y, z)));
 }
 
 fn mesh2d_tangent_local_to_world(world_from_local: mat4x4<f32>, vertex_tangent: vec4<f32>) -> vec4<f32> {
@@ -44,6 +90,55 @@ fn mesh2d_tangent_local_to_world(world_from_local: mat4x4<f32>, vertex_tangent:
     );
 }
 
+// Returns the screen-space motion of a fragment between the previous and current frame, in the
+// same convention as the 3d motion vector prepass. The 2d view does not track its previous
+// transform, so only motion of the mesh itself is captured.
+fn mesh2d_motion_vector(world_position: vec4<f32>, previous_world_position: vec4<f32>) -> vec2<f32> {
+    let clip_position_t = view.clip_from_world * world_position;
+    let clip_position = clip_position_t.xy / clip_position_t.w;
+    let previous_clip_position_t = view.clip_from_world * previous_world_position;
+    let previous_clip_position = previous_clip_position_t.xy / previous_clip_position_t.w;
+    return (clip_position - previous_clip_position) * vec2(0.5, -0.5);
+}
+
+// Maps a color through the view's `ColorGradingLut2d`. Only meaningful when the
+// `COLOR_GRADING_LUT` shader def is set, otherwise the view binds a placeholder texture.
+fn mesh2d_color_grading_lut(color: vec4<f32>) -> vec4<f32> {
+    let size = vec3<f32>(textureDimensions(color_grading_lut_texture));
+    let uvw = (saturate(color.rgb) * (size - 1.0) + 0.5) / size;
+    let graded = textureSampleLevel(color_grading_lut_texture, color_grading_lut_sampler, uvw, 0.0);
+    return vec4<f32>(graded.rgb, color.a);
+}
+
+// A 4x4 Bayer matrix, the thresholds of an ordered dither.
+const MESH2D_DITHER_MATRIX: array<f32, 16> = array<f32, 16>(0.0, 8.0, 2.0, 10.0, 12.0, 4.0, 14.0, 6.0, 3.0, 11.0, 1.0, 9.0, 15.0, 7.0, 13.0, 5.0);
+
+// Offsets a color by up to half an 8-bit quantization step with an ordered dither, so that
+// gradients finer than the output's precision are spread over neighboring pixels instead of
+// banding. Meant for materials specialized with the `MATERIAL_DITHER` shader def.
+fn mesh2d_dither(color: vec4<f32>, frag_coord: vec2<f32>) -> vec4<f32> {
+    // Copied to a variable, as constant arrays can't be indexed dynamically on every backend.
+    var matrix = MESH2D_DITHER_MATRIX;
+    let pixel = vec2<u32>(frag_coord) % 4u;
+    let threshold = (matrix[pixel.y * 4u + pixel.x] + 0.5) / 16.0;
+    return vec4<f32>(color.rgb + (threshold - 0.5) / 255.0, color.a);
+}
+
+// Premultiplies a color by its alpha, and scales its alpha down by how additively it blends.
+fn mesh2d_additive_mix(color: vec4<f32>, mix: AdditiveMix2d) -> vec4<f32> {
+    return vec4<f32>(color.rgb * color.a, color.a * (1.0 - mix.additive));
+}
+
+// Replaces NaN channels with zero, and clamps the color channels to `max_value` and alpha to one.
+fn mesh2d_clamp_output(color: vec4<f32>, max_value: f32) -> vec4<f32> {
+    let sanitized = select(color, vec4<f32>(0.0), color != color);
+    return clamp(sanitized, vec4<f32>(0.0), vec4<f32>(vec3<f32>(max_value), 1.0));
+}
+
 fn get_tag(instance_index: u32) -> u32 {
     return mesh[instance_index].tag;
+}
+
+fn get_tint(instance_index: u32) -> vec4<f32> {
+    return mesh[instance_index].tint;
 }
\ No newline at end of file
@@ -0,0 +1,17 @@
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_vertex_output::VertexOutput,
+}
+
+// The fragment shader of the prepass for materials without a prepass fragment shader, on views
+// with a motion vector prepass. Materials that don't write motion vectors get a target with an
+// empty write mask, so they have nothing to output.
+#ifdef MOTION_VECTOR_PREPASS
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec2<f32> {
+    return mesh_functions::mesh2d_motion_vector(in.world_position, in.previous_world_position);
+}
+#else
+@fragment
+fn fragment() {}
+#endif
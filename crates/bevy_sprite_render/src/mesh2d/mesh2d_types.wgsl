@@ -14,4 +14,22 @@ struct Mesh2d {
     // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
     flags: u32,
     tag: u32,
+    // The index of the previous frame's `world_from_local` in `previous_world_from_local`, only
+    // set while a view has a motion vector prepass.
+    previous_world_from_local_index: u32,
+    // The linear `Mesh2dTint` of the instance, white if it has none.
+    tint: vec4<f32>,
+};
+
+// A sine wave displacing vertices, see `VertexDisplacement2d`.
+struct VertexDisplacement2d {
+    amplitude: f32,
+    frequency: f32,
+    speed: f32,
+    axis: vec2<f32>,
+};
+
+// How much a material blends additively rather than with its alpha, see `AdditiveMix2d`.
+struct AdditiveMix2d {
+    additive: f32,
 };
@@ -13,4 +13,16 @@ struct VertexOutput {
     #ifdef VERTEX_COLORS
     @location(4) color: vec4<f32>,
     #endif
+    #ifdef MOTION_VECTOR_PREPASS
+    @location(5) previous_world_position: vec4<f32>,
+    #endif
+    // The `Mesh2dTint` of the instance. Only set by the default vertex shader, which is signaled
+    // to fragment shaders by the `MESH2D_TINT` shader def.
+    @location(6) @interpolate(flat) tint: vec4<f32>,
+    // The `MeshTag` of the instance, e.g. to index into a material's storage buffer.
+    @location(7) @interpolate(flat) tag: u32,
+}
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
 }
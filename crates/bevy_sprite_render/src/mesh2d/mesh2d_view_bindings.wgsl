@@ -9,3 +9,6 @@
 
 @group(0) @binding(2) var dt_lut_texture: texture_3d<f32>;
 @group(0) @binding(3) var dt_lut_sampler: sampler;
+
+@group(0) @binding(4) var color_grading_lut_texture: texture_3d<f32>;
+@group(0) @binding(5) var color_grading_lut_sampler: sampler;
@@ -1,9 +1,23 @@
 mod color_material;
+mod fullscreen_material;
+mod instanced;
+mod masked_material;
 mod material;
 mod mesh;
+mod palette_material;
+#[cfg(not(target_arch = "wasm32"))]
+mod pipeline_disk_cache;
+mod sdf_material;
 mod wireframe2d;
 
 pub use color_material::*;
+pub use fullscreen_material::*;
+pub use instanced::*;
+pub use masked_material::*;
 pub use material::*;
 pub use mesh::*;
+pub use palette_material::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use pipeline_disk_cache::*;
+pub use sdf_material::*;
 pub use wireframe2d::*;
@@ -0,0 +1,169 @@
+use crate::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin};
+use bevy_app::{App, Plugin};
+use bevy_asset::{embedded_asset, embedded_path, Asset, AssetApp, AssetPath, Handle};
+use bevy_color::LinearRgba;
+use bevy_image::Image;
+use bevy_mesh::MeshVertexBufferLayoutRef;
+use bevy_reflect::prelude::*;
+use bevy_render::render_resource::*;
+use bevy_shader::{ShaderDefVal, ShaderRef};
+
+/// Adds the [`PaletteMaterial2d`] material.
+///
+/// This plugin isn't added by the [`SpriteRenderPlugin`](crate::SpriteRenderPlugin), so that apps
+/// only pay for the materials they use.
+#[derive(Default)]
+pub struct PaletteMaterial2dPlugin;
+
+impl Plugin for PaletteMaterial2dPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "palette_material.wgsl");
+
+        app.add_plugins(Material2dPlugin::<PaletteMaterial2d>::default())
+            .register_asset_reflect::<PaletteMaterial2d>();
+    }
+}
+
+/// A [2d material](Material2d) that colors [2d meshes](crate::Mesh2d) by looking up the pixels of
+/// an index texture in a palette, for palette swaps such as team colors or retro sprites.
+///
+/// The red channel of each pixel of [`indices`](Self::indices) selects a color of
+/// [`palette`](Self::palette): a value of `n / 255`, as stored by an `R8Unorm` or `Rgba8Unorm`
+/// texture, selects the color at index `n`. Indices past the end of the palette select its last
+/// color. Pixels are loaded without filtering, so the index texture is never blended between
+/// indices. Swapping palettes only changes this small uniform, not the texture.
+///
+/// The palette is bound as a uniform array of [`PaletteMaterial2d::SIZE`] colors, which the
+/// shader indexes as `palette[index]`.
+///
+/// Requires the [`PaletteMaterial2dPlugin`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[reflect(Default, Debug, Clone)]
+pub struct PaletteMaterial2d {
+    /// The colors selected by the index texture.
+    #[uniform(0)]
+    pub palette: [LinearRgba; PaletteMaterial2d::SIZE],
+    /// The texture whose red channel holds the palette index of each pixel.
+    #[texture(1)]
+    pub indices: Handle<Image>,
+    pub alpha_mode: AlphaMode2d,
+}
+
+impl PaletteMaterial2d {
+    /// The number of colors in a palette.
+    pub const SIZE: usize = 16;
+
+    /// Creates a material that looks up `indices` in `palette`.
+    ///
+    /// Colors past [`PaletteMaterial2d::SIZE`] are ignored, and missing colors are transparent.
+    pub fn new(indices: Handle<Image>, palette: impl IntoIterator<Item = LinearRgba>) -> Self {
+        let mut material = Self {
+            indices,
+            ..Default::default()
+        };
+        for (slot, color) in material.palette.iter_mut().zip(palette) {
+            *slot = color;
+        }
+        material
+    }
+}
+
+impl Default for PaletteMaterial2d {
+    fn default() -> Self {
+        PaletteMaterial2d {
+            palette: [LinearRgba::NONE; Self::SIZE],
+            indices: Handle::default(),
+            alpha_mode: AlphaMode2d::Blend,
+        }
+    }
+}
+
+impl Material2d for PaletteMaterial2d {
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Path(
+            AssetPath::from_path_buf(embedded_path!("palette_material.wgsl"))
+                .with_source("embedded"),
+        )
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        self.alpha_mode
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        if let Some(fragment) = descriptor.fragment.as_mut() {
+            fragment.shader_defs.push(ShaderDefVal::UInt(
+                "PALETTE_SIZE".into(),
+                PaletteMaterial2d::SIZE as u32,
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mesh2dPipelineKey;
+    use bevy_color::ColorToComponents;
+    use bevy_mesh::{Mesh, MeshVertexBufferLayouts, PrimitiveTopology};
+
+    #[test]
+    fn palette_is_an_indexable_uniform_array() {
+        let palette = (0..PaletteMaterial2d::SIZE)
+            .map(|index| LinearRgba::new(index as f32, 0.5, 0.25, 1.0))
+            .collect::<Vec<_>>();
+        let material = PaletteMaterial2d::new(Handle::default(), palette.iter().copied());
+
+        // Each color takes one 16-byte element of the array, as `array<vec4<f32>, N>` expects.
+        assert_eq!(
+            <[LinearRgba; PaletteMaterial2d::SIZE]>::min_size().get(),
+            16 * PaletteMaterial2d::SIZE as u64
+        );
+        let mut buffer = encase::UniformBuffer::new(Vec::<u8>::new());
+        buffer.write(&material.palette).unwrap();
+        let bytes = buffer.into_inner();
+        for (index, color) in palette.iter().enumerate() {
+            let element: Vec<f32> = bytes[index * 16..(index + 1) * 16]
+                .chunks_exact(4)
+                .map(|component| f32::from_le_bytes(component.try_into().unwrap()))
+                .collect();
+            assert_eq!(element, color.to_f32_array());
+        }
+
+        // The shader's array is sized to match.
+        let mesh = Mesh::new(PrimitiveTopology::TriangleList, Default::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[0.0f32; 3]; 3]);
+        let layout = mesh.get_mesh_vertex_buffer_layout(&mut MeshVertexBufferLayouts::default());
+        let mut descriptor = RenderPipelineDescriptor {
+            fragment: Some(FragmentState::default()),
+            ..Default::default()
+        };
+        PaletteMaterial2d::specialize(
+            &mut descriptor,
+            &layout,
+            Material2dKey {
+                mesh_key: Mesh2dPipelineKey::NONE,
+                bind_group_data: (),
+                blend_state: None,
+                depth_write_enabled: None,
+                depth_compare: None,
+            },
+        )
+        .unwrap();
+        assert!(descriptor
+            .fragment
+            .unwrap()
+            .shader_defs
+            .contains(&ShaderDefVal::UInt(
+                "PALETTE_SIZE".into(),
+                PaletteMaterial2d::SIZE as u32
+            )));
+        assert!(include_str!("palette_material.wgsl")
+            .contains("var<uniform> palette: array<vec4<f32>, #{PALETTE_SIZE}>;"));
+    }
+}
@@ -0,0 +1,37 @@
+#import bevy_sprite::{
+    mesh2d_vertex_output::{VertexOutput, FragmentOutput},
+    mesh2d_view_bindings::view,
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> palette: array<vec4<f32>, #{PALETTE_SIZE}>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var indices: texture_2d<f32>;
+
+@fragment
+fn fragment(
+    mesh: VertexOutput,
+) -> FragmentOutput {
+    // Loaded rather than sampled, so that neighboring indices are never blended together.
+    let size = textureDimensions(indices);
+    let texel = min(vec2<u32>(mesh.uv * vec2<f32>(size)), size - 1u);
+    let index = min(u32(round(textureLoad(indices, texel, 0).r * 255.0)), #{PALETTE_SIZE} - 1u);
+    var output_color = palette[index];
+
+#ifdef VERTEX_COLORS
+    output_color = output_color * mesh.color;
+#endif
+#ifdef MESH2D_TINT
+    output_color = output_color * mesh.tint;
+#endif
+
+#ifdef TONEMAP_IN_SHADER
+    output_color = tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+
+    var out: FragmentOutput;
+    out.color = output_color;
+    return out;
+}
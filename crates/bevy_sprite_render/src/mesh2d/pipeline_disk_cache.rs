@@ -0,0 +1,184 @@
+use crate::{Material2d, Material2dPipelineDepthFormats};
+use bevy_app::{App, Plugin};
+use bevy_ecs::prelude::*;
+use bevy_platform::collections::HashSet;
+use bevy_render::{
+    render_resource::{CachedRenderPipelineId, PipelineCache},
+    renderer::RenderAdapterInfo,
+    Render, RenderApp, RenderStartup, RenderSystems,
+};
+use bevy_tasks::IoTaskPool;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Stores the compiled [`Material2d`] pipelines on disk, so that later runs of the app can skip
+/// most of their compilation.
+///
+/// On startup, the cache written by a previous run to [`directory`](Self::directory) is loaded
+/// into the [`PipelineCache`], if it was written for the same driver. Pipelines are then compiled
+/// as usual: the driver reuses the ones it finds in the cache and compiles the rest, so a missing,
+/// outdated, or corrupt cache only costs the compilation it would have saved. Once all pipelines
+/// have finished compiling, the cache is written back on the [`IoTaskPool`] if any
+/// [`Material2d`] pipeline was compiled since it was last written.
+///
+/// Only backends with driver-level pipeline caches, currently Vulkan, are supported. On other
+/// backends this plugin does nothing. It isn't available on wasm, which has no file system.
+#[derive(Debug, Clone)]
+pub struct Material2dPipelineDiskCachePlugin {
+    /// The directory the cache is stored in. It is created if it doesn't exist.
+    pub directory: PathBuf,
+}
+
+impl Material2dPipelineDiskCachePlugin {
+    /// Creates a plugin that stores the cache in `directory`.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+}
+
+impl Plugin for Material2dPipelineDiskCachePlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        let directory = self.directory.clone();
+        render_app
+            .add_systems(
+                RenderStartup,
+                move |mut commands: Commands,
+                      adapter_info: Res<RenderAdapterInfo>,
+                      mut pipeline_cache: ResMut<PipelineCache>| {
+                    let Some(adapter_key) = adapter_info.pipeline_cache_key() else {
+                        return;
+                    };
+                    let disk_cache =
+                        Material2dPipelineDiskCache::new(directory.clone(), adapter_key);
+                    if pipeline_cache.load_driver_cache(disk_cache.driver_cache_path()) {
+                        commands.insert_resource(disk_cache);
+                    }
+                },
+            )
+            .add_systems(
+                Render,
+                save_material2d_pipeline_disk_cache
+                    .in_set(RenderSystems::Cleanup)
+                    .run_if(resource_exists::<Material2dPipelineDiskCache>),
+            );
+    }
+}
+
+/// The on-disk cache of [`Material2d`] pipelines, see [`Material2dPipelineDiskCachePlugin`].
+///
+/// The compiled pipelines are stored by the driver, and written by
+/// [`PipelineCache::write_driver_cache`] to [`driver_cache_path`](Self::driver_cache_path). The
+/// driver doesn't report which pipelines it found in the cache, so this only tracks the
+/// [`Material2d`] pipelines that finished compiling in this run, to know when the cache needs to
+/// be written back.
+#[derive(Resource, Debug)]
+pub struct Material2dPipelineDiskCache {
+    driver_cache_path: PathBuf,
+    recorded: HashSet<CachedRenderPipelineId>,
+    dirty: bool,
+}
+
+impl Material2dPipelineDiskCache {
+    /// Creates the cache stored in `directory` for the adapter identified by `adapter_key`, as
+    /// returned by [`RenderAdapterInfo::pipeline_cache_key`].
+    pub fn new(directory: impl Into<PathBuf>, adapter_key: impl AsRef<str>) -> Self {
+        let adapter_key = adapter_key.as_ref();
+        Self {
+            driver_cache_path: directory.into().join(format!("{adapter_key}.pipelines")),
+            recorded: HashSet::default(),
+            dirty: false,
+        }
+    }
+
+    /// The file the driver's compiled pipelines are stored in.
+    pub fn driver_cache_path(&self) -> &Path {
+        &self.driver_cache_path
+    }
+
+    /// Records that the pipeline with the given id finished compiling, returning `true` if it
+    /// wasn't recorded before.
+    pub fn record(&mut self, id: CachedRenderPipelineId) -> bool {
+        if !self.recorded.insert(id) {
+            return false;
+        }
+        self.dirty = true;
+        true
+    }
+
+    /// Returns `true` if pipelines have been recorded since the cache was last written.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+}
+
+/// Records the compiled pipelines of `M` in the [`Material2dPipelineDiskCache`].
+pub fn record_material2d_pipelines<M: Material2d>(
+    depth_formats: Res<Material2dPipelineDepthFormats<M>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut disk_cache: ResMut<Material2dPipelineDiskCache>,
+) {
+    for id in depth_formats.keys() {
+        if pipeline_cache.get_render_pipeline(*id).is_some() {
+            disk_cache.record(*id);
+        }
+    }
+}
+
+/// Writes the [`Material2dPipelineDiskCache`] back to disk once new pipelines have finished
+/// compiling.
+///
+/// Only the driver's data is retrieved here, the file is written on the [`IoTaskPool`] so that the
+/// render thread doesn't wait on the disk.
+pub fn save_material2d_pipeline_disk_cache(
+    pipeline_cache: Res<PipelineCache>,
+    mut disk_cache: ResMut<Material2dPipelineDiskCache>,
+) {
+    if !disk_cache.is_dirty() || pipeline_cache.waiting_pipelines().next().is_some() {
+        return;
+    }
+    // Only written once per batch of new pipelines, whether or not the write succeeds, so that a
+    // failing disk isn't retried every frame.
+    disk_cache.dirty = false;
+    let Some(driver_data) = pipeline_cache.driver_cache_data() else {
+        return;
+    };
+    let driver_cache_path = disk_cache.driver_cache_path.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            if let Err(err) = PipelineCache::write_driver_cache(&driver_cache_path, &driver_data) {
+                warn!("Failed to save the Material2d pipeline cache: {err}");
+            }
+        })
+        .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newly_compiled_pipelines_dirty_the_cache() {
+        let mut cache = Material2dPipelineDiskCache::new("cache", "adapter");
+        assert_eq!(
+            cache.driver_cache_path(),
+            Path::new("cache").join("adapter.pipelines")
+        );
+        assert!(!cache.is_dirty());
+
+        let pipeline = CachedRenderPipelineId::new(0);
+        assert!(cache.record(pipeline));
+        assert!(cache.is_dirty());
+
+        // Pipelines are only recorded once, so the cache isn't written again until others compile.
+        cache.dirty = false;
+        assert!(!cache.record(pipeline));
+        assert!(!cache.is_dirty());
+        assert!(cache.record(CachedRenderPipelineId::new(1)));
+        assert!(cache.is_dirty());
+    }
+}
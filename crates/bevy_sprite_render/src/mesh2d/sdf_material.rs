@@ -0,0 +1,149 @@
+use crate::{AlphaMode2d, Material2d, Material2dPlugin};
+use bevy_app::{App, Plugin};
+use bevy_asset::{embedded_asset, embedded_path, Asset, AssetApp, AssetPath, Handle};
+use bevy_color::{Color, ColorToComponents, LinearRgba};
+use bevy_image::Image;
+use bevy_math::Vec4;
+use bevy_reflect::prelude::*;
+use bevy_render::{render_asset::RenderAssets, render_resource::*, texture::GpuImage};
+use bevy_shader::ShaderRef;
+
+/// Adds the [`SdfMaterial2d`] material.
+///
+/// This plugin isn't added by the [`SpriteRenderPlugin`](crate::SpriteRenderPlugin), so that apps
+/// only pay for the materials they use.
+#[derive(Default)]
+pub struct SdfMaterial2dPlugin;
+
+impl Plugin for SdfMaterial2dPlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "sdf_material.wgsl");
+
+        app.add_plugins(Material2dPlugin::<SdfMaterial2d>::default())
+            .register_asset_reflect::<SdfMaterial2d>();
+    }
+}
+
+/// A [2d material](Material2d) that draws a shape stored in a signed distance field texture, such
+/// as an icon or a glyph, with edges that stay crisp at any scale.
+///
+/// The red channel of [`texture`](Self::texture) holds the distance to the edge of the shape,
+/// remapped so that [`threshold`](Self::threshold) lies on the edge and greater values are inside.
+/// The edge is antialiased over about one screen pixel, using the screen-space derivatives of the
+/// distance, so it neither blurs when zoomed in nor aliases when zoomed out.
+///
+/// Requires the [`SdfMaterial2dPlugin`].
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[reflect(Default, Debug, Clone)]
+#[uniform(0, SdfMaterial2dUniform)]
+pub struct SdfMaterial2d {
+    /// The color inside the shape.
+    pub color: Color,
+    /// The color of the outline around the shape.
+    pub outline_color: Color,
+    /// The distance value on the edge of the shape, between `0.0` and `1.0`.
+    pub threshold: f32,
+    /// Scales the width of the antialiased edge. `1.0` blends over about one screen pixel, larger
+    /// values soften the edge, and smaller ones sharpen it.
+    pub spread: f32,
+    /// The width of the outline, in the units of the distance field. No outline is drawn if this is
+    /// `0.0`.
+    pub outline_width: f32,
+    /// The signed distance field.
+    #[texture(1)]
+    #[sampler(2)]
+    pub texture: Handle<Image>,
+}
+
+impl SdfMaterial2d {
+    /// Creates a material drawing the shape of the signed distance field `texture` in `color`.
+    pub fn new(texture: Handle<Image>, color: impl Into<Color>) -> Self {
+        Self {
+            texture,
+            color: color.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Adds an outline of the given color and width around the shape.
+    pub fn with_outline(mut self, color: impl Into<Color>, width: f32) -> Self {
+        self.outline_color = color.into();
+        self.outline_width = width;
+        self
+    }
+}
+
+impl Default for SdfMaterial2d {
+    fn default() -> Self {
+        SdfMaterial2d {
+            color: Color::WHITE,
+            outline_color: Color::BLACK,
+            threshold: 0.5,
+            spread: 1.0,
+            outline_width: 0.0,
+            texture: Handle::default(),
+        }
+    }
+}
+
+/// The GPU representation of the uniform data of a [`SdfMaterial2d`].
+#[derive(Clone, Default, ShaderType)]
+pub struct SdfMaterial2dUniform {
+    pub color: Vec4,
+    pub outline_color: Vec4,
+    pub threshold: f32,
+    pub spread: f32,
+    pub outline_width: f32,
+}
+
+impl AsBindGroupShaderType<SdfMaterial2dUniform> for SdfMaterial2d {
+    fn as_bind_group_shader_type(&self, _images: &RenderAssets<GpuImage>) -> SdfMaterial2dUniform {
+        SdfMaterial2dUniform {
+            color: LinearRgba::from(self.color).to_f32_array().into(),
+            outline_color: LinearRgba::from(self.outline_color).to_f32_array().into(),
+            threshold: self.threshold,
+            spread: self.spread,
+            outline_width: self.outline_width,
+        }
+    }
+}
+
+impl Material2d for SdfMaterial2d {
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Path(
+            AssetPath::from_path_buf(embedded_path!("sdf_material.wgsl")).with_source("embedded"),
+        )
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        // The antialiased edge is partially transparent.
+        AlphaMode2d::Blend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdf_texture_and_edge_uniforms_are_bound() {
+        let images = RenderAssets::<GpuImage>::default();
+        let material =
+            SdfMaterial2d::new(Handle::default(), LinearRgba::RED).with_outline(Color::BLACK, 0.1);
+        let uniform: SdfMaterial2dUniform = material.as_bind_group_shader_type(&images);
+        assert_eq!(uniform.color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(uniform.outline_color, Vec4::new(0.0, 0.0, 0.0, 1.0));
+        assert_eq!(uniform.threshold, 0.5);
+        assert_eq!(uniform.spread, 1.0);
+        assert_eq!(uniform.outline_width, 0.1);
+
+        // The texture and sampler are bound after the uniform, and the edge is antialiased with
+        // screen-space derivatives around the threshold.
+        let shader = include_str!("sdf_material.wgsl");
+        assert!(shader.contains("@binding(0) var<uniform> material: SdfMaterial2d;"));
+        assert!(shader.contains("@binding(1) var texture: texture_2d<f32>;"));
+        assert!(shader.contains("@binding(2) var texture_sampler: sampler;"));
+        assert!(shader.contains("fwidth(distance)"));
+        assert!(shader.contains("smoothstep(material.threshold - width"));
+    }
+}
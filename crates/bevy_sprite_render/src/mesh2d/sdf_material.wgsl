@@ -0,0 +1,56 @@
+#import bevy_sprite::{
+    mesh2d_vertex_output::{VertexOutput, FragmentOutput},
+    mesh2d_view_bindings::view,
+}
+
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+struct SdfMaterial2d {
+    color: vec4<f32>,
+    outline_color: vec4<f32>,
+    threshold: f32,
+    spread: f32,
+    outline_width: f32,
+};
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(0) var<uniform> material: SdfMaterial2d;
+@group(#{MATERIAL_BIND_GROUP}) @binding(1) var texture: texture_2d<f32>;
+@group(#{MATERIAL_BIND_GROUP}) @binding(2) var texture_sampler: sampler;
+
+@fragment
+fn fragment(
+    mesh: VertexOutput,
+) -> FragmentOutput {
+    let distance = textureSample(texture, texture_sampler, mesh.uv).r;
+
+    // How much the distance changes across a screen pixel, so that the antialiased edge stays
+    // about one pixel wide however the shape is scaled.
+    let width = max(fwidth(distance), 0.00001) * material.spread;
+    let fill = smoothstep(material.threshold - width, material.threshold + width, distance);
+
+    var output_color = material.color;
+    var coverage = fill;
+    if material.outline_width > 0.0 {
+        let outline_edge = material.threshold - material.outline_width;
+        coverage = smoothstep(outline_edge - width, outline_edge + width, distance);
+        output_color = mix(material.outline_color, material.color, fill);
+    }
+    output_color.a = output_color.a * coverage;
+
+#ifdef VERTEX_COLORS
+    output_color = output_color * mesh.color;
+#endif
+#ifdef MESH2D_TINT
+    output_color = output_color * mesh.tint;
+#endif
+
+#ifdef TONEMAP_IN_SHADER
+    output_color = tonemapping::tone_mapping(output_color, view.color_grading);
+#endif
+
+    var out: FragmentOutput;
+    out.color = output_color;
+    return out;
+}
@@ -41,7 +41,8 @@ use bevy_render::{
         SetItemPipeline, TrackedRenderPass, ViewBinnedRenderPhases,
     },
     render_resource::*,
-    renderer::{RenderContext, ViewQuery},
+    renderer::{RenderContext, RenderDevice, ViewQuery},
+    settings::WgpuFeatures,
     sync_world::{MainEntity, MainEntityHashMap},
     view::{
         ExtractedView, RenderVisibleEntities, RetainedViewEntity, ViewDepthTexture, ViewTarget,
@@ -50,7 +51,7 @@ use bevy_render::{
 };
 use bevy_shader::Shader;
 use core::{hash::Hash, ops::Range};
-use tracing::error;
+use tracing::{error, warn};
 
 /// A [`Plugin`] that draws wireframes for 2D meshes.
 ///
@@ -60,7 +61,8 @@ use tracing::error;
 /// - Vulkan
 /// - Metal
 ///
-/// This is a native only feature.
+/// This is a native only feature. On devices without [`WgpuFeatures::POLYGON_MODE_LINE`] and
+/// [`WgpuFeatures::IMMEDIATES`], the plugin logs a warning and draws nothing.
 #[derive(Debug, Default)]
 pub struct Wireframe2dPlugin {
     /// Debugging flags that can optionally be set when constructing the renderer.
@@ -103,11 +105,23 @@ impl Plugin for Wireframe2dPlugin {
                 .after(AssetEventSystems)
                 .run_if(resource_exists::<Wireframe2dConfig>),
         );
+    }
 
+    fn finish(&self, app: &mut App) {
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
+        let required_features = WgpuFeatures::POLYGON_MODE_LINE | WgpuFeatures::IMMEDIATES;
+        let render_device = render_app.world().resource::<RenderDevice>();
+        if !render_device.features().contains(required_features) {
+            warn!(
+                "Wireframe2dPlugin not loaded. GPU lacks support for required features: {:?}.",
+                required_features
+            );
+            return;
+        }
+
         render_app
             .init_resource::<WireframeEntitySpecializationTicks>()
             .init_resource::<SpecializedWireframePipelineCache>()
@@ -336,7 +350,6 @@ impl SpecializedMeshPipeline for Wireframe2dPipeline {
         descriptor.immediate_size = 16;
         let fragment = descriptor.fragment.as_mut().unwrap();
         fragment.shader = self.shader.clone();
-        descriptor.primitive.polygon_mode = PolygonMode::Line;
         descriptor.depth_stencil.as_mut().unwrap().bias.slope_scale = 1.0;
         Ok(descriptor)
     }
@@ -760,7 +773,14 @@ pub fn specialize_wireframes(
             };
 
             let mut mesh_key = *view_key;
-            mesh_key |= Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology());
+            mesh_key |= Mesh2dPipelineKey::WIREFRAME
+                | Mesh2dPipelineKey::from_primitive_topology(mesh.primitive_topology())
+                | Mesh2dPipelineKey::from_strip_index_format(
+                    mesh.primitive_topology(),
+                    &mesh.buffer_info,
+                );
+            // Wireframes are drawn in a flat color, so they are never color graded.
+            mesh_key.remove(Mesh2dPipelineKey::COLOR_GRADING_LUT);
 
             let pipeline_id =
                 pipelines.specialize(&pipeline_cache, &pipeline, mesh_key, &mesh.layout);
@@ -847,3 +867,25 @@ fn queue_wireframes(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh2d::mesh::tests::{test_layout, test_pipeline};
+
+    #[test]
+    fn wireframe_pipeline_draws_lines() {
+        let pipeline = Wireframe2dPipeline {
+            mesh_pipeline: test_pipeline(),
+            shader: Handle::default(),
+        };
+        let layout = test_layout();
+
+        // Wireframe meshes are specialized with the wireframe bit of their key.
+        let descriptor = pipeline
+            .specialize(Mesh2dPipelineKey::WIREFRAME, &layout)
+            .unwrap();
+        assert_eq!(descriptor.primitive.polygon_mode, PolygonMode::Line);
+        assert_eq!(descriptor.immediate_size, 16);
+    }
+}
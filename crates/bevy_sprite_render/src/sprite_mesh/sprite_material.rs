@@ -113,7 +113,12 @@ impl AsBindGroupShaderType<SpriteMaterialUniform> for SpriteMaterial {
                 alpha_cutoff = c;
                 flags |= SpriteMaterialFlags::ALPHA_MODE_MASK;
             }
-            AlphaMode2d::Blend => flags |= SpriteMaterialFlags::ALPHA_MODE_BLEND,
+            AlphaMode2d::Blend
+            | AlphaMode2d::Premultiplied
+            | AlphaMode2d::Additive
+            | AlphaMode2d::Multiply => {
+                flags |= SpriteMaterialFlags::ALPHA_MODE_BLEND;
+            }
         };
 
         if self.flip_x {
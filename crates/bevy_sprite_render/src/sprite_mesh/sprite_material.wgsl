@@ -60,6 +60,8 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #ifdef VERTEX_COLORS
     out.color = vertex.color;
 #endif
+
+    out.tint = mesh_functions::get_tint(vertex.instance_index);
     return out;
 }
 
@@ -150,6 +150,8 @@ pub fn viewport_picking(
                 location,
                 pointer_id: viewport_pointer_id,
                 action: input.action,
+                modifiers: input.modifiers,
+                time: input.time,
             });
         }
     }
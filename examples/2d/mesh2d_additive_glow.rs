@@ -0,0 +1,85 @@
+//! Overlapping glows that brighten each other with a custom additive blend state.
+
+use bevy::{
+    color::palettes::css::{DEEP_SKY_BLUE, GOLD, HOT_PINK},
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{
+        AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState,
+    },
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/additive_glow_2d.wgsl";
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, Material2dPlugin::<GlowMaterial>::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, orbit)
+        .run();
+}
+
+#[derive(Component)]
+struct Orbit {
+    phase: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<GlowMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    let quad = meshes.add(Rectangle::default());
+    for (index, color) in [HOT_PINK, GOLD, DEEP_SKY_BLUE].into_iter().enumerate() {
+        commands.spawn((
+            Mesh2d(quad.clone()),
+            MeshMaterial2d(materials.add(GlowMaterial {
+                color: color.into(),
+            })),
+            Transform::from_scale(Vec3::splat(320.)),
+            Orbit {
+                phase: index as f32 * std::f32::consts::TAU / 3.,
+            },
+        ));
+    }
+}
+
+// Move the glows in and out of each other, so the overlaps add up to white
+fn orbit(time: Res<Time>, mut glows: Query<(&Orbit, &mut Transform)>) {
+    let radius = 60. + 40. * ops::sin(time.elapsed_secs());
+    for (orbit, mut transform) in &mut glows {
+        let angle = orbit.phase + time.elapsed_secs() * 0.5;
+        transform.translation.x = radius * ops::cos(angle);
+        transform.translation.y = radius * ops::sin(angle);
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct GlowMaterial {
+    #[uniform(0)]
+    color: LinearRgba,
+}
+
+impl Material2d for GlowMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    // Add the glow, weighted by its alpha, on top of whatever is behind it.
+    // A custom blend state always draws the material in the transparent phase.
+    fn blend_state(&self) -> Option<BlendState> {
+        Some(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent::OVER,
+        })
+    }
+}
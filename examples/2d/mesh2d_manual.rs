@@ -33,8 +33,8 @@ use bevy::{
     },
     sprite_render::{
         extract_mesh2d, init_mesh_2d_pipeline, DrawMesh2d, Material2dBindGroupId, Mesh2dPipeline,
-        Mesh2dPipelineKey, Mesh2dTransforms, MeshFlags, RenderMesh2dInstance, SetMesh2dBindGroup,
-        SetMesh2dViewBindGroup,
+        Mesh2dPipelineKey, Mesh2dTransforms, MeshFlags, RenderLayer2dOrder, RenderMesh2dInstance,
+        SetMesh2dBindGroup, SetMesh2dViewBindGroup,
     },
 };
 use std::f32::consts::PI;
@@ -352,8 +352,11 @@ pub fn extract_colored_mesh2d(
             continue;
         }
 
+        let world_from_local = transform.affine().into();
         let transforms = Mesh2dTransforms {
-            world_from_local: transform.affine().into(),
+            world_from_local,
+            previous_world_from_local: world_from_local,
+            previous_world_from_local_index: 0,
             flags: MeshFlags::empty().bits(),
         };
 
@@ -366,6 +369,8 @@ pub fn extract_colored_mesh2d(
                 material_bind_group_id: Material2dBindGroupId::default(),
                 automatic_batching: false,
                 tag: 0,
+                tint: LinearRgba::WHITE,
+                order: RenderLayer2dOrder::default(),
             },
         );
     }
@@ -0,0 +1,77 @@
+//! A custom 2d material that requires its meshes to have vertex colors, and shades them with a
+//! gradient between the colors of their vertices.
+
+use bevy::{
+    mesh::VertexAttributeDescriptor,
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::AsBindGroup,
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/vertex_color_material_2d.wgsl";
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            Material2dPlugin::<VertexColorMaterial>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<VertexColorMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    // One color per corner of the quad
+    let mesh = Mesh::from(Rectangle::default()).with_inserted_attribute(
+        Mesh::ATTRIBUTE_COLOR,
+        vec![
+            LinearRgba::RED.to_f32_array(),
+            LinearRgba::GREEN.to_f32_array(),
+            LinearRgba::BLUE.to_f32_array(),
+            LinearRgba::WHITE.to_f32_array(),
+        ],
+    );
+
+    commands.spawn((
+        Mesh2d(meshes.add(mesh)),
+        MeshMaterial2d(materials.add(VertexColorMaterial { brightness: 1.5 })),
+        Transform::default().with_scale(Vec3::splat(256.)),
+    ));
+
+    // A mesh without vertex colors fails to specialize, logging a `MissingVertexAttribute`
+    // error, and isn't drawn.
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::default())),
+        MeshMaterial2d(materials.add(VertexColorMaterial { brightness: 1.0 })),
+        Transform::from_xyz(256., 0., 0.).with_scale(Vec3::splat(128.)),
+    ));
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct VertexColorMaterial {
+    #[uniform(0)]
+    brightness: f32,
+}
+
+impl Material2d for VertexColorMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    // The default mesh2d pipeline reads vertex colors at location 4 when the mesh has them.
+    // Requiring them makes meshes without vertex colors fail to specialize, rather than the
+    // shader failing to compile. Custom attributes for a custom vertex shader can be required
+    // from location 5 on the same way.
+    fn vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+        vec![Mesh::ATTRIBUTE_COLOR.at_shader_location(4)]
+    }
+}
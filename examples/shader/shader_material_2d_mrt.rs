@@ -0,0 +1,143 @@
+//! A 2d material that writes to two render targets at once: its color, and the UV coordinates of
+//! the mesh, like the geometry buffer of a deferred renderer.
+//!
+//! An off-screen camera binds the second target with `ExtraColorTargets2d`, and the material
+//! describes both targets with `Material2d::color_target_states`. Both images are then shown side
+//! by side.
+
+use bevy::{
+    camera::{visibility::RenderLayers, RenderTarget},
+    core_pipeline::core_2d::ExtraColorTargets2d,
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::{AsBindGroup, ColorTargetState, ColorWrites, TextureFormat},
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/multiple_render_targets_2d.wgsl";
+
+/// The format of the image the UV coordinates are written to
+const UV_TARGET_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+/// The size of both targets
+const TARGET_SIZE: u32 = 256;
+
+/// The layer of the meshes drawn by the off-screen camera
+const MRT_LAYER: RenderLayers = RenderLayers::layer(1);
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            Material2dPlugin::<MultipleTargetsMaterial>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, rotate)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotate;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<MultipleTargetsMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let color_target = images.add(Image::new_target_texture(
+        TARGET_SIZE,
+        TARGET_SIZE,
+        TextureFormat::bevy_default(),
+        None,
+    ));
+    let uv_target = images.add(Image::new_target_texture(
+        TARGET_SIZE,
+        TARGET_SIZE,
+        UV_TARGET_FORMAT,
+        None,
+    ));
+
+    // This camera draws the meshes on `MRT_LAYER` into both images. Every mesh it draws has to
+    // write to both targets, so it only sees the meshes with the material.
+    commands.spawn((
+        Camera2d,
+        Camera {
+            // Render before the camera that shows the targets
+            order: -1,
+            clear_color: ClearColorConfig::Custom(Color::NONE),
+            ..default()
+        },
+        RenderTarget::Image(color_target.clone().into()),
+        ExtraColorTargets2d(vec![uv_target.clone()]),
+        // The extra target isn't multisampled
+        Msaa::Off,
+        MRT_LAYER,
+    ));
+
+    commands.spawn((
+        Mesh2d(meshes.add(RegularPolygon::new(80., 6))),
+        MeshMaterial2d(materials.add(MultipleTargetsMaterial {
+            color: LinearRgba::rgb(0.9, 0.4, 0.1),
+        })),
+        Transform::from_xyz(-40., 20., 0.),
+        Rotate,
+        MRT_LAYER,
+    ));
+    commands.spawn((
+        Mesh2d(meshes.add(Circle::new(50.))),
+        MeshMaterial2d(materials.add(MultipleTargetsMaterial {
+            color: LinearRgba::rgb(0.1, 0.5, 0.9),
+        })),
+        Transform::from_xyz(60., -50., 1.),
+        MRT_LAYER,
+    ));
+
+    // This camera shows the color target on the left, and the UV target on the right
+    commands.spawn(Camera2d);
+    commands.spawn((
+        Sprite::from_image(color_target),
+        Transform::from_xyz(-140., 0., 0.),
+    ));
+    commands.spawn((
+        Sprite::from_image(uv_target),
+        Transform::from_xyz(140., 0., 0.),
+    ));
+}
+
+fn rotate(time: Res<Time>, mut transforms: Query<&mut Transform, With<Rotate>>) {
+    for mut transform in &mut transforms {
+        transform.rotate_z(time.delta_secs());
+    }
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct MultipleTargetsMaterial {
+    #[uniform(0)]
+    color: LinearRgba,
+}
+
+impl Material2d for MultipleTargetsMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+
+    // One target for each `@location` of the fragment shader output, matching the view target
+    // and the `ExtraColorTargets2d` of the camera
+    fn color_target_states() -> Vec<Option<ColorTargetState>> {
+        vec![
+            Some(ColorTargetState {
+                format: TextureFormat::bevy_default(),
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            }),
+            Some(ColorTargetState {
+                format: UV_TARGET_FORMAT,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            }),
+        ]
+    }
+}
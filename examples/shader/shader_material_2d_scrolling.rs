@@ -0,0 +1,70 @@
+//! A 2d material whose shader scrolls its texture over time, reading the elapsed time from the
+//! globals uniform of the view bind group.
+
+use bevy::{
+    image::{ImageAddressMode, ImageLoaderSettings, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    reflect::TypePath,
+    render::render_resource::AsBindGroup,
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/scrolling_uv_2d.wgsl";
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            Material2dPlugin::<ScrollingMaterial>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ScrollingMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(Camera2d);
+
+    // The texture repeats, so that it wraps around as it scrolls
+    let texture = asset_server.load_with_settings(
+        "branding/icon.png",
+        |settings: &mut ImageLoaderSettings| {
+            settings.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+                address_mode_u: ImageAddressMode::Repeat,
+                address_mode_v: ImageAddressMode::Repeat,
+                ..default()
+            });
+        },
+    );
+
+    commands.spawn((
+        Mesh2d(meshes.add(Rectangle::default())),
+        MeshMaterial2d(materials.add(ScrollingMaterial {
+            velocity: Vec2::new(0.25, 0.1),
+            texture,
+        })),
+        Transform::default().with_scale(Vec3::splat(256.)),
+    ));
+}
+
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct ScrollingMaterial {
+    /// How far the texture scrolls each second, in UV units
+    #[uniform(0)]
+    velocity: Vec2,
+    #[texture(1)]
+    #[sampler(2)]
+    texture: Handle<Image>,
+}
+
+impl Material2d for ScrollingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+}
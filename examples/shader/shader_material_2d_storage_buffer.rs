@@ -0,0 +1,96 @@
+//! A 2d material that binds a uniform and a storage buffer, and picks a color from the storage
+//! buffer for each mesh using its [`MeshTag`].
+
+use bevy::{
+    mesh::MeshTag,
+    prelude::*,
+    reflect::TypePath,
+    render::{render_resource::AsBindGroup, storage::ShaderBuffer},
+    shader::ShaderRef,
+    sprite_render::{Material2d, Material2dPlugin},
+};
+
+/// This example uses a shader source file from the assets subdirectory
+const SHADER_ASSET_PATH: &str = "shaders/storage_buffer_2d.wgsl";
+
+const COLOR_COUNT: usize = 5;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            Material2dPlugin::<StorageBufferMaterial>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, update)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut buffers: ResMut<Assets<ShaderBuffer>>,
+    mut materials: ResMut<Assets<StorageBufferMaterial>>,
+) {
+    commands.spawn(Camera2d);
+
+    let colors = buffers.add(ShaderBuffer::from(vec![[1.0f32; 4]; COLOR_COUNT]));
+    let material = materials.add(StorageBufferMaterial {
+        brightness: 1.0,
+        colors,
+    });
+    let mesh = meshes.add(Circle::new(30.0));
+
+    // All the circles share one material, and so are drawn in a single batch.
+    let mut tag = 0;
+    for x in -6..=6 {
+        for y in -3..=3 {
+            commands.spawn((
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                MeshTag(tag),
+                Transform::from_xyz(x as f32 * 80.0, y as f32 * 80.0, 0.0),
+            ));
+            tag += 1;
+        }
+    }
+}
+
+// Animate the colors in the storage buffer, and the brightness in the uniform
+fn update(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StorageBufferMaterial>>,
+    mut buffers: ResMut<Assets<ShaderBuffer>>,
+) {
+    let t = time.elapsed_secs() * 2.0;
+    for (_, material) in materials.iter_mut() {
+        material.brightness = ops::sin(t * 0.5) * 0.25 + 0.75;
+
+        let Some(buffer) = buffers.get_mut(&material.colors) else {
+            continue;
+        };
+        buffer.set_data(
+            (0..COLOR_COUNT)
+                .map(|i| {
+                    let hue = (t * 30.0 + i as f32 * 360.0 / COLOR_COUNT as f32) % 360.0;
+                    LinearRgba::from(Color::hsl(hue, 0.8, 0.6)).to_f32_array()
+                })
+                .collect::<Vec<[f32; 4]>>(),
+        );
+    }
+}
+
+// This is the struct that will be passed to your shader
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct StorageBufferMaterial {
+    #[uniform(0)]
+    brightness: f32,
+    #[storage(1, read_only)]
+    colors: Handle<ShaderBuffer>,
+}
+
+impl Material2d for StorageBufferMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SHADER_ASSET_PATH.into()
+    }
+}
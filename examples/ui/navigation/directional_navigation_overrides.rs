@@ -30,9 +30,9 @@ use bevy::{
     math::{CompassOctant, Dir2},
     picking::{
         backend::HitData,
-        pointer::{Location, PointerId},
+        pointer::{Location, PointerId, PointerModifiers},
     },
-    platform::collections::HashSet,
+    platform::{collections::HashSet, time::Instant},
     prelude::*,
     ui::auto_directional_navigation::{AutoDirectionalNavigation, AutoDirectionalNavigator},
 };
@@ -842,6 +842,8 @@ fn interact_with_focused_button(
                 },
                 position: Vec2::ZERO,
             },
+            modifiers: PointerModifiers::default(),
+            pointer_time: Instant::now(),
             event: Click {
                 button: PointerButton::Primary,
                 hit: HitData {
@@ -851,6 +853,7 @@ fn interact_with_focused_button(
                     normal: None,
                 },
                 duration: Duration::from_secs_f32(0.1),
+                count: 1,
             },
         });
     }
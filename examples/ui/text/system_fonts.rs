@@ -1,10 +1,7 @@
 //! This example displays a scrollable list of all available system fonts.
 //! Demonstrates loading and querying system fonts via cosmic-text.
 
-use bevy::{
-    diagnostic::FrameTimeDiagnosticsPlugin, input::mouse::MouseScrollUnit, prelude::*,
-    text::CosmicFontSystem,
-};
+use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*, text::CosmicFontSystem};
 
 fn main() {
     let mut app = App::new();
@@ -98,8 +95,8 @@ fn setup(mut commands: Commands, font_system: Res<CosmicFontSystem>) {
                      mut query: Query<(&mut ScrollPosition, &ComputedNode)>| {
                         if let Ok((mut scroll_position, node)) = query.get_mut(on_scroll.entity) {
                             let dy = match on_scroll.unit {
-                                MouseScrollUnit::Line => on_scroll.y * 20.,
-                                MouseScrollUnit::Pixel => on_scroll.y,
+                                ScrollUnit::Line => on_scroll.delta.y * 20.,
+                                ScrollUnit::Pixel => on_scroll.delta.y,
                             };
                             let range = (node.content_size.y - node.size.y).max(0.)
                                 * node.inverse_scale_factor;